@@ -120,7 +120,7 @@ fn big_read_test() {
 
 pub fn print_speed(speed: f64) {
     println!("speed: {} (bytes/s)", speed);
-    let speeds = vec![
+    let speeds = [
         speed / 1024.0,
         speed / 1024.0 / 1024.0,
         speed / 1024.0 / 1024.0 / 1024.0,
@@ -272,7 +272,7 @@ fn cli_read_test() {
         let read_data = std::fs::read(&arg).unwrap();
         // read_test(read_data);
         let len = read_data.len();
-        let mut data = read_data;
+        let data = read_data;
 
         std::thread::sleep(std::time::Duration::from_secs(1));
         let start_time = std::time::Instant::now();
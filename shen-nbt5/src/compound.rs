@@ -0,0 +1,205 @@
+//! 带索引的 compound
+//!
+//! [`NbtValue::Compound`] 里存的是一个 `Vec`, 找一个 key 只能从头找到尾;
+//! [`NbtCompound`] 另外用一个 `HashMap` 记下每个 key 的位置, 查找和插入都是 O(1), 同时保留插入的顺序
+//!
+//! 放进去的 compound 的名字不会改; Java 版写的是嵌套 compound 的名字, 基岩版写的是 key,
+//! 所以自己创建的 compound 要按照要写的格式设置名字
+//!
+//! ```rust
+//! use shen_nbt5::compound::NbtCompound;
+//! use shen_nbt5::NbtValue;
+//!
+//! let mut section: NbtCompound = [("Y", NbtValue::Byte(0))].into_iter().collect();
+//! section.insert("block_states", NbtValue::Compound(None, Vec::new()));
+//! if let Some(NbtValue::Byte(y)) = section.get_mut("Y") {
+//!     *y = 4;
+//! }
+//! section.insert("SkyLight", NbtValue::ByteArray(vec![0; 2048]));
+//! assert!(section.remove("block_states").is_some());
+//!
+//! let keys: Vec<_> = section.keys().collect();
+//! assert_eq!(keys, ["Y", "SkyLight"]);
+//! assert_eq!(section["Y"], NbtValue::Byte(4));
+//! ```
+
+use std::collections::HashMap;
+use std::ops::Index;
+
+use crate::{NbtError, NbtValue};
+
+/// 保留插入顺序, 用 key 查找是 O(1) 的 compound
+///
+/// 见 [模块文档](self)
+#[derive(Debug, Clone, Default)]
+pub struct NbtCompound {
+    /// 按照插入的顺序
+    entries: Vec<(String, NbtValue)>,
+    /// key 在 `entries` 里的下标
+    index: HashMap<String, usize>,
+}
+
+impl NbtCompound {
+    /// 空的 compound
+    pub fn new() -> Self { Self::default() }
+
+    /// 预先分配 `capacity` 个 key 的空间
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            entries: Vec::with_capacity(capacity),
+            index: HashMap::with_capacity(capacity),
+        }
+    }
+
+    /// key 的数量
+    pub fn len(&self) -> usize { self.entries.len() }
+
+    /// 是不是空的
+    pub fn is_empty(&self) -> bool { self.entries.is_empty() }
+
+    /// 有没有这个 key
+    pub fn contains_key(&self, key: &str) -> bool { self.position(key).is_some() }
+
+    /// 拿到 key 对应的值
+    pub fn get(&self, key: &str) -> Option<&NbtValue> {
+        self.position(key).map(|idx| &self.entries[idx].1)
+    }
+
+    /// 拿到 key 对应的值, 可以直接修改
+    pub fn get_mut(&mut self, key: &str) -> Option<&mut NbtValue> {
+        self.position(key).map(|idx| &mut self.entries[idx].1)
+    }
+
+    /// 插入一个值, 返回旧的值
+    ///
+    /// 已经有这个 key 的时候只会替换值, 位置不变
+    pub fn insert(
+        &mut self,
+        key: impl Into<String>,
+        value: impl Into<NbtValue>,
+    ) -> Option<NbtValue> {
+        let key = key.into();
+        let value = value.into();
+        match self.index.get(&key) {
+            Some(idx) => Some(std::mem::replace(&mut self.entries[*idx].1, value)),
+            None => {
+                self.index.insert(key.clone(), self.entries.len());
+                self.entries.push((key, value));
+                None
+            }
+        }
+    }
+
+    /// 删掉一个 key, 返回删掉的值
+    ///
+    /// 后面的 key 会往前挪, 跟 `Vec::remove` 一样是 O(n)
+    pub fn remove(&mut self, key: &str) -> Option<NbtValue> {
+        let removed = self.index.remove(key)?;
+        for idx in self.index.values_mut() {
+            if *idx > removed {
+                *idx -= 1;
+            }
+        }
+        Some(self.entries.remove(removed).1)
+    }
+
+    /// 按照插入的顺序遍历
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &NbtValue)> {
+        self.entries.iter().map(|(k, v)| (k, v))
+    }
+
+    /// 按照插入的顺序遍历, 值可以直接修改
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (&String, &mut NbtValue)> {
+        self.entries.iter_mut().map(|(k, v)| (&*k, v))
+    }
+
+    /// 按照插入的顺序遍历所有的 key
+    pub fn keys(&self) -> impl Iterator<Item = &String> { self.entries.iter().map(|(k, _)| k) }
+
+    /// 按照插入的顺序遍历所有的值
+    pub fn values(&self) -> impl Iterator<Item = &NbtValue> { self.entries.iter().map(|(_, v)| v) }
+
+    /// 跟 [`NbtValue::Compound`] 里一样的 `Vec`
+    pub fn as_slice(&self) -> &[(String, NbtValue)] { &self.entries }
+
+    /// 转换成 [`NbtValue::Compound`] 里的 `Vec`
+    pub fn into_vec(self) -> Vec<(String, NbtValue)> { self.entries }
+
+    /// 转换成名字是 `name` 的 [`NbtValue::Compound`]
+    pub fn into_value(self, name: Option<String>) -> NbtValue {
+        NbtValue::Compound(name, self.entries)
+    }
+
+    /// key 在 `entries` 里的下标
+    fn position(&self, key: &str) -> Option<usize> { self.index.get(key).copied() }
+}
+
+/// 只比较内容和顺序
+impl PartialEq for NbtCompound {
+    fn eq(&self, other: &Self) -> bool { self.entries == other.entries }
+}
+
+impl Index<&str> for NbtCompound {
+    type Output = NbtValue;
+
+    /// 没有这个 key 的时候会 panic
+    fn index(&self, key: &str) -> &NbtValue {
+        match self.get(key) {
+            Some(value) => value,
+            None => panic!("NbtCompound 里没有 key {:?}", key),
+        }
+    }
+}
+
+impl<K: Into<String>, V: Into<NbtValue>> Extend<(K, V)> for NbtCompound {
+    fn extend<T: IntoIterator<Item = (K, V)>>(&mut self, iter: T) {
+        for (key, value) in iter {
+            self.insert(key, value);
+        }
+    }
+}
+
+impl<K: Into<String>, V: Into<NbtValue>> FromIterator<(K, V)> for NbtCompound {
+    fn from_iter<T: IntoIterator<Item = (K, V)>>(iter: T) -> Self {
+        let mut compound = NbtCompound::new();
+        compound.extend(iter);
+        compound
+    }
+}
+
+/// 重复的 key 只保留最后一个值, 位置是第一次出现的位置
+impl From<Vec<(String, NbtValue)>> for NbtCompound {
+    fn from(entries: Vec<(String, NbtValue)>) -> Self {
+        let mut compound = NbtCompound::with_capacity(entries.len());
+        compound.extend(entries);
+        compound
+    }
+}
+
+impl From<NbtCompound> for Vec<(String, NbtValue)> {
+    fn from(compound: NbtCompound) -> Self { compound.entries }
+}
+
+/// 没有名字的 [`NbtValue::Compound`]
+impl From<NbtCompound> for NbtValue {
+    fn from(compound: NbtCompound) -> Self { compound.into_value(None) }
+}
+
+/// 会丢掉 compound 的名字, 不是 compound 的时候返回 [`NbtError::IncorrectType`]
+impl TryFrom<NbtValue> for NbtCompound {
+    type Error = NbtError;
+
+    fn try_from(value: NbtValue) -> Result<Self, NbtError> {
+        match value {
+            NbtValue::Compound(_, entries) => Ok(entries.into()),
+            other => Err(NbtError::IncorrectType(10, other.tag())),
+        }
+    }
+}
+
+impl IntoIterator for NbtCompound {
+    type Item = (String, NbtValue);
+    type IntoIter = std::vec::IntoIter<(String, NbtValue)>;
+
+    fn into_iter(self) -> Self::IntoIter { self.entries.into_iter() }
+}
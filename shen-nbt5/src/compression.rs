@@ -0,0 +1,101 @@
+//! gzip/zlib 压缩的 NBT (需要开启 `compression` feature)
+//!
+//! 读取的时候会根据开头的几个字节自动判断是 gzip, zlib 还是没压缩的
+
+use std::io::{Read, Write};
+use std::path::Path;
+
+use flate2::read::{GzDecoder, ZlibDecoder};
+use flate2::write::{GzEncoder, ZlibEncoder};
+
+use crate::nbt_version::{NbtReadTrait, NbtWriteTrait};
+use crate::{NbtResult, NbtValue};
+
+/// 压缩方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Compression {
+    /// gzip, 开头是 `1F 8B`
+    #[default]
+    Gzip,
+    /// zlib, 开头一般是 `78 xx`
+    Zlib,
+    /// 没压缩
+    None,
+}
+
+impl Compression {
+    /// 根据开头的几个字节判断压缩方式
+    ///
+    /// 没压缩的 NBT 开头是 tag (`0A`/`09`), 不会跟 gzip/zlib 的头混在一起
+    pub fn detect(data: &[u8]) -> Compression {
+        match data {
+            [0x1F, 0x8B, ..] => Compression::Gzip,
+            // zlib 的头: CM = 8 (deflate), 而且 CMF * 256 + FLG 是 31 的倍数
+            [cmf, flg, ..]
+                if cmf & 0x0F == 8 && u16::from_be_bytes([*cmf, *flg]).is_multiple_of(31) =>
+            {
+                Compression::Zlib
+            }
+            _ => Compression::None,
+        }
+    }
+
+    /// 解压
+    pub fn decompress(self, data: &[u8]) -> NbtResult<Vec<u8>> {
+        let mut buff = Vec::with_capacity(data.len());
+        match self {
+            Compression::Gzip => GzDecoder::new(data).read_to_end(&mut buff)?,
+            Compression::Zlib => ZlibDecoder::new(data).read_to_end(&mut buff)?,
+            Compression::None => return Ok(data.to_vec()),
+        };
+        Ok(buff)
+    }
+
+    /// 压缩, 用的是 `flate2` 的默认压缩等级
+    pub fn compress(self, data: &[u8]) -> NbtResult<Vec<u8>> {
+        let level = flate2::Compression::default();
+        Ok(match self {
+            Compression::Gzip => {
+                let mut encoder = GzEncoder::new(Vec::new(), level);
+                encoder.write_all(data)?;
+                encoder.finish()?
+            }
+            Compression::Zlib => {
+                let mut encoder = ZlibEncoder::new(Vec::new(), level);
+                encoder.write_all(data)?;
+                encoder.finish()?
+            }
+            Compression::None => data.to_vec(),
+        })
+    }
+}
+
+impl NbtValue {
+    /// 解析可能压缩过的 Nbt 数据
+    ///
+    /// 根据开头的几个字节自动判断是 gzip, zlib 还是没压缩的
+    pub fn from_compressed<R>(data: &[u8]) -> NbtResult<NbtValue>
+    where
+        R: NbtReadTrait,
+    {
+        let mut data = Compression::detect(data).decompress(data)?;
+        NbtValue::from_binary::<R>(&mut data)
+    }
+
+    /// 读取一个 Nbt 文件, 可以是压缩过的
+    pub fn from_file<R>(path: impl AsRef<Path>) -> NbtResult<NbtValue>
+    where
+        R: NbtReadTrait,
+    {
+        let data = std::fs::read(path)?;
+        NbtValue::from_compressed::<R>(&data)
+    }
+
+    /// 写成二进制, 再用 `compression` 压缩
+    pub fn to_compressed<W>(&self, compression: Compression) -> NbtResult<Vec<u8>>
+    where
+        W: NbtWriteTrait,
+    {
+        compression.compress(&W::to_bytes(self)?)
+    }
+}
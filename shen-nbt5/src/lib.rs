@@ -28,10 +28,19 @@
 //! }
 //! ```
 
+#[cfg(feature = "compression")]
+pub mod compression;
+pub mod compound;
+pub mod mcstructure;
+pub mod path;
 pub mod reader;
+pub mod schematic;
+pub mod stream;
+pub mod structure;
 pub mod writer;
 
 use reader::NbtReader;
+use stream::NbtStreamReader;
 
 #[cfg(test)]
 mod tests;
@@ -48,30 +57,170 @@ mod tests;
 /// - `BedrockNetVarInt`
 ///   基岩版 网络 NBT 格式
 pub mod nbt_version {
-    use super::{NbtReader, NbtResult, NbtValue};
+    use std::io::{Read, Write};
 
+    use super::{NbtError, NbtReader, NbtResult, NbtStreamReader, NbtValue, RECURSE_LIMIT};
+
+    /// 从任何 [`Read`] 里一点一点读取 NBT
+    ///
+    /// 读出来的值跟 [`NbtReadTrait::from_reader`] 完全一样, 只会读需要的字节
+    ///
+    /// 数据不够的时候返回 [`NbtError::UnexpectedEof`],
+    /// 嵌套超过 [`RECURSE_LIMIT`] 的时候返回 [`NbtError::NbtDepthTooBig`], 不会 panic
+    pub trait NbtStreamReadTrait {
+        /// 根节点有没有名字
+        const ROOT_NAMED: bool = true;
+        /// 根节点可不可以是 List
+        const LIST_ROOT: bool = false;
+        /// Compound 里面的 Compound 的名字是不是它的 key (Java 版的读取就是这样的)
+        const NESTED_COMPOUND_NAMED: bool = false;
+
+        /// 读取一个 i16(short)
+        fn read_i16<R: Read>(reader: &mut NbtStreamReader<R>) -> NbtResult<i16>;
+        /// 读取一个 i32(int)
+        fn read_i32<R: Read>(reader: &mut NbtStreamReader<R>) -> NbtResult<i32>;
+        /// 读取一个 i64(long)
+        fn read_i64<R: Read>(reader: &mut NbtStreamReader<R>) -> NbtResult<i64>;
+        /// 读取一个 f32(float)
+        fn read_f32<R: Read>(reader: &mut NbtStreamReader<R>) -> NbtResult<f32>;
+        /// 读取一个 f64(double)
+        fn read_f64<R: Read>(reader: &mut NbtStreamReader<R>) -> NbtResult<f64>;
+        /// 读取 List/Array 的长度
+        fn read_len<R: Read>(reader: &mut NbtStreamReader<R>) -> NbtResult<usize>;
+        /// 读取一个 NBT 字符串
+        fn read_nbt_string<R: Read>(reader: &mut NbtStreamReader<R>) -> NbtResult<String>;
+
+        /// 读取一个 i8(byte) 数组
+        fn read_i8_array<R: Read>(reader: &mut NbtStreamReader<R>) -> NbtResult<Vec<i8>> {
+            let len = Self::read_len(reader)?;
+            Ok(reader.read_bytes(len)?.into_iter().map(|n| n as i8).collect())
+        }
+        /// 读取一个 i32(int) 数组
+        fn read_i32_array<R: Read>(reader: &mut NbtStreamReader<R>) -> NbtResult<Vec<i32>> {
+            let len = Self::read_len(reader)?;
+            // 不按照长度提前分配, 防止一个假的长度直接把内存吃满
+            let mut value = Vec::with_capacity(len.min(1024));
+            for _ in 0..len {
+                value.push(Self::read_i32(reader)?);
+            }
+            Ok(value)
+        }
+        /// 读取一个 i64(long) 数组
+        fn read_i64_array<R: Read>(reader: &mut NbtStreamReader<R>) -> NbtResult<Vec<i64>> {
+            let len = Self::read_len(reader)?;
+            let mut value = Vec::with_capacity(len.min(1024));
+            for _ in 0..len {
+                value.push(Self::read_i64(reader)?);
+            }
+            Ok(value)
+        }
+        /// 读取 `type_id` 类型的值 (不包括 tag)
+        ///
+        /// `depth` 是这个值所在的 List/Compound 的嵌套深度
+        fn read_value<R: Read>(
+            reader: &mut NbtStreamReader<R>,
+            type_id: u8,
+            name: Option<&String>,
+            depth: usize,
+        ) -> NbtResult<NbtValue> {
+            if matches!(type_id, 9 | 10) && depth >= RECURSE_LIMIT {
+                return Err(NbtError::NbtDepthTooBig(RECURSE_LIMIT));
+            }
+            Ok(match type_id {
+                1 => NbtValue::Byte(reader.read_i8()?),
+                2 => NbtValue::Short(Self::read_i16(reader)?),
+                3 => NbtValue::Int(Self::read_i32(reader)?),
+                4 => NbtValue::Long(Self::read_i64(reader)?),
+                5 => NbtValue::Float(Self::read_f32(reader)?),
+                6 => NbtValue::Double(Self::read_f64(reader)?),
+                7 => NbtValue::ByteArray(Self::read_i8_array(reader)?),
+                8 => NbtValue::String(Self::read_nbt_string(reader)?),
+                9 => NbtValue::List(Self::read_list(reader, depth + 1)?),
+                10 => {
+                    let name = name.filter(|_| Self::NESTED_COMPOUND_NAMED).cloned();
+                    NbtValue::Compound(name, Self::read_compound(reader, depth + 1)?)
+                }
+                11 => NbtValue::IntArray(Self::read_i32_array(reader)?),
+                12 => NbtValue::LongArray(Self::read_i64_array(reader)?),
+                _ => return Err(NbtError::UnknownType(type_id)),
+            })
+        }
+        /// 读取一个列表类型(List), `depth` 是它自己的嵌套深度
+        fn read_list<R: Read>(
+            reader: &mut NbtStreamReader<R>,
+            depth: usize,
+        ) -> NbtResult<Vec<NbtValue>> {
+            let type_id = reader.read_u8()?;
+            let len = Self::read_len(reader)?;
+            let mut list = Vec::with_capacity(len.min(1024));
+            for _ in 0..len {
+                list.push(Self::read_value(reader, type_id, None, depth)?);
+            }
+            Ok(list)
+        }
+        /// 读取一个复合标签类型(Compound), `depth` 是它自己的嵌套深度
+        fn read_compound<R: Read>(
+            reader: &mut NbtStreamReader<R>,
+            depth: usize,
+        ) -> NbtResult<Vec<(String, NbtValue)>> {
+            let mut compound = Vec::with_capacity(10);
+            loop {
+                let tag_id = reader.read_u8()?;
+                if tag_id == 0 {
+                    break;
+                }
+                let name = Self::read_nbt_string(reader)?;
+                let value = Self::read_value(reader, tag_id, Some(&name), depth)?;
+                compound.push((name, value));
+            }
+            Ok(compound)
+        }
+
+        fn from_stream<R: Read>(reader: &mut NbtStreamReader<R>) -> NbtResult<NbtValue> {
+            match reader.read_u8()? {
+                9 if Self::LIST_ROOT => Ok(NbtValue::List(Self::read_list(reader, 1)?)),
+                10 => {
+                    let name = match Self::ROOT_NAMED {
+                        true => Some(Self::read_nbt_string(reader)?),
+                        false => None,
+                    };
+                    Ok(NbtValue::Compound(name, Self::read_compound(reader, 1)?))
+                }
+                x => Err(NbtError::WrongRootType(x)),
+            }
+        }
+    }
+
+    /// 写入 NBT
+    ///
+    /// 所有的方法都可以直接写进任何 [`Write`] (文件, `GzEncoder`, socket 之类的)
+    ///
+    /// 写入会分成很多次很小的 `write`, 写文件或者 socket 的时候最好套一层 `BufWriter`
     pub trait NbtWriteTrait {
         /// 写入一个 i8(byte) 数组
-        fn write_i8_array(writer: &mut Vec<u8>, data: &[i8]);
+        fn write_i8_array(writer: &mut impl Write, data: &[i8]) -> NbtResult<()>;
         /// 写入一个 i32(int) 数组
-        fn write_i32_array(writer: &mut Vec<u8>, data: &[i32]);
+        fn write_i32_array(writer: &mut impl Write, data: &[i32]) -> NbtResult<()>;
         /// 写入一个 i64(long) 数组
-        fn write_i64_array(writer: &mut Vec<u8>, data: &[i64]);
+        fn write_i64_array(writer: &mut impl Write, data: &[i64]) -> NbtResult<()>;
         /// 写入一个 NBT 字符串
-        fn write_nbt_string(writer: &mut Vec<u8>, data: &str);
+        ///
+        /// 编码之后超过长度限制会返回 [`NbtError::LenTooBig`](super::NbtError::LenTooBig)
+        fn write_nbt_string(writer: &mut impl Write, data: &str) -> NbtResult<()>;
         /// 向 `writer` 写入一个列表类型(List)
-        fn write_list(writer: &mut Vec<u8>, data: &[NbtValue]) -> NbtResult<()>;
+        fn write_list(writer: &mut impl Write, data: &[NbtValue]) -> NbtResult<()>;
         /// 向 `writer` 写入一个复合标签类型(Compound)
         ///
         /// 如果 `name` 为 `None` 则不写入名字
         fn write_compound(
-            writer: &mut Vec<u8>,
+            writer: &mut impl Write,
             name: Option<&String>,
             data: &[(String, NbtValue)],
         ) -> NbtResult<()>;
 
-        fn write_to(value: &NbtValue, buff: &mut Vec<u8>) -> NbtResult<()>;
-        fn write_to_with_name(name: &str, value: &NbtValue, buff: &mut Vec<u8>) -> NbtResult<()>;
+        fn write_to(value: &NbtValue, buff: &mut impl Write) -> NbtResult<()>;
+        fn write_to_with_name(name: &str, value: &NbtValue, buff: &mut impl Write)
+            -> NbtResult<()>;
 
         fn to_bytes(value: &NbtValue) -> NbtResult<Vec<u8>> {
             let mut buff = Vec::new();
@@ -105,6 +254,8 @@ pub mod nbt_version {
     /// # 编码特点
     ///
     /// 大端, 大端, 还是 xx 的 大端!
+    ///
+    /// 字符串是 m-utf8 (Modified UTF-8)
     pub enum Java {}
     /// 1.20.2+(协议号 >= 764) 及以后 的网路传输 NBT 格式
     ///
@@ -117,6 +268,8 @@ pub mod nbt_version {
     /// # 编码特点
     ///
     /// 小端, 小端, 还是 xx 的 小端!
+    ///
+    /// 字符串是普通的 utf-8
     pub enum BedrockDisk {}
     /// 基岩版 网络 NBT 格式
     /// 最痛苦的一集
@@ -125,6 +278,8 @@ pub mod nbt_version {
     ///
     /// VarInt, VarLong, ZigZagVarInt, ZigZagVarLong
     /// 全都有
+    ///
+    /// 字符串是普通的 utf-8
     pub enum BedrockNetVarInt {}
 }
 
@@ -190,11 +345,40 @@ pub enum NbtError {
     ListTypeNotSame(Vec<NbtTypeId>),
     /// 错误类型
     IncorrectType(NbtTypeId, NbtTypeId),
+    /// 写入的时候长度超过了格式的限制
+    ///
+    /// path, len, max
+    /// 三个参数分别表示
+    /// - 出问题的位置 (类似 `Inventory[0].tag.Name`, 空的表示根节点)
+    /// - 实际长度
+    /// - 最大长度
+    LenTooBig(String, usize, usize),
+    /// 流式读取的时候数据提前结束了
+    ///
+    /// 参数表示已经读了多少字节
+    UnexpectedEof(usize),
+    /// 读写的时候出现的 IO 错误
+    ///
+    /// 错误类型, 错误信息
+    IoError(std::io::ErrorKind, String),
+    /// 结构文件的格式不对 (缺少字段, 类型不对之类的)
+    StructureError(String),
+    /// NBT path 语法错误
+    ///
+    /// 列 (按字符计算, 从 1 开始), 错误信息
+    NbtPathSyntax(usize, String),
+    /// NBT 深度过大
+    NbtDepthTooBig(usize),
 }
 
 /// 返回类型
 pub type NbtResult<T> = std::result::Result<T, NbtError>;
 
+/// 流式读取只能递归, 所以限制一下 List/Compound 的嵌套深度, 防止爆栈
+///
+/// 原版限制: 512
+pub const RECURSE_LIMIT: usize = 512;
+
 impl std::error::Error for NbtError {}
 
 impl std::fmt::Display for NbtError {
@@ -239,10 +423,65 @@ impl std::fmt::Display for NbtError {
             NbtError::IncorrectType(expect, got) => {
                 write!(f, "错误类型: 期望: {}, 实际: {}", expect, got)
             }
+            NbtError::LenTooBig(path, len, max) => {
+                let path = if path.is_empty() { "(根节点)" } else { path };
+                write!(f, "{} 长度过大: {}, 最大为 {}", path, len, max)
+            }
+            NbtError::UnexpectedEof(cursor) => {
+                write!(f, "数据提前结束: 读到 {} 的时候没有数据了", cursor)
+            }
+            NbtError::IoError(kind, msg) => write!(f, "IO 错误: {:?} {}", kind, msg),
+            NbtError::StructureError(msg) => write!(f, "结构文件错误: {}", msg),
+            NbtError::NbtPathSyntax(column, msg) => {
+                write!(f, "NBT path 语法错误: {} 于 第 {} 列", msg, column)
+            }
+            NbtError::NbtDepthTooBig(n) => write!(f, "NBT 深度过大, 仅支持 {} 深度", n),
+        }
+    }
+}
+
+impl From<std::io::Error> for NbtError {
+    fn from(e: std::io::Error) -> Self { NbtError::IoError(e.kind(), e.to_string()) }
+}
+
+impl NbtError {
+    /// 在错误的位置前面加上所在的 compound key
+    pub(crate) fn in_compound(self, key: &str) -> Self {
+        match self {
+            NbtError::LenTooBig(path, len, max) => {
+                NbtError::LenTooBig(join_path(path_key(key), path), len, max)
+            }
+            other => other,
+        }
+    }
+
+    /// 在错误的位置前面加上所在的 list 下标
+    pub(crate) fn in_list(self, idx: usize) -> Self {
+        match self {
+            NbtError::LenTooBig(path, len, max) => {
+                NbtError::LenTooBig(join_path(format!("[{}]", idx), path), len, max)
+            }
+            other => other,
         }
     }
 }
 
+/// 只有字母数字和 `_-+` 的 key 不用加引号
+fn path_key(key: &str) -> String {
+    if !key.is_empty() && key.chars().all(|c| c.is_ascii_alphanumeric() || "_-+".contains(c)) {
+        return key.to_string();
+    }
+    format!("\"{}\"", key.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn join_path(parent: String, child: String) -> String {
+    if child.is_empty() || child.starts_with('[') {
+        parent + &child
+    } else {
+        parent + "." + &child
+    }
+}
+
 /// 核心 Value
 ///
 /// 暂时不支持 `from_value` 和 `to_value`
@@ -292,6 +531,16 @@ impl NbtValue {
         R::from_reader(reader)
     }
 
+    /// 从任何 [`std::io::Read`] 里读取 Nbt 数据
+    ///
+    /// 只会读根节点需要的字节, 读文件的时候最好套一层 `BufReader`
+    pub fn from_stream<R>(reader: impl std::io::Read) -> NbtResult<NbtValue>
+    where
+        R: nbt_version::NbtStreamReadTrait,
+    {
+        R::from_stream(&mut NbtStreamReader::new(reader))
+    }
+
     pub fn tag(&self) -> NbtTypeId {
         match self {
             NbtValue::Byte(_) => 1,
@@ -309,14 +558,15 @@ impl NbtValue {
         }
     }
 
-    pub fn write_to<W>(&self, buff: &mut Vec<u8>) -> NbtResult<()>
+    /// 写入到 `buff`, 可以是 `Vec<u8>`, 也可以是任何 [`std::io::Write`]
+    pub fn write_to<W>(&self, buff: &mut impl std::io::Write) -> NbtResult<()>
     where
         W: nbt_version::NbtWriteTrait,
     {
         W::write_to(self, buff)
     }
 
-    pub fn write_to_with_name<W>(&self, name: &str, buff: &mut Vec<u8>) -> NbtResult<()>
+    pub fn write_to_with_name<W>(&self, name: &str, buff: &mut impl std::io::Write) -> NbtResult<()>
     where
         W: nbt_version::NbtWriteTrait,
     {
@@ -372,6 +622,7 @@ impl NbtValue {
             _ => Err(NbtError::IncorrectType(6_u8, self.tag())),
         }
     }
+    /// 会复制一份, 只是读的话用 [`NbtValue::as_i8_array_ref`]
     #[inline]
     pub fn as_i8_array(&self) -> NbtResult<Vec<i8>> {
         match self {
@@ -379,6 +630,7 @@ impl NbtValue {
             _ => Err(NbtError::IncorrectType(7_u8, self.tag())),
         }
     }
+    /// 会复制一份, 只是读的话用 [`NbtValue::as_i32_array_ref`]
     #[inline]
     pub fn as_i32_array(&self) -> NbtResult<Vec<i32>> {
         match self {
@@ -386,6 +638,7 @@ impl NbtValue {
             _ => Err(NbtError::IncorrectType(11_u8, self.tag())),
         }
     }
+    /// 会复制一份, 只是读的话用 [`NbtValue::as_i64_array_ref`]
     #[inline]
     pub fn as_i64_array(&self) -> NbtResult<Vec<i64>> {
         match self {
@@ -393,6 +646,7 @@ impl NbtValue {
             _ => Err(NbtError::IncorrectType(12_u8, self.tag())),
         }
     }
+    /// 会复制一份, 只是读的话用 [`NbtValue::as_string_ref`]
     #[inline]
     pub fn as_string(&self) -> NbtResult<String> {
         match self {
@@ -400,6 +654,7 @@ impl NbtValue {
             _ => Err(NbtError::IncorrectType(8_u8, self.tag())),
         }
     }
+    /// 会复制一份, 只是读的话用 [`NbtValue::as_list_ref`]
     #[inline]
     pub fn as_list(&self) -> NbtResult<Vec<NbtValue>> {
         match self {
@@ -407,13 +662,192 @@ impl NbtValue {
             _ => Err(NbtError::IncorrectType(9_u8, self.tag())),
         }
     }
+    /// 会复制一份, 只是读的话用 [`NbtValue::as_compound_ref`]
     #[inline]
+    #[allow(clippy::type_complexity)]
     pub fn as_compound(&self) -> NbtResult<(Option<&String>, Vec<(String, NbtValue)>)> {
         match self {
             NbtValue::Compound(name, v) => Ok((name.as_ref(), v.clone())),
             _ => Err(NbtError::IncorrectType(10_u8, self.tag())),
         }
     }
+    /// 可变借用数字, 类型不对的时候返回 [`NbtError::IncorrectType`]
+    #[inline]
+    pub fn as_i8_mut(&mut self) -> NbtResult<&mut i8> {
+        match self {
+            NbtValue::Byte(v) => Ok(v),
+            other => Err(NbtError::IncorrectType(1_u8, other.tag())),
+        }
+    }
+    #[inline]
+    pub fn as_i16_mut(&mut self) -> NbtResult<&mut i16> {
+        match self {
+            NbtValue::Short(v) => Ok(v),
+            other => Err(NbtError::IncorrectType(2_u8, other.tag())),
+        }
+    }
+    #[inline]
+    pub fn as_i32_mut(&mut self) -> NbtResult<&mut i32> {
+        match self {
+            NbtValue::Int(v) => Ok(v),
+            other => Err(NbtError::IncorrectType(3_u8, other.tag())),
+        }
+    }
+    #[inline]
+    pub fn as_i64_mut(&mut self) -> NbtResult<&mut i64> {
+        match self {
+            NbtValue::Long(v) => Ok(v),
+            other => Err(NbtError::IncorrectType(4_u8, other.tag())),
+        }
+    }
+    #[inline]
+    pub fn as_f32_mut(&mut self) -> NbtResult<&mut f32> {
+        match self {
+            NbtValue::Float(v) => Ok(v),
+            other => Err(NbtError::IncorrectType(5_u8, other.tag())),
+        }
+    }
+    #[inline]
+    pub fn as_f64_mut(&mut self) -> NbtResult<&mut f64> {
+        match self {
+            NbtValue::Double(v) => Ok(v),
+            other => Err(NbtError::IncorrectType(6_u8, other.tag())),
+        }
+    }
+    /// 借用里面的值, 不会复制
+    #[inline]
+    pub fn as_i8_array_ref(&self) -> NbtResult<&[i8]> {
+        match self {
+            NbtValue::ByteArray(v) => Ok(v),
+            other => Err(NbtError::IncorrectType(7_u8, other.tag())),
+        }
+    }
+    /// 可变借用里面的值
+    #[inline]
+    pub fn as_i8_array_mut(&mut self) -> NbtResult<&mut Vec<i8>> {
+        match self {
+            NbtValue::ByteArray(v) => Ok(v),
+            other => Err(NbtError::IncorrectType(7_u8, other.tag())),
+        }
+    }
+    /// 拿走里面的值, 不会复制
+    #[inline]
+    pub fn into_i8_array(self) -> NbtResult<Vec<i8>> {
+        match self {
+            NbtValue::ByteArray(v) => Ok(v),
+            other => Err(NbtError::IncorrectType(7_u8, other.tag())),
+        }
+    }
+    #[inline]
+    pub fn as_i32_array_ref(&self) -> NbtResult<&[i32]> {
+        match self {
+            NbtValue::IntArray(v) => Ok(v),
+            other => Err(NbtError::IncorrectType(11_u8, other.tag())),
+        }
+    }
+    #[inline]
+    pub fn as_i32_array_mut(&mut self) -> NbtResult<&mut Vec<i32>> {
+        match self {
+            NbtValue::IntArray(v) => Ok(v),
+            other => Err(NbtError::IncorrectType(11_u8, other.tag())),
+        }
+    }
+    #[inline]
+    pub fn into_i32_array(self) -> NbtResult<Vec<i32>> {
+        match self {
+            NbtValue::IntArray(v) => Ok(v),
+            other => Err(NbtError::IncorrectType(11_u8, other.tag())),
+        }
+    }
+    #[inline]
+    pub fn as_i64_array_ref(&self) -> NbtResult<&[i64]> {
+        match self {
+            NbtValue::LongArray(v) => Ok(v),
+            other => Err(NbtError::IncorrectType(12_u8, other.tag())),
+        }
+    }
+    #[inline]
+    pub fn as_i64_array_mut(&mut self) -> NbtResult<&mut Vec<i64>> {
+        match self {
+            NbtValue::LongArray(v) => Ok(v),
+            other => Err(NbtError::IncorrectType(12_u8, other.tag())),
+        }
+    }
+    #[inline]
+    pub fn into_i64_array(self) -> NbtResult<Vec<i64>> {
+        match self {
+            NbtValue::LongArray(v) => Ok(v),
+            other => Err(NbtError::IncorrectType(12_u8, other.tag())),
+        }
+    }
+    #[inline]
+    pub fn as_string_ref(&self) -> NbtResult<&str> {
+        match self {
+            NbtValue::String(v) => Ok(v),
+            other => Err(NbtError::IncorrectType(8_u8, other.tag())),
+        }
+    }
+    #[inline]
+    pub fn as_string_mut(&mut self) -> NbtResult<&mut String> {
+        match self {
+            NbtValue::String(v) => Ok(v),
+            other => Err(NbtError::IncorrectType(8_u8, other.tag())),
+        }
+    }
+    #[inline]
+    pub fn into_string(self) -> NbtResult<String> {
+        match self {
+            NbtValue::String(v) => Ok(v),
+            other => Err(NbtError::IncorrectType(8_u8, other.tag())),
+        }
+    }
+    #[inline]
+    pub fn as_list_ref(&self) -> NbtResult<&[NbtValue]> {
+        match self {
+            NbtValue::List(v) => Ok(v),
+            other => Err(NbtError::IncorrectType(9_u8, other.tag())),
+        }
+    }
+    #[inline]
+    pub fn as_list_mut(&mut self) -> NbtResult<&mut Vec<NbtValue>> {
+        match self {
+            NbtValue::List(v) => Ok(v),
+            other => Err(NbtError::IncorrectType(9_u8, other.tag())),
+        }
+    }
+    #[inline]
+    pub fn into_list(self) -> NbtResult<Vec<NbtValue>> {
+        match self {
+            NbtValue::List(v) => Ok(v),
+            other => Err(NbtError::IncorrectType(9_u8, other.tag())),
+        }
+    }
+    #[inline]
+    #[allow(clippy::type_complexity)]
+    pub fn as_compound_ref(&self) -> NbtResult<(Option<&String>, &[(String, NbtValue)])> {
+        match self {
+            NbtValue::Compound(name, v) => Ok((name.as_ref(), v)),
+            other => Err(NbtError::IncorrectType(10_u8, other.tag())),
+        }
+    }
+    #[inline]
+    #[allow(clippy::type_complexity)]
+    pub fn as_compound_mut(
+        &mut self,
+    ) -> NbtResult<(&mut Option<String>, &mut Vec<(String, NbtValue)>)> {
+        match self {
+            NbtValue::Compound(name, v) => Ok((name, v)),
+            other => Err(NbtError::IncorrectType(10_u8, other.tag())),
+        }
+    }
+    #[inline]
+    #[allow(clippy::type_complexity)]
+    pub fn into_compound(self) -> NbtResult<(Option<String>, Vec<(String, NbtValue)>)> {
+        match self {
+            NbtValue::Compound(name, v) => Ok((name, v)),
+            other => Err(NbtError::IncorrectType(10_u8, other.tag())),
+        }
+    }
 
     #[inline]
     pub fn is_i8(&self) -> bool { matches!(self, NbtValue::Byte(_)) }
@@ -0,0 +1,333 @@
+//! 基岩版的结构文件 (`.mcstructure`)
+//!
+//! 用的是 [`BedrockDisk`] 格式, 格式见 <https://wiki.bedrock.dev/nbt/mcstructure>
+//!
+//! - `structure.block_indices`: 两层方块, 第二层一般是含水方块里的水, `-1` 表示没有方块
+//! - `structure.palette.default.block_palette`: 调色板
+//! - `structure.palette.default.block_position_data`: 方块实体之类的额外数据, key 是方块下标
+//!
+//! 方块按照 XYZ 的顺序存放: `index = (x * size_y + y) * size_z + z`, 跟 Java 版的不一样
+//!
+//! ```rust
+//! use shen_nbt5::mcstructure::{BedrockBlockState, McStructure};
+//! use shen_nbt5::NbtValue;
+//!
+//! let mut structure = McStructure::new([1, 2, 1]);
+//! structure.palette.push(BedrockBlockState::new("minecraft:stone"));
+//! structure.layers[0] = vec![0, -1];
+//!
+//! let mut data = structure.to_binary().unwrap();
+//! let read = McStructure::from_binary(&mut data).unwrap();
+//! assert_eq!(read, structure);
+//! let blocks: Vec<_> = read.blocks().map(|b| (b.pos, b.state.name.as_str())).collect();
+//! assert_eq!(blocks, [([0, 0, 0], "minecraft:stone")]);
+//! ```
+
+use std::collections::BTreeMap;
+
+use crate::nbt_version::{BedrockDisk, NbtWriteTrait};
+use crate::structure::{compound, find, get, int_pos, list};
+use crate::{NbtError, NbtResult, NbtValue};
+
+/// 写入的格式版本
+pub const FORMAT_VERSION: i32 = 1;
+
+/// 基岩版的方块状态
+///
+/// 跟 Java 版不一样, 属性的值是有类型的 (byte/int/string)
+#[derive(Debug, Clone, PartialEq)]
+pub struct BedrockBlockState {
+    /// 方块 id, 比如 `minecraft:stone`
+    pub name: String,
+    /// 方块状态, 按照文件里的顺序
+    pub states: Vec<(String, NbtValue)>,
+    /// 方块状态的版本
+    pub version: i32,
+}
+
+impl BedrockBlockState {
+    /// 没有状态的方块, 版本是 0
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            states: Vec::new(),
+            version: 0,
+        }
+    }
+
+    /// 拿到某个状态的值
+    pub fn state(&self, key: &str) -> Option<&NbtValue> { find(&self.states, key) }
+
+    fn from_value(value: &NbtValue) -> NbtResult<Self> {
+        let data = compound(value, "block_palette")?;
+        let states = match find(data, "states") {
+            Some(states) => compound(states, "states")?.clone(),
+            None => Vec::new(),
+        };
+        let version = match find(data, "version") {
+            Some(version) => version.as_i32()?,
+            None => 0,
+        };
+        Ok(Self {
+            name: get(data, "name")?.as_string()?,
+            states,
+            version,
+        })
+    }
+
+    fn to_value(&self) -> NbtValue {
+        NbtValue::Compound(
+            None,
+            vec![
+                ("name".to_string(), NbtValue::String(self.name.clone())),
+                ("states".to_string(), NbtValue::Compound(None, self.states.clone())),
+                ("version".to_string(), NbtValue::Int(self.version)),
+            ],
+        )
+    }
+}
+
+/// 放在结构里的方块, 已经从调色板里找到了方块状态
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PlacedBedrockBlock<'a> {
+    /// 在结构里的坐标
+    pub pos: [i32; 3],
+    /// 第一层的方块状态
+    pub state: &'a BedrockBlockState,
+    /// 第二层的方块状态 (比如含水方块里的水)
+    pub extra: Option<&'a BedrockBlockState>,
+    /// `block_position_data` 里的数据 (比如 `block_entity_data`)
+    pub data: Option<&'a NbtValue>,
+}
+
+/// 一个 `.mcstructure` 文件
+#[derive(Debug, Clone, PartialEq)]
+pub struct McStructure {
+    /// 格式版本
+    pub format_version: i32,
+    /// 结构的大小
+    pub size: [i32; 3],
+    /// 保存的时候结构在世界里的坐标
+    pub world_origin: [i32; 3],
+    /// 两层方块在调色板里的下标, `-1` 表示没有方块
+    pub layers: [Vec<i32>; 2],
+    /// `default` 调色板
+    pub palette: Vec<BedrockBlockState>,
+    /// 每个方块的额外数据, key 是方块下标
+    pub block_position_data: BTreeMap<usize, NbtValue>,
+    /// 所有的实体
+    pub entities: Vec<NbtValue>,
+}
+
+impl McStructure {
+    /// 一个空的结构, 所有位置都没有方块
+    pub fn new(size: [i32; 3]) -> Self {
+        let len = size.iter().map(|x| (*x).max(0) as usize).product();
+        Self {
+            format_version: FORMAT_VERSION,
+            size,
+            world_origin: [0; 3],
+            layers: [vec![-1; len], vec![-1; len]],
+            palette: Vec::new(),
+            block_position_data: BTreeMap::new(),
+            entities: Vec::new(),
+        }
+    }
+
+    /// 从 [`BedrockDisk`] 格式的二进制读取
+    pub fn from_binary(data: &mut [u8]) -> NbtResult<Self> {
+        Self::from_value(&NbtValue::from_binary::<BedrockDisk>(data)?)
+    }
+
+    /// 写成 [`BedrockDisk`] 格式的二进制
+    pub fn to_binary(&self) -> NbtResult<Vec<u8>> { BedrockDisk::to_bytes(&self.to_value()?) }
+
+    /// 从读出来的 NBT 转换
+    ///
+    /// 方块数量跟大小对不上, 或者下标超出调色板的时候返回 [`NbtError::StructureError`]
+    pub fn from_value(value: &NbtValue) -> NbtResult<Self> {
+        let root = compound(value, "(根节点)")?;
+        let structure = compound(get(root, "structure")?, "structure")?;
+        let palette = compound(get(structure, "palette")?, "palette")?;
+        let palette = compound(get(palette, "default")?, "default")?;
+
+        let mut layers = [Vec::new(), Vec::new()];
+        for (idx, layer) in list(get(structure, "block_indices")?, "block_indices")?
+            .iter()
+            .take(2)
+            .enumerate()
+        {
+            layers[idx] = list(layer, "block_indices")?
+                .iter()
+                .map(NbtValue::as_i32)
+                .collect::<NbtResult<_>>()?;
+        }
+        let mut block_position_data = BTreeMap::new();
+        if let Some(data) = find(palette, "block_position_data") {
+            for (key, value) in compound(data, "block_position_data")? {
+                let idx = key.parse().map_err(|_| {
+                    NbtError::StructureError(format!(
+                        "block_position_data 的 key 不是下标: {}",
+                        key
+                    ))
+                })?;
+                block_position_data.insert(idx, value.clone());
+            }
+        }
+        let entities = match find(structure, "entities") {
+            Some(entities) => list(entities, "entities")?.clone(),
+            None => Vec::new(),
+        };
+        let world_origin = match find(root, "structure_world_origin") {
+            Some(origin) => int_pos(origin)?,
+            None => [0; 3],
+        };
+
+        let result = Self {
+            format_version: get(root, "format_version")?.as_i32()?,
+            size: int_pos(get(root, "size")?)?,
+            world_origin,
+            layers,
+            palette: list(get(palette, "block_palette")?, "block_palette")?
+                .iter()
+                .map(BedrockBlockState::from_value)
+                .collect::<NbtResult<_>>()?,
+            block_position_data,
+            entities,
+        };
+        result.validate()?;
+        Ok(result)
+    }
+
+    /// 转换成可以直接写入的 NBT (根节点名字是空的)
+    pub fn to_value(&self) -> NbtResult<NbtValue> {
+        self.validate()?;
+        let int_list =
+            |data: &[i32]| NbtValue::List(data.iter().map(|x| NbtValue::Int(*x)).collect());
+        let block_position_data = self
+            .block_position_data
+            .iter()
+            .map(|(idx, value)| (idx.to_string(), value.clone()))
+            .collect();
+        let palette = NbtValue::Compound(
+            None,
+            vec![(
+                "default".to_string(),
+                NbtValue::Compound(
+                    None,
+                    vec![
+                        (
+                            "block_palette".to_string(),
+                            NbtValue::List(
+                                self.palette.iter().map(BedrockBlockState::to_value).collect(),
+                            ),
+                        ),
+                        (
+                            "block_position_data".to_string(),
+                            NbtValue::Compound(None, block_position_data),
+                        ),
+                    ],
+                ),
+            )],
+        );
+        let structure = NbtValue::Compound(
+            None,
+            vec![
+                (
+                    "block_indices".to_string(),
+                    NbtValue::List(vec![int_list(&self.layers[0]), int_list(&self.layers[1])]),
+                ),
+                ("entities".to_string(), NbtValue::List(self.entities.clone())),
+                ("palette".to_string(), palette),
+            ],
+        );
+        Ok(NbtValue::Compound(
+            Some(String::new()),
+            vec![
+                ("format_version".to_string(), NbtValue::Int(self.format_version)),
+                ("size".to_string(), int_list(&self.size)),
+                ("structure".to_string(), structure),
+                ("structure_world_origin".to_string(), int_list(&self.world_origin)),
+            ],
+        ))
+    }
+
+    /// 方块的数量, 溢出的时候返回 [`NbtError::StructureError`]
+    pub fn volume(&self) -> NbtResult<usize> {
+        self.size
+            .iter()
+            .try_fold(1_usize, |acc, x| acc.checked_mul((*x).max(0) as usize))
+            .ok_or_else(|| NbtError::StructureError(format!("大小 {:?} 太大了", self.size)))
+    }
+
+    /// 坐标对应的下标, 超出范围的时候是 `None`
+    pub fn index(&self, pos: [i32; 3]) -> Option<usize> {
+        if (0..3).any(|i| pos[i] < 0 || pos[i] >= self.size[i]) {
+            return None;
+        }
+        let [x, y, z] = pos.map(|x| x as usize);
+        Some((x * self.size[1] as usize + y) * self.size[2] as usize + z)
+    }
+
+    /// 下标对应的坐标
+    pub fn pos(&self, index: usize) -> [i32; 3] {
+        let (size_y, size_z) = (self.size[1] as usize, self.size[2] as usize);
+        [
+            (index / size_z / size_y) as i32,
+            (index / size_z % size_y) as i32,
+            (index % size_z) as i32,
+        ]
+    }
+
+    /// 拿到某个位置第一层的方块状态, 没有方块的时候是 `None`
+    pub fn get(&self, pos: [i32; 3]) -> Option<&BedrockBlockState> {
+        self.state(0, self.index(pos)?)
+    }
+
+    /// 按照文件里的顺序 (X, Y, Z) 遍历所有第一层有方块的位置
+    pub fn blocks(&self) -> impl Iterator<Item = PlacedBedrockBlock<'_>> {
+        (0..self.layers[0].len()).filter_map(|idx| {
+            Some(PlacedBedrockBlock {
+                pos: self.pos(idx),
+                state: self.state(0, idx)?,
+                extra: self.state(1, idx),
+                data: self.block_position_data.get(&idx),
+            })
+        })
+    }
+
+    fn state(&self, layer: usize, idx: usize) -> Option<&BedrockBlockState> {
+        let state = *self.layers[layer].get(idx)?;
+        self.palette.get(usize::try_from(state).ok()?)
+    }
+
+    fn validate(&self) -> NbtResult<()> {
+        let len = self.volume()?;
+        for layer in &self.layers {
+            if layer.len() != len {
+                return Err(NbtError::StructureError(format!(
+                    "方块数量 {} 跟大小 {:?} 对不上",
+                    layer.len(),
+                    self.size
+                )));
+            }
+            if let Some(idx) =
+                layer.iter().position(|x| *x < -1 || *x as i64 >= self.palette.len() as i64)
+            {
+                return Err(NbtError::StructureError(format!(
+                    "{:?} 的调色板下标 {} 超出了调色板的长度 {}",
+                    self.pos(idx),
+                    layer[idx],
+                    self.palette.len()
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl TryFrom<&NbtValue> for McStructure {
+    type Error = NbtError;
+
+    fn try_from(value: &NbtValue) -> NbtResult<Self> { McStructure::from_value(value) }
+}
@@ -0,0 +1,866 @@
+//! NBT path, 语法跟原版 `/data` 命令里的一样
+//!
+//! - `a.b`: compound 里的 key, 有特殊字符的 key 可以加引号: `"a b".c`
+//! - `a{b:1b}`: key 是 `a`, 而且能匹配上 `{b:1b}` 的值
+//! - `{b:1b}`: 能匹配上的根节点, 只能放在最前面
+//! - `a[0]` `a[-1]`: list 或者数组的下标, 负数从后往前数
+//! - `a[]`: list 或者数组里所有的元素
+//! - `a[{b:1b}]`: list 里所有能匹配上的元素
+//!
+//! 匹配的规则也跟原版一样: compound 只要求 pattern 里的 key 都有, 而且值都能匹配上;
+//! list 要求 pattern 里的每个元素都能在目标里找到能匹配上的, 空的 list 只能匹配空的 list;
+//! 其他的类型必须相等
+//!
+//! 数组 (`[B;` `[I;` `[L;`) 里的元素不是 [`NbtValue`], 所以 [`NbtPath::get`] 返回的是 [`Cow`],
+//! 数组里的元素会复制成 [`NbtValue::Byte`]/[`NbtValue::Int`]/[`NbtValue::Long`];
+//! 跟原版一样, 所有的数字都可以放进数组里, 会转换成数组元素的类型
+//!
+//! 放进 compound 里的 compound 的名字跟旁边已经有的 compound 一样: 旁边的名字是 key (Java 版读出来的)
+//! 就用 key, 旁边的没有名字 (基岩版读出来的) 就去掉名字, 旁边没有 compound 的时候看上一层,
+//! 都没有的时候不改; 放进 list 里的没有名字
+//!
+//! ```rust
+//! use shen_nbt5::path::NbtPath;
+//! use shen_nbt5::NbtValue;
+//!
+//! let mut player = NbtValue::Compound(None, Vec::new());
+//! let name: NbtPath = "Inventory[{Slot:0b}].tag.display.Name".parse().unwrap();
+//! assert_eq!(name.set(&mut player, NbtValue::String("石头".to_string())).unwrap(), 1);
+//! assert_eq!(name.get(&player).as_deref(), Some(&NbtValue::String("石头".to_string())));
+//!
+//! let slot = NbtPath::parse("Inventory[].Slot").unwrap();
+//! assert_eq!(slot.get_all(&player).len(), 1);
+//! assert_eq!(slot.remove(&mut player), 1);
+//! assert!(slot.get(&player).is_none());
+//!
+//! let uuid = NbtPath::parse("UUID").unwrap();
+//! uuid.set(&mut player, NbtValue::IntArray(vec![1, 2, 3, 4])).unwrap();
+//! let last = NbtPath::parse("UUID[-1]").unwrap();
+//! assert_eq!(last.get(&player).as_deref(), Some(&NbtValue::Int(4)));
+//! assert_eq!(last.set(&mut player, NbtValue::Byte(5)).unwrap(), 1);
+//! assert_eq!(uuid.get(&player).as_deref(), Some(&NbtValue::IntArray(vec![1, 2, 3, 5])));
+//! ```
+
+use std::borrow::Cow;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::{NbtError, NbtResult, NbtValue};
+
+/// path 里的一个节点
+#[derive(Debug, Clone, PartialEq)]
+enum PathNode {
+    /// `a`
+    Key(String),
+    /// `a{b:1b}`
+    MatchElement(String, NbtValue),
+    /// `{b:1b}`, 只能是第一个
+    MatchRoot(NbtValue),
+    /// `[0]`
+    Index(i32),
+    /// `[]`
+    AllElements,
+    /// `[{b:1b}]`
+    MatchObject(NbtValue),
+}
+
+/// 放进 compound 里的 compound 的名字
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Naming {
+    /// 不知道, 不改名字
+    Unknown,
+    /// 名字是 key, Java 版读出来的是这样
+    Key,
+    /// 没有名字, 基岩版读出来的是这样
+    Unnamed,
+}
+
+impl Naming {
+    /// `data` 里已经有的 compound 的名字, 没有 compound 的时候跟 `self` 一样
+    fn of(self, data: &[(String, NbtValue)]) -> Self {
+        data.iter()
+            .find_map(|(key, value)| match value {
+                NbtValue::Compound(Some(name), _) if name == key => Some(Naming::Key),
+                NbtValue::Compound(..) => Some(Naming::Unnamed),
+                _ => None,
+            })
+            .unwrap_or(self)
+    }
+
+    /// 按照这个规则给放在 `key` 下面的 `value` 起名字
+    fn apply(self, value: NbtValue, key: &str) -> NbtValue {
+        match self {
+            Naming::Unknown => value,
+            Naming::Key => with_name(value, Some(key)),
+            Naming::Unnamed => with_name(value, None),
+        }
+    }
+}
+
+/// 解析好的 NBT path
+///
+/// 见 [模块文档](self)
+#[derive(Debug, Clone, PartialEq)]
+pub struct NbtPath {
+    /// 原来的字符串
+    source: String,
+    /// 至少有一个
+    nodes: Vec<PathNode>,
+}
+
+impl NbtPath {
+    /// 解析 NBT path
+    ///
+    /// 语法错误的时候返回 [`NbtError::NbtPathSyntax`]
+    pub fn parse(path: &str) -> NbtResult<Self> {
+        let nodes = PathParser {
+            data: path,
+            cursor: 0,
+        }
+        .parse()?;
+        Ok(Self {
+            source: path.to_string(),
+            nodes,
+        })
+    }
+
+    /// 第一个能匹配上的值
+    pub fn get<'a>(&self, root: &'a NbtValue) -> Option<Cow<'a, NbtValue>> {
+        self.get_all(root).into_iter().next()
+    }
+
+    /// 所有能匹配上的值, 数组里的元素是复制出来的
+    pub fn get_all<'a>(&self, root: &'a NbtValue) -> Vec<Cow<'a, NbtValue>> {
+        let mut current = vec![Cow::Borrowed(root)];
+        for node in &self.nodes {
+            let mut next = Vec::new();
+            for value in current {
+                // 数组里的元素是数字, 不会再往下走了
+                if let Cow::Borrowed(value) = value {
+                    node.collect(value, &mut next);
+                }
+            }
+            current = next;
+        }
+        current
+    }
+
+    /// 把所有能匹配上的值换成 `value`, 返回换了几个
+    ///
+    /// 跟原版一样, 中间缺少的 compound/list 会自动创建, `a{b:1b}` 缺少的时候会放进去 `{b:1b}`,
+    /// `a[{b:1b}]` 没有能匹配上的时候会加一个 `{b:1b}` 进去
+    ///
+    /// 换掉 list 里的元素之后类型不一样的时候返回 [`NbtError::ListTypeNotSame`],
+    /// 放进数组里的不是数字的时候返回 [`NbtError::IncorrectType`]
+    pub fn set(&self, root: &mut NbtValue, value: NbtValue) -> NbtResult<usize> {
+        let last = self.nodes.last().unwrap();
+        let mut count = 0;
+        for (parent, naming) in self.parents_mut(root, true) {
+            count += last.set(parent, naming, &value)?;
+        }
+        Ok(count)
+    }
+
+    /// 删掉所有能匹配上的值, 返回删掉了几个
+    ///
+    /// 根节点删不掉
+    pub fn remove(&self, root: &mut NbtValue) -> usize {
+        let last = self.nodes.last().unwrap();
+        self.parents_mut(root, false)
+            .into_iter()
+            .map(|(parent, _)| last.remove(parent))
+            .sum()
+    }
+
+    /// 最后一个节点的所有父节点, 和它们上面的 compound 的名字规则
+    fn parents_mut<'a>(
+        &self,
+        root: &'a mut NbtValue,
+        create: bool,
+    ) -> Vec<(&'a mut NbtValue, Naming)> {
+        let mut current = vec![(root, Naming::Unknown)];
+        for (idx, node) in self.nodes[..self.nodes.len() - 1].iter().enumerate() {
+            let next_node = self.nodes.get(idx + 1).filter(|_| create);
+            let mut next = Vec::new();
+            for (value, naming) in current {
+                node.collect_mut(value, naming, next_node, &mut next);
+            }
+            current = next;
+        }
+        current
+    }
+}
+
+impl FromStr for NbtPath {
+    type Err = NbtError;
+
+    fn from_str(s: &str) -> NbtResult<Self> { Self::parse(s) }
+}
+
+impl fmt::Display for NbtPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { f.write_str(&self.source) }
+}
+
+impl PathNode {
+    fn collect<'a>(&self, value: &'a NbtValue, out: &mut Vec<Cow<'a, NbtValue>>) {
+        match (self, value) {
+            (PathNode::Key(key), NbtValue::Compound(_, data)) => {
+                out.extend(find(data, key).map(Cow::Borrowed))
+            }
+            (PathNode::MatchElement(key, filter), NbtValue::Compound(_, data)) => {
+                out.extend(find(data, key).filter(|v| matches(filter, v)).map(Cow::Borrowed))
+            }
+            (PathNode::MatchRoot(filter), value) if matches(filter, value) => {
+                out.push(Cow::Borrowed(value))
+            }
+            (PathNode::Index(idx), NbtValue::List(list)) => {
+                out.extend(list_index(list.len(), *idx).map(|idx| Cow::Borrowed(&list[idx])))
+            }
+            (PathNode::AllElements, NbtValue::List(list)) => {
+                out.extend(list.iter().map(Cow::Borrowed))
+            }
+            (PathNode::MatchObject(filter), NbtValue::List(list)) => {
+                out.extend(list.iter().filter(|v| matches(filter, v)).map(Cow::Borrowed))
+            }
+            (PathNode::Index(idx), array) => {
+                let idx = array_len(array).and_then(|len| list_index(len, *idx));
+                out.extend(idx.map(|idx| Cow::Owned(array_get(array, idx))))
+            }
+            (PathNode::AllElements, array) => {
+                let len = array_len(array).unwrap_or(0);
+                out.extend((0..len).map(|idx| Cow::Owned(array_get(array, idx))))
+            }
+            _ => {}
+        }
+    }
+
+    /// `create` 是下一个节点, 有的时候会创建缺少的值
+    fn collect_mut<'a>(
+        &self,
+        value: &'a mut NbtValue,
+        naming: Naming,
+        create: Option<&PathNode>,
+        out: &mut Vec<(&'a mut NbtValue, Naming)>,
+    ) {
+        match (self, value) {
+            (PathNode::Key(key), NbtValue::Compound(_, data)) => {
+                let naming = naming.of(data);
+                match data.iter().position(|(k, _)| k == key) {
+                    Some(idx) => out.push((&mut data[idx].1, naming)),
+                    None => {
+                        if let Some(next) = create {
+                            data.push((key.clone(), naming.apply(next.empty_parent(), key)));
+                            out.push((&mut data.last_mut().unwrap().1, naming));
+                        }
+                    }
+                }
+            }
+            (PathNode::MatchElement(key, filter), NbtValue::Compound(_, data)) => {
+                let naming = naming.of(data);
+                match data.iter().position(|(k, _)| k == key) {
+                    Some(idx) => {
+                        if matches(filter, &data[idx].1) {
+                            out.push((&mut data[idx].1, naming))
+                        }
+                    }
+                    None => {
+                        if create.is_some() {
+                            data.push((key.clone(), naming.apply(filter.clone(), key)));
+                            out.push((&mut data.last_mut().unwrap().1, naming));
+                        }
+                    }
+                }
+            }
+            (PathNode::MatchRoot(filter), value) if matches(filter, value) => {
+                out.push((value, naming))
+            }
+            (PathNode::Index(idx), NbtValue::List(list)) => {
+                if let Some(idx) = list_index(list.len(), *idx) {
+                    out.push((&mut list[idx], naming))
+                }
+            }
+            (PathNode::AllElements, NbtValue::List(list)) => {
+                out.extend(list.iter_mut().map(|v| (v, naming)))
+            }
+            (PathNode::MatchObject(filter), NbtValue::List(list)) => {
+                if create.is_some()
+                    && !list.iter().any(|v| matches(filter, v))
+                    && list.first().is_none_or(|v| v.tag() == filter.tag())
+                {
+                    list.push(with_name(filter.clone(), None));
+                }
+                out.extend(list.iter_mut().filter(|v| matches(filter, v)).map(|v| (v, naming)))
+            }
+            _ => {}
+        }
+    }
+
+    /// 自动创建的时候, 这个节点的父节点应该是什么
+    fn empty_parent(&self) -> NbtValue {
+        match self {
+            PathNode::Key(_) | PathNode::MatchElement(..) | PathNode::MatchRoot(_) => {
+                NbtValue::Compound(None, Vec::new())
+            }
+            PathNode::Index(_) | PathNode::AllElements | PathNode::MatchObject(_) => {
+                NbtValue::List(Vec::new())
+            }
+        }
+    }
+
+    fn set(&self, parent: &mut NbtValue, naming: Naming, value: &NbtValue) -> NbtResult<usize> {
+        match (self, parent) {
+            (PathNode::Key(key), NbtValue::Compound(_, data)) => {
+                let value = naming.of(data).apply(value.clone(), key);
+                match data.iter_mut().find(|(k, _)| k == key) {
+                    Some((_, old)) => *old = value,
+                    None => data.push((key.clone(), value)),
+                }
+                Ok(1)
+            }
+            (PathNode::MatchElement(key, filter), NbtValue::Compound(_, data)) => {
+                let naming = naming.of(data);
+                match data.iter_mut().find(|(k, _)| k == key) {
+                    Some((_, old)) if matches(filter, old) => {
+                        *old = naming.apply(value.clone(), key);
+                        Ok(1)
+                    }
+                    _ => Ok(0),
+                }
+            }
+            (PathNode::MatchRoot(filter), parent) => match matches(filter, parent) {
+                true => {
+                    *parent = value.clone();
+                    Ok(1)
+                }
+                false => Ok(0),
+            },
+            (PathNode::Index(idx), NbtValue::List(list)) => {
+                let Some(idx) = list_index(list.len(), *idx) else {
+                    return Ok(0);
+                };
+                check_list(list, value, |i, _| i == idx)?;
+                list[idx] = with_name(value.clone(), None);
+                Ok(1)
+            }
+            (PathNode::AllElements, NbtValue::List(list)) => {
+                let value = with_name(value.clone(), None);
+                if list.is_empty() {
+                    list.push(value);
+                } else {
+                    list.iter_mut().for_each(|v| *v = value.clone());
+                }
+                Ok(list.len())
+            }
+            (PathNode::MatchObject(filter), NbtValue::List(list)) => {
+                check_list(list, value, |_, v| matches(filter, v))?;
+                let value = with_name(value.clone(), None);
+                let mut count = 0;
+                for v in list.iter_mut().filter(|v| matches(filter, v)) {
+                    *v = value.clone();
+                    count += 1;
+                }
+                Ok(count)
+            }
+            (PathNode::Index(idx), array) => {
+                match array_len(array).and_then(|len| list_index(len, *idx)) {
+                    Some(idx) => array_set(array, Some(idx), value),
+                    None => Ok(0),
+                }
+            }
+            (PathNode::AllElements, array) if array_len(array).is_some() => {
+                array_set(array, None, value)
+            }
+            _ => Ok(0),
+        }
+    }
+
+    fn remove(&self, parent: &mut NbtValue) -> usize {
+        let Some(before) = child_count(parent) else {
+            return 0;
+        };
+        match (self, &mut *parent) {
+            (PathNode::Key(key), NbtValue::Compound(_, data)) => data.retain(|(k, _)| k != key),
+            (PathNode::MatchElement(key, filter), NbtValue::Compound(_, data)) => {
+                data.retain(|(k, v)| k != key || !matches(filter, v))
+            }
+            (PathNode::Index(idx), NbtValue::List(list)) => {
+                if let Some(idx) = list_index(list.len(), *idx) {
+                    list.remove(idx);
+                }
+            }
+            (PathNode::AllElements, NbtValue::List(list)) => list.clear(),
+            (PathNode::MatchObject(filter), NbtValue::List(list)) => {
+                list.retain(|v| !matches(filter, v))
+            }
+            (PathNode::Index(idx), array) => {
+                if let Some(idx) = array_len(array).and_then(|len| list_index(len, *idx)) {
+                    array_remove(array, Some(idx));
+                }
+            }
+            (PathNode::AllElements, array) => array_remove(array, None),
+            _ => {}
+        }
+        before - child_count(parent).unwrap_or(before)
+    }
+}
+
+fn find<'a>(data: &'a [(String, NbtValue)], key: &str) -> Option<&'a NbtValue> {
+    data.iter().find(|(k, _)| k == key).map(|(_, v)| v)
+}
+
+/// compound 的名字换成 `name`, 其他的不变
+pub(crate) fn with_name(value: NbtValue, name: Option<&str>) -> NbtValue {
+    match value {
+        NbtValue::Compound(_, data) => NbtValue::Compound(name.map(str::to_string), data),
+        other => other,
+    }
+}
+
+/// compound/list/数组里有几个元素, 其他的类型是 `None`
+fn child_count(value: &NbtValue) -> Option<usize> {
+    match value {
+        NbtValue::Compound(_, data) => Some(data.len()),
+        NbtValue::List(list) => Some(list.len()),
+        other => array_len(other),
+    }
+}
+
+/// 数组 (`[B;` `[I;` `[L;`) 的长度, 不是数组的时候是 `None`
+fn array_len(value: &NbtValue) -> Option<usize> {
+    match value {
+        NbtValue::ByteArray(data) => Some(data.len()),
+        NbtValue::IntArray(data) => Some(data.len()),
+        NbtValue::LongArray(data) => Some(data.len()),
+        _ => None,
+    }
+}
+
+/// 复制出数组里的一个元素, 下标要先检查过
+fn array_get(array: &NbtValue, idx: usize) -> NbtValue {
+    match array {
+        NbtValue::ByteArray(data) => NbtValue::Byte(data[idx]),
+        NbtValue::IntArray(data) => NbtValue::Int(data[idx]),
+        NbtValue::LongArray(data) => NbtValue::Long(data[idx]),
+        _ => unreachable!("先用 array_len 检查过了"),
+    }
+}
+
+/// 换掉数组里下标是 `idx` 的元素, `None` 的时候换掉所有的, 空的数组会放进去一个
+///
+/// 跟原版的 `NumericTag` 一样, 浮点数向下取整, 整数直接截断; 不是数字的时候返回 [`NbtError::IncorrectType`]
+fn array_set(array: &mut NbtValue, idx: Option<usize>, value: &NbtValue) -> NbtResult<usize> {
+    fn fill<T: Copy>(data: &mut Vec<T>, idx: Option<usize>, value: T) -> usize {
+        match idx {
+            Some(idx) => {
+                data[idx] = value;
+                1
+            }
+            None if data.is_empty() => {
+                data.push(value);
+                1
+            }
+            None => {
+                data.fill(value);
+                data.len()
+            }
+        }
+    }
+    let number = match value {
+        NbtValue::Byte(x) => *x as i64,
+        NbtValue::Short(x) => *x as i64,
+        NbtValue::Int(x) => *x as i64,
+        NbtValue::Long(x) => *x,
+        NbtValue::Float(x) => x.floor() as i64,
+        NbtValue::Double(x) => x.floor() as i64,
+        other => {
+            let expect = match array {
+                NbtValue::ByteArray(_) => 1,
+                NbtValue::IntArray(_) => 3,
+                _ => 4,
+            };
+            return Err(NbtError::IncorrectType(expect, other.tag()));
+        }
+    };
+    Ok(match array {
+        NbtValue::ByteArray(data) => fill(data, idx, number as i8),
+        NbtValue::IntArray(data) => fill(data, idx, number as i32),
+        NbtValue::LongArray(data) => fill(data, idx, number),
+        _ => 0,
+    })
+}
+
+/// 删掉数组里下标是 `idx` 的元素, `None` 的时候全删掉
+fn array_remove(array: &mut NbtValue, idx: Option<usize>) {
+    fn remove<T>(data: &mut Vec<T>, idx: Option<usize>) {
+        match idx {
+            Some(idx) => {
+                data.remove(idx);
+            }
+            None => data.clear(),
+        }
+    }
+    match array {
+        NbtValue::ByteArray(data) => remove(data, idx),
+        NbtValue::IntArray(data) => remove(data, idx),
+        NbtValue::LongArray(data) => remove(data, idx),
+        _ => {}
+    }
+}
+
+/// 负数从后往前数, 超出范围的时候是 `None`
+fn list_index(len: usize, idx: i32) -> Option<usize> {
+    let idx = match idx < 0 {
+        true => len as i64 + idx as i64,
+        false => idx as i64,
+    };
+    (0..len as i64).contains(&idx).then_some(idx as usize)
+}
+
+/// 没被换掉的元素跟 `value` 的类型不一样的时候返回错误
+fn check_list(
+    list: &[NbtValue],
+    value: &NbtValue,
+    replaced: impl Fn(usize, &NbtValue) -> bool,
+) -> NbtResult<()> {
+    match list
+        .iter()
+        .enumerate()
+        .find(|(idx, v)| !replaced(*idx, v) && v.tag() != value.tag())
+    {
+        Some((_, other)) => Err(NbtError::ListTypeNotSame(vec![other.tag(), value.tag()])),
+        None => Ok(()),
+    }
+}
+
+/// `target` 能不能匹配上 `pattern`
+///
+/// 跟原版的 `NbtUtils.compareNbt(pattern, target, true)` 一样, 不会比较 compound 的名字
+pub fn matches(pattern: &NbtValue, target: &NbtValue) -> bool {
+    match (pattern, target) {
+        (NbtValue::Compound(_, pattern), NbtValue::Compound(_, target)) => {
+            pattern.iter().all(|(key, p)| find(target, key).is_some_and(|t| matches(p, t)))
+        }
+        (NbtValue::List(pattern), NbtValue::List(target)) => match pattern.is_empty() {
+            true => target.is_empty(),
+            false => pattern.iter().all(|p| target.iter().any(|t| matches(p, t))),
+        },
+        _ => pattern == target,
+    }
+}
+
+/// 不需要引号的 key 里可以有的字符
+fn is_key_char(c: char) -> bool { !matches!(c, ' ' | '"' | '\'' | '[' | ']' | '.' | '{' | '}') }
+
+/// SNBT 里不需要引号的字符
+fn is_unquoted_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.' | '+')
+}
+
+/// 是不是 `[-+]?(0|[1-9][0-9]*)`
+fn is_integer(s: &str) -> bool {
+    let digits = s.strip_prefix(['-', '+']).unwrap_or(s);
+    match digits.as_bytes() {
+        [] => false,
+        [b'0'] => true,
+        [b'0', ..] => false,
+        digits => digits.iter().all(u8::is_ascii_digit),
+    }
+}
+
+/// 是不是 `[-+]?([0-9]+[.]?|[0-9]*[.][0-9]+)(e[-+]?[0-9]+)?`
+///
+/// `need_dot` 为 true 时必须带一个 `.` (没有后缀的 double)
+fn is_float(s: &str, need_dot: bool) -> bool {
+    let s = s.strip_prefix(['-', '+']).unwrap_or(s);
+    let (body, exp) = match s.find(['e', 'E']) {
+        Some(idx) => (&s[..idx], Some(&s[idx + 1..])),
+        None => (s, None),
+    };
+    if let Some(exp) = exp {
+        let exp = exp.strip_prefix(['-', '+']).unwrap_or(exp);
+        if exp.is_empty() || !exp.bytes().all(|c| c.is_ascii_digit()) {
+            return false;
+        }
+    }
+    let (int_part, frac_part) = match body.split_once('.') {
+        Some((int_part, frac_part)) => (int_part, Some(frac_part)),
+        None => (body, None),
+    };
+    if !int_part.bytes().all(|c| c.is_ascii_digit()) {
+        return false;
+    }
+    match frac_part {
+        Some(frac_part) => {
+            frac_part.bytes().all(|c| c.is_ascii_digit())
+                && !(int_part.is_empty() && frac_part.is_empty())
+        }
+        None => !need_dot && !int_part.is_empty(),
+    }
+}
+
+/// 推断一个没有引号的值的类型, 数字超出范围的时候会当成字符串
+fn parse_unquoted(token: &str) -> NbtValue {
+    if token.eq_ignore_ascii_case("true") {
+        return NbtValue::Byte(1);
+    }
+    if token.eq_ignore_ascii_case("false") {
+        return NbtValue::Byte(0);
+    }
+    let (body, suffix) = token.split_at(token.len() - 1);
+    let value = match suffix {
+        "b" | "B" if is_integer(body) => body.parse().ok().map(NbtValue::Byte),
+        "s" | "S" if is_integer(body) => body.parse().ok().map(NbtValue::Short),
+        "l" | "L" if is_integer(body) => body.parse().ok().map(NbtValue::Long),
+        "f" | "F" if is_float(body, false) => body.parse().ok().map(NbtValue::Float),
+        "d" | "D" if is_float(body, false) => body.parse().ok().map(NbtValue::Double),
+        _ if is_integer(token) => token.parse().ok().map(NbtValue::Int),
+        _ if is_float(token, true) => token.parse().ok().map(NbtValue::Double),
+        _ => None,
+    };
+    value.unwrap_or_else(|| NbtValue::String(token.to_string()))
+}
+
+struct PathParser<'data> {
+    data: &'data str,
+    /// 字节
+    cursor: usize,
+}
+
+impl<'data> PathParser<'data> {
+    fn parse(mut self) -> NbtResult<Vec<PathNode>> {
+        let mut nodes = Vec::new();
+        loop {
+            nodes.push(self.read_node(nodes.is_empty())?);
+            match self.peek() {
+                None => return Ok(nodes),
+                Some('[' | '{') => {}
+                Some('.') => self.cursor += 1,
+                Some(_) => return Err(self.error_at(self.cursor, "期望 '.' '[' 或者 '{'")),
+            }
+        }
+    }
+
+    fn read_node(&mut self, root: bool) -> NbtResult<PathNode> {
+        match self.peek() {
+            Some('{') if root => Ok(PathNode::MatchRoot(self.read_value(None)?)),
+            Some('{') => Err(self.error_at(self.cursor, "只有第一个节点可以是 '{...}'")),
+            Some('[') => {
+                self.cursor += 1;
+                let node = match self.peek() {
+                    Some(']') => PathNode::AllElements,
+                    Some('{') => PathNode::MatchObject(self.read_value(None)?),
+                    _ => {
+                        let start = self.cursor;
+                        let len = self.data[start..]
+                            .find(|c: char| c != '-' && !c.is_ascii_digit())
+                            .unwrap_or(self.data.len() - start);
+                        self.cursor += len;
+                        let idx = self.data[start..self.cursor]
+                            .parse()
+                            .map_err(|_| self.error_at(start, "期望一个下标"))?;
+                        PathNode::Index(idx)
+                    }
+                };
+                match self.peek() {
+                    Some(']') => {
+                        self.cursor += 1;
+                        Ok(node)
+                    }
+                    _ => Err(self.error_at(self.cursor, "期望 ']'")),
+                }
+            }
+            _ => {
+                let key = match self.peek() {
+                    Some('"' | '\'') => self.read_quoted()?,
+                    _ => self.read_while(is_key_char, "期望一个 key")?.to_string(),
+                };
+                match self.peek() {
+                    Some('{') => {
+                        let filter = self.read_value(Some(&key))?;
+                        Ok(PathNode::MatchElement(key, filter))
+                    }
+                    _ => Ok(PathNode::Key(key)),
+                }
+            }
+        }
+    }
+
+    /// 读一个 SNBT 的值, compound 的名字是 `name`
+    fn read_value(&mut self, name: Option<&str>) -> NbtResult<NbtValue> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some('{') => {
+                self.cursor += 1;
+                let mut data = Vec::new();
+                self.skip_whitespace();
+                if self.peek() == Some('}') {
+                    self.cursor += 1;
+                } else {
+                    loop {
+                        self.skip_whitespace();
+                        let key = match self.peek() {
+                            Some('"' | '\'') => self.read_quoted()?,
+                            _ => self.read_while(is_unquoted_char, "期望一个 key")?.to_string(),
+                        };
+                        self.expect(':')?;
+                        let value = self.read_value(Some(&key))?;
+                        data.push((key, value));
+                        if self.next_or_close('}')? {
+                            break;
+                        }
+                    }
+                }
+                Ok(NbtValue::Compound(name.map(str::to_string), data))
+            }
+            Some('[') => {
+                let array_type = match self.data[self.cursor + 1..].get(..2) {
+                    Some(prefix @ ("B;" | "I;" | "L;")) => Some(prefix.as_bytes()[0]),
+                    _ => None,
+                };
+                match array_type {
+                    Some(array_type) => {
+                        self.cursor += 3;
+                        self.read_array(array_type)
+                    }
+                    None => {
+                        self.cursor += 1;
+                        let mut list: Vec<NbtValue> = Vec::new();
+                        self.skip_whitespace();
+                        if self.peek() == Some(']') {
+                            self.cursor += 1;
+                            return Ok(NbtValue::List(list));
+                        }
+                        loop {
+                            self.skip_whitespace();
+                            let start = self.cursor;
+                            let value = self.read_value(None)?;
+                            if list.first().is_some_and(|first| first.tag() != value.tag()) {
+                                return Err(self.error_at(start, "List 里的类型不同"));
+                            }
+                            list.push(value);
+                            if self.next_or_close(']')? {
+                                return Ok(NbtValue::List(list));
+                            }
+                        }
+                    }
+                }
+            }
+            Some('"' | '\'') => Ok(NbtValue::String(self.read_quoted()?)),
+            _ => Ok(parse_unquoted(self.read_while(is_unquoted_char, "期望一个值")?)),
+        }
+    }
+
+    /// 读取一个 `[B;` `[I;` `[L;` 数组, 当前 cursor 在 `;` 后面
+    fn read_array(&mut self, array_type: u8) -> NbtResult<NbtValue> {
+        let (mut bytes, mut ints, mut longs) = (Vec::new(), Vec::new(), Vec::new());
+        self.skip_whitespace();
+        if self.peek() == Some(']') {
+            self.cursor += 1;
+        } else {
+            loop {
+                self.skip_whitespace();
+                let start = self.cursor;
+                let token = self.read_while(is_unquoted_char, "期望一个值")?;
+                let value = match (array_type, parse_unquoted(token)) {
+                    (b'B', NbtValue::Int(x)) => i8::try_from(x).ok().map(NbtValue::Byte),
+                    (b'L', NbtValue::Int(x)) => Some(NbtValue::Long(x as i64)),
+                    (_, value) => Some(value),
+                };
+                match (array_type, value) {
+                    (b'B', Some(NbtValue::Byte(x))) => bytes.push(x),
+                    (b'I', Some(NbtValue::Int(x))) => ints.push(x),
+                    (b'L', Some(NbtValue::Long(x))) => longs.push(x),
+                    _ => {
+                        return Err(self.error_at(
+                            start,
+                            format!("[{};] 里的类型不对: {:?}", array_type as char, token),
+                        ))
+                    }
+                }
+                if self.next_or_close(']')? {
+                    break;
+                }
+            }
+        }
+        Ok(match array_type {
+            b'B' => NbtValue::ByteArray(bytes),
+            b'I' => NbtValue::IntArray(ints),
+            _ => NbtValue::LongArray(longs),
+        })
+    }
+
+    /// 读取一个带引号的字符串 (`"` 或者 `'`), 只支持 `\\` `\"` `\'` 转义
+    fn read_quoted(&mut self) -> NbtResult<String> {
+        let start = self.cursor;
+        let quote = self.peek().unwrap();
+        self.cursor += 1;
+        let mut result = String::new();
+        let mut chars = self.data[self.cursor..].char_indices();
+        while let Some((idx, c)) = chars.next() {
+            match c {
+                '\\' => match chars.next() {
+                    Some((_, c @ ('\\' | '"' | '\''))) => result.push(c),
+                    _ => return Err(self.error_at(self.cursor + idx, "无效的转义")),
+                },
+                c if c == quote => {
+                    self.cursor += idx + c.len_utf8();
+                    return Ok(result);
+                }
+                c => result.push(c),
+            }
+        }
+        Err(self.error_at(start, "字符串没有结束"))
+    }
+
+    /// 读取一段都满足 `allowed` 的字符, 是空的时候返回 `msg` 的错误
+    fn read_while(&mut self, allowed: fn(char) -> bool, msg: &str) -> NbtResult<&'data str> {
+        let start = self.cursor;
+        let len = self.data[start..].find(|c| !allowed(c)).unwrap_or(self.data.len() - start);
+        if len == 0 {
+            return Err(self.error_at(start, msg));
+        }
+        self.cursor += len;
+        Ok(&self.data[start..self.cursor])
+    }
+
+    /// 读完一个元素之后, 是 `,` 的时候返回 false, 是 `close` 的时候返回 true
+    fn next_or_close(&mut self, close: char) -> NbtResult<bool> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some(',') => {
+                self.cursor += 1;
+                Ok(false)
+            }
+            Some(c) if c == close => {
+                self.cursor += 1;
+                Ok(true)
+            }
+            _ => Err(self.error_at(self.cursor, format!("期望 ',' 或 '{}'", close))),
+        }
+    }
+
+    /// 跳过空白之后, 期望一个 `expect` 字符
+    fn expect(&mut self, expect: char) -> NbtResult<()> {
+        self.skip_whitespace();
+        match self.peek() == Some(expect) {
+            true => {
+                self.cursor += 1;
+                Ok(())
+            }
+            false => Err(self.error_at(self.cursor, format!("期望 '{}'", expect))),
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(|c| c.is_ascii_whitespace()) {
+            self.cursor += 1;
+        }
+    }
+
+    #[inline]
+    fn peek(&self) -> Option<char> { self.data[self.cursor..].chars().next() }
+
+    fn error_at(&self, pos: usize, msg: impl Into<String>) -> NbtError {
+        NbtError::NbtPathSyntax(self.data[..pos].chars().count() + 1, msg.into())
+    }
+}
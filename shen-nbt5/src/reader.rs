@@ -1,6 +1,8 @@
 use crate::nbt_version::{BedrockDisk, BedrockNetVarInt, Java, JavaNetAfter1_20_2, NbtReadTrait};
 use crate::{nbt_version, NbtError, NbtResult, NbtValue};
 
+use simd_cesu8::mutf8;
+
 /// 用于读取 NBT 数据
 pub struct NbtReader<'data> {
     /// NBT 数据
@@ -18,7 +20,7 @@ impl nbt_version::NbtReadTrait for nbt_version::Java {
     #[inline]
     fn read_nbt_string(reader: &mut NbtReader) -> NbtResult<String> {
         let len = reader.read_be_u16() as usize;
-        reader.read_string(len)
+        reader.read_mutf8_string(len)
     }
     #[inline]
     fn read_i8_array(reader: &mut NbtReader) -> NbtResult<Vec<i8>> {
@@ -341,7 +343,7 @@ macro_rules! read_uncheck {
         ///
         /// 转换大小端(大端)
         ///
-        /// # 安全性
+        /// # Safety
         /// 允许未对齐的地址
         /// 长度溢出会导致 UB
         #[inline]
@@ -357,7 +359,7 @@ macro_rules! read_uncheck {
         ///
         /// 转换大小端(小端)
         ///
-        /// # 安全性
+        /// # Safety
         /// 允许未对齐的地址
         /// 长度溢出会导致 UB
         #[inline]
@@ -372,7 +374,7 @@ macro_rules! read_uncheck {
 }
 
 impl NbtReader<'_> {
-    pub fn new(data: &mut [u8]) -> NbtReader { NbtReader { data, cursor: 0 } }
+    pub fn new(data: &mut [u8]) -> NbtReader<'_> { NbtReader { data, cursor: 0 } }
     /// 向后滚动
     #[inline]
     pub fn roll_back(&mut self, len: usize) { self.cursor -= len; }
@@ -653,47 +655,47 @@ impl NbtReader<'_> {
     pub fn read_le_f64(&mut self) -> f64 { f64::from_bits(self.read_le_u64()) }
     /// 读取一个大端 f32 数据
     ///
-    /// # 安全性
+    /// # Safety
     /// 允许未对齐的地址
     /// 长度溢出会导致 UB
     #[inline]
     pub unsafe fn read_be_f32_unsafe(&mut self) -> f32 {
         let value = self.read_be_u32_unsafe();
-        std::mem::transmute::<u32, f32>(value)
+        f32::from_bits(value)
     }
     /// 读取一个小端 f32 数据
     ///
-    /// # 安全性
+    /// # Safety
     /// 允许未对齐的地址
     /// 长度溢出会导致 UB
     #[inline]
     pub unsafe fn read_le_f32_unsafe(&mut self) -> f32 {
         let value = self.read_le_u32_unsafe();
-        std::mem::transmute::<u32, f32>(value)
+        f32::from_bits(value)
     }
     /// 读取一个大端 f64 数据
     ///
-    /// # 安全性
+    /// # Safety
     /// 允许未对齐的地址
     /// 长度溢出会导致 UB
     #[inline]
     pub unsafe fn read_be_f64_unsafe(&mut self) -> f64 {
         let value = self.read_be_u64_unsafe();
-        std::mem::transmute::<u64, f64>(value)
+        f64::from_bits(value)
     }
     /// 读取一个小端 f64 数据
     ///
-    /// # 安全性
+    /// # Safety
     /// 允许未对齐的地址
     /// 长度溢出会导致 UB
     #[inline]
     pub unsafe fn read_le_f64_unsafe(&mut self) -> f64 {
         let value = self.read_le_u64_unsafe();
-        std::mem::transmute::<u64, f64>(value)
+        f64::from_bits(value)
     }
     /// 读取指定长度的 u8 数组
     ///
-    /// # 安全性
+    /// # Safety
     ///
     /// 长度溢出会导致 panic
     #[inline]
@@ -704,7 +706,7 @@ impl NbtReader<'_> {
     }
     /// 读取指定长度的 i8 数组
     ///
-    /// # 安全性
+    /// # Safety
     ///
     /// 长度溢出会导致 UB
     #[inline]
@@ -715,7 +717,7 @@ impl NbtReader<'_> {
     }
     /// 读取指定长度的 i8 数组
     ///
-    /// # 安全性
+    /// # Safety
     ///
     /// 长度溢出会导致 panic
     #[inline]
@@ -726,14 +728,14 @@ impl NbtReader<'_> {
     }
     /// 读取指定长度的 i16 数组
     ///
-    /// # 安全性
+    /// # Safety
     ///
     /// 长度溢出会导致 UB
     #[inline]
     pub unsafe fn read_be_i16_array_unsafe(&mut self, len: usize) -> Vec<i16> {
         let mut value: Vec<i16> = Vec::with_capacity(len);
         std::ptr::copy_nonoverlapping(
-            self.data[self.cursor..].as_ptr() as *const u8,
+            self.data[self.cursor..].as_ptr(),
             value.as_ptr() as *mut u8,
             len * 2,
         );
@@ -746,14 +748,14 @@ impl NbtReader<'_> {
     }
     /// 读取指定长度的 i32 数组
     ///
-    /// # 安全性
+    /// # Safety
     ///
     /// 长度溢出会导致 UB
     #[inline]
     pub unsafe fn read_be_i32_array_unsafe(&mut self, len: usize) -> Vec<i32> {
         let mut value: Vec<i32> = Vec::with_capacity(len);
         std::ptr::copy_nonoverlapping(
-            self.data[self.cursor..].as_ptr() as *const u8,
+            self.data[self.cursor..].as_ptr(),
             value.as_ptr() as *mut u8,
             len * 4,
         );
@@ -766,14 +768,14 @@ impl NbtReader<'_> {
     }
     /// 读取指定长度的 i64 数组
     ///
-    /// # 安全性
+    /// # Safety
     ///
     /// 长度溢出会导致 UB
     #[inline]
     pub unsafe fn read_be_i64_array_unsafe(&mut self, len: usize) -> Vec<i64> {
         let mut value: Vec<i64> = Vec::with_capacity(len);
         std::ptr::copy_nonoverlapping(
-            self.data[self.cursor..].as_ptr() as *const u8,
+            self.data[self.cursor..].as_ptr(),
             value.as_ptr() as *mut u8,
             len * 8,
         );
@@ -786,7 +788,7 @@ impl NbtReader<'_> {
     }
     /// 读取指定长度的 i32 数组
     ///
-    /// # 安全性
+    /// # Safety
     ///
     /// 长度溢出会导致 panic
     #[inline]
@@ -800,7 +802,7 @@ impl NbtReader<'_> {
     }
     /// 读取指定长度的 i64 数组
     ///
-    /// # 安全性
+    /// # Safety
     ///
     /// 长度溢出会导致 panic
     #[inline]
@@ -814,7 +816,7 @@ impl NbtReader<'_> {
     }
     /// 读取指定长度的 le i16 数组
     ///
-    /// # 安全性
+    /// # Safety
     ///
     /// 长度溢出会导致 panic
     #[inline]
@@ -828,7 +830,7 @@ impl NbtReader<'_> {
     }
     /// 读取指定长度的 le i32 数组
     ///
-    /// # 安全性
+    /// # Safety
     ///
     /// 长度溢出会导致 panic
     #[inline]
@@ -842,7 +844,7 @@ impl NbtReader<'_> {
     }
     /// 读取指定长度的 le i64 数组
     ///
-    /// # 安全性
+    /// # Safety
     ///
     /// 长度溢出会导致 panic
     #[inline]
@@ -856,7 +858,7 @@ impl NbtReader<'_> {
     }
     /// 读取指定长度的 utf-8 字符串
     ///
-    /// # 安全性
+    /// # Safety
     ///
     /// 长度溢出会导致 panic
     #[inline]
@@ -868,4 +870,16 @@ impl NbtReader<'_> {
         self.cursor += len;
         Ok(value.into_owned())
     }
+    /// 读取指定长度的 m-utf8 字符串 (Java 版用的)
+    ///
+    /// 跟 [`NbtReader::read_string`] 一样, 不合法的部分会被替换成 `U+FFFD`
+    #[inline]
+    pub fn read_mutf8_string(&mut self, len: usize) -> Result<String, NbtError> {
+        if len + self.cursor > self.data.len() {
+            return Err(NbtError::CursorOverflow(self.cursor, len, self.data.len()));
+        }
+        let value = mutf8::decode_lossy(&self.data[self.cursor..self.cursor + len]);
+        self.cursor += len;
+        Ok(value.into_owned())
+    }
 }
@@ -0,0 +1,329 @@
+//! Litematica 的 `.litematic`
+//!
+//! 一个文件里可以有好几个区域, 每个区域都有自己的调色板 (第 0 个一定是空气)
+//!
+//! 方块数据是紧密排列的 long 数组: 每个方块占 `max(2, ceil(log2(调色板长度)))` 位,
+//! 可以跨过两个 long 的边界
+//!
+//! 区域的大小可以是负的, 读取的时候会换成从最小的角开始的正的大小
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::{
+    checked_volume, double_pos, with_id, write_double_pos, BlockEntity, BlockVolume, Entity,
+};
+use crate::structure::{compound, find, get, list, named, BlockState};
+use crate::{NbtError, NbtResult, NbtValue};
+
+/// 写入的版本
+pub const VERSION: i32 = 6;
+
+/// 一个区域
+#[derive(Debug, Clone, PartialEq)]
+pub struct Region {
+    /// 区域的名字
+    pub name: String,
+    /// 区域最小的角相对于整个原理图原点的位置
+    pub position: [i32; 3],
+    /// 区域里的方块
+    pub volume: BlockVolume,
+}
+
+/// 一个 `.litematic` 文件
+#[derive(Debug, Clone, PartialEq)]
+pub struct Litematic {
+    /// 原理图的名字
+    pub name: String,
+    /// 作者
+    pub author: String,
+    /// 描述
+    pub description: String,
+    /// 格式版本
+    pub version: i32,
+    /// 格式的小版本, 老的文件没有
+    pub sub_version: Option<i32>,
+    /// 所有的区域
+    pub regions: Vec<Region>,
+}
+
+impl Litematic {
+    /// 只有一个区域的原理图, 区域的名字跟原理图一样
+    pub fn new(name: impl Into<String>, volume: BlockVolume) -> Self {
+        let name = name.into();
+        Self {
+            name: name.clone(),
+            author: String::new(),
+            description: String::new(),
+            version: VERSION,
+            sub_version: Some(1),
+            regions: vec![Region {
+                name,
+                position: [0; 3],
+                volume,
+            }],
+        }
+    }
+
+    /// 解析 `.litematic` 的 NBT
+    ///
+    /// 每个区域的 [`BlockVolume::data_version`] 都是文件里的 `MinecraftDataVersion`
+    pub fn decode(value: &NbtValue) -> NbtResult<Self> {
+        let root = compound(value, "(根节点)")?;
+        let data_version = find(root, "MinecraftDataVersion").map(NbtValue::as_i32).transpose()?;
+        let metadata = match find(root, "Metadata") {
+            Some(metadata) => compound(metadata, "Metadata")?.as_slice(),
+            None => &[],
+        };
+        let text = |key: &str| match find(metadata, key) {
+            Some(value) => value.as_string(),
+            None => Ok(String::new()),
+        };
+        let mut regions = Vec::new();
+        for (name, region) in compound(get(root, "Regions")?, "Regions")? {
+            let mut region = read_region(name, compound(region, name)?)?;
+            region.volume.data_version = data_version;
+            regions.push(region);
+        }
+        Ok(Self {
+            name: text("Name")?,
+            author: text("Author")?,
+            description: text("Description")?,
+            version: get(root, "Version")?.as_i32()?,
+            sub_version: find(root, "SubVersion").map(NbtValue::as_i32).transpose()?,
+            regions,
+        })
+    }
+
+    /// 写成 `.litematic` 的 NBT
+    ///
+    /// `MinecraftDataVersion` 用的是第一个有 [`BlockVolume::data_version`] 的区域的
+    pub fn encode(&self) -> NbtResult<NbtValue> {
+        let mut regions = Vec::with_capacity(self.regions.len());
+        let (mut min, mut max) = ([i32::MAX; 3], [i32::MIN; 3]);
+        let mut total_blocks = 0;
+        let mut total_volume = 0;
+        for region in &self.regions {
+            regions.push((region.name.clone(), write_region(region)?));
+            for i in 0..3 {
+                let end =
+                    region.position[i].checked_add(region.volume.size[i]).ok_or_else(|| {
+                        NbtError::StructureError(format!(
+                            "区域 {} 的位置 {:?} 加上大小 {:?} 溢出了",
+                            region.name, region.position, region.volume.size
+                        ))
+                    })?;
+                min[i] = min[i].min(region.position[i]);
+                max[i] = max[i].max(end);
+            }
+            total_blocks += region.volume.iter().filter(|(_, s)| s.name != AIR).count();
+            total_volume += region.volume.volume();
+        }
+        let mut enclosing = [0; 3];
+        if !self.regions.is_empty() {
+            for i in 0..3 {
+                enclosing[i] = max[i].checked_sub(min[i]).ok_or_else(|| {
+                    NbtError::StructureError(format!("区域的范围 {:?} 到 {:?} 溢出了", min, max))
+                })?;
+            }
+        }
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as i64)
+            .unwrap_or(0);
+        let metadata = vec![
+            ("EnclosingSize".to_string(), xyz("EnclosingSize", enclosing)),
+            ("Author".to_string(), NbtValue::String(self.author.clone())),
+            ("Description".to_string(), NbtValue::String(self.description.clone())),
+            ("Name".to_string(), NbtValue::String(self.name.clone())),
+            ("RegionCount".to_string(), NbtValue::Int(self.regions.len() as i32)),
+            ("TimeCreated".to_string(), NbtValue::Long(now)),
+            ("TimeModified".to_string(), NbtValue::Long(now)),
+            ("TotalBlocks".to_string(), NbtValue::Int(total_blocks as i32)),
+            ("TotalVolume".to_string(), NbtValue::Int(total_volume as i32)),
+        ];
+
+        let mut data = Vec::with_capacity(5);
+        if let Some(version) = self.regions.iter().find_map(|r| r.volume.data_version) {
+            data.push(("MinecraftDataVersion".to_string(), NbtValue::Int(version)));
+        }
+        data.push(("Version".to_string(), NbtValue::Int(self.version)));
+        if let Some(sub_version) = self.sub_version {
+            data.push(("SubVersion".to_string(), NbtValue::Int(sub_version)));
+        }
+        data.push(("Metadata".to_string(), named("Metadata", metadata)));
+        data.push(("Regions".to_string(), named("Regions", regions)));
+        Ok(NbtValue::Compound(Some(String::new()), data))
+    }
+
+    /// 解析 gzip 压缩过的 `.litematic` 文件
+    #[cfg(feature = "compression")]
+    pub fn from_bytes(data: &[u8]) -> NbtResult<Self> { Self::decode(&super::read_gzip(data)?) }
+
+    /// 写成 gzip 压缩过的 `.litematic` 文件
+    #[cfg(feature = "compression")]
+    pub fn to_bytes(&self) -> NbtResult<Vec<u8>> { super::write_gzip(&self.encode()?) }
+}
+
+const AIR: &str = "minecraft:air";
+
+fn read_xyz(value: &NbtValue, what: &str) -> NbtResult<[i32; 3]> {
+    let data = compound(value, what)?;
+    Ok([get(data, "x")?.as_i32()?, get(data, "y")?.as_i32()?, get(data, "z")?.as_i32()?])
+}
+
+fn xyz(key: &str, pos: [i32; 3]) -> NbtValue {
+    let data = ["x", "y", "z"].iter().zip(pos).map(|(k, v)| (k.to_string(), NbtValue::Int(v)));
+    named(key, data.collect())
+}
+
+/// 每个方块占多少位
+fn bits(palette_len: usize) -> usize {
+    (usize::BITS - palette_len.saturating_sub(1).leading_zeros()).max(2) as usize
+}
+
+fn read_region(name: &str, data: &[(String, NbtValue)]) -> NbtResult<Region> {
+    let position = read_xyz(get(data, "Position")?, "Position")?;
+    let size = read_xyz(get(data, "Size")?, "Size")?;
+    // 负的大小表示区域是往负方向延伸的
+    let mut abs_size = [0; 3];
+    let mut start = [0; 3];
+    for i in 0..3 {
+        let shifted = match size[i] < 0 {
+            true => position[i].checked_add(size[i] + 1),
+            false => Some(position[i]),
+        };
+        let (Some(abs), Some(shifted)) = (size[i].checked_abs(), shifted) else {
+            return Err(NbtError::StructureError(format!(
+                "区域 {} 的位置 {:?} 或者大小 {:?} 溢出了",
+                name, position, size
+            )));
+        };
+        abs_size[i] = abs;
+        start[i] = shifted;
+    }
+    let (position, size) = (start, abs_size);
+    let palette: Vec<BlockState> = list(get(data, "BlockStatePalette")?, "BlockStatePalette")?
+        .iter()
+        .map(BlockState::from_value)
+        .collect::<NbtResult<_>>()?;
+
+    let longs = match get(data, "BlockStates")? {
+        NbtValue::LongArray(longs) => longs,
+        other => return Err(NbtError::IncorrectType(12, other.tag())),
+    };
+    // 先检查 BlockStates 的长度, 对上了才分配
+    let bits = bits(palette.len());
+    let len = checked_volume(size)?;
+    let need = len.checked_mul(bits).map(|x| x.div_ceil(64));
+    if need.is_none_or(|need| longs.len() < need) {
+        return Err(NbtError::StructureError(format!(
+            "区域 {} 的 BlockStates 太短: {}, 大小是 {:?}",
+            name,
+            longs.len(),
+            size
+        )));
+    }
+    let mask = (1_u64 << bits) - 1;
+    let blocks = (0..len)
+        .map(|idx| {
+            let start = idx * bits;
+            let (long, offset) = (start / 64, start % 64);
+            let mut value = longs[long] as u64 >> offset;
+            if offset + bits > 64 {
+                value |= (longs[long + 1] as u64) << (64 - offset);
+            }
+            (value & mask) as u32
+        })
+        .collect();
+    let mut volume = BlockVolume::from_blocks(size, palette, blocks)?;
+
+    if let Some(block_entities) = find(data, "TileEntities") {
+        for block_entity in list(block_entities, "TileEntities")? {
+            let block_entity = compound(block_entity, "TileEntities")?;
+            let pos = [
+                get(block_entity, "x")?.as_i32()?,
+                get(block_entity, "y")?.as_i32()?,
+                get(block_entity, "z")?.as_i32()?,
+            ];
+            volume.block_entities.push(BlockEntity {
+                pos,
+                nbt: with_id(None, block_entity, &["x", "y", "z"]),
+            });
+        }
+    }
+    if let Some(entities) = find(data, "Entities") {
+        for entity in list(entities, "Entities")? {
+            let entity = compound(entity, "Entities")?;
+            volume.entities.push(Entity {
+                pos: double_pos(entity, "Pos")?,
+                nbt: with_id(None, entity, &["Pos"]),
+            });
+        }
+    }
+    Ok(Region {
+        name: name.to_string(),
+        position,
+        volume,
+    })
+}
+
+fn write_region(region: &Region) -> NbtResult<NbtValue> {
+    let volume = &region.volume;
+    volume.validate()?;
+    let (palette, blocks) = volume.compact(Some(&BlockState::new(AIR)));
+    let bits = bits(palette.len());
+    let mut longs = vec![0_u64; (blocks.len() * bits).div_ceil(64)];
+    for (idx, value) in blocks.iter().enumerate() {
+        let start = idx * bits;
+        let (long, offset) = (start / 64, start % 64);
+        longs[long] |= (*value as u64) << offset;
+        if offset + bits > 64 {
+            longs[long + 1] |= (*value as u64) >> (64 - offset);
+        }
+    }
+    let block_entities = volume
+        .block_entities
+        .iter()
+        .map(|block_entity| {
+            let mut data = match &block_entity.nbt {
+                NbtValue::Compound(_, data) => data.clone(),
+                _ => Vec::new(),
+            };
+            for (key, value) in ["x", "y", "z"].iter().zip(block_entity.pos) {
+                data.push((key.to_string(), NbtValue::Int(value)));
+            }
+            NbtValue::Compound(None, data)
+        })
+        .collect();
+    let entities = volume
+        .entities
+        .iter()
+        .map(|entity| {
+            let mut data = match &entity.nbt {
+                NbtValue::Compound(_, data) => data.clone(),
+                _ => Vec::new(),
+            };
+            data.push(("Pos".to_string(), write_double_pos(entity.pos)));
+            NbtValue::Compound(None, data)
+        })
+        .collect();
+    Ok(named(
+        &region.name,
+        vec![
+            ("Position".to_string(), xyz("Position", region.position)),
+            ("Size".to_string(), xyz("Size", volume.size)),
+            (
+                "BlockStatePalette".to_string(),
+                NbtValue::List(palette.iter().map(BlockState::to_value).collect()),
+            ),
+            (
+                "BlockStates".to_string(),
+                NbtValue::LongArray(longs.into_iter().map(|x| x as i64).collect()),
+            ),
+            ("TileEntities".to_string(), NbtValue::List(block_entities)),
+            ("Entities".to_string(), NbtValue::List(entities)),
+            ("PendingBlockTicks".to_string(), NbtValue::List(Vec::new())),
+            ("PendingFluidTicks".to_string(), NbtValue::List(Vec::new())),
+        ],
+    ))
+}
@@ -0,0 +1,117 @@
+//! MCEdit / 老 WorldEdit 的 `.schematic` (1.13 之前)
+//!
+//! 方块是数字 id (`Blocks` + `AddBlocks` 的高 4 位) 加上 4 位的附加值 (`Data`)
+//!
+//! 数字 id 跟现在的方块状态对应起来需要一张很大的表, 这里默认只会把 `0` 变成空气,
+//! 其他的变成 `legacy:<id>[data=<附加值>]`, 需要的话可以用 [`decode_with`] 换成自己的表
+//!
+//! 只能读, 不能写
+
+use std::collections::HashMap;
+
+use super::{checked_volume, dimension, double_pos, with_id, BlockEntity, BlockVolume, Entity};
+use crate::structure::{compound, find, get, list, BlockState};
+use crate::{NbtError, NbtResult, NbtValue};
+
+/// 默认的对应方式
+pub fn legacy_state(id: u16, data: u8) -> BlockState {
+    match id {
+        0 => BlockState::new("minecraft:air"),
+        _ => BlockState::new(format!("legacy:{}", id)).with_property("data", data.to_string()),
+    }
+}
+
+/// 用 [`legacy_state`] 解析 `.schematic` 的 NBT
+pub fn decode(value: &NbtValue) -> NbtResult<BlockVolume> { decode_with(value, legacy_state) }
+
+/// 解析 `.schematic` 的 NBT, 用 `map` 把数字 id 和附加值变成方块状态
+///
+/// 实体的坐标是原来的世界坐标
+pub fn decode_with(
+    value: &NbtValue,
+    mut map: impl FnMut(u16, u8) -> BlockState,
+) -> NbtResult<BlockVolume> {
+    let data = compound(value, "(根节点)")?;
+    if let Some(materials) = find(data, "Materials") {
+        let materials = materials.as_string()?;
+        if materials != "Alpha" {
+            return Err(NbtError::StructureError(format!("不支持的 Materials: {}", materials)));
+        }
+    }
+    let size = [
+        dimension(data, "Width")?,
+        dimension(data, "Height")?,
+        dimension(data, "Length")?,
+    ];
+    let bytes = |key: &str| match find(data, key) {
+        Some(NbtValue::ByteArray(bytes)) => Ok(Some(bytes)),
+        Some(other) => Err(NbtError::IncorrectType(7, other.tag())),
+        None => Ok(None),
+    };
+    let blocks =
+        bytes("Blocks")?.ok_or_else(|| NbtError::StructureError("缺少 Blocks".to_string()))?;
+    let metas = bytes("Data")?.ok_or_else(|| NbtError::StructureError("缺少 Data".to_string()))?;
+    // Schematica 用的是 Add
+    let add = match bytes("AddBlocks")? {
+        Some(add) => Some(add),
+        None => bytes("Add")?,
+    };
+    // 先检查方块数据的长度, 对上了才分配
+    let len = checked_volume(size)?;
+    if blocks.len() < len || metas.len() < len || add.is_some_and(|add| add.len() < len.div_ceil(2))
+    {
+        return Err(NbtError::StructureError(format!("方块数据不够 {} 个", len)));
+    }
+
+    let mut states = Vec::new();
+    let mut palette = HashMap::new();
+    let mut block_ids = Vec::with_capacity(len);
+    for idx in 0..len {
+        let mut id = blocks[idx] as u8 as u16;
+        if let Some(add) = add {
+            let add = add[idx >> 1] as u8;
+            id |= match idx & 1 {
+                0 => (add as u16 & 0x0F) << 8,
+                _ => (add as u16 & 0xF0) << 4,
+            };
+        }
+        let key = (id, metas[idx] as u8 & 0x0F);
+        block_ids.push(*palette.entry(key).or_insert_with(|| {
+            states.push(map(key.0, key.1));
+            states.len() as u32 - 1
+        }));
+    }
+    if states.is_empty() {
+        states.push(BlockState::new("minecraft:air"));
+    }
+    let mut volume = BlockVolume::from_blocks(size, states, block_ids)?;
+
+    if let Some(block_entities) = find(data, "TileEntities") {
+        for block_entity in list(block_entities, "TileEntities")? {
+            let block_entity = compound(block_entity, "TileEntities")?;
+            let pos = [
+                get(block_entity, "x")?.as_i32()?,
+                get(block_entity, "y")?.as_i32()?,
+                get(block_entity, "z")?.as_i32()?,
+            ];
+            volume.block_entities.push(BlockEntity {
+                pos,
+                nbt: with_id(None, block_entity, &["x", "y", "z"]),
+            });
+        }
+    }
+    if let Some(entities) = find(data, "Entities") {
+        for entity in list(entities, "Entities")? {
+            let entity = compound(entity, "Entities")?;
+            volume.entities.push(Entity {
+                pos: double_pos(entity, "Pos")?,
+                nbt: with_id(None, entity, &["Pos"]),
+            });
+        }
+    }
+    Ok(volume)
+}
+
+/// 用 [`legacy_state`] 解析 gzip 压缩过的 `.schematic` 文件
+#[cfg(feature = "compression")]
+pub fn from_bytes(data: &[u8]) -> NbtResult<BlockVolume> { decode(&super::read_gzip(data)?) }
@@ -0,0 +1,277 @@
+//! 建筑用的原理图 (schematic) 文件
+//!
+//! - [`sponge`]: WorldEdit 的 `.schem` (可以读 Sponge v1/v2/v3, 写入的是 v3)
+//! - [`litematica`]: Litematica 的 `.litematic`
+//! - [`mcedit`]: MCEdit / 老 WorldEdit 的 `.schematic` (只能读)
+//!
+//! 都会转换成同一个 [`BlockVolume`]
+//!
+//! 这些文件都是 gzip 压缩过的 Java NBT, 开启 `compression` feature 之后可以用各个模块的
+//! `from_bytes` / `to_bytes` 直接读写文件内容
+//!
+//! ```rust
+//! use shen_nbt5::schematic::{litematica, sponge, BlockVolume};
+//! use shen_nbt5::structure::BlockState;
+//!
+//! let mut volume = BlockVolume::new([2, 2, 2], BlockState::new("minecraft:air")).unwrap();
+//! volume.set([1, 0, 1], "minecraft:chest[facing=north]".parse().unwrap());
+//!
+//! let value = sponge::encode(&volume).unwrap();
+//! let read = sponge::decode(&value).unwrap();
+//! assert_eq!(read.get([1, 0, 1]).unwrap().to_string(), "minecraft:chest[facing=north]");
+//!
+//! let litematic = litematica::Litematic::new("chest", volume);
+//! let read = litematica::Litematic::decode(&litematic.encode().unwrap()).unwrap();
+//! let region = &read.regions[0].volume;
+//! assert_eq!(region.get([1, 0, 1]).unwrap().property("facing"), Some("north"));
+//! assert_eq!(region.iter().filter(|(_, s)| s.name == "minecraft:air").count(), 7);
+//! ```
+
+pub mod litematica;
+pub mod mcedit;
+pub mod sponge;
+
+use std::collections::HashMap;
+
+use crate::structure::{get, BlockState};
+use crate::{NbtError, NbtResult, NbtValue};
+
+/// 方块实体
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlockEntity {
+    /// 在 [`BlockVolume`] 里的坐标
+    pub pos: [i32; 3],
+    /// 方块实体的 NBT, 包括 `id`, 不包括坐标
+    pub nbt: NbtValue,
+}
+
+/// 实体
+#[derive(Debug, Clone, PartialEq)]
+pub struct Entity {
+    /// 坐标, 除了 [`mcedit`] 都是相对于 [`BlockVolume`] 的
+    pub pos: [f64; 3],
+    /// 实体的 NBT, 包括 `id`, 不包括 `Pos`
+    pub nbt: NbtValue,
+}
+
+/// 一块长方体区域里的方块
+///
+/// 方块按照 YZX 的顺序存放: `index = (y * size_z + z) * size_x + x`, 跟这几种格式都一样
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlockVolume {
+    /// x, y, z 方向的大小
+    pub size: [i32; 3],
+    /// 调色板
+    pub palette: Vec<BlockState>,
+    /// 每个方块在调色板里的下标
+    pub blocks: Vec<u32>,
+    /// 方块实体
+    pub block_entities: Vec<BlockEntity>,
+    /// 实体
+    pub entities: Vec<Entity>,
+    /// 数据版本, MCEdit 的没有
+    pub data_version: Option<i32>,
+}
+
+impl BlockVolume {
+    /// 用 `fill` 填满的区域, 小于 0 的大小会被当成 0
+    ///
+    /// 方块数量溢出或者分配不了的时候返回 [`NbtError::StructureError`]
+    pub fn new(size: [i32; 3], fill: BlockState) -> NbtResult<Self> {
+        let size = size.map(|x| x.max(0));
+        Ok(Self {
+            size,
+            palette: vec![fill],
+            blocks: filled_blocks(checked_volume(size)?)?,
+            block_entities: Vec::new(),
+            entities: Vec::new(),
+            data_version: None,
+        })
+    }
+
+    /// 读出来的方块数据, 会检查方块数量和调色板下标
+    pub(crate) fn from_blocks(
+        size: [i32; 3],
+        palette: Vec<BlockState>,
+        blocks: Vec<u32>,
+    ) -> NbtResult<Self> {
+        let volume = Self {
+            size,
+            palette,
+            blocks,
+            block_entities: Vec::new(),
+            entities: Vec::new(),
+            data_version: None,
+        };
+        volume.validate()?;
+        Ok(volume)
+    }
+
+    /// 方块的数量
+    pub fn volume(&self) -> usize { self.size.iter().map(|x| *x as usize).product() }
+
+    /// 坐标对应的下标, 超出范围的时候是 `None`
+    pub fn index(&self, pos: [i32; 3]) -> Option<usize> {
+        if (0..3).any(|i| pos[i] < 0 || pos[i] >= self.size[i]) {
+            return None;
+        }
+        let [x, y, z] = pos.map(|x| x as usize);
+        Some((y * self.size[2] as usize + z) * self.size[0] as usize + x)
+    }
+
+    /// 下标对应的坐标
+    pub fn pos(&self, index: usize) -> [i32; 3] {
+        let (size_x, size_z) = (self.size[0] as usize, self.size[2] as usize);
+        [
+            (index % size_x) as i32,
+            (index / size_x / size_z) as i32,
+            (index / size_x % size_z) as i32,
+        ]
+    }
+
+    /// 拿到某个位置的方块状态
+    pub fn get(&self, pos: [i32; 3]) -> Option<&BlockState> {
+        self.palette.get(*self.blocks.get(self.index(pos)?)? as usize)
+    }
+
+    /// 设置某个位置的方块状态, 调色板里没有的话会加进去
+    ///
+    /// 超出范围的时候返回 `false`
+    pub fn set(&mut self, pos: [i32; 3], state: BlockState) -> bool {
+        let Some(index) = self.index(pos) else {
+            return false;
+        };
+        let state = match self.palette.iter().position(|s| *s == state) {
+            Some(idx) => idx,
+            None => {
+                self.palette.push(state);
+                self.palette.len() - 1
+            }
+        };
+        self.blocks[index] = state as u32;
+        true
+    }
+
+    /// 拿到某个位置的方块实体
+    pub fn block_entity(&self, pos: [i32; 3]) -> Option<&NbtValue> {
+        self.block_entities.iter().find(|b| b.pos == pos).map(|b| &b.nbt)
+    }
+
+    /// 按照 YZX 的顺序遍历所有的方块
+    pub fn iter(&self) -> impl Iterator<Item = ([i32; 3], &BlockState)> {
+        self.blocks
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, state)| Some((self.pos(idx), self.palette.get(*state as usize)?)))
+    }
+
+    /// 检查方块数量和调色板下标
+    pub(crate) fn validate(&self) -> NbtResult<()> {
+        if checked_volume(self.size).ok() != Some(self.blocks.len()) {
+            return Err(NbtError::StructureError(format!(
+                "方块数量 {} 跟大小 {:?} 对不上",
+                self.blocks.len(),
+                self.size
+            )));
+        }
+        if let Some(idx) = self.blocks.iter().position(|b| *b as usize >= self.palette.len()) {
+            return Err(NbtError::StructureError(format!(
+                "{:?} 的调色板下标 {} 超出了调色板的长度 {}",
+                self.pos(idx),
+                self.blocks[idx],
+                self.palette.len()
+            )));
+        }
+        Ok(())
+    }
+
+    /// 去掉重复的调色板, 有 `first` 的时候放在第一个
+    ///
+    /// 返回新的调色板和每个方块新的下标
+    pub(crate) fn compact(&self, first: Option<&BlockState>) -> (Vec<BlockState>, Vec<u32>) {
+        let mut palette = Vec::new();
+        let mut index = HashMap::new();
+        for state in first.into_iter().chain(self.palette.iter()) {
+            index.entry(state).or_insert_with(|| {
+                palette.push(state.clone());
+                palette.len() as u32 - 1
+            });
+        }
+        let remap: Vec<u32> = self.palette.iter().map(|s| index[s]).collect();
+        let blocks = self.blocks.iter().map(|b| remap[*b as usize]).collect();
+        (palette, blocks)
+    }
+}
+
+/// 把 `id` 从 NBT 里拿出来, 返回 id 和剩下的
+pub(crate) fn split_id(nbt: &NbtValue) -> (String, Vec<(String, NbtValue)>) {
+    let mut id = String::new();
+    let mut rest = Vec::new();
+    if let NbtValue::Compound(_, data) = nbt {
+        for (key, value) in data {
+            match (key.as_str(), value) {
+                ("id", NbtValue::String(s)) => id = s.clone(),
+                _ => rest.push((key.clone(), value.clone())),
+            }
+        }
+    }
+    (id, rest)
+}
+
+/// 去掉 `skip` 里的 key, 有 `id` 的时候放在最前面
+pub(crate) fn with_id(id: Option<&str>, data: &[(String, NbtValue)], skip: &[&str]) -> NbtValue {
+    let mut nbt = Vec::with_capacity(data.len() + 1);
+    if let Some(id) = id {
+        nbt.push(("id".to_string(), NbtValue::String(id.to_string())));
+    }
+    nbt.extend(data.iter().filter(|(k, _)| !skip.contains(&k.as_str())).cloned());
+    NbtValue::Compound(None, nbt)
+}
+
+/// 大小对应的方块数量, 负数或者溢出的时候返回 [`NbtError::StructureError`]
+///
+/// 读文件的时候要先跟方块数据的长度比较, 不能直接按照大小分配
+pub(crate) fn checked_volume(size: [i32; 3]) -> NbtResult<usize> {
+    size.iter()
+        .try_fold(1_usize, |acc, x| acc.checked_mul(usize::try_from(*x).ok()?))
+        .ok_or_else(|| NbtError::StructureError(format!("大小 {:?} 不对", size)))
+}
+
+/// `len` 个调色板里第一个方块, 分配不了的时候返回 [`NbtError::StructureError`]
+pub(crate) fn filled_blocks(len: usize) -> NbtResult<Vec<u32>> {
+    let mut blocks = Vec::new();
+    blocks
+        .try_reserve_exact(len)
+        .map_err(|_| NbtError::StructureError(format!("方块数量太多了: {}", len)))?;
+    blocks.resize(len, 0);
+    Ok(blocks)
+}
+
+/// 大小是 unsigned short
+pub(crate) fn dimension(data: &[(String, NbtValue)], key: &str) -> NbtResult<i32> {
+    Ok(get(data, key)?.as_i16()? as u16 as i32)
+}
+
+/// `[x, y, z]` 三个 double
+pub(crate) fn double_pos(data: &[(String, NbtValue)], key: &str) -> NbtResult<[f64; 3]> {
+    match get(data, key)? {
+        NbtValue::List(list) if list.len() == 3 => {
+            Ok([list[0].as_f64()?, list[1].as_f64()?, list[2].as_f64()?])
+        }
+        _ => Err(NbtError::StructureError(format!("{} 不是 3 个 double", key))),
+    }
+}
+
+pub(crate) fn write_double_pos(pos: [f64; 3]) -> NbtValue {
+    NbtValue::List(pos.iter().map(|x| NbtValue::Double(*x)).collect())
+}
+
+#[cfg(feature = "compression")]
+pub(crate) fn read_gzip(data: &[u8]) -> NbtResult<NbtValue> {
+    NbtValue::from_compressed::<crate::nbt_version::Java>(data)
+}
+
+#[cfg(feature = "compression")]
+pub(crate) fn write_gzip(value: &NbtValue) -> NbtResult<Vec<u8>> {
+    value.to_compressed::<crate::nbt_version::Java>(crate::compression::Compression::Gzip)
+}
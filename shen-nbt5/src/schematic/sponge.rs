@@ -0,0 +1,255 @@
+//! WorldEdit 的 `.schem` (Sponge Schematic)
+//!
+//! 格式见 <https://github.com/SpongePowered/Schematic-Specification>
+//!
+//! - v1/v2: 根节点就是 `Schematic`, 调色板和方块数据直接放在里面
+//! - v3: 根节点下面有一个 `Schematic`, 方块放在 `Blocks` 里, 方块实体和实体的数据放在 `Data` 里
+//!
+//! 方块数据是 VarInt 编码的调色板下标, 读取的时候会忽略 `Offset` 和 `Metadata`
+
+use super::{
+    checked_volume, dimension, double_pos, filled_blocks, split_id, with_id, write_double_pos,
+    BlockEntity, BlockVolume, Entity,
+};
+use crate::structure::{compound, find, get, list, named, BlockState};
+use crate::{NbtError, NbtResult, NbtValue};
+
+/// 写入的版本
+pub const VERSION: i32 = 3;
+
+/// 解析 `.schem` 的 NBT
+pub fn decode(value: &NbtValue) -> NbtResult<BlockVolume> {
+    let root = compound(value, "(根节点)")?;
+    let data = match find(root, "Schematic") {
+        Some(inner) => compound(inner, "Schematic")?,
+        None => root,
+    };
+    let version = get(data, "Version")?.as_i32()?;
+    let size = [
+        dimension(data, "Width")?,
+        dimension(data, "Height")?,
+        dimension(data, "Length")?,
+    ];
+    let len = checked_volume(size)?;
+
+    let (palette, block_data, block_entities) = if version >= 3 {
+        match find(data, "Blocks") {
+            Some(blocks) => {
+                let blocks = compound(blocks, "Blocks")?;
+                (
+                    Some(get(blocks, "Palette")?),
+                    Some(get(blocks, "Data")?),
+                    find(blocks, "BlockEntities"),
+                )
+            }
+            None => (None, None, None),
+        }
+    } else {
+        let block_entities = find(data, "BlockEntities").or(find(data, "TileEntities"));
+        (Some(get(data, "Palette")?), Some(get(data, "BlockData")?), block_entities)
+    };
+
+    let palette = match palette {
+        Some(palette) => read_palette(compound(palette, "Palette")?)?,
+        None => vec![BlockState::new("minecraft:air")],
+    };
+    // 先解出方块数据, 跟大小对上了才分配
+    let blocks = match block_data {
+        Some(block_data) => {
+            let NbtValue::ByteArray(bytes) = block_data else {
+                return Err(NbtError::IncorrectType(7, block_data.tag()));
+            };
+            if bytes.len() < len {
+                return Err(NbtError::StructureError(format!(
+                    "方块数据 {} 个字节, 不够 {:?} 的 {} 个方块",
+                    bytes.len(),
+                    size,
+                    len
+                )));
+            }
+            read_var_ints(bytes)?
+        }
+        None => filled_blocks(len)?,
+    };
+    let mut volume = BlockVolume::from_blocks(size, palette, blocks)?;
+    volume.data_version = find(data, "DataVersion").map(NbtValue::as_i32).transpose()?;
+
+    if let Some(block_entities) = block_entities {
+        for block_entity in list(block_entities, "BlockEntities")? {
+            let block_entity = compound(block_entity, "BlockEntities")?;
+            let pos = match get(block_entity, "Pos")? {
+                NbtValue::IntArray(pos) if pos.len() == 3 => [pos[0], pos[1], pos[2]],
+                _ => {
+                    return Err(NbtError::StructureError(
+                        "方块实体的 Pos 不是 3 个 int".to_string(),
+                    ))
+                }
+            };
+            volume.block_entities.push(BlockEntity {
+                pos,
+                nbt: with_data(block_entity, version)?,
+            });
+        }
+    }
+    if let Some(entities) = find(data, "Entities") {
+        for entity in list(entities, "Entities")? {
+            let entity = compound(entity, "Entities")?;
+            volume.entities.push(Entity {
+                pos: double_pos(entity, "Pos")?,
+                nbt: with_data(entity, version)?,
+            });
+        }
+    }
+    Ok(volume)
+}
+
+/// 写成 v3 的 `.schem` 的 NBT
+///
+/// 大小超过 65535 或者方块数据不对的时候返回 [`NbtError::StructureError`]
+pub fn encode(volume: &BlockVolume) -> NbtResult<NbtValue> {
+    volume.validate()?;
+    if let Some(size) = volume.size.iter().find(|x| **x > u16::MAX as i32) {
+        return Err(NbtError::StructureError(format!("大小超过了 65535: {}", size)));
+    }
+    let (palette, blocks) = volume.compact(None);
+    let palette = palette
+        .iter()
+        .enumerate()
+        .map(|(idx, state)| (state.to_string(), NbtValue::Int(idx as i32)))
+        .collect();
+    let block_entities = volume
+        .block_entities
+        .iter()
+        .map(|block_entity| {
+            let (id, data) = split_id(&block_entity.nbt);
+            NbtValue::Compound(
+                None,
+                vec![
+                    ("Pos".to_string(), NbtValue::IntArray(block_entity.pos.to_vec())),
+                    ("Id".to_string(), NbtValue::String(id)),
+                    ("Data".to_string(), named("Data", data)),
+                ],
+            )
+        })
+        .collect();
+    let entities = volume
+        .entities
+        .iter()
+        .map(|entity| {
+            let (id, data) = split_id(&entity.nbt);
+            NbtValue::Compound(
+                None,
+                vec![
+                    ("Pos".to_string(), write_double_pos(entity.pos)),
+                    ("Id".to_string(), NbtValue::String(id)),
+                    ("Data".to_string(), named("Data", data)),
+                ],
+            )
+        })
+        .collect();
+
+    let mut data = vec![("Version".to_string(), NbtValue::Int(VERSION))];
+    if let Some(version) = volume.data_version {
+        data.push(("DataVersion".to_string(), NbtValue::Int(version)));
+    }
+    for (key, size) in ["Width", "Height", "Length"].iter().zip(volume.size) {
+        data.push((key.to_string(), NbtValue::Short(size as u16 as i16)));
+    }
+    data.push(("Offset".to_string(), NbtValue::IntArray(vec![0; 3])));
+    data.push((
+        "Blocks".to_string(),
+        named(
+            "Blocks",
+            vec![
+                ("Palette".to_string(), named("Palette", palette)),
+                ("Data".to_string(), NbtValue::ByteArray(write_var_ints(&blocks))),
+                ("BlockEntities".to_string(), NbtValue::List(block_entities)),
+            ],
+        ),
+    ));
+    data.push(("Entities".to_string(), NbtValue::List(entities)));
+    Ok(NbtValue::Compound(
+        Some(String::new()),
+        vec![("Schematic".to_string(), named("Schematic", data))],
+    ))
+}
+
+/// 解析 gzip 压缩过的 `.schem` 文件
+#[cfg(feature = "compression")]
+pub fn from_bytes(data: &[u8]) -> NbtResult<BlockVolume> { decode(&super::read_gzip(data)?) }
+
+/// 写成 gzip 压缩过的 `.schem` 文件
+#[cfg(feature = "compression")]
+pub fn to_bytes(volume: &BlockVolume) -> NbtResult<Vec<u8>> { super::write_gzip(&encode(volume)?) }
+
+/// 下标必须正好是 `0..palette.len()`
+fn read_palette(palette: &[(String, NbtValue)]) -> NbtResult<Vec<BlockState>> {
+    let mut states = vec![None; palette.len()];
+    for (name, idx) in palette {
+        let idx = idx.as_i32()?;
+        let state = usize::try_from(idx).ok().and_then(|idx| states.get_mut(idx));
+        let Some(state) = state else {
+            return Err(NbtError::StructureError(format!(
+                "调色板下标 {} 超出了调色板的长度 {}",
+                idx,
+                palette.len()
+            )));
+        };
+        *state = Some(name.parse()?);
+    }
+    states
+        .into_iter()
+        .enumerate()
+        .map(|(idx, s)| s.ok_or_else(|| NbtError::StructureError(format!("调色板里没有 {}", idx))))
+        .collect()
+}
+
+/// v3 的数据在 `Data` 里, v1/v2 的直接放在外面
+fn with_data(data: &[(String, NbtValue)], version: i32) -> NbtResult<NbtValue> {
+    let id = find(data, "Id").map(NbtValue::as_string).transpose()?;
+    match version >= 3 {
+        true => {
+            let inner = match find(data, "Data") {
+                Some(inner) => compound(inner, "Data")?.as_slice(),
+                None => &[],
+            };
+            Ok(with_id(id.as_deref(), inner, &["id"]))
+        }
+        false => Ok(with_id(id.as_deref(), data, &["Id", "Pos", "id"])),
+    }
+}
+
+fn read_var_ints(bytes: &[i8]) -> NbtResult<Vec<u32>> {
+    let mut result = Vec::with_capacity(bytes.len());
+    let mut value = 0_u32;
+    let mut shift = 0;
+    for byte in bytes.iter().map(|b| *b as u8) {
+        if shift >= 32 {
+            return Err(NbtError::VarIntTooBig(shift / 7));
+        }
+        value |= ((byte & 0x7F) as u32) << shift;
+        shift += 7;
+        if byte & 0x80 == 0 {
+            result.push(value);
+            value = 0;
+            shift = 0;
+        }
+    }
+    if shift != 0 {
+        return Err(NbtError::StructureError("方块数据的最后一个 VarInt 不完整".to_string()));
+    }
+    Ok(result)
+}
+
+fn write_var_ints(values: &[u32]) -> Vec<i8> {
+    let mut result = Vec::with_capacity(values.len());
+    for value in values {
+        let mut value = *value;
+        while value >= 0x80 {
+            result.push((value as u8 | 0x80) as i8);
+            value >>= 7;
+        }
+        result.push(value as i8);
+    }
+    result
+}
@@ -0,0 +1,299 @@
+//! 从任何 [`Read`] 里流式读取 NBT
+//!
+//! 跟 [`NbtReader`](crate::reader::NbtReader) 不一样, 不需要先把所有数据读进内存
+//!
+//! ```rust
+//! use shen_nbt5::nbt_version::Java;
+//! use shen_nbt5::NbtValue;
+//!
+//! let mut input: &[u8] = &[0x0A, 0x00, 0x00, 0x01, 0x00, 0x01, b'a', 0x7F, 0x00, 0xFF];
+//! let value = NbtValue::from_stream::<Java>(&mut input).unwrap();
+//! assert_eq!(value, NbtValue::Compound(Some("".to_string()), vec![("a".to_string(), NbtValue::Byte(127))]));
+//! // 根节点后面的数据还在
+//! assert_eq!(input, &[0xFF]);
+//! ```
+
+use std::io::{self, Read};
+
+use crate::nbt_version::{
+    BedrockDisk, BedrockNetVarInt, Java, JavaNetAfter1_20_2, NbtStreamReadTrait,
+};
+use crate::{NbtError, NbtResult};
+
+use simd_cesu8::mutf8;
+
+/// 用于从 [`Read`] 里读取 NBT 数据
+pub struct NbtStreamReader<R> {
+    inner: R,
+    /// 已经读了多少字节
+    pub cursor: usize,
+}
+
+macro_rules! read_number {
+    ($be_name:ident, $le_name:ident, $ty:ty) => {
+        #[doc = concat!("读取一个大端 ", stringify!($ty))]
+        #[inline]
+        pub fn $be_name(&mut self) -> NbtResult<$ty> {
+            Ok(<$ty>::from_be_bytes(self.read_array()?))
+        }
+        #[doc = concat!("读取一个小端 ", stringify!($ty))]
+        #[inline]
+        pub fn $le_name(&mut self) -> NbtResult<$ty> {
+            Ok(<$ty>::from_le_bytes(self.read_array()?))
+        }
+    };
+}
+
+impl<R: Read> NbtStreamReader<R> {
+    pub fn new(inner: R) -> Self { Self { inner, cursor: 0 } }
+
+    /// 拿回里面的 reader
+    pub fn into_inner(self) -> R { self.inner }
+
+    fn map_io_err(&self, e: io::Error) -> NbtError {
+        match e.kind() {
+            io::ErrorKind::UnexpectedEof => NbtError::UnexpectedEof(self.cursor),
+            _ => e.into(),
+        }
+    }
+
+    /// 读取 `N` 个字节
+    #[inline]
+    pub fn read_array<const N: usize>(&mut self) -> NbtResult<[u8; N]> {
+        let mut buf = [0; N];
+        if let Err(e) = self.inner.read_exact(&mut buf) {
+            return Err(self.map_io_err(e));
+        }
+        self.cursor += N;
+        Ok(buf)
+    }
+
+    /// 读取 `len` 个字节
+    ///
+    /// 不会按照 `len` 提前分配内存, 防止一个假的长度直接把内存吃满
+    pub fn read_bytes(&mut self, len: usize) -> NbtResult<Vec<u8>> {
+        let mut data = Vec::new();
+        let read = (&mut self.inner).take(len as u64).read_to_end(&mut data);
+        self.cursor += data.len();
+        if let Err(e) = read {
+            return Err(self.map_io_err(e));
+        }
+        if data.len() < len {
+            return Err(NbtError::UnexpectedEof(self.cursor));
+        }
+        Ok(data)
+    }
+
+    /// 读取一个 u8 类型的数据
+    #[inline]
+    pub fn read_u8(&mut self) -> NbtResult<u8> { Ok(self.read_array::<1>()?[0]) }
+    /// 读取一个 i8 类型的数据
+    #[inline]
+    pub fn read_i8(&mut self) -> NbtResult<i8> { Ok(self.read_u8()? as i8) }
+
+    read_number!(read_be_i16, read_le_i16, i16);
+    read_number!(read_be_u16, read_le_u16, u16);
+    read_number!(read_be_i32, read_le_i32, i32);
+    read_number!(read_be_i64, read_le_i64, i64);
+    read_number!(read_be_f32, read_le_f32, f32);
+    read_number!(read_be_f64, read_le_f64, f64);
+
+    /// 读取一个 Varint, 跟 [`NbtReader::read_var_i32`](crate::reader::NbtReader::read_var_i32) 一样
+    #[inline]
+    pub fn read_var_i32(&mut self) -> NbtResult<i32> {
+        let mut value = 0;
+        let mut size = 0;
+        loop {
+            let byte = self.read_u8()?;
+            value |= ((byte & 0b0111_1111) as i32) << (size * 7);
+            size += 1;
+            if size > 5 {
+                return Err(NbtError::VarIntTooBig(value as usize));
+            }
+            if (byte & 0b1000_0000) == 0 {
+                break;
+            }
+        }
+        Ok(value)
+    }
+    /// 读取一个 Varlong, 跟 [`NbtReader::read_var_i64`](crate::reader::NbtReader::read_var_i64) 一样
+    #[inline]
+    pub fn read_var_i64(&mut self) -> NbtResult<i64> {
+        let mut value = 0;
+        let mut size = 0;
+        loop {
+            let byte = self.read_u8()?;
+            value |= ((byte & 0b0111_1111) as i64) << (size * 7);
+            size += 1;
+            if size > 10 {
+                return Err(NbtError::VarlongTooBig(value as usize));
+            }
+            if (byte & 0b1000_0000) == 0 {
+                break;
+            }
+        }
+        Ok(value)
+    }
+    /// 读取一个 zigzag 编码的 varint
+    #[inline]
+    pub fn read_zigzag_var_i32(&mut self) -> NbtResult<i32> {
+        let value = self.read_var_i32()?;
+        Ok((value >> 1) ^ (-(value & 1)))
+    }
+    /// 读取一个 zigzag 编码的 varlong
+    #[inline]
+    pub fn read_zigzag_var_i64(&mut self) -> NbtResult<i64> {
+        let value = self.read_var_i64()?;
+        Ok((value >> 1) ^ (-(value & 1)))
+    }
+    /// 读取指定长度的 utf-8 字符串
+    ///
+    /// 不合法的部分会被替换成 `U+FFFD`
+    #[inline]
+    pub fn read_string(&mut self, len: usize) -> NbtResult<String> {
+        let data = self.read_bytes(len)?;
+        Ok(String::from_utf8_lossy(&data).into_owned())
+    }
+    /// 读取指定长度的 m-utf8 字符串 (Java 版用的)
+    ///
+    /// 不合法的部分会被替换成 `U+FFFD`
+    #[inline]
+    pub fn read_mutf8_string(&mut self, len: usize) -> NbtResult<String> {
+        let data = self.read_bytes(len)?;
+        Ok(mutf8::decode_lossy(&data).into_owned())
+    }
+}
+
+/// Java 版 绝大部分的 NBT 格式
+///
+/// 全都是大端
+impl NbtStreamReadTrait for Java {
+    const NESTED_COMPOUND_NAMED: bool = true;
+
+    #[inline]
+    fn read_i16<R: Read>(reader: &mut NbtStreamReader<R>) -> NbtResult<i16> { reader.read_be_i16() }
+    #[inline]
+    fn read_i32<R: Read>(reader: &mut NbtStreamReader<R>) -> NbtResult<i32> { reader.read_be_i32() }
+    #[inline]
+    fn read_i64<R: Read>(reader: &mut NbtStreamReader<R>) -> NbtResult<i64> { reader.read_be_i64() }
+    #[inline]
+    fn read_f32<R: Read>(reader: &mut NbtStreamReader<R>) -> NbtResult<f32> { reader.read_be_f32() }
+    #[inline]
+    fn read_f64<R: Read>(reader: &mut NbtStreamReader<R>) -> NbtResult<f64> { reader.read_be_f64() }
+    #[inline]
+    fn read_len<R: Read>(reader: &mut NbtStreamReader<R>) -> NbtResult<usize> {
+        Ok(reader.read_be_i32()? as usize)
+    }
+    #[inline]
+    fn read_nbt_string<R: Read>(reader: &mut NbtStreamReader<R>) -> NbtResult<String> {
+        let len = reader.read_be_u16()? as usize;
+        reader.read_mutf8_string(len)
+    }
+}
+
+/// 网络 NBT: 1.20.2+ 的网络 NBT 根节点没有名字
+impl NbtStreamReadTrait for JavaNetAfter1_20_2 {
+    const ROOT_NAMED: bool = false;
+    const NESTED_COMPOUND_NAMED: bool = true;
+
+    #[inline]
+    fn read_i16<R: Read>(reader: &mut NbtStreamReader<R>) -> NbtResult<i16> {
+        Java::read_i16(reader)
+    }
+    #[inline]
+    fn read_i32<R: Read>(reader: &mut NbtStreamReader<R>) -> NbtResult<i32> {
+        Java::read_i32(reader)
+    }
+    #[inline]
+    fn read_i64<R: Read>(reader: &mut NbtStreamReader<R>) -> NbtResult<i64> {
+        Java::read_i64(reader)
+    }
+    #[inline]
+    fn read_f32<R: Read>(reader: &mut NbtStreamReader<R>) -> NbtResult<f32> {
+        Java::read_f32(reader)
+    }
+    #[inline]
+    fn read_f64<R: Read>(reader: &mut NbtStreamReader<R>) -> NbtResult<f64> {
+        Java::read_f64(reader)
+    }
+    #[inline]
+    fn read_len<R: Read>(reader: &mut NbtStreamReader<R>) -> NbtResult<usize> {
+        Java::read_len(reader)
+    }
+    #[inline]
+    fn read_nbt_string<R: Read>(reader: &mut NbtStreamReader<R>) -> NbtResult<String> {
+        Java::read_nbt_string(reader)
+    }
+}
+
+/// 基岩版 所有都是小端
+impl NbtStreamReadTrait for BedrockDisk {
+    const LIST_ROOT: bool = true;
+
+    #[inline]
+    fn read_i16<R: Read>(reader: &mut NbtStreamReader<R>) -> NbtResult<i16> { reader.read_le_i16() }
+    #[inline]
+    fn read_i32<R: Read>(reader: &mut NbtStreamReader<R>) -> NbtResult<i32> { reader.read_le_i32() }
+    #[inline]
+    fn read_i64<R: Read>(reader: &mut NbtStreamReader<R>) -> NbtResult<i64> { reader.read_le_i64() }
+    #[inline]
+    fn read_f32<R: Read>(reader: &mut NbtStreamReader<R>) -> NbtResult<f32> { reader.read_le_f32() }
+    #[inline]
+    fn read_f64<R: Read>(reader: &mut NbtStreamReader<R>) -> NbtResult<f64> { reader.read_le_f64() }
+    #[inline]
+    fn read_len<R: Read>(reader: &mut NbtStreamReader<R>) -> NbtResult<usize> {
+        Ok(reader.read_le_i32()? as usize)
+    }
+    #[inline]
+    fn read_nbt_string<R: Read>(reader: &mut NbtStreamReader<R>) -> NbtResult<String> {
+        let len = reader.read_le_u16()? as usize;
+        reader.read_string(len)
+    }
+}
+
+/// 最痛苦的来了
+///
+/// int/long 是 zigzag varint, 但是 int/long 数组里面的还是小端
+impl NbtStreamReadTrait for BedrockNetVarInt {
+    const LIST_ROOT: bool = true;
+
+    #[inline]
+    fn read_i16<R: Read>(reader: &mut NbtStreamReader<R>) -> NbtResult<i16> { reader.read_le_i16() }
+    #[inline]
+    fn read_i32<R: Read>(reader: &mut NbtStreamReader<R>) -> NbtResult<i32> {
+        reader.read_zigzag_var_i32()
+    }
+    #[inline]
+    fn read_i64<R: Read>(reader: &mut NbtStreamReader<R>) -> NbtResult<i64> {
+        reader.read_zigzag_var_i64()
+    }
+    #[inline]
+    fn read_f32<R: Read>(reader: &mut NbtStreamReader<R>) -> NbtResult<f32> { reader.read_le_f32() }
+    #[inline]
+    fn read_f64<R: Read>(reader: &mut NbtStreamReader<R>) -> NbtResult<f64> { reader.read_le_f64() }
+    #[inline]
+    fn read_len<R: Read>(reader: &mut NbtStreamReader<R>) -> NbtResult<usize> {
+        Ok(reader.read_zigzag_var_i32()? as usize)
+    }
+    #[inline]
+    fn read_nbt_string<R: Read>(reader: &mut NbtStreamReader<R>) -> NbtResult<String> {
+        let len = reader.read_var_i32()? as usize;
+        reader.read_string(len)
+    }
+    fn read_i32_array<R: Read>(reader: &mut NbtStreamReader<R>) -> NbtResult<Vec<i32>> {
+        let len = Self::read_len(reader)?;
+        let mut value = Vec::with_capacity(len.min(1024));
+        for _ in 0..len {
+            value.push(reader.read_le_i32()?);
+        }
+        Ok(value)
+    }
+    fn read_i64_array<R: Read>(reader: &mut NbtStreamReader<R>) -> NbtResult<Vec<i64>> {
+        let len = Self::read_len(reader)?;
+        let mut value = Vec::with_capacity(len.min(1024));
+        for _ in 0..len {
+            value.push(reader.read_le_i64()?);
+        }
+        Ok(value)
+    }
+}
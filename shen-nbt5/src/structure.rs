@@ -0,0 +1,435 @@
+//! Java 版的结构文件 (结构方块保存的 `.nbt`)
+//!
+//! 格式见 <https://minecraft.wiki/w/Structure_file>
+//!
+//! ```rust
+//! use shen_nbt5::structure::{BlockState, Structure, StructureBlock};
+//!
+//! let mut structure = Structure::new([1, 2, 1]);
+//! structure.palettes = vec![vec![
+//!     BlockState::new("minecraft:oak_fence"),
+//!     BlockState::new("minecraft:lantern").with_property("hanging", "false"),
+//! ]];
+//! structure.blocks.push(StructureBlock::new([0, 0, 0], 0));
+//! structure.blocks.push(StructureBlock::new([0, 1, 0], 1));
+//!
+//! let value = structure.to_value();
+//! let read = Structure::from_value(&value).unwrap();
+//! assert_eq!(read, structure);
+//! let names: Vec<String> = read.placed_blocks().map(|b| b.state.to_string()).collect();
+//! assert_eq!(names, ["minecraft:oak_fence", "minecraft:lantern[hanging=false]"]);
+//! ```
+
+use std::fmt::Display;
+use std::str::FromStr;
+
+use crate::{NbtError, NbtResult, NbtValue};
+
+/// 一个方块状态, 比如 `minecraft:lantern[hanging=false]`
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct BlockState {
+    /// 方块 id, 比如 `minecraft:lantern`
+    pub name: String,
+    /// 方块状态的属性, 按照文件里的顺序
+    pub properties: Vec<(String, String)>,
+}
+
+impl BlockState {
+    /// 没有属性的方块状态
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            properties: Vec::new(),
+        }
+    }
+
+    /// 加上一个属性
+    pub fn with_property(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.properties.push((key.into(), value.into()));
+        self
+    }
+
+    /// 拿到某个属性的值
+    pub fn property(&self, key: &str) -> Option<&str> {
+        self.properties.iter().find(|(k, _)| k == key).map(|(_, v)| v.as_str())
+    }
+
+    /// 从 `{Name: "...", Properties: {...}}` 读取
+    pub fn from_value(value: &NbtValue) -> NbtResult<Self> {
+        let data = compound(value, "palette")?;
+        let name = get(data, "Name")?.as_string()?;
+        let properties = match find(data, "Properties") {
+            Some(properties) => compound(properties, "Properties")?
+                .iter()
+                .map(|(k, v)| Ok((k.clone(), v.as_string()?)))
+                .collect::<NbtResult<_>>()?,
+            None => Vec::new(),
+        };
+        Ok(Self { name, properties })
+    }
+
+    /// 写成 `{Name: "...", Properties: {...}}`, 没有属性的时候不会写 `Properties`
+    pub fn to_value(&self) -> NbtValue {
+        let mut data = vec![("Name".to_string(), NbtValue::String(self.name.clone()))];
+        if !self.properties.is_empty() {
+            let properties = self
+                .properties
+                .iter()
+                .map(|(k, v)| (k.clone(), NbtValue::String(v.clone())))
+                .collect();
+            data.push(("Properties".to_string(), named("Properties", properties)));
+        }
+        NbtValue::Compound(None, data)
+    }
+}
+
+impl Display for BlockState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name)?;
+        if !self.properties.is_empty() {
+            write!(f, "[")?;
+            for (idx, (key, value)) in self.properties.iter().enumerate() {
+                if idx != 0 {
+                    write!(f, ",")?;
+                }
+                write!(f, "{}={}", key, value)?;
+            }
+            write!(f, "]")?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for BlockState {
+    type Err = NbtError;
+
+    /// 解析 `minecraft:lantern[hanging=false]` 这样的字符串
+    fn from_str(s: &str) -> NbtResult<Self> {
+        let err = || NbtError::StructureError(format!("方块状态格式不对: {}", s));
+        let Some((name, rest)) = s.split_once('[') else {
+            return Ok(BlockState::new(s));
+        };
+        let properties = rest.strip_suffix(']').ok_or_else(err)?;
+        let mut state = BlockState::new(name);
+        for property in properties.split(',').filter(|p| !p.is_empty()) {
+            let (key, value) = property.split_once('=').ok_or_else(err)?;
+            state.properties.push((key.to_string(), value.to_string()));
+        }
+        Ok(state)
+    }
+}
+
+/// 结构里的一个方块
+#[derive(Debug, Clone, PartialEq)]
+pub struct StructureBlock {
+    /// 在结构里的坐标
+    pub pos: [i32; 3],
+    /// 在调色板里的下标
+    pub state: usize,
+    /// 方块实体的 NBT (不包括坐标)
+    pub nbt: Option<NbtValue>,
+}
+
+impl StructureBlock {
+    /// 没有方块实体的方块
+    pub fn new(pos: [i32; 3], state: usize) -> Self {
+        Self {
+            pos,
+            state,
+            nbt: None,
+        }
+    }
+
+    fn from_value(value: &NbtValue) -> NbtResult<Self> {
+        let data = compound(value, "blocks")?;
+        let state = get(data, "state")?.as_i32()?;
+        if state < 0 {
+            return Err(NbtError::StructureError(format!("方块状态下标 < 0: {}", state)));
+        }
+        let nbt = match find(data, "nbt") {
+            Some(nbt) => Some(NbtValue::Compound(None, compound(nbt, "nbt")?.clone())),
+            None => None,
+        };
+        Ok(Self {
+            pos: int_pos(get(data, "pos")?)?,
+            state: state as usize,
+            nbt,
+        })
+    }
+
+    fn to_value(&self) -> NbtValue {
+        let mut data = vec![
+            ("state".to_string(), NbtValue::Int(self.state as i32)),
+            (
+                "pos".to_string(),
+                NbtValue::List(self.pos.iter().map(|x| NbtValue::Int(*x)).collect()),
+            ),
+        ];
+        if let Some(NbtValue::Compound(_, nbt)) = &self.nbt {
+            data.push(("nbt".to_string(), named("nbt", nbt.clone())));
+        }
+        NbtValue::Compound(None, data)
+    }
+}
+
+/// 结构里的一个实体
+#[derive(Debug, Clone, PartialEq)]
+pub struct StructureEntity {
+    /// 精确坐标
+    pub pos: [f64; 3],
+    /// 所在的方块坐标
+    pub block_pos: [i32; 3],
+    /// 实体的 NBT (不包括 `Pos` 和 `UUID`)
+    pub nbt: NbtValue,
+}
+
+impl StructureEntity {
+    fn from_value(value: &NbtValue) -> NbtResult<Self> {
+        let data = compound(value, "entities")?;
+        let pos = match get(data, "pos")? {
+            NbtValue::List(list) if list.len() == 3 => {
+                [list[0].as_f64()?, list[1].as_f64()?, list[2].as_f64()?]
+            }
+            _ => return Err(NbtError::StructureError("实体坐标不是 3 个 double".to_string())),
+        };
+        Ok(Self {
+            pos,
+            block_pos: int_pos(get(data, "blockPos")?)?,
+            nbt: NbtValue::Compound(None, compound(get(data, "nbt")?, "nbt")?.clone()),
+        })
+    }
+
+    fn to_value(&self) -> NbtValue {
+        let nbt = match &self.nbt {
+            NbtValue::Compound(_, nbt) => nbt.clone(),
+            _ => Vec::new(),
+        };
+        NbtValue::Compound(
+            None,
+            vec![
+                (
+                    "pos".to_string(),
+                    NbtValue::List(self.pos.iter().map(|x| NbtValue::Double(*x)).collect()),
+                ),
+                (
+                    "blockPos".to_string(),
+                    NbtValue::List(self.block_pos.iter().map(|x| NbtValue::Int(*x)).collect()),
+                ),
+                ("nbt".to_string(), named("nbt", nbt)),
+            ],
+        )
+    }
+}
+
+/// 放在结构里的方块, 已经从调色板里找到了方块状态
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PlacedBlock<'a> {
+    /// 在结构里的坐标
+    pub pos: [i32; 3],
+    /// 方块状态
+    pub state: &'a BlockState,
+    /// 方块实体的 NBT
+    pub nbt: Option<&'a NbtValue>,
+}
+
+/// 一个结构文件
+#[derive(Debug, Clone, PartialEq)]
+pub struct Structure {
+    /// 数据版本, 老的结构文件可能没有
+    pub data_version: Option<i32>,
+    /// 结构的大小
+    pub size: [i32; 3],
+    /// 调色板
+    ///
+    /// 文件里是 `palette` 的时候只有一个, 是 `palettes` (比如沉船) 的时候每个都是一种随机的样子
+    pub palettes: Vec<Vec<BlockState>>,
+    /// 所有的方块 (不包括结构空位)
+    pub blocks: Vec<StructureBlock>,
+    /// 所有的实体
+    pub entities: Vec<StructureEntity>,
+    /// 不认识的其他字段, 比如老版本的 `author`, 写入的时候会原样写回去
+    pub extra: Vec<(String, NbtValue)>,
+}
+
+impl Structure {
+    /// 一个空的结构
+    pub fn new(size: [i32; 3]) -> Self {
+        Self {
+            data_version: None,
+            size,
+            palettes: vec![Vec::new()],
+            blocks: Vec::new(),
+            entities: Vec::new(),
+            extra: Vec::new(),
+        }
+    }
+
+    /// 从读出来的 NBT 转换
+    ///
+    /// 方块的状态下标超出调色板的时候会返回 [`NbtError::StructureError`]
+    pub fn from_value(value: &NbtValue) -> NbtResult<Self> {
+        let data = compound(value, "(根节点)")?;
+        let mut structure = Structure::new([0; 3]);
+        let mut palettes = None;
+        for (key, value) in data {
+            match key.as_str() {
+                "DataVersion" => structure.data_version = Some(value.as_i32()?),
+                "size" => structure.size = int_pos(value)?,
+                "palette" => palettes = Some(vec![read_palette(value)?]),
+                "palettes" => {
+                    palettes = Some(
+                        list(value, "palettes")?
+                            .iter()
+                            .map(read_palette)
+                            .collect::<NbtResult<_>>()?,
+                    )
+                }
+                "blocks" => {
+                    structure.blocks = list(value, "blocks")?
+                        .iter()
+                        .map(StructureBlock::from_value)
+                        .collect::<NbtResult<_>>()?
+                }
+                "entities" => {
+                    structure.entities = list(value, "entities")?
+                        .iter()
+                        .map(StructureEntity::from_value)
+                        .collect::<NbtResult<_>>()?
+                }
+                _ => structure.extra.push((key.clone(), value.clone())),
+            }
+        }
+        structure.palettes = palettes
+            .ok_or_else(|| NbtError::StructureError("缺少 palette 或者 palettes".to_string()))?;
+        let len = structure.palettes.iter().map(Vec::len).min().unwrap_or(0);
+        if let Some(block) = structure.blocks.iter().find(|b| b.state >= len) {
+            return Err(NbtError::StructureError(format!(
+                "方块 {:?} 的状态下标 {} 超出了调色板的长度 {}",
+                block.pos, block.state, len
+            )));
+        }
+        Ok(structure)
+    }
+
+    /// 转换成可以直接写入的 NBT (根节点名字是空的)
+    ///
+    /// 只有一个调色板的时候写成 `palette`, 不然写成 `palettes`
+    pub fn to_value(&self) -> NbtValue {
+        let mut data = Vec::with_capacity(6 + self.extra.len());
+        data.push((
+            "size".to_string(),
+            NbtValue::List(self.size.iter().map(|x| NbtValue::Int(*x)).collect()),
+        ));
+        if let [palette] = self.palettes.as_slice() {
+            data.push(("palette".to_string(), write_palette(palette)));
+        } else {
+            data.push((
+                "palettes".to_string(),
+                NbtValue::List(self.palettes.iter().map(|p| write_palette(p)).collect()),
+            ));
+        }
+        data.push((
+            "blocks".to_string(),
+            NbtValue::List(self.blocks.iter().map(StructureBlock::to_value).collect()),
+        ));
+        data.push((
+            "entities".to_string(),
+            NbtValue::List(self.entities.iter().map(StructureEntity::to_value).collect()),
+        ));
+        if let Some(version) = self.data_version {
+            data.push(("DataVersion".to_string(), NbtValue::Int(version)));
+        }
+        data.extend(self.extra.iter().cloned());
+        NbtValue::Compound(Some(String::new()), data)
+    }
+
+    /// 第一个调色板
+    pub fn palette(&self) -> &[BlockState] {
+        self.palettes.first().map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// 用第一个调色板遍历所有的方块
+    pub fn placed_blocks(&self) -> impl Iterator<Item = PlacedBlock<'_>> {
+        self.placed_blocks_with(0).into_iter().flatten()
+    }
+
+    /// 用第 `palette` 个调色板遍历所有的方块, 没有这个调色板的时候返回 `None`
+    pub fn placed_blocks_with(
+        &self,
+        palette: usize,
+    ) -> Option<impl Iterator<Item = PlacedBlock<'_>>> {
+        let palette = self.palettes.get(palette)?;
+        Some(self.blocks.iter().filter_map(move |block| {
+            Some(PlacedBlock {
+                pos: block.pos,
+                state: palette.get(block.state)?,
+                nbt: block.nbt.as_ref(),
+            })
+        }))
+    }
+}
+
+impl TryFrom<&NbtValue> for Structure {
+    type Error = NbtError;
+
+    fn try_from(value: &NbtValue) -> NbtResult<Self> { Structure::from_value(value) }
+}
+
+impl From<&Structure> for NbtValue {
+    fn from(structure: &Structure) -> Self { structure.to_value() }
+}
+
+fn read_palette(value: &NbtValue) -> NbtResult<Vec<BlockState>> {
+    list(value, "palette")?.iter().map(BlockState::from_value).collect()
+}
+
+fn write_palette(palette: &[BlockState]) -> NbtValue {
+    NbtValue::List(palette.iter().map(BlockState::to_value).collect())
+}
+
+/// compound 里的 compound 要带上自己的 key 作为名字, 不然 Java 写不出 key
+pub(crate) fn named(key: &str, data: Vec<(String, NbtValue)>) -> NbtValue {
+    NbtValue::Compound(Some(key.to_string()), data)
+}
+
+pub(crate) fn compound<'a>(
+    value: &'a NbtValue,
+    what: &str,
+) -> NbtResult<&'a Vec<(String, NbtValue)>> {
+    match value {
+        NbtValue::Compound(_, data) => Ok(data),
+        _ => Err(NbtError::StructureError(format!(
+            "{} 应该是 NbtCompound, 实际是 {}",
+            what,
+            value.tag()
+        ))),
+    }
+}
+
+pub(crate) fn list<'a>(value: &'a NbtValue, what: &str) -> NbtResult<&'a Vec<NbtValue>> {
+    match value {
+        NbtValue::List(data) => Ok(data),
+        _ => Err(NbtError::StructureError(format!(
+            "{} 应该是 NbtList, 实际是 {}",
+            what,
+            value.tag()
+        ))),
+    }
+}
+
+pub(crate) fn find<'a>(data: &'a [(String, NbtValue)], key: &str) -> Option<&'a NbtValue> {
+    data.iter().find(|(k, _)| k == key).map(|(_, v)| v)
+}
+
+pub(crate) fn get<'a>(data: &'a [(String, NbtValue)], key: &str) -> NbtResult<&'a NbtValue> {
+    find(data, key).ok_or_else(|| NbtError::StructureError(format!("缺少 {}", key)))
+}
+
+pub(crate) fn int_pos(value: &NbtValue) -> NbtResult<[i32; 3]> {
+    match value {
+        NbtValue::List(list) if list.len() == 3 => {
+            Ok([list[0].as_i32()?, list[1].as_i32()?, list[2].as_i32()?])
+        }
+        _ => Err(NbtError::StructureError("坐标不是 3 个 int".to_string())),
+    }
+}
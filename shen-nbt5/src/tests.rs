@@ -1,4 +1,4 @@
-use crate::{nbt_version, NbtReader, NbtTypeConversion, NbtValue};
+use crate::{nbt_version, NbtError, NbtReader, NbtTypeConversion, NbtValue};
 
 /// 生成测试数据
 pub fn gen_datas(len: usize) -> Vec<u8> {
@@ -323,6 +323,7 @@ mod unsafe_test {
 }
 
 mod nbt {
+    #[cfg(feature = "test")]
     use std::io::Read;
 
     use super::*;
@@ -376,6 +377,843 @@ mod nbt {
         assert_eq!(buff, data);
     }
 
+    #[test]
+    fn string_encoding() {
+        let value = NbtValue::Compound(
+            Some("".to_string()),
+            vec![("名字".to_string(), NbtValue::String("😀\0".to_string()))],
+        );
+        // Java 版是 m-utf8
+        let mut java = value.to_binary::<nbt_version::Java>().unwrap();
+        assert!(java.ends_with(&[0x08, 0xED, 0xA0, 0xBD, 0xED, 0xB8, 0x80, 0xC0, 0x80, 0x00]));
+        // 基岩版是 utf-8
+        let bedrock = value.to_binary::<nbt_version::BedrockDisk>().unwrap();
+        assert!(bedrock.ends_with(&[0x05, 0x00, 0xF0, 0x9F, 0x98, 0x80, 0x00, 0x00]));
+        // 转过来转过去都不会变
+        let from_java = NbtValue::from_binary::<nbt_version::Java>(&mut java).unwrap();
+        assert_eq!(from_java, value);
+        let mut to_bedrock = from_java.to_binary::<nbt_version::BedrockDisk>().unwrap();
+        assert_eq!(to_bedrock, bedrock);
+        let from_bedrock = NbtValue::from_binary::<nbt_version::BedrockDisk>(&mut to_bedrock);
+        assert_eq!(from_bedrock, Ok(value));
+    }
+
+    #[test]
+    fn len_too_big() {
+        let long = NbtValue::String("a".repeat(70000));
+        let tag = NbtValue::Compound(Some("tag".to_string()), vec![("Name".to_string(), long)]);
+        let item = NbtValue::Compound(None, vec![("tag".to_string(), tag)]);
+        let value = NbtValue::Compound(
+            Some("".to_string()),
+            vec![("Inventory".to_string(), NbtValue::List(vec![item]))],
+        );
+        let err = NbtError::LenTooBig("Inventory[0].tag.Name".to_string(), 70000, 65535);
+        assert_eq!(value.to_binary::<nbt_version::Java>(), Err(err.clone()));
+        assert_eq!(value.to_binary::<nbt_version::JavaNetAfter1_20_2>(), Err(err.clone()));
+        assert_eq!(value.to_binary::<nbt_version::BedrockDisk>(), Err(err));
+        // 基岩版网络格式的字符串长度是 VarInt
+        assert!(value.to_binary::<nbt_version::BedrockNetVarInt>().is_ok());
+        // 算的是编码之后的长度, `\0` 在 m-utf8 里是两个字节
+        let value = NbtValue::Compound(
+            None,
+            vec![("a b".to_string(), NbtValue::String("\0".repeat(40000)))],
+        );
+        let err = value.to_binary::<nbt_version::Java>().unwrap_err();
+        assert_eq!(err.to_string(), "\"a b\" 长度过大: 80000, 最大为 65535");
+        assert!(value.to_binary::<nbt_version::BedrockDisk>().is_ok());
+    }
+
+    #[test]
+    fn write_to_io() {
+        use std::io::Read;
+
+        let value = NbtValue::Compound(
+            Some("root".to_string()),
+            vec![
+                ("name".to_string(), NbtValue::String("Bananrama".to_string())),
+                ("list".to_string(), NbtValue::List(vec![NbtValue::Int(1), NbtValue::Int(2)])),
+                ("longs".to_string(), NbtValue::LongArray(vec![i64::MIN, 0, i64::MAX])),
+            ],
+        );
+        fn check<V: nbt_version::NbtWriteTrait>(value: &NbtValue) {
+            let expect = value.to_binary::<V>().unwrap();
+            // 直接写进 GzEncoder
+            let mut encoder = flate2::write::GzEncoder::new(Vec::new(), Default::default());
+            value.write_to::<V>(&mut encoder).unwrap();
+            let compressed = encoder.finish().unwrap();
+            let mut data = Vec::new();
+            flate2::read::GzDecoder::new(compressed.as_slice())
+                .read_to_end(&mut data)
+                .unwrap();
+            assert_eq!(data, expect);
+            // 写不下的时候返回 IO 错误
+            let mut buff = [0_u8; 8];
+            let result = value.write_to::<V>(&mut &mut buff[..]);
+            assert!(matches!(result, Err(NbtError::IoError(std::io::ErrorKind::WriteZero, _))));
+        }
+        check::<nbt_version::Java>(&value);
+        check::<nbt_version::JavaNetAfter1_20_2>(&value);
+        check::<nbt_version::BedrockDisk>(&value);
+        check::<nbt_version::BedrockNetVarInt>(&value);
+    }
+
+    #[test]
+    fn read_from_stream() {
+        use std::io::Write;
+
+        // 只有 Java 版写入的时候会用 Compound 的名字代替 key
+        let value = |tag_name: Option<&str>| {
+            NbtValue::Compound(
+                Some("root".to_string()),
+                vec![
+                    ("name".to_string(), NbtValue::String("名字😀".to_string())),
+                    ("list".to_string(), NbtValue::List(vec![NbtValue::Int(-1), NbtValue::Int(2)])),
+                    ("bytes".to_string(), NbtValue::ByteArray(vec![-1, 0, 1])),
+                    ("ints".to_string(), NbtValue::IntArray(vec![i32::MIN, 0, i32::MAX])),
+                    ("longs".to_string(), NbtValue::LongArray(vec![i64::MIN, 0, i64::MAX])),
+                    (
+                        "tag".to_string(),
+                        NbtValue::Compound(
+                            tag_name.map(str::to_string),
+                            vec![("pi".to_string(), NbtValue::Double(std::f64::consts::PI))],
+                        ),
+                    ),
+                ],
+            )
+        };
+        fn check<V: nbt_version::NbtReadTrait + nbt_version::NbtStreamReadTrait>(data: &[u8]) {
+            // 从 GzDecoder 里直接读
+            let mut encoder = flate2::write::GzEncoder::new(Vec::new(), Default::default());
+            encoder.write_all(data).unwrap();
+            let compressed = encoder.finish().unwrap();
+            let from_stream =
+                NbtValue::from_stream::<V>(flate2::read::GzDecoder::new(compressed.as_slice()));
+            let expect = NbtValue::from_binary::<V>(&mut data.to_vec()).unwrap();
+            assert_eq!(from_stream, Ok(expect));
+            // 数据不够的时候不会 panic
+            for len in 0..data.len() {
+                let result = NbtValue::from_stream::<V>(&data[..len]);
+                assert!(matches!(result, Err(NbtError::UnexpectedEof(_))), "{}", len);
+            }
+        }
+        let java = value(Some("tag")).to_binary::<nbt_version::Java>().unwrap();
+        check::<nbt_version::Java>(&java);
+        let java_net = value(None).to_binary::<nbt_version::JavaNetAfter1_20_2>().unwrap();
+        check::<nbt_version::JavaNetAfter1_20_2>(&java_net);
+        let bedrock = value(None).to_binary::<nbt_version::BedrockDisk>().unwrap();
+        check::<nbt_version::BedrockDisk>(&bedrock);
+        // 基岩版的根节点可以是 List
+        let list = NbtValue::List(vec![NbtValue::String("a".to_string())]);
+        check::<nbt_version::BedrockDisk>(&list.to_binary::<nbt_version::BedrockDisk>().unwrap());
+        // {"": {a: -1, b: [I; 1]}, ["hi"]}
+        check::<nbt_version::BedrockNetVarInt>(&[
+            0x0A, 0x00, 0x03, 0x01, b'a', 0x01, 0x0B, 0x01, b'b', 0x02, 0x01, 0x00, 0x00, 0x00,
+            0x00,
+        ]);
+        check::<nbt_version::BedrockNetVarInt>(&[0x09, 0x08, 0x02, 0x02, b'h', b'i']);
+    }
+
+    #[test]
+    fn stream_depth_limit() {
+        // {"": [[[[ ... 一直套下去, 根节点算一层
+        let nested = |depth: usize| {
+            let mut data = vec![0x0A, 0x00, 0x00, 0x09, 0x00, 0x00];
+            for _ in 0..depth - 2 {
+                data.extend_from_slice(&[0x09, 0x00, 0x00, 0x00, 0x01]);
+            }
+            data
+        };
+        let data = nested(crate::RECURSE_LIMIT + 1);
+        let result = NbtValue::from_stream::<nbt_version::Java>(data.as_slice());
+        assert_eq!(result, Err(NbtError::NbtDepthTooBig(crate::RECURSE_LIMIT)));
+        // 没到限制的时候只是数据不够
+        let data = nested(crate::RECURSE_LIMIT);
+        let result = NbtValue::from_stream::<nbt_version::Java>(data.as_slice());
+        assert!(matches!(result, Err(NbtError::UnexpectedEof(_))));
+        // 一个很长的流也不会爆栈
+        let data = nested(100_000);
+        let result = NbtValue::from_stream::<nbt_version::Java>(data.as_slice());
+        assert_eq!(result, Err(NbtError::NbtDepthTooBig(crate::RECURSE_LIMIT)));
+    }
+
+    #[test]
+    #[cfg(feature = "compression")]
+    fn compressed() {
+        use crate::compression::Compression;
+
+        let value = NbtValue::Compound(
+            Some("hello world".to_string()),
+            vec![("name".to_string(), NbtValue::String("Bananrama".to_string()))],
+        );
+        for compression in [Compression::Gzip, Compression::Zlib, Compression::None] {
+            let data = value.to_compressed::<nbt_version::Java>(compression).unwrap();
+            assert_eq!(Compression::detect(&data), compression);
+            let read = NbtValue::from_compressed::<nbt_version::Java>(&data);
+            assert_eq!(read, Ok(value.clone()));
+        }
+        let path = std::env::temp_dir().join(format!("shen-nbt5-{}.nbt", std::process::id()));
+        let data = value.to_compressed::<nbt_version::BedrockDisk>(Compression::Zlib).unwrap();
+        std::fs::write(&path, data).unwrap();
+        let read = NbtValue::from_file::<nbt_version::BedrockDisk>(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(read, Ok(value));
+        let missing = NbtValue::from_file::<nbt_version::Java>(path);
+        assert!(matches!(missing, Err(NbtError::IoError(std::io::ErrorKind::NotFound, _))));
+    }
+
+    #[test]
+    fn structure_file() {
+        use crate::structure::{BlockState, Structure, StructureBlock};
+
+        let mut structure = Structure::new([1, 3, 1]);
+        structure.data_version = Some(3953);
+        structure.palettes = vec![
+            vec![BlockState::new("minecraft:oak_fence"), BlockState::new("minecraft:chest")],
+            vec![
+                BlockState::new("minecraft:spruce_fence"),
+                BlockState::new("minecraft:chest").with_property("facing", "north"),
+            ],
+        ];
+        structure.blocks.push(StructureBlock::new([0, 0, 0], 0));
+        let mut chest = StructureBlock::new([0, 1, 0], 1);
+        chest.nbt = Some(NbtValue::Compound(
+            None,
+            vec![
+                ("id".to_string(), NbtValue::String("minecraft:chest".to_string())),
+                ("Items".to_string(), NbtValue::List(vec![])),
+            ],
+        ));
+        structure.blocks.push(chest);
+        structure.entities.push(crate::structure::StructureEntity {
+            pos: [0.5, 2.0, 0.5],
+            block_pos: [0, 2, 0],
+            nbt: NbtValue::Compound(
+                None,
+                vec![("id".to_string(), NbtValue::String("minecraft:armor_stand".to_string()))],
+            ),
+        });
+        structure
+            .extra
+            .push(("author".to_string(), NbtValue::String("shenjack".to_string())));
+
+        // 写成二进制再读回来
+        let mut data = structure.to_value().to_binary::<nbt_version::Java>().unwrap();
+        let value = NbtValue::from_binary::<nbt_version::Java>(&mut data).unwrap();
+        let read = Structure::from_value(&value).unwrap();
+        assert_eq!(read, structure);
+        assert_eq!(read.placed_blocks().count(), 2);
+        let placed: Vec<_> = read.placed_blocks_with(1).unwrap().collect();
+        assert_eq!(placed[1].state.to_string(), "minecraft:chest[facing=north]");
+        assert_eq!(placed[1].state.property("facing"), Some("north"));
+        assert_eq!(placed[1].nbt, structure.blocks[1].nbt.as_ref());
+        assert!(read.placed_blocks_with(2).is_none());
+
+        // 只有一个调色板的时候写成 palette
+        structure.palettes.pop();
+        let NbtValue::Compound(_, data) = structure.to_value() else {
+            unreachable!()
+        };
+        assert!(data.iter().any(|(k, _)| k == "palette"));
+        assert!(!data.iter().any(|(k, _)| k == "palettes"));
+
+        // 下标超出调色板
+        structure.blocks.push(StructureBlock::new([0, 2, 0], 2));
+        let result = Structure::try_from(&structure.to_value());
+        assert!(matches!(result, Err(NbtError::StructureError(_))));
+        let empty = NbtValue::Compound(Some(String::new()), vec![]);
+        assert!(matches!(Structure::from_value(&empty), Err(NbtError::StructureError(_))));
+    }
+
+    /// 一个有很多种方块的区域, 还有方块实体和实体
+    fn schematic_volume() -> crate::schematic::BlockVolume {
+        use crate::schematic::{BlockEntity, BlockVolume, Entity};
+        use crate::structure::BlockState;
+
+        let mut volume = BlockVolume::new([7, 5, 9], BlockState::new("minecraft:air")).unwrap();
+        volume.data_version = Some(3953);
+        for idx in 0..volume.volume() {
+            let pos = volume.pos(idx);
+            let state =
+                BlockState::new("minecraft:wool").with_property("n", (idx % 200).to_string());
+            volume.set(pos, state);
+        }
+        volume.set([0, 0, 0], BlockState::new("minecraft:air"));
+        let string = |s: &str| NbtValue::String(s.to_string());
+        volume.block_entities.push(BlockEntity {
+            pos: [1, 2, 3],
+            nbt: NbtValue::Compound(
+                None,
+                vec![
+                    ("id".to_string(), string("minecraft:sign")),
+                    (
+                        "front_text".to_string(),
+                        NbtValue::Compound(Some("front_text".to_string()), vec![]),
+                    ),
+                ],
+            ),
+        });
+        volume.entities.push(Entity {
+            pos: [0.5, 1.0, 2.5],
+            nbt: NbtValue::Compound(None, vec![("id".to_string(), string("minecraft:pig"))]),
+        });
+        volume
+    }
+
+    #[test]
+    fn schematic_sponge() {
+        use crate::schematic::sponge;
+
+        let volume = schematic_volume();
+        assert_eq!(volume.palette.len(), 201);
+        let mut data = sponge::encode(&volume).unwrap().to_binary::<nbt_version::Java>().unwrap();
+        let value = NbtValue::from_binary::<nbt_version::Java>(&mut data).unwrap();
+        assert_eq!(sponge::decode(&value).unwrap(), volume);
+        #[cfg(feature = "compression")]
+        {
+            let data = sponge::to_bytes(&volume).unwrap();
+            assert_eq!(&data[..2], &[0x1F, 0x8B]);
+            assert_eq!(sponge::from_bytes(&data).unwrap(), volume);
+        }
+
+        // v2: 调色板和方块数据直接放在根节点里, 方块实体的数据不在 Data 里
+        let string = |s: &str| NbtValue::String(s.to_string());
+        let value = NbtValue::Compound(
+            Some("Schematic".to_string()),
+            vec![
+                ("Version".to_string(), NbtValue::Int(2)),
+                ("Width".to_string(), NbtValue::Short(2)),
+                ("Height".to_string(), NbtValue::Short(1)),
+                ("Length".to_string(), NbtValue::Short(1)),
+                (
+                    "Palette".to_string(),
+                    NbtValue::Compound(
+                        Some("Palette".to_string()),
+                        vec![
+                            (
+                                "minecraft:chest[facing=west,type=single]".to_string(),
+                                NbtValue::Int(1),
+                            ),
+                            ("minecraft:stone".to_string(), NbtValue::Int(0)),
+                        ],
+                    ),
+                ),
+                ("BlockData".to_string(), NbtValue::ByteArray(vec![1, 0])),
+                (
+                    "BlockEntities".to_string(),
+                    NbtValue::List(vec![NbtValue::Compound(
+                        None,
+                        vec![
+                            ("Pos".to_string(), NbtValue::IntArray(vec![0, 0, 0])),
+                            ("Id".to_string(), string("minecraft:chest")),
+                            ("Lock".to_string(), string("key")),
+                        ],
+                    )]),
+                ),
+            ],
+        );
+        let volume = sponge::decode(&value).unwrap();
+        assert_eq!(volume.get([0, 0, 0]).unwrap().property("type"), Some("single"));
+        assert_eq!(volume.get([1, 0, 0]).unwrap().name, "minecraft:stone");
+        let expect = NbtValue::Compound(
+            None,
+            vec![
+                ("id".to_string(), string("minecraft:chest")),
+                ("Lock".to_string(), string("key")),
+            ],
+        );
+        assert_eq!(volume.block_entity([0, 0, 0]), Some(&expect));
+        assert_eq!(volume.data_version, None);
+
+        // 方块数据不够
+        let NbtValue::Compound(name, mut data) = value else {
+            unreachable!()
+        };
+        data[5].1 = NbtValue::ByteArray(vec![1]);
+        let result = sponge::decode(&NbtValue::Compound(name.clone(), data.clone()));
+        assert!(matches!(result, Err(NbtError::StructureError(_))));
+
+        // 大小是 65535 的时候不能先按照大小分配
+        let mut huge = data.clone();
+        for (_, size) in &mut huge[1..4] {
+            *size = NbtValue::Short(-1);
+        }
+        let result = sponge::decode(&NbtValue::Compound(name.clone(), huge));
+        assert!(matches!(result, Err(NbtError::StructureError(_))));
+        // 调色板下标太大
+        data[4].1 = NbtValue::Compound(
+            Some("Palette".to_string()),
+            vec![("minecraft:air".to_string(), NbtValue::Int(i32::MAX))],
+        );
+        let result = sponge::decode(&NbtValue::Compound(name, data));
+        assert!(matches!(result, Err(NbtError::StructureError(_))));
+    }
+
+    #[test]
+    fn schematic_litematica() {
+        use crate::schematic::litematica::{Litematic, Region};
+        use crate::schematic::BlockVolume;
+        use crate::structure::BlockState;
+
+        let mut litematic = Litematic::new("test", schematic_volume());
+        litematic.author = "shenjack".to_string();
+        let mut small = BlockVolume::new([2, 1, 2], BlockState::new("minecraft:air")).unwrap();
+        small.set([0, 0, 0], BlockState::new("minecraft:stone"));
+        small.set([0, 0, 1], BlockState::new("minecraft:stone"));
+        small.set([1, 0, 1], BlockState::new("minecraft:stone"));
+        small.data_version = Some(3953);
+        litematic.regions.push(Region {
+            name: "small".to_string(),
+            position: [-2, 0, 5],
+            volume: small,
+        });
+        let value = litematic.encode().unwrap();
+        let mut data = value.to_binary::<nbt_version::Java>().unwrap();
+        let read =
+            Litematic::decode(&NbtValue::from_binary::<nbt_version::Java>(&mut data).unwrap());
+        assert_eq!(read.unwrap(), litematic);
+
+        // 两位一个方块: 1, 0, 1, 1
+        let NbtValue::Compound(_, root) = &value else {
+            unreachable!()
+        };
+        let regions = &root.iter().find(|(k, _)| k == "Regions").unwrap().1;
+        let NbtValue::Compound(_, regions) = regions else {
+            unreachable!()
+        };
+        let NbtValue::Compound(_, small) = &regions[1].1 else {
+            unreachable!()
+        };
+        let states = small.iter().find(|(k, _)| k == "BlockStates").unwrap();
+        assert_eq!(states.1, NbtValue::LongArray(vec![0b01_01_00_01]));
+
+        // 负的大小
+        let mut small = small.clone();
+        let size = NbtValue::Compound(
+            Some("Size".to_string()),
+            vec![
+                ("x".to_string(), NbtValue::Int(-2)),
+                ("y".to_string(), NbtValue::Int(1)),
+                ("z".to_string(), NbtValue::Int(-2)),
+            ],
+        );
+        small.iter_mut().find(|(k, _)| k == "Size").unwrap().1 = size;
+        let value = NbtValue::Compound(
+            None,
+            vec![
+                ("Version".to_string(), NbtValue::Int(5)),
+                (
+                    "Regions".to_string(),
+                    NbtValue::Compound(
+                        Some("Regions".to_string()),
+                        vec![("a".to_string(), NbtValue::Compound(Some("a".to_string()), small))],
+                    ),
+                ),
+            ],
+        );
+        let read = Litematic::decode(&value).unwrap();
+        assert_eq!(read.version, 5);
+        assert_eq!(read.regions[0].position, [-3, 0, 4]);
+        assert_eq!(read.regions[0].volume.size, [2, 1, 2]);
+        assert_eq!(read.regions[0].volume.get([1, 0, 1]).unwrap().name, "minecraft:stone");
+
+        // 大小溢出
+        let NbtValue::Compound(_, mut root) = value else {
+            unreachable!()
+        };
+        for (x, y, z) in [(i32::MIN, 1, 1), (i32::MAX, i32::MAX, i32::MAX)] {
+            let NbtValue::Compound(_, regions) = &mut root[1].1 else {
+                unreachable!()
+            };
+            let NbtValue::Compound(_, region) = &mut regions[0].1 else {
+                unreachable!()
+            };
+            let size = &mut region.iter_mut().find(|(k, _)| k == "Size").unwrap().1;
+            *size = NbtValue::Compound(
+                Some("Size".to_string()),
+                vec![
+                    ("x".to_string(), NbtValue::Int(x)),
+                    ("y".to_string(), NbtValue::Int(y)),
+                    ("z".to_string(), NbtValue::Int(z)),
+                ],
+            );
+            let result = Litematic::decode(&NbtValue::Compound(None, root.clone()));
+            assert!(matches!(result, Err(NbtError::StructureError(_))));
+        }
+        // 能读出来, 但是位置加上大小溢出了
+        let mut far = read;
+        far.regions[0].position = [i32::MAX, 0, 0];
+        far.regions[0].volume =
+            BlockVolume::new([1, 1, 1], BlockState::new("minecraft:air")).unwrap();
+        assert!(matches!(far.encode(), Err(NbtError::StructureError(_))));
+        let result = BlockVolume::new([i32::MAX; 3], BlockState::new("minecraft:air"));
+        assert!(matches!(result, Err(NbtError::StructureError(_))));
+        #[cfg(feature = "compression")]
+        assert_eq!(Litematic::from_bytes(&litematic.to_bytes().unwrap()).unwrap(), litematic);
+    }
+
+    #[test]
+    fn schematic_mcedit() {
+        use crate::schematic::mcedit;
+        use crate::structure::BlockState;
+
+        let value = NbtValue::Compound(
+            Some("Schematic".to_string()),
+            vec![
+                ("Width".to_string(), NbtValue::Short(3)),
+                ("Height".to_string(), NbtValue::Short(1)),
+                ("Length".to_string(), NbtValue::Short(1)),
+                ("Materials".to_string(), NbtValue::String("Alpha".to_string())),
+                ("Blocks".to_string(), NbtValue::ByteArray(vec![0, 35, 1])),
+                ("Data".to_string(), NbtValue::ByteArray(vec![0, 14, 0])),
+                // 第 3 个方块的 id 是 0x101
+                ("AddBlocks".to_string(), NbtValue::ByteArray(vec![0, 0x01])),
+                (
+                    "TileEntities".to_string(),
+                    NbtValue::List(vec![NbtValue::Compound(
+                        None,
+                        vec![
+                            ("id".to_string(), NbtValue::String("Chest".to_string())),
+                            ("x".to_string(), NbtValue::Int(2)),
+                            ("y".to_string(), NbtValue::Int(0)),
+                            ("z".to_string(), NbtValue::Int(0)),
+                        ],
+                    )]),
+                ),
+            ],
+        );
+        let volume = mcedit::decode(&value).unwrap();
+        let names: Vec<String> = volume.iter().map(|(_, s)| s.to_string()).collect();
+        assert_eq!(names, ["minecraft:air", "legacy:35[data=14]", "legacy:257[data=0]"]);
+        assert!(volume.block_entity([2, 0, 0]).is_some());
+
+        let volume = mcedit::decode_with(&value, |id, data| match (id, data) {
+            (35, 14) => BlockState::new("minecraft:red_wool"),
+            _ => mcedit::legacy_state(id, data),
+        })
+        .unwrap();
+        assert_eq!(volume.get([1, 0, 0]).unwrap().name, "minecraft:red_wool");
+
+        let NbtValue::Compound(name, mut data) = value else {
+            unreachable!()
+        };
+        let mut huge = data.clone();
+        for (_, size) in &mut huge[0..3] {
+            *size = NbtValue::Short(-1);
+        }
+        let result = mcedit::decode(&NbtValue::Compound(name.clone(), huge));
+        assert!(matches!(result, Err(NbtError::StructureError(_))));
+
+        data[3].1 = NbtValue::String("Pocket".to_string());
+        let result = mcedit::decode(&NbtValue::Compound(name, data));
+        assert!(matches!(result, Err(NbtError::StructureError(_))));
+    }
+
+    #[test]
+    fn bedrock_mcstructure() {
+        use crate::mcstructure::{BedrockBlockState, McStructure};
+
+        let mut structure = McStructure::new([2, 3, 4]);
+        structure.world_origin = [100, 64, -20];
+        let mut stairs = BedrockBlockState::new("minecraft:oak_stairs");
+        stairs.states.push(("upside_down_bit".to_string(), NbtValue::Byte(0)));
+        stairs.states.push(("weirdo_direction".to_string(), NbtValue::Int(2)));
+        stairs.version = 18_090_528;
+        structure.palette = vec![
+            BedrockBlockState::new("minecraft:air"),
+            stairs,
+            BedrockBlockState::new("minecraft:water"),
+        ];
+        structure.layers[0] = (0..24).map(|i| i % 2).collect();
+        structure.layers[1][1] = 2;
+        let chest = NbtValue::Compound(
+            None,
+            vec![(
+                "block_entity_data".to_string(),
+                NbtValue::Compound(
+                    None,
+                    vec![("id".to_string(), NbtValue::String("Chest".to_string()))],
+                ),
+            )],
+        );
+        structure.block_position_data.insert(3, chest.clone());
+
+        let mut data = structure.to_binary().unwrap();
+        let read = McStructure::from_binary(&mut data).unwrap();
+        assert_eq!(read, structure);
+        // 没有实体的时候 entities 是空的 list
+        assert!(read.entities.is_empty());
+
+        // 按照 X, Y, Z 的顺序
+        let blocks: Vec<_> = read.blocks().collect();
+        assert_eq!(blocks.len(), 24);
+        assert_eq!(blocks[1].pos, [0, 0, 1]);
+        assert_eq!(blocks[4].pos, [0, 1, 0]);
+        assert_eq!(blocks[12].pos, [1, 0, 0]);
+        assert_eq!(blocks[1].extra.unwrap().name, "minecraft:water");
+        assert_eq!(blocks[3].data, Some(&chest));
+        assert_eq!(read.get([0, 0, 1]).unwrap().state("weirdo_direction"), Some(&NbtValue::Int(2)));
+        assert_eq!(read.index([1, 2, 3]), Some(23));
+        assert_eq!(read.index([2, 0, 0]), None);
+
+        structure.layers[0][5] = 3;
+        assert!(matches!(structure.to_value(), Err(NbtError::StructureError(_))));
+        structure.layers[0].pop();
+        assert!(matches!(structure.to_binary(), Err(NbtError::StructureError(_))));
+
+        // 大小溢出
+        let NbtValue::Compound(name, mut root) = read.to_value().unwrap() else {
+            unreachable!()
+        };
+        root[1].1 = NbtValue::List(vec![NbtValue::Int(i32::MAX); 3]);
+        let result = McStructure::from_value(&NbtValue::Compound(name, root));
+        assert!(matches!(result, Err(NbtError::StructureError(_))));
+    }
+
+    #[test]
+    fn nbt_path() {
+        use crate::path::NbtPath;
+
+        let string = |s: &str| NbtValue::String(s.to_string());
+        let path = |s: &str| NbtPath::parse(s).unwrap();
+        let item = |slot: i8, id: &str| {
+            NbtValue::Compound(
+                None,
+                vec![("Slot".to_string(), NbtValue::Byte(slot)), ("id".to_string(), string(id))],
+            )
+        };
+        // 跟 Java 版读出来的一样, 嵌套的 compound 名字是 key
+        let abilities = NbtValue::Compound(Some("abilities".to_string()), Vec::new());
+        let mut player = NbtValue::Compound(
+            Some(String::new()),
+            vec![
+                ("abilities".to_string(), abilities.clone()),
+                (
+                    "Inventory".to_string(),
+                    NbtValue::List(vec![item(0, "minecraft:stone"), item(1, "minecraft:dirt")]),
+                ),
+                (
+                    "Pos".to_string(),
+                    NbtValue::List(vec![NbtValue::Double(1.0), NbtValue::Double(2.0)]),
+                ),
+                ("a b".to_string(), NbtValue::IntArray(vec![1, 2])),
+            ],
+        );
+
+        // 读
+        assert_eq!(
+            path("Inventory[{Slot:1b}].id").get(&player).as_deref(),
+            Some(&string("minecraft:dirt"))
+        );
+        assert_eq!(path("Inventory[-2].Slot").get(&player).as_deref(), Some(&NbtValue::Byte(0)));
+        assert_eq!(path("Inventory[2]").get(&player), None);
+        assert_eq!(path("Inventory[].id").get_all(&player).len(), 2);
+        assert_eq!(path("Inventory[{id:'minecraft:stone'}]").get_all(&player).len(), 1);
+        assert_eq!(path(r#""a b""#).get(&player).as_deref(), Some(&NbtValue::IntArray(vec![1, 2])));
+        assert_eq!(path("\"a b\"{}").get_all(&player).len(), 0);
+        // 数组里的元素是复制出来的
+        assert_eq!(path("'a b'[-1]").get(&player).as_deref(), Some(&NbtValue::Int(2)));
+        assert_eq!(path("'a b'[]").get_all(&player).len(), 2);
+        assert_eq!(path("'a b'[2]").get(&player), None);
+        assert_eq!(path("'a b'[0].x").get(&player), None);
+        assert_eq!(
+            path("{Pos:[2.0d]}.Pos[0]").get(&player).as_deref(),
+            Some(&NbtValue::Double(1.0))
+        );
+        assert_eq!(path("{Pos:[3.0d]}.Pos").get(&player), None);
+        assert_eq!(path("{Inventory:[{Slot:0b}],'a b':[I;1,2]}").get_all(&player).len(), 1);
+
+        // 写, 缺少的会自动创建
+        let name = path("Inventory[{Slot:0b}].tag.display.Name");
+        assert_eq!(name.set(&mut player, string("石头")), Ok(1));
+        assert_eq!(name.get(&player).as_deref(), Some(&string("石头")));
+        assert_eq!(path("Inventory[{Slot:2b}].id").set(&mut player, string("x")), Ok(1));
+        assert_eq!(path("Inventory[].Count").set(&mut player, NbtValue::Byte(1)), Ok(3));
+        assert_eq!(path("Pos[1]").set(&mut player, NbtValue::Double(3.0)), Ok(1));
+        assert_eq!(
+            path("Pos[0]").set(&mut player, NbtValue::Float(3.0)),
+            Err(NbtError::ListTypeNotSame(vec![6, 5]))
+        );
+        assert_eq!(path("Pos[2]").set(&mut player, NbtValue::Double(3.0)), Ok(0));
+        // 数字会转换成数组元素的类型
+        assert_eq!(path("'a b'[1]").set(&mut player, NbtValue::Double(-2.5)), Ok(1));
+        assert_eq!(path("'a b'[1]").get(&player).as_deref(), Some(&NbtValue::Int(-3)));
+        assert_eq!(
+            path("'a b'[0]").set(&mut player, string("x")),
+            Err(NbtError::IncorrectType(3, 8))
+        );
+        // 自动创建的 compound 名字跟旁边的一样是 key, 可以直接写成 Java 版的
+        let mut data = player.to_binary::<nbt_version::Java>().unwrap();
+        let read = NbtValue::from_binary::<nbt_version::Java>(&mut data).unwrap();
+        assert_eq!(read, player);
+        assert_eq!(name.get(&read).as_deref(), Some(&string("石头")));
+
+        // 删
+        assert_eq!(path("Inventory[].Count").remove(&mut player), 3);
+        assert_eq!(path("Inventory[{Slot:2b}]").remove(&mut player), 1);
+        assert_eq!(path("Inventory[0].tag").remove(&mut player), 1);
+        assert_eq!(path("Pos[-1]").remove(&mut player), 1);
+        assert_eq!(path("'a b'[0]").remove(&mut player), 1);
+        assert_eq!(path("\"a b\"").get(&player).as_deref(), Some(&NbtValue::IntArray(vec![-3])));
+        assert_eq!(path("'a b'[]").remove(&mut player), 1);
+        assert_eq!(path("\"a b\"").remove(&mut player), 1);
+        assert_eq!(path("missing.x").remove(&mut player), 0);
+        assert_eq!(
+            player,
+            NbtValue::Compound(
+                Some(String::new()),
+                vec![
+                    ("abilities".to_string(), abilities),
+                    (
+                        "Inventory".to_string(),
+                        NbtValue::List(vec![item(0, "minecraft:stone"), item(1, "minecraft:dirt")]),
+                    ),
+                    ("Pos".to_string(), NbtValue::List(vec![NbtValue::Double(1.0)])),
+                ],
+            )
+        );
+
+        // 基岩版的嵌套 compound 没有名字, 自动创建的也没有
+        let mut bedrock = NbtValue::Compound(Some(String::new()), Vec::new());
+        assert_eq!(path("a.b").set(&mut bedrock, NbtValue::Int(1)), Ok(1));
+        let nested = NbtValue::Compound(Some("x".to_string()), Vec::new());
+        assert_eq!(path("a.c").set(&mut bedrock, nested), Ok(1));
+        assert_eq!(
+            path("a.c").get(&bedrock).as_deref(),
+            Some(&NbtValue::Compound(None, Vec::new()))
+        );
+        let mut data = bedrock.to_binary::<nbt_version::BedrockDisk>().unwrap();
+        let read = NbtValue::from_binary::<nbt_version::BedrockDisk>(&mut data).unwrap();
+        assert_eq!(read, bedrock);
+        assert_eq!(path("a.b").get(&read).as_deref(), Some(&NbtValue::Int(1)));
+
+        // 语法错误
+        assert_eq!(path("a[0].b").to_string(), "a[0].b");
+        let column = |s: &str| match NbtPath::parse(s) {
+            Err(NbtError::NbtPathSyntax(column, _)) => column,
+            other => panic!("{:?}", other),
+        };
+        assert_eq!(column(""), 1);
+        assert_eq!(column("a."), 3);
+        assert_eq!(column("物品 a"), 3);
+        assert_eq!(column("a[x]"), 3);
+        assert_eq!(column("a[0"), 4);
+        assert_eq!(column("a.{b:1}"), 3);
+        assert_eq!(column("a{b:}"), 5);
+        assert_eq!(column("a{b:[1,2b]}"), 8);
+        assert_eq!(column("a{b:[B;1,300]}"), 10);
+        assert_eq!(column("'a"), 1);
+    }
+
+    #[test]
+    fn nbt_compound() {
+        use crate::compound::NbtCompound;
+
+        let mut compound: NbtCompound =
+            [("c", NbtValue::Int(1)), ("a", NbtValue::Int(2)), ("b", NbtValue::Int(3))]
+                .into_iter()
+                .collect();
+        assert_eq!(compound.insert("a", NbtValue::Int(4)), Some(NbtValue::Int(2)));
+        let d = NbtValue::Compound(Some("d".to_string()), Vec::new());
+        assert_eq!(compound.insert("d", d.clone()), None);
+        assert_eq!(compound.remove("c"), Some(NbtValue::Int(1)));
+        assert_eq!(compound.remove("c"), None);
+        *compound.get_mut("b").unwrap() = NbtValue::Byte(6);
+        for (_, value) in compound.iter_mut() {
+            if let NbtValue::Int(x) = value {
+                *x += 10;
+            }
+        }
+        assert_eq!(compound.len(), 3);
+        assert!(!compound.contains_key("c"));
+        assert_eq!(compound["a"], NbtValue::Int(14));
+        // 放进去的 compound 名字不变
+        assert_eq!(compound.get("d"), Some(&d));
+        let value = compound.clone().into_value(Some("root".to_string()));
+        let mut data = value.to_binary::<nbt_version::Java>().unwrap();
+        let read = NbtValue::from_binary::<nbt_version::Java>(&mut data).unwrap();
+        assert_eq!(read, value);
+        assert_eq!(NbtCompound::try_from(read).unwrap(), compound);
+
+        // 基岩版读出来的嵌套 compound 没有名字, 转换之后还能原样写回去
+        let bedrock = NbtValue::Compound(
+            Some(String::new()),
+            vec![(
+                "x".to_string(),
+                NbtValue::Compound(None, vec![("y".to_string(), NbtValue::Int(2))]),
+            )],
+        );
+        let mut data = bedrock.to_binary::<nbt_version::BedrockDisk>().unwrap();
+        let read = NbtValue::from_binary::<nbt_version::BedrockDisk>(&mut data.clone()).unwrap();
+        let converted = NbtCompound::try_from(read).unwrap().into_value(Some(String::new()));
+        assert_eq!(converted, bedrock);
+        assert_eq!(converted.to_binary::<nbt_version::BedrockDisk>().unwrap(), data);
+        let read = NbtValue::from_binary::<nbt_version::BedrockDisk>(&mut data).unwrap();
+        assert_eq!(read, bedrock);
+
+        // 删掉之后后面的下标也要对
+        compound.remove("a");
+        compound.insert("a", NbtValue::Int(1));
+        assert_eq!(compound.keys().collect::<Vec<_>>(), ["b", "d", "a"]);
+        assert_eq!(compound.get("d").map(NbtValue::tag), Some(10));
+        assert_eq!(compound.get("a"), Some(&NbtValue::Int(1)));
+
+        // 重复的 key 保留第一次的位置, 最后一次的值
+        let duplicated = NbtCompound::from(vec![
+            ("x".to_string(), NbtValue::Int(1)),
+            ("y".to_string(), NbtValue::Int(2)),
+            ("x".to_string(), NbtValue::Int(3)),
+        ]);
+        assert_eq!(
+            duplicated.into_vec(),
+            vec![("x".to_string(), NbtValue::Int(3)), ("y".to_string(), NbtValue::Int(2))]
+        );
+        assert_eq!(NbtCompound::try_from(NbtValue::Int(1)), Err(NbtError::IncorrectType(10, 3)));
+    }
+
+    #[test]
+    fn value_accessors() {
+        let mut value = NbtValue::Compound(
+            None,
+            vec![
+                ("Level".to_string(), NbtValue::Int(3)),
+                ("Data".to_string(), NbtValue::ByteArray(vec![1, 2])),
+                ("Name".to_string(), NbtValue::String("shenjack".to_string())),
+                (
+                    "Pos".to_string(),
+                    NbtValue::List(vec![NbtValue::Double(1.0), NbtValue::Double(2.0)]),
+                ),
+            ],
+        );
+        let (name, data) = value.as_compound_ref().unwrap();
+        assert_eq!(name, None);
+        assert_eq!(data[0].1.as_i32(), Ok(3));
+        assert_eq!(data[1].1.as_i8_array_ref(), Ok(&[1_i8, 2][..]));
+        assert_eq!(data[2].1.as_string_ref(), Ok("shenjack"));
+        assert_eq!(data[3].1.as_list_ref().map(<[_]>::len), Ok(2));
+        // 类型不对
+        assert_eq!(data[1].1.as_i32_array_ref(), Err(NbtError::IncorrectType(11, 7)));
+        assert_eq!(data[2].1.as_list_ref(), Err(NbtError::IncorrectType(9, 8)));
+        assert_eq!(data[3].1.as_compound_ref(), Err(NbtError::IncorrectType(10, 9)));
+
+        let (name, data) = value.as_compound_mut().unwrap();
+        *name = Some("root".to_string());
+        *data[0].1.as_i32_mut().unwrap() += 1;
+        data[1].1.as_i8_array_mut().unwrap().push(3);
+        data[2].1.as_string_mut().unwrap().push_str("yuanjie");
+        *data[3].1.as_list_mut().unwrap()[0].as_f64_mut().unwrap() = 0.5;
+        assert_eq!(data[3].1.as_f64_mut(), Err(NbtError::IncorrectType(6, 9)));
+
+        let (name, data) = value.into_compound().unwrap();
+        assert_eq!(name.as_deref(), Some("root"));
+        let mut data = data.into_iter().map(|(_, v)| v);
+        assert_eq!(data.next().unwrap().as_i32(), Ok(4));
+        assert_eq!(data.next().unwrap().into_i8_array(), Ok(vec![1, 2, 3]));
+        assert_eq!(data.next().unwrap().into_string(), Ok("shenjackyuanjie".to_string()));
+        let pos = data.next().unwrap();
+        assert_eq!(pos.clone().into_i64_array(), Err(NbtError::IncorrectType(12, 9)));
+        assert_eq!(pos.into_list(), Ok(vec![NbtValue::Double(0.5), NbtValue::Double(2.0)]));
+    }
+
     #[test]
     fn hello_write_java_net() {
         let data = NbtValue::Compound(
@@ -1,50 +1,76 @@
 use crate::nbt_version::{BedrockDisk, BedrockNetVarInt, Java, JavaNetAfter1_20_2, NbtWriteTrait};
 use crate::{NbtError, NbtResult, NbtValue};
 
+use std::io::Write;
+
+use simd_cesu8::mutf8;
+
+/// Java 版和基岩版本地格式的字符串长度是 u16
+const MAX_STR_LEN: usize = u16::MAX as usize;
+/// 数组和列表的长度是 i32 (基岩版网络格式的字符串也是)
+const MAX_LEN: usize = i32::MAX as usize;
+
+/// 超过长度限制的时候返回 [`NbtError::LenTooBig`], 位置由外层补上
+#[inline]
+fn check_len(len: usize, max: usize) -> NbtResult<()> {
+    if len > max {
+        return Err(NbtError::LenTooBig(String::new(), len, max));
+    }
+    Ok(())
+}
+
 /// 最简单的一集
 impl NbtWriteTrait for Java {
     #[inline]
-    fn write_i8_array(writer: &mut Vec<u8>, data: &[i8]) {
+    fn write_i8_array(writer: &mut impl Write, data: &[i8]) -> NbtResult<()> {
+        check_len(data.len(), MAX_LEN)?;
         // 写好 tag 了, 直接写入信息
         // 写入长度
-        writer.extend_from_slice(&(data.len() as i32).to_be_bytes());
+        writer.write_all(&(data.len() as i32).to_be_bytes())?;
         // 写入数据
-        writer.extend_from_slice(data.iter().map(|x| *x as u8).collect::<Vec<u8>>().as_slice());
+        writer.write_all(data.iter().map(|x| *x as u8).collect::<Vec<u8>>().as_slice())?;
+        Ok(())
     }
     #[inline]
-    fn write_i32_array(writer: &mut Vec<u8>, data: &[i32]) {
+    fn write_i32_array(writer: &mut impl Write, data: &[i32]) -> NbtResult<()> {
+        check_len(data.len(), MAX_LEN)?;
         // 写好 tag 了, 直接写入信息
         // 写入长度
-        writer.extend_from_slice(&(data.len() as i32).to_be_bytes());
+        writer.write_all(&(data.len() as i32).to_be_bytes())?;
         // 写入数据
-        writer.extend_from_slice(
-            &data.iter().map(|x| x.to_be_bytes()).collect::<Vec<[u8; 4]>>().concat(),
-        );
+        writer
+            .write_all(&data.iter().map(|x| x.to_be_bytes()).collect::<Vec<[u8; 4]>>().concat())?;
+        Ok(())
     }
     #[inline]
-    fn write_i64_array(writer: &mut Vec<u8>, data: &[i64]) {
+    fn write_i64_array(writer: &mut impl Write, data: &[i64]) -> NbtResult<()> {
+        check_len(data.len(), MAX_LEN)?;
         // 写好 tag 了, 直接写入信息
         // 写入长度
-        writer.extend_from_slice(&(data.len() as i32).to_be_bytes());
+        writer.write_all(&(data.len() as i32).to_be_bytes())?;
         // 写入数据
-        writer.extend_from_slice(
-            &data.iter().map(|x| x.to_be_bytes()).collect::<Vec<[u8; 8]>>().concat(),
-        );
+        writer
+            .write_all(&data.iter().map(|x| x.to_be_bytes()).collect::<Vec<[u8; 8]>>().concat())?;
+        Ok(())
     }
     #[inline]
-    fn write_nbt_string(writer: &mut Vec<u8>, data: &str) {
+    fn write_nbt_string(writer: &mut impl Write, data: &str) -> NbtResult<()> {
+        // Java 版用的是 m-utf8
+        let data = mutf8::encode(data);
+        check_len(data.len(), MAX_STR_LEN)?;
         // 写入长度
-        writer.extend_from_slice(&(data.len() as u16).to_be_bytes());
+        writer.write_all(&(data.len() as u16).to_be_bytes())?;
         // 写入数据
-        writer.extend_from_slice(data.as_bytes());
+        writer.write_all(&data)?;
+        Ok(())
     }
     #[inline]
-    fn write_list(writer: &mut Vec<u8>, data: &[NbtValue]) -> NbtResult<()> {
+    fn write_list(writer: &mut impl Write, data: &[NbtValue]) -> NbtResult<()> {
         if data.is_empty() {
             // 写入一个空的 tag
-            writer.extend_from_slice(&0i8.to_be_bytes());
+            writer.write_all(&0i8.to_be_bytes())?;
             // 写入空长度
-            writer.extend_from_slice(&0i32.to_be_bytes());
+            writer.write_all(&0i32.to_be_bytes())?;
             return Ok(());
         }
         // 遍历检查一遍所有的 tag 是否一致
@@ -52,86 +78,104 @@ impl NbtWriteTrait for Java {
         if !data.iter().all(|x| x.tag() == tag) {
             return Err(NbtError::ListTypeNotSame(data.iter().map(|x| x.tag()).collect()));
         }
+        check_len(data.len(), MAX_LEN)?;
         // 写入 tag
-        writer.push(tag);
+        writer.write_all(&[tag])?;
         // 写入长度
-        writer.extend_from_slice(&(data.len() as i32).to_be_bytes());
+        writer.write_all(&(data.len() as i32).to_be_bytes())?;
         // 写入数据
-        for i in data {
+        for (idx, i) in data.iter().enumerate() {
             match i {
-                NbtValue::Byte(x) => writer.push(*x as u8),
-                NbtValue::Short(x) => writer.extend_from_slice(&x.to_be_bytes()),
-                NbtValue::Int(x) => writer.extend_from_slice(&x.to_be_bytes()),
-                NbtValue::Long(x) => writer.extend_from_slice(&x.to_be_bytes()),
-                NbtValue::Float(x) => writer.extend_from_slice(&x.to_be_bytes()),
-                NbtValue::Double(x) => writer.extend_from_slice(&x.to_be_bytes()),
-                NbtValue::ByteArray(x) => Self::write_i8_array(writer, x),
-                NbtValue::IntArray(x) => Self::write_i32_array(writer, x),
-                NbtValue::LongArray(x) => Self::write_i64_array(writer, x),
-                NbtValue::String(x) => Self::write_nbt_string(writer, x),
-                NbtValue::List(x) => Self::write_list(writer, x)?,
-                NbtValue::Compound(_, data) => Self::write_compound(writer, None, data)?,
+                NbtValue::Byte(x) => writer.write_all(&[*x as u8])?,
+                NbtValue::Short(x) => writer.write_all(&x.to_be_bytes())?,
+                NbtValue::Int(x) => writer.write_all(&x.to_be_bytes())?,
+                NbtValue::Long(x) => writer.write_all(&x.to_be_bytes())?,
+                NbtValue::Float(x) => writer.write_all(&x.to_be_bytes())?,
+                NbtValue::Double(x) => writer.write_all(&x.to_be_bytes())?,
+                NbtValue::ByteArray(x) => {
+                    Self::write_i8_array(writer, x).map_err(|e| e.in_list(idx))?
+                }
+                NbtValue::IntArray(x) => {
+                    Self::write_i32_array(writer, x).map_err(|e| e.in_list(idx))?
+                }
+                NbtValue::LongArray(x) => {
+                    Self::write_i64_array(writer, x).map_err(|e| e.in_list(idx))?
+                }
+                NbtValue::String(x) => {
+                    Self::write_nbt_string(writer, x).map_err(|e| e.in_list(idx))?
+                }
+                NbtValue::List(x) => Self::write_list(writer, x).map_err(|e| e.in_list(idx))?,
+                NbtValue::Compound(_, data) => {
+                    Self::write_compound(writer, None, data).map_err(|e| e.in_list(idx))?
+                }
             }
         }
         Ok(())
     }
     #[inline]
     fn write_compound(
-        writer: &mut Vec<u8>,
+        writer: &mut impl Write,
         name: Option<&String>,
         data: &[(String, NbtValue)],
     ) -> NbtResult<()> {
         // 如果是列表元素时不用写入名字和key
         // 写入自己的名字
         if let Some(name) = name {
-            Self::write_nbt_string(writer, name);
+            Self::write_nbt_string(writer, name)?;
         }
         for (key, value) in data {
             // 写入 tag
-            writer.push(value.tag());
+            writer.write_all(&[value.tag()])?;
             // 写入 key，如果是Compound就不写入，因为key就是名字
             if let NbtValue::Compound(_, _) = value {
             } else {
-                Self::write_nbt_string(writer, key)
+                Self::write_nbt_string(writer, key).map_err(|e| e.in_compound(key))?
             };
             // 写入 value
             match value {
-                NbtValue::Byte(x) => writer.push(*x as u8),
-                NbtValue::Short(x) => writer.extend_from_slice(&x.to_be_bytes()),
-                NbtValue::Int(x) => writer.extend_from_slice(&x.to_be_bytes()),
-                NbtValue::Long(x) => writer.extend_from_slice(&x.to_be_bytes()),
-                NbtValue::Float(x) => writer.extend_from_slice(&x.to_be_bytes()),
-                NbtValue::Double(x) => writer.extend_from_slice(&x.to_be_bytes()),
-                NbtValue::ByteArray(x) => Self::write_i8_array(writer, x),
-                NbtValue::IntArray(x) => Self::write_i32_array(writer, x),
-                NbtValue::LongArray(x) => Self::write_i64_array(writer, x),
-                NbtValue::String(x) => Self::write_nbt_string(writer, x),
-                NbtValue::List(x) => Self::write_list(writer, x)?,
-                NbtValue::Compound(name, data) => {
-                    Self::write_compound(writer, name.as_ref(), data)?
+                NbtValue::Byte(x) => writer.write_all(&[*x as u8])?,
+                NbtValue::Short(x) => writer.write_all(&x.to_be_bytes())?,
+                NbtValue::Int(x) => writer.write_all(&x.to_be_bytes())?,
+                NbtValue::Long(x) => writer.write_all(&x.to_be_bytes())?,
+                NbtValue::Float(x) => writer.write_all(&x.to_be_bytes())?,
+                NbtValue::Double(x) => writer.write_all(&x.to_be_bytes())?,
+                NbtValue::ByteArray(x) => {
+                    Self::write_i8_array(writer, x).map_err(|e| e.in_compound(key))?
+                }
+                NbtValue::IntArray(x) => {
+                    Self::write_i32_array(writer, x).map_err(|e| e.in_compound(key))?
+                }
+                NbtValue::LongArray(x) => {
+                    Self::write_i64_array(writer, x).map_err(|e| e.in_compound(key))?
                 }
+                NbtValue::String(x) => {
+                    Self::write_nbt_string(writer, x).map_err(|e| e.in_compound(key))?
+                }
+                NbtValue::List(x) => Self::write_list(writer, x).map_err(|e| e.in_compound(key))?,
+                NbtValue::Compound(name, data) => Self::write_compound(writer, name.as_ref(), data)
+                    .map_err(|e| e.in_compound(key))?,
             }
         }
         // 写入结束 tag
-        writer.push(0);
+        writer.write_all(&[0])?;
         Ok(())
     }
-    fn write_to(value: &NbtValue, buff: &mut Vec<u8>) -> NbtResult<()> {
+    fn write_to(value: &NbtValue, buff: &mut impl Write) -> NbtResult<()> {
         // 写入 tag
         match value {
             NbtValue::Compound(name, data) => {
-                buff.push(value.tag());
+                buff.write_all(&[value.tag()])?;
                 Self::write_compound(buff, name.as_ref(), data)?
             }
             x => return Err(NbtError::WrongRootType(x.tag())),
         }
         Ok(())
     }
-    fn write_to_with_name(name: &str, value: &NbtValue, buff: &mut Vec<u8>) -> NbtResult<()> {
+    fn write_to_with_name(name: &str, value: &NbtValue, buff: &mut impl Write) -> NbtResult<()> {
         // 写入 tag
-        buff.push(value.tag());
+        buff.write_all(&[value.tag()])?;
         // 写入 key
-        Self::write_nbt_string(buff, name);
+        Self::write_nbt_string(buff, name)?;
         // 写入 value
         Self::write_to(value, buff)?;
         Ok(())
@@ -140,64 +184,83 @@ impl NbtWriteTrait for Java {
 
 impl NbtWriteTrait for JavaNetAfter1_20_2 {
     #[inline]
-    fn write_i8_array(writer: &mut Vec<u8>, data: &[i8]) { Java::write_i8_array(writer, data); }
+    fn write_i8_array(writer: &mut impl Write, data: &[i8]) -> NbtResult<()> {
+        Java::write_i8_array(writer, data)
+    }
     #[inline]
-    fn write_i32_array(writer: &mut Vec<u8>, data: &[i32]) { Java::write_i32_array(writer, data); }
+    fn write_i32_array(writer: &mut impl Write, data: &[i32]) -> NbtResult<()> {
+        Java::write_i32_array(writer, data)
+    }
     #[inline]
-    fn write_i64_array(writer: &mut Vec<u8>, data: &[i64]) { Java::write_i64_array(writer, data); }
+    fn write_i64_array(writer: &mut impl Write, data: &[i64]) -> NbtResult<()> {
+        Java::write_i64_array(writer, data)
+    }
     #[inline]
-    fn write_nbt_string(writer: &mut Vec<u8>, data: &str) { Java::write_nbt_string(writer, data); }
+    fn write_nbt_string(writer: &mut impl Write, data: &str) -> NbtResult<()> {
+        Java::write_nbt_string(writer, data)
+    }
     #[inline]
-    fn write_list(writer: &mut Vec<u8>, data: &[NbtValue]) -> NbtResult<()> {
+    fn write_list(writer: &mut impl Write, data: &[NbtValue]) -> NbtResult<()> {
         Java::write_list(writer, data)
     }
     #[inline]
     fn write_compound(
-        writer: &mut Vec<u8>,
+        writer: &mut impl Write,
         name: Option<&String>,
         data: &[(String, NbtValue)],
     ) -> NbtResult<()> {
         Java::write_compound(writer, name, data)
     }
     #[inline]
-    fn write_to(value: &NbtValue, buff: &mut Vec<u8>) -> NbtResult<()> {
+    fn write_to(value: &NbtValue, buff: &mut impl Write) -> NbtResult<()> {
         // 写入 tag
         match value {
             NbtValue::Compound(_, data) => {
                 // 忽略名字
-                buff.push(value.tag());
+                buff.write_all(&[value.tag()])?;
                 for (key, value) in data {
                     // 写入 tag
-                    buff.push(value.tag());
+                    buff.write_all(&[value.tag()])?;
                     // 写入 key
-                    Self::write_nbt_string(buff, key);
+                    Self::write_nbt_string(buff, key).map_err(|e| e.in_compound(key))?;
                     // 写入 value
                     match value {
-                        NbtValue::Byte(x) => buff.push(*x as u8),
-                        NbtValue::Short(x) => buff.extend_from_slice(&x.to_be_bytes()),
-                        NbtValue::Int(x) => buff.extend_from_slice(&x.to_be_bytes()),
-                        NbtValue::Long(x) => buff.extend_from_slice(&x.to_be_bytes()),
-                        NbtValue::Float(x) => buff.extend_from_slice(&x.to_be_bytes()),
-                        NbtValue::Double(x) => buff.extend_from_slice(&x.to_be_bytes()),
-                        NbtValue::ByteArray(x) => Self::write_i8_array(buff, x),
-                        NbtValue::IntArray(x) => Self::write_i32_array(buff, x),
-                        NbtValue::LongArray(x) => Self::write_i64_array(buff, x),
-                        NbtValue::String(x) => Self::write_nbt_string(buff, x),
-                        NbtValue::List(x) => Self::write_list(buff, x)?,
+                        NbtValue::Byte(x) => buff.write_all(&[*x as u8])?,
+                        NbtValue::Short(x) => buff.write_all(&x.to_be_bytes())?,
+                        NbtValue::Int(x) => buff.write_all(&x.to_be_bytes())?,
+                        NbtValue::Long(x) => buff.write_all(&x.to_be_bytes())?,
+                        NbtValue::Float(x) => buff.write_all(&x.to_be_bytes())?,
+                        NbtValue::Double(x) => buff.write_all(&x.to_be_bytes())?,
+                        NbtValue::ByteArray(x) => {
+                            Self::write_i8_array(buff, x).map_err(|e| e.in_compound(key))?
+                        }
+                        NbtValue::IntArray(x) => {
+                            Self::write_i32_array(buff, x).map_err(|e| e.in_compound(key))?
+                        }
+                        NbtValue::LongArray(x) => {
+                            Self::write_i64_array(buff, x).map_err(|e| e.in_compound(key))?
+                        }
+                        NbtValue::String(x) => {
+                            Self::write_nbt_string(buff, x).map_err(|e| e.in_compound(key))?
+                        }
+                        NbtValue::List(x) => {
+                            Self::write_list(buff, x).map_err(|e| e.in_compound(key))?
+                        }
                         NbtValue::Compound(name, data) => {
-                            Self::write_compound(buff, name.as_ref(), data)?
+                            Self::write_compound(buff, name.as_ref(), data)
+                                .map_err(|e| e.in_compound(key))?
                         }
                     }
                 }
                 // 写入结束 tag
-                buff.push(0);
+                buff.write_all(&[0])?;
                 Ok(())
             }
             x => Err(NbtError::WrongRootType(x.tag())),
         }
     }
     #[inline]
-    fn write_to_with_name(_name: &str, value: &NbtValue, buff: &mut Vec<u8>) -> NbtResult<()> {
+    fn write_to_with_name(_name: &str, value: &NbtValue, buff: &mut impl Write) -> NbtResult<()> {
         // drop name
         JavaNetAfter1_20_2::write_to(value, buff)
     }
@@ -212,42 +275,50 @@ impl NbtWriteTrait for JavaNetAfter1_20_2 {
 /// 比较痛苦的一集
 impl NbtWriteTrait for BedrockDisk {
     #[inline]
-    fn write_i8_array(writer: &mut Vec<u8>, data: &[i8]) {
+    fn write_i8_array(writer: &mut impl Write, data: &[i8]) -> NbtResult<()> {
+        check_len(data.len(), MAX_LEN)?;
         // 写入长度
-        writer.extend_from_slice(&(data.len() as i32).to_le_bytes());
+        writer.write_all(&(data.len() as i32).to_le_bytes())?;
         // 写入数据
-        writer.extend_from_slice(data.iter().map(|x| *x as u8).collect::<Vec<u8>>().as_slice());
+        writer.write_all(data.iter().map(|x| *x as u8).collect::<Vec<u8>>().as_slice())?;
+        Ok(())
     }
     #[inline]
-    fn write_i32_array(writer: &mut Vec<u8>, data: &[i32]) {
+    fn write_i32_array(writer: &mut impl Write, data: &[i32]) -> NbtResult<()> {
+        check_len(data.len(), MAX_LEN)?;
         // 写入长度
-        writer.extend_from_slice(&(data.len() as i32).to_le_bytes());
+        writer.write_all(&(data.len() as i32).to_le_bytes())?;
         // 写入数据
-        writer.extend_from_slice(
-            &data.iter().map(|x| x.to_le_bytes()).collect::<Vec<[u8; 4]>>().concat(),
-        );
+        writer
+            .write_all(&data.iter().map(|x| x.to_le_bytes()).collect::<Vec<[u8; 4]>>().concat())?;
+        Ok(())
     }
     #[inline]
-    fn write_i64_array(writer: &mut Vec<u8>, data: &[i64]) {
+    fn write_i64_array(writer: &mut impl Write, data: &[i64]) -> NbtResult<()> {
+        check_len(data.len(), MAX_LEN)?;
         // 写入长度
-        writer.extend_from_slice(&(data.len() as i32).to_le_bytes());
+        writer.write_all(&(data.len() as i32).to_le_bytes())?;
         // 写入数据
-        writer.extend_from_slice(
-            &data.iter().map(|x| x.to_le_bytes()).collect::<Vec<[u8; 8]>>().concat(),
-        );
+        writer
+            .write_all(&data.iter().map(|x| x.to_le_bytes()).collect::<Vec<[u8; 8]>>().concat())?;
+        Ok(())
     }
     #[inline]
-    fn write_nbt_string(writer: &mut Vec<u8>, data: &str) {
+    fn write_nbt_string(writer: &mut impl Write, data: &str) -> NbtResult<()> {
+        check_len(data.len(), MAX_STR_LEN)?;
         // 写入长度
-        writer.extend_from_slice(&(data.len() as u16).to_le_bytes());
+        writer.write_all(&(data.len() as u16).to_le_bytes())?;
         // 写入数据
-        writer.extend_from_slice(data.as_bytes());
+        writer.write_all(data.as_bytes())?;
+        Ok(())
     }
     #[inline]
-    fn write_list(writer: &mut Vec<u8>, data: &[NbtValue]) -> NbtResult<()> {
+    fn write_list(writer: &mut impl Write, data: &[NbtValue]) -> NbtResult<()> {
         if data.is_empty() {
             // 写入一个空的 tag
-            writer.extend_from_slice(&0i8.to_le_bytes());
+            writer.write_all(&0i8.to_le_bytes())?;
+            // 写入空长度
+            writer.write_all(&0i32.to_le_bytes())?;
             return Ok(());
         }
         // 遍历检查一遍所有的 tag 是否一致
@@ -255,76 +326,94 @@ impl NbtWriteTrait for BedrockDisk {
         if !data.iter().all(|x| x.tag() == tag) {
             return Err(NbtError::ListTypeNotSame(data.iter().map(|x| x.tag()).collect()));
         }
+        check_len(data.len(), MAX_LEN)?;
         // 写入 tag
-        writer.push(tag);
+        writer.write_all(&[tag])?;
         // 写入长度
-        writer.extend_from_slice(&(data.len() as i32).to_le_bytes());
+        writer.write_all(&(data.len() as i32).to_le_bytes())?;
         // 写入数据
-        for i in data {
+        for (idx, i) in data.iter().enumerate() {
             match i {
-                NbtValue::Byte(x) => writer.push(*x as u8),
-                NbtValue::Short(x) => writer.extend_from_slice(&x.to_le_bytes()),
-                NbtValue::Int(x) => writer.extend_from_slice(&x.to_le_bytes()),
-                NbtValue::Long(x) => writer.extend_from_slice(&x.to_le_bytes()),
-                NbtValue::Float(x) => writer.extend_from_slice(&x.to_le_bytes()),
-                NbtValue::Double(x) => writer.extend_from_slice(&x.to_le_bytes()),
-                NbtValue::ByteArray(x) => Self::write_i8_array(writer, x),
-                NbtValue::IntArray(x) => Self::write_i32_array(writer, x),
-                NbtValue::LongArray(x) => Self::write_i64_array(writer, x),
-                NbtValue::String(x) => Self::write_nbt_string(writer, x),
-                NbtValue::List(x) => Self::write_list(writer, x)?,
-                NbtValue::Compound(_, data) => Self::write_compound(writer, None, data)?,
+                NbtValue::Byte(x) => writer.write_all(&[*x as u8])?,
+                NbtValue::Short(x) => writer.write_all(&x.to_le_bytes())?,
+                NbtValue::Int(x) => writer.write_all(&x.to_le_bytes())?,
+                NbtValue::Long(x) => writer.write_all(&x.to_le_bytes())?,
+                NbtValue::Float(x) => writer.write_all(&x.to_le_bytes())?,
+                NbtValue::Double(x) => writer.write_all(&x.to_le_bytes())?,
+                NbtValue::ByteArray(x) => {
+                    Self::write_i8_array(writer, x).map_err(|e| e.in_list(idx))?
+                }
+                NbtValue::IntArray(x) => {
+                    Self::write_i32_array(writer, x).map_err(|e| e.in_list(idx))?
+                }
+                NbtValue::LongArray(x) => {
+                    Self::write_i64_array(writer, x).map_err(|e| e.in_list(idx))?
+                }
+                NbtValue::String(x) => {
+                    Self::write_nbt_string(writer, x).map_err(|e| e.in_list(idx))?
+                }
+                NbtValue::List(x) => Self::write_list(writer, x).map_err(|e| e.in_list(idx))?,
+                NbtValue::Compound(_, data) => {
+                    Self::write_compound(writer, None, data).map_err(|e| e.in_list(idx))?
+                }
             }
         }
         Ok(())
     }
     #[inline]
     fn write_compound(
-        writer: &mut Vec<u8>,
+        writer: &mut impl Write,
         name: Option<&String>,
         data: &[(String, NbtValue)],
     ) -> NbtResult<()> {
         // 写入自己的名字
         if let Some(name) = name {
-            Self::write_nbt_string(writer, name);
+            Self::write_nbt_string(writer, name)?;
         }
         for (key, value) in data {
             // 写入 tag
-            writer.push(value.tag());
+            writer.write_all(&[value.tag()])?;
             // 写入 key
-            Self::write_nbt_string(writer, key);
+            Self::write_nbt_string(writer, key).map_err(|e| e.in_compound(key))?;
             // 写入 value
             match value {
-                NbtValue::Byte(x) => writer.push(*x as u8),
-                NbtValue::Short(x) => writer.extend_from_slice(&x.to_le_bytes()),
-                NbtValue::Int(x) => writer.extend_from_slice(&x.to_le_bytes()),
-                NbtValue::Long(x) => writer.extend_from_slice(&x.to_le_bytes()),
-                NbtValue::Float(x) => writer.extend_from_slice(&x.to_le_bytes()),
-                NbtValue::Double(x) => writer.extend_from_slice(&x.to_le_bytes()),
-                NbtValue::ByteArray(x) => Self::write_i8_array(writer, x),
-                NbtValue::IntArray(x) => Self::write_i32_array(writer, x),
-                NbtValue::LongArray(x) => Self::write_i64_array(writer, x),
-                NbtValue::String(x) => Self::write_nbt_string(writer, x),
-                NbtValue::List(x) => Self::write_list(writer, x)?,
-                NbtValue::Compound(name, data) => {
-                    Self::write_compound(writer, name.as_ref(), data)?
+                NbtValue::Byte(x) => writer.write_all(&[*x as u8])?,
+                NbtValue::Short(x) => writer.write_all(&x.to_le_bytes())?,
+                NbtValue::Int(x) => writer.write_all(&x.to_le_bytes())?,
+                NbtValue::Long(x) => writer.write_all(&x.to_le_bytes())?,
+                NbtValue::Float(x) => writer.write_all(&x.to_le_bytes())?,
+                NbtValue::Double(x) => writer.write_all(&x.to_le_bytes())?,
+                NbtValue::ByteArray(x) => {
+                    Self::write_i8_array(writer, x).map_err(|e| e.in_compound(key))?
+                }
+                NbtValue::IntArray(x) => {
+                    Self::write_i32_array(writer, x).map_err(|e| e.in_compound(key))?
+                }
+                NbtValue::LongArray(x) => {
+                    Self::write_i64_array(writer, x).map_err(|e| e.in_compound(key))?
                 }
+                NbtValue::String(x) => {
+                    Self::write_nbt_string(writer, x).map_err(|e| e.in_compound(key))?
+                }
+                NbtValue::List(x) => Self::write_list(writer, x).map_err(|e| e.in_compound(key))?,
+                NbtValue::Compound(name, data) => Self::write_compound(writer, name.as_ref(), data)
+                    .map_err(|e| e.in_compound(key))?,
             }
         }
         // 写入结束 tag
-        writer.push(0);
+        writer.write_all(&[0])?;
         Ok(())
     }
 
-    fn write_to(value: &NbtValue, buff: &mut Vec<u8>) -> NbtResult<()> {
+    fn write_to(value: &NbtValue, buff: &mut impl Write) -> NbtResult<()> {
         // 写入 tag
         match value {
             NbtValue::Compound(name, data) => {
-                buff.push(value.tag());
+                buff.write_all(&[value.tag()])?;
                 Self::write_compound(buff, name.as_ref(), data)?
             }
             NbtValue::List(data) => {
-                buff.push(value.tag());
+                buff.write_all(&[value.tag()])?;
                 Self::write_list(buff, data)?
             }
             x => return Err(NbtError::WrongRootType(x.tag())),
@@ -332,11 +421,11 @@ impl NbtWriteTrait for BedrockDisk {
         Ok(())
     }
 
-    fn write_to_with_name(name: &str, value: &NbtValue, buff: &mut Vec<u8>) -> NbtResult<()> {
+    fn write_to_with_name(name: &str, value: &NbtValue, buff: &mut impl Write) -> NbtResult<()> {
         // 写入 tag
-        buff.push(value.tag());
+        buff.write_all(&[value.tag()])?;
         // 写入 key
-        Self::write_nbt_string(buff, name);
+        Self::write_nbt_string(buff, name)?;
         // 写入 value
         Self::write_to(value, buff)?;
         Ok(())
@@ -419,34 +508,40 @@ pub fn zigzag_var_i64_to_bytes(value: i64) -> Vec<u8> {
 
 /// 最痛苦的一集
 impl NbtWriteTrait for BedrockNetVarInt {
-    fn write_i8_array(writer: &mut Vec<u8>, data: &[i8]) {
+    fn write_i8_array(writer: &mut impl Write, data: &[i8]) -> NbtResult<()> {
+        check_len(data.len(), MAX_LEN)?;
         // zigzag var i32
-        writer.extend_from_slice(&zigzag_var_i32_to_bytes(data.len() as i32));
-        writer.extend_from_slice(data.iter().map(|x| *x as u8).collect::<Vec<u8>>().as_slice());
+        writer.write_all(&zigzag_var_i32_to_bytes(data.len() as i32))?;
+        writer.write_all(data.iter().map(|x| *x as u8).collect::<Vec<u8>>().as_slice())?;
+        Ok(())
     }
-    fn write_i32_array(writer: &mut Vec<u8>, data: &[i32]) {
+    fn write_i32_array(writer: &mut impl Write, data: &[i32]) -> NbtResult<()> {
+        check_len(data.len(), MAX_LEN)?;
         // zigzag var i32
-        writer.extend_from_slice(&zigzag_var_i32_to_bytes(data.len() as i32));
-        writer.extend_from_slice(
-            &data.iter().map(|x| x.to_le_bytes()).collect::<Vec<[u8; 4]>>().concat(),
-        );
+        writer.write_all(&zigzag_var_i32_to_bytes(data.len() as i32))?;
+        writer
+            .write_all(&data.iter().map(|x| x.to_le_bytes()).collect::<Vec<[u8; 4]>>().concat())?;
+        Ok(())
     }
-    fn write_i64_array(writer: &mut Vec<u8>, data: &[i64]) {
+    fn write_i64_array(writer: &mut impl Write, data: &[i64]) -> NbtResult<()> {
+        check_len(data.len(), MAX_LEN)?;
         // zigzag var i32
-        writer.extend_from_slice(&zigzag_var_i32_to_bytes(data.len() as i32));
-        writer.extend_from_slice(
-            &data.iter().map(|x| x.to_le_bytes()).collect::<Vec<[u8; 8]>>().concat(),
-        );
+        writer.write_all(&zigzag_var_i32_to_bytes(data.len() as i32))?;
+        writer
+            .write_all(&data.iter().map(|x| x.to_le_bytes()).collect::<Vec<[u8; 8]>>().concat())?;
+        Ok(())
     }
-    fn write_nbt_string(writer: &mut Vec<u8>, data: &str) {
+    fn write_nbt_string(writer: &mut impl Write, data: &str) -> NbtResult<()> {
+        check_len(data.len(), MAX_LEN)?;
         // zigzag var i32
-        writer.extend_from_slice(&zigzag_var_i32_to_bytes(data.len() as i32));
-        writer.extend_from_slice(data.as_bytes());
+        writer.write_all(&zigzag_var_i32_to_bytes(data.len() as i32))?;
+        writer.write_all(data.as_bytes())?;
+        Ok(())
     }
-    fn write_list(writer: &mut Vec<u8>, data: &[NbtValue]) -> NbtResult<()> {
+    fn write_list(writer: &mut impl Write, data: &[NbtValue]) -> NbtResult<()> {
         if data.is_empty() {
             // 写入一个空的 tag
-            writer.extend_from_slice(&0i8.to_le_bytes());
+            writer.write_all(&0i8.to_le_bytes())?;
             return Ok(());
         }
         // 遍历检查一遍所有的 tag 是否一致
@@ -454,86 +549,104 @@ impl NbtWriteTrait for BedrockNetVarInt {
         if !data.iter().all(|x| x.tag() == tag) {
             return Err(NbtError::ListTypeNotSame(data.iter().map(|x| x.tag()).collect()));
         }
+        check_len(data.len(), MAX_LEN)?;
         // 写入 tag
-        writer.push(tag);
+        writer.write_all(&[tag])?;
         // zigzag var i32
-        writer.extend_from_slice(&zigzag_var_i32_to_bytes(data.len() as i32));
+        writer.write_all(&zigzag_var_i32_to_bytes(data.len() as i32))?;
         // 写入数据
-        for i in data {
+        for (idx, i) in data.iter().enumerate() {
             match i {
-                NbtValue::Byte(x) => writer.push(*x as u8),
-                NbtValue::Short(x) => writer.extend_from_slice(&x.to_le_bytes()),
-                NbtValue::Int(x) => writer.extend_from_slice(&x.to_le_bytes()),
-                NbtValue::Long(x) => writer.extend_from_slice(&x.to_le_bytes()),
-                NbtValue::Float(x) => writer.extend_from_slice(&x.to_le_bytes()),
-                NbtValue::Double(x) => writer.extend_from_slice(&x.to_le_bytes()),
-                NbtValue::ByteArray(x) => Self::write_i8_array(writer, x),
-                NbtValue::IntArray(x) => Self::write_i32_array(writer, x),
-                NbtValue::LongArray(x) => Self::write_i64_array(writer, x),
-                NbtValue::String(x) => Self::write_nbt_string(writer, x),
-                NbtValue::List(x) => Self::write_list(writer, x)?,
-                NbtValue::Compound(_, data) => Self::write_compound(writer, None, data)?,
+                NbtValue::Byte(x) => writer.write_all(&[*x as u8])?,
+                NbtValue::Short(x) => writer.write_all(&x.to_le_bytes())?,
+                NbtValue::Int(x) => writer.write_all(&x.to_le_bytes())?,
+                NbtValue::Long(x) => writer.write_all(&x.to_le_bytes())?,
+                NbtValue::Float(x) => writer.write_all(&x.to_le_bytes())?,
+                NbtValue::Double(x) => writer.write_all(&x.to_le_bytes())?,
+                NbtValue::ByteArray(x) => {
+                    Self::write_i8_array(writer, x).map_err(|e| e.in_list(idx))?
+                }
+                NbtValue::IntArray(x) => {
+                    Self::write_i32_array(writer, x).map_err(|e| e.in_list(idx))?
+                }
+                NbtValue::LongArray(x) => {
+                    Self::write_i64_array(writer, x).map_err(|e| e.in_list(idx))?
+                }
+                NbtValue::String(x) => {
+                    Self::write_nbt_string(writer, x).map_err(|e| e.in_list(idx))?
+                }
+                NbtValue::List(x) => Self::write_list(writer, x).map_err(|e| e.in_list(idx))?,
+                NbtValue::Compound(_, data) => {
+                    Self::write_compound(writer, None, data).map_err(|e| e.in_list(idx))?
+                }
             }
         }
         Ok(())
     }
     fn write_compound(
-        writer: &mut Vec<u8>,
+        writer: &mut impl Write,
         name: Option<&String>,
         data: &[(String, NbtValue)],
     ) -> NbtResult<()> {
         // 写入自己的名字
         if let Some(name) = name {
-            Self::write_nbt_string(writer, name);
+            Self::write_nbt_string(writer, name)?;
         }
         for (key, value) in data {
             // 写入 tag
-            writer.push(value.tag());
+            writer.write_all(&[value.tag()])?;
             // 写入 key
-            Self::write_nbt_string(writer, key);
+            Self::write_nbt_string(writer, key).map_err(|e| e.in_compound(key))?;
             // 写入 value
             match value {
-                NbtValue::Byte(x) => writer.push(*x as u8),
-                NbtValue::Short(x) => writer.extend_from_slice(&x.to_le_bytes()),
-                NbtValue::Int(x) => writer.extend_from_slice(&x.to_le_bytes()),
-                NbtValue::Long(x) => writer.extend_from_slice(&x.to_le_bytes()),
-                NbtValue::Float(x) => writer.extend_from_slice(&x.to_le_bytes()),
-                NbtValue::Double(x) => writer.extend_from_slice(&x.to_le_bytes()),
-                NbtValue::ByteArray(x) => Self::write_i8_array(writer, x),
-                NbtValue::IntArray(x) => Self::write_i32_array(writer, x),
-                NbtValue::LongArray(x) => Self::write_i64_array(writer, x),
-                NbtValue::String(x) => Self::write_nbt_string(writer, x),
-                NbtValue::List(x) => Self::write_list(writer, x)?,
-                NbtValue::Compound(name, data) => {
-                    Self::write_compound(writer, name.as_ref(), data)?
+                NbtValue::Byte(x) => writer.write_all(&[*x as u8])?,
+                NbtValue::Short(x) => writer.write_all(&x.to_le_bytes())?,
+                NbtValue::Int(x) => writer.write_all(&x.to_le_bytes())?,
+                NbtValue::Long(x) => writer.write_all(&x.to_le_bytes())?,
+                NbtValue::Float(x) => writer.write_all(&x.to_le_bytes())?,
+                NbtValue::Double(x) => writer.write_all(&x.to_le_bytes())?,
+                NbtValue::ByteArray(x) => {
+                    Self::write_i8_array(writer, x).map_err(|e| e.in_compound(key))?
+                }
+                NbtValue::IntArray(x) => {
+                    Self::write_i32_array(writer, x).map_err(|e| e.in_compound(key))?
+                }
+                NbtValue::LongArray(x) => {
+                    Self::write_i64_array(writer, x).map_err(|e| e.in_compound(key))?
+                }
+                NbtValue::String(x) => {
+                    Self::write_nbt_string(writer, x).map_err(|e| e.in_compound(key))?
                 }
+                NbtValue::List(x) => Self::write_list(writer, x).map_err(|e| e.in_compound(key))?,
+                NbtValue::Compound(name, data) => Self::write_compound(writer, name.as_ref(), data)
+                    .map_err(|e| e.in_compound(key))?,
             }
         }
         // 写入结束 tag
-        writer.push(0);
+        writer.write_all(&[0])?;
         Ok(())
     }
 
-    fn write_to(value: &NbtValue, buff: &mut Vec<u8>) -> NbtResult<()> {
+    fn write_to(value: &NbtValue, buff: &mut impl Write) -> NbtResult<()> {
         // 写入 tag
         match value {
             NbtValue::Compound(name, data) => {
-                buff.push(value.tag());
+                buff.write_all(&[value.tag()])?;
                 Self::write_compound(buff, name.as_ref(), data)?
             }
             NbtValue::List(data) => {
-                buff.push(value.tag());
+                buff.write_all(&[value.tag()])?;
                 Self::write_list(buff, data)?
             }
             x => return Err(NbtError::WrongRootType(x.tag())),
         }
         Ok(())
     }
-    fn write_to_with_name(name: &str, value: &NbtValue, buff: &mut Vec<u8>) -> NbtResult<()> {
+    fn write_to_with_name(name: &str, value: &NbtValue, buff: &mut impl Write) -> NbtResult<()> {
         // 写入 tag
-        buff.push(value.tag());
+        buff.write_all(&[value.tag()])?;
         // 写入 key
-        Self::write_nbt_string(buff, name);
+        Self::write_nbt_string(buff, name)?;
         // 写入 value
         Self::write_to(value, buff)?;
         Ok(())
@@ -1,3 +1,7 @@
+pub mod bedrock_disk_own;
+pub mod bedrock_disk_read;
+pub mod bedrock_net_own;
+pub mod bedrock_net_read;
 pub mod java_own;
 pub mod java_read;
 
@@ -1,5 +1,8 @@
 use crate::borrow::NbtBorrowTrait;
-use crate::tests::{BIG_TEST_DATA, COMPLEX_PLR_DATA, HELLO_WORLD_DATA};
+use crate::tests::{
+    BIG_TEST_BEDROCK_DATA, BIG_TEST_BEDROCK_NET_DATA, BIG_TEST_DATA, COMPLEX_PLR_DATA,
+    HELLO_WORLD_BEDROCK_DATA, HELLO_WORLD_BEDROCK_NET_DATA, HELLO_WORLD_DATA,
+};
 use crate::{nbt_version, NbtReader, NbtValue};
 
 #[test]
@@ -122,4 +125,167 @@ fn complex_player() {
     println!("{}", owned_data.display_data());
 
     panic!();
-}
\ No newline at end of file
+}
+
+#[test]
+fn bedrock_utf8_strings() {
+    let value = NbtValue::Compound(
+        Some("根".into()),
+        vec![("名字".into(), NbtValue::List(vec![NbtValue::String("😀\0".into())]))],
+    );
+
+    let data = value.to_binary::<nbt_version::BedrockDisk>().unwrap();
+    let mut reader = NbtReader::new(&data);
+    let borrow_data = nbt_version::BedrockDisk::from_reader(&mut reader).unwrap();
+    assert_eq!(nbt_version::BedrockDisk::read_data(&borrow_data, &mut reader), value);
+
+    let data = value.to_binary::<nbt_version::BedrockNetVarInt>().unwrap();
+    let mut reader = NbtReader::new(&data);
+    let borrow_data = nbt_version::BedrockNetVarInt::from_reader(&mut reader).unwrap();
+    assert_eq!(nbt_version::BedrockNetVarInt::read_data(&borrow_data, &mut reader), value);
+}
+
+#[test]
+fn hello_world_bedrock_disk() {
+    let mut reader = NbtReader::new(&HELLO_WORLD_BEDROCK_DATA);
+
+    let data = nbt_version::BedrockDisk::from_reader(&mut reader).unwrap();
+    let owned_data = nbt_version::BedrockDisk::read_data(&data, &mut reader);
+
+    let expect_data = NbtValue::Compound(
+        Some("hello world".into()),
+        vec![("name".into(), NbtValue::String("Bananrama".into()))],
+    );
+
+    assert_eq!(owned_data, expect_data);
+}
+
+#[test]
+fn big_test_bedrock_disk() {
+    let mut reader = NbtReader::new(&BIG_TEST_BEDROCK_DATA);
+    let data = nbt_version::BedrockDisk::from_reader(&mut reader).unwrap();
+    let owned_data = nbt_version::BedrockDisk::read_data(&data, &mut reader);
+
+    // 跟 Java 版的结果应该是一样的
+    let mut java_reader = NbtReader::new(&BIG_TEST_DATA);
+    let java_data = nbt_version::Java::from_reader(&mut java_reader).unwrap();
+    let java_owned_data = nbt_version::Java::read_data(&java_data, &mut java_reader);
+
+    assert_eq!(owned_data, java_owned_data);
+}
+
+#[test]
+fn bedrock_disk_list_root() {
+    let data = [
+        0x09, // TAG_List
+        0x03, // TAG_Int
+        0x02, 0x00, 0x00, 0x00, // len: 2
+        0x01, 0x00, 0x00, 0x00, // 1
+        0xFF, 0xFF, 0xFF, 0xFF, // -1
+    ];
+    let mut reader = NbtReader::new(&data);
+    let borrow_data = nbt_version::BedrockDisk::from_reader(&mut reader).unwrap();
+    let owned_data = nbt_version::BedrockDisk::read_data(&borrow_data, &mut reader);
+
+    assert_eq!(owned_data, NbtValue::List(vec![NbtValue::Int(1), NbtValue::Int(-1)]));
+}
+
+#[test]
+fn bedrock_disk_compound_list_root() {
+    let data = [
+        0x09, // TAG_List
+        0x0A, // TAG_Compound
+        0x02, 0x00, 0x00, 0x00, // len: 2
+        0x01, 0x01, 0x00, 0x61, 0x7F, 0x00, // {a: 127b}
+        0x00, // {}
+    ];
+    let mut reader = NbtReader::new(&data);
+    let borrow_data = nbt_version::BedrockDisk::from_reader(&mut reader).unwrap();
+    let owned_data = nbt_version::BedrockDisk::read_data(&borrow_data, &mut reader);
+
+    assert_eq!(
+        owned_data,
+        NbtValue::List(vec![
+            NbtValue::Compound(None, vec![("a".into(), NbtValue::Byte(127))]),
+            NbtValue::Compound(None, vec![]),
+        ])
+    );
+}
+
+#[test]
+fn hello_world_bedrock_net() {
+    let mut reader = NbtReader::new(&HELLO_WORLD_BEDROCK_NET_DATA);
+    let data = nbt_version::BedrockNetVarInt::from_reader(&mut reader).unwrap();
+    let owned_data = nbt_version::BedrockNetVarInt::read_data(&data, &mut reader);
+
+    assert_eq!(
+        owned_data,
+        NbtValue::Compound(
+            Some("hello world".into()),
+            vec![("name".into(), NbtValue::String("Bananrama".into()))]
+        )
+    );
+}
+
+#[test]
+fn big_test_bedrock_net() {
+    let mut reader = NbtReader::new(&BIG_TEST_BEDROCK_NET_DATA);
+    let data = nbt_version::BedrockNetVarInt::from_reader(&mut reader).unwrap();
+    let owned_data = nbt_version::BedrockNetVarInt::read_data(&data, &mut reader);
+
+    let mut java_reader = NbtReader::new(&BIG_TEST_DATA);
+    let java_data = nbt_version::Java::from_reader(&mut java_reader).unwrap();
+    let java_owned_data = nbt_version::Java::read_data(&java_data, &mut java_reader);
+
+    assert_eq!(owned_data, java_owned_data);
+}
+
+#[test]
+fn bedrock_net_varint_values() {
+    let data = [
+        0x0A, 0x00, // TAG_Compound, 没有名字
+        0x03, 0x01, 0x61, 0x01, // a: -1 (zigzag)
+        0x03, 0x01, 0x62, 0xFE, 0xFF, 0xFF, 0xFF, 0x0F, // b: i32::MAX
+        0x04, 0x01, 0x63, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF,
+        0x01, // c: i64::MIN
+        0x09, 0x01, 0x64, 0x04, 0x04, // d: TAG_Long list, len: 2
+        0x80, 0x01, // 64
+        0x03, // -2
+        0x0B, 0x01, 0x65, 0x02, // e: IntArray, len: 1
+        0x2A, 0x00, 0x00, 0x00, // 42, 定长小端
+        0x00,
+    ];
+    let mut reader = NbtReader::new(&data);
+    let borrow_data = nbt_version::BedrockNetVarInt::from_reader(&mut reader).unwrap();
+    let owned_data = nbt_version::BedrockNetVarInt::read_data(&borrow_data, &mut reader);
+
+    assert_eq!(
+        owned_data,
+        NbtValue::Compound(
+            Some("".into()),
+            vec![
+                ("a".into(), NbtValue::Int(-1)),
+                ("b".into(), NbtValue::Int(i32::MAX)),
+                ("c".into(), NbtValue::Long(i64::MIN)),
+                ("d".into(), NbtValue::List(vec![NbtValue::Long(64), NbtValue::Long(-2)])),
+                ("e".into(), NbtValue::IntArray(vec![42])),
+            ]
+        )
+    );
+}
+
+#[test]
+fn bedrock_net_list_root() {
+    let data = [
+        0x09, // TAG_List
+        0x03, // TAG_Int
+        0x04, // len: 2
+        0x02, // 1
+        0x01, // -1
+    ];
+    let mut reader = NbtReader::new(&data);
+    let borrow_data = nbt_version::BedrockNetVarInt::from_reader(&mut reader).unwrap();
+    let owned_data = nbt_version::BedrockNetVarInt::read_data(&borrow_data, &mut reader);
+
+    assert_eq!(owned_data, NbtValue::List(vec![NbtValue::Int(1), NbtValue::Int(-1)]));
+}
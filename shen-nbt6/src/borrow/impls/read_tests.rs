@@ -1,6 +1,8 @@
 use crate::borrow::{BorrowNbtValue as BValue, NbtBorrowTrait};
-use crate::tests::{BIG_TEST_DATA, HELLO_WORLD_DATA};
-use crate::{nbt_version, NbtReader};
+use crate::tests::{
+    BIG_TEST_DATA, HELLO_WORLD_BEDROCK_DATA, HELLO_WORLD_BEDROCK_NET_DATA, HELLO_WORLD_DATA,
+};
+use crate::{nbt_version, NbtError, NbtReader, NbtValue};
 
 #[test]
 fn hello_world_borrow() {
@@ -15,6 +17,58 @@ fn hello_world_borrow() {
     assert_eq!(data.unwrap(), correct_data);
 }
 
+#[test]
+fn hello_world_bedrock_disk_borrow() {
+    let mut reader = NbtReader::new(&HELLO_WORLD_BEDROCK_DATA);
+
+    let data = nbt_version::BedrockDisk::from_reader(&mut reader);
+    if let Err(e) = data {
+        println!("cursor state:\n{}", reader.show_cursor_fancy(None));
+        panic!("{}", e);
+    }
+    // 跟 Java 版的位置完全一致, 只是字节序不同
+    let correct_data = BValue::Compound(0, Some(11), vec![(17, 4, BValue::String(23, 9))]);
+    assert_eq!(data.unwrap(), correct_data);
+}
+
+#[test]
+fn bedrock_disk_wrong_root() {
+    let mut reader = NbtReader::new(&[0x03, 0x00, 0x00]);
+    let data = nbt_version::BedrockDisk::from_reader(&mut reader);
+    assert_eq!(data, Err(NbtError::WrongRootType(3)));
+}
+
+#[test]
+fn hello_world_bedrock_net_borrow() {
+    let mut reader = NbtReader::new(&HELLO_WORLD_BEDROCK_NET_DATA);
+
+    let data = nbt_version::BedrockNetVarInt::from_reader(&mut reader);
+    if let Err(e) = data {
+        println!("cursor state:\n{}", reader.show_cursor_fancy(None));
+        panic!("{}", e);
+    }
+    // 根节点的 ptr 直接指向 name
+    let correct_data = BValue::Compound(2, Some(11), vec![(15, 4, BValue::String(20, 9))]);
+    assert_eq!(data.unwrap(), correct_data);
+}
+
+#[test]
+fn bedrock_net_varint_too_big() {
+    // a: Int, 但是 VarInt 有 6 个字节
+    let data = [0x0A, 0x00, 0x03, 0x01, 0x61, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x01, 0x00];
+    let mut reader = NbtReader::new(&data);
+    let value = nbt_version::BedrockNetVarInt::from_reader(&mut reader);
+    assert_eq!(value, Err(NbtError::VarIntTooBig(6)));
+
+    // a: Long, 但是 VarLong 有 11 个字节
+    let mut data = vec![0x0A, 0x00, 0x04, 0x01, 0x61];
+    data.extend([0xFF; 10]);
+    data.extend([0x01, 0x00]);
+    let mut reader = NbtReader::new(&data);
+    let value = nbt_version::BedrockNetVarInt::from_reader(&mut reader);
+    assert_eq!(value, Err(NbtError::VarlongTooBig(11)));
+}
+
 #[test]
 fn big_test() {
     let mut reader = NbtReader::new(&BIG_TEST_DATA);
@@ -94,3 +148,33 @@ fn big_test() {
     println!("data: {:#?}", data);
     assert_eq!(data, correct_data);
 }
+
+#[test]
+fn bedrock_disk_invalid_utf8() {
+    // {a: "\xFF"}, 两种读法都应该返回 Utf8Error
+    let disk = [0x0A, 0x00, 0x00, 0x08, 0x01, 0x00, 0x61, 0x01, 0x00, 0xFF, 0x00];
+    let mut reader = NbtReader::new(&disk);
+    let borrow = nbt_version::BedrockDisk::from_reader(&mut reader);
+    assert!(matches!(borrow, Err(NbtError::Utf8Error(_))));
+    let owned = NbtValue::from_binary::<nbt_version::BedrockDisk>(&disk);
+    assert!(matches!(owned, Err(NbtError::Utf8Error(_))));
+
+    // 名字不合法也一样
+    let disk = [0x0A, 0x01, 0x00, 0xC0, 0x00];
+    let mut reader = NbtReader::new(&disk);
+    let borrow = nbt_version::BedrockDisk::from_reader(&mut reader);
+    assert!(matches!(borrow, Err(NbtError::Utf8Error(_))));
+    let owned = NbtValue::from_binary::<nbt_version::BedrockDisk>(&disk);
+    assert!(matches!(owned, Err(NbtError::Utf8Error(_))));
+}
+
+#[test]
+fn bedrock_net_invalid_utf8() {
+    // {a: "\xFF"}, 两种读法都应该返回 Utf8Error
+    let net = [0x0A, 0x00, 0x08, 0x01, 0x61, 0x01, 0xFF, 0x00];
+    let mut reader = NbtReader::new(&net);
+    let borrow = nbt_version::BedrockNetVarInt::from_reader(&mut reader);
+    assert!(matches!(borrow, Err(NbtError::Utf8Error(_))));
+    let owned = NbtValue::from_binary::<nbt_version::BedrockNetVarInt>(&net);
+    assert!(matches!(owned, Err(NbtError::Utf8Error(_))));
+}
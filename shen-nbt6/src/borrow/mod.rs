@@ -150,7 +150,7 @@ impl BorrowNbtValue {
         }
     }
 
-    pub fn from_binary<R>(data: &[u8]) -> NbtResult<(NbtReader, BorrowNbtValue)>
+    pub fn from_binary<R>(data: &[u8]) -> NbtResult<(NbtReader<'_>, BorrowNbtValue)>
     where
         R: NbtBorrowTrait,
     {
@@ -177,3 +177,21 @@ impl NbtBorrowTrait for nbt_version::JavaNetAfter1_20_2 {
         impls::java_own::own_value(value, reader)
     }
 }
+
+impl NbtBorrowTrait for nbt_version::BedrockDisk {
+    fn from_reader(reader: &mut NbtReader) -> NbtResult<BorrowNbtValue> {
+        impls::bedrock_disk_read::bedrock_disk_from_reader(reader)
+    }
+    fn read_data(value: &BorrowNbtValue, reader: &mut NbtReader) -> NbtValue {
+        impls::bedrock_disk_own::own_value(value, reader)
+    }
+}
+
+impl NbtBorrowTrait for nbt_version::BedrockNetVarInt {
+    fn from_reader(reader: &mut NbtReader) -> NbtResult<BorrowNbtValue> {
+        impls::bedrock_net_read::bedrock_net_from_reader(reader)
+    }
+    fn read_data(value: &BorrowNbtValue, reader: &mut NbtReader) -> NbtValue {
+        impls::bedrock_net_own::own_value(value, reader)
+    }
+}
@@ -0,0 +1,265 @@
+//! 带索引的 compound
+//!
+//! [`NbtValue::Compound`] 里存的是一个 `Vec`, 找一个 key 只能从头找到尾;
+//! [`NbtCompound`] 另外用一个 `HashMap` 记下每个 key 的位置, 查找和插入都是 O(1), 同时保留插入的顺序
+//!
+//! 用 `&str` 查找的时候会把 `&str` 编码成 m-utf8 (大部分时候不需要复制) 直接跟存着的字节比,
+//! 不会去解码存着的 key
+//!
+//! ```rust
+//! use shen_nbt6::{nbt, NbtCompound, NbtValue};
+//!
+//! let mut section = NbtCompound::try_from(nbt!({ Y: 0B, block_states: { palette: [] } })).unwrap();
+//! assert_eq!(section.get("Y"), Some(&NbtValue::Byte(0)));
+//! if let Some(NbtValue::Byte(y)) = section.get_mut("Y") {
+//!     *y = 4;
+//! }
+//! section.insert("SkyLight", vec![0_i8; 2048]);
+//! assert!(section.remove("block_states").is_some());
+//!
+//! let keys: Vec<_> = section.keys().map(|k| k.decode_lossy()).collect();
+//! assert_eq!(keys, ["Y", "SkyLight"]);
+//! assert_eq!(section["Y"], NbtValue::Byte(4));
+//! ```
+
+use std::collections::HashMap;
+use std::ops::Index;
+
+use simd_cesu8::mutf8;
+
+use crate::{nbt_consts, Mutf8String, NbtError, NbtValue};
+
+/// 保留插入顺序, 用 key 查找是 O(1) 的 compound
+///
+/// 见 [模块文档](self)
+#[derive(Debug, Clone, Default)]
+pub struct NbtCompound {
+    /// 按照插入的顺序
+    entries: Vec<(Mutf8String, NbtValue)>,
+    /// key 在 `entries` 里的下标
+    index: HashMap<Mutf8String, usize>,
+}
+
+impl NbtCompound {
+    /// 空的 compound
+    pub fn new() -> Self { Self::default() }
+
+    /// 预先分配 `capacity` 个 key 的空间
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            entries: Vec::with_capacity(capacity),
+            index: HashMap::with_capacity(capacity),
+        }
+    }
+
+    /// key 的数量
+    pub fn len(&self) -> usize { self.entries.len() }
+
+    /// 是不是空的
+    pub fn is_empty(&self) -> bool { self.entries.is_empty() }
+
+    /// 有没有这个 key
+    pub fn contains_key(&self, key: &str) -> bool { self.position(key).is_some() }
+
+    /// 拿到 key 对应的值
+    pub fn get(&self, key: &str) -> Option<&NbtValue> {
+        self.position(key).map(|idx| &self.entries[idx].1)
+    }
+
+    /// 拿到 key 对应的值, 可以直接修改
+    pub fn get_mut(&mut self, key: &str) -> Option<&mut NbtValue> {
+        self.position(key).map(|idx| &mut self.entries[idx].1)
+    }
+
+    /// 插入一个值, 返回旧的值
+    ///
+    /// 已经有这个 key 的时候只会替换值, 位置不变
+    pub fn insert(
+        &mut self,
+        key: impl Into<Mutf8String>,
+        value: impl Into<NbtValue>,
+    ) -> Option<NbtValue> {
+        let key = key.into();
+        let value = value.into();
+        match self.index.get(&key) {
+            Some(idx) => Some(std::mem::replace(&mut self.entries[*idx].1, value)),
+            None => {
+                self.index.insert(key.clone(), self.entries.len());
+                self.entries.push((key, value));
+                None
+            }
+        }
+    }
+
+    /// 删掉一个 key, 返回删掉的值
+    ///
+    /// 后面的 key 会往前挪, 跟 `Vec::remove` 一样是 O(n)
+    pub fn remove(&mut self, key: &str) -> Option<NbtValue> {
+        let removed = self.index.remove(&*mutf8::encode(key))?;
+        for idx in self.index.values_mut() {
+            if *idx > removed {
+                *idx -= 1;
+            }
+        }
+        Some(self.entries.remove(removed).1)
+    }
+
+    /// 按照插入的顺序遍历
+    pub fn iter(&self) -> impl Iterator<Item = (&Mutf8String, &NbtValue)> {
+        self.entries.iter().map(|(k, v)| (k, v))
+    }
+
+    /// 按照插入的顺序遍历, 值可以直接修改
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (&Mutf8String, &mut NbtValue)> {
+        self.entries.iter_mut().map(|(k, v)| (&*k, v))
+    }
+
+    /// 按照插入的顺序遍历所有的 key
+    pub fn keys(&self) -> impl Iterator<Item = &Mutf8String> { self.entries.iter().map(|(k, _)| k) }
+
+    /// 按照插入的顺序遍历所有的值
+    pub fn values(&self) -> impl Iterator<Item = &NbtValue> { self.entries.iter().map(|(_, v)| v) }
+
+    /// 跟 [`NbtValue::Compound`] 里一样的 `Vec`
+    pub fn as_slice(&self) -> &[(Mutf8String, NbtValue)] { &self.entries }
+
+    /// 转换成 [`NbtValue::Compound`] 里的 `Vec`
+    pub fn into_vec(self) -> Vec<(Mutf8String, NbtValue)> { self.entries }
+
+    /// 转换成名字是 `name` 的 [`NbtValue::Compound`]
+    pub fn into_value(self, name: Option<Mutf8String>) -> NbtValue {
+        NbtValue::Compound(name, self.entries)
+    }
+
+    /// m-utf8 编码之后的 key 在 `entries` 里的下标
+    fn position(&self, key: &str) -> Option<usize> { self.index.get(&*mutf8::encode(key)).copied() }
+}
+
+/// 只比较内容和顺序
+impl PartialEq for NbtCompound {
+    fn eq(&self, other: &Self) -> bool { self.entries == other.entries }
+}
+
+impl Index<&str> for NbtCompound {
+    type Output = NbtValue;
+
+    /// 没有这个 key 的时候会 panic
+    fn index(&self, key: &str) -> &NbtValue {
+        match self.get(key) {
+            Some(value) => value,
+            None => panic!("NbtCompound 里没有 key {:?}", key),
+        }
+    }
+}
+
+impl<K: Into<Mutf8String>, V: Into<NbtValue>> Extend<(K, V)> for NbtCompound {
+    fn extend<T: IntoIterator<Item = (K, V)>>(&mut self, iter: T) {
+        for (key, value) in iter {
+            self.insert(key, value);
+        }
+    }
+}
+
+impl<K: Into<Mutf8String>, V: Into<NbtValue>> FromIterator<(K, V)> for NbtCompound {
+    fn from_iter<T: IntoIterator<Item = (K, V)>>(iter: T) -> Self {
+        let mut compound = NbtCompound::new();
+        compound.extend(iter);
+        compound
+    }
+}
+
+/// 重复的 key 只保留最后一个值, 位置是第一次出现的位置
+impl From<Vec<(Mutf8String, NbtValue)>> for NbtCompound {
+    fn from(entries: Vec<(Mutf8String, NbtValue)>) -> Self {
+        let mut compound = NbtCompound::with_capacity(entries.len());
+        compound.extend(entries);
+        compound
+    }
+}
+
+impl From<NbtCompound> for Vec<(Mutf8String, NbtValue)> {
+    fn from(compound: NbtCompound) -> Self { compound.entries }
+}
+
+/// 没有名字的 [`NbtValue::Compound`]
+impl From<NbtCompound> for NbtValue {
+    fn from(compound: NbtCompound) -> Self { compound.into_value(None) }
+}
+
+/// 会丢掉 compound 的名字, 不是 compound 的时候返回 [`NbtError::IncorrectType`]
+impl TryFrom<NbtValue> for NbtCompound {
+    type Error = NbtError;
+
+    fn try_from(value: NbtValue) -> Result<Self, NbtError> {
+        match value {
+            NbtValue::Compound(_, entries) => Ok(entries.into()),
+            other => Err(NbtError::IncorrectType(nbt_consts::TAG_COMPOUND, other.tag())),
+        }
+    }
+}
+
+impl IntoIterator for NbtCompound {
+    type Item = (Mutf8String, NbtValue);
+    type IntoIter = std::vec::IntoIter<(Mutf8String, NbtValue)>;
+
+    fn into_iter(self) -> Self::IntoIter { self.entries.into_iter() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nbt;
+
+    #[test]
+    fn compound_order() {
+        let mut compound: NbtCompound = [("c", 1), ("a", 2), ("b", 3)].into_iter().collect();
+        assert_eq!(compound.insert("a", 4), Some(NbtValue::Int(2)));
+        assert_eq!(compound.insert("d", 5), None);
+        assert_eq!(compound.remove("c"), Some(NbtValue::Int(1)));
+        assert_eq!(compound.remove("c"), None);
+        *compound.get_mut("b").unwrap() = NbtValue::Byte(6);
+        for (_, value) in compound.iter_mut() {
+            if let NbtValue::Int(x) = value {
+                *x += 10;
+            }
+        }
+        assert_eq!(compound.len(), 3);
+        assert_eq!(compound.get("a"), Some(&NbtValue::Int(14)));
+        assert_eq!(compound.get("b"), Some(&NbtValue::Byte(6)));
+        assert_eq!(compound["d"], NbtValue::Int(15));
+        assert!(!compound.contains_key("c"));
+        assert_eq!(NbtValue::from(compound.clone()), nbt!({ a: 14, b: 6b, d: 15 }));
+
+        // 删掉之后后面的下标也要对
+        compound.remove("a");
+        compound.insert("a", 1);
+        assert_eq!(
+            compound.values().cloned().collect::<Vec<_>>(),
+            [6i8.into(), 15.into(), 1.into()]
+        );
+        assert_eq!(compound.get("d"), Some(&NbtValue::Int(15)));
+        assert_eq!(compound.get("a"), Some(&NbtValue::Int(1)));
+    }
+
+    #[test]
+    fn compound_mutf8_keys() {
+        let value = nbt!({ "a\0b": 1, "😀": 2, "中文": 3, a: 4 });
+        let compound = NbtCompound::try_from(value.clone()).unwrap();
+        assert_eq!(compound.get("a\0b"), Some(&NbtValue::Int(1)));
+        assert_eq!(compound.get("😀"), Some(&NbtValue::Int(2)));
+        assert_eq!(compound.get("中文"), Some(&NbtValue::Int(3)));
+        assert_eq!(compound.get("a"), Some(&NbtValue::Int(4)));
+        assert_eq!(compound.get("a\0"), None);
+        assert_eq!(compound.clone().into_value(None), value);
+        assert!(compound.keys().zip(["a\0b", "😀", "中文", "a"]).all(|(k, s)| k.eq_str(s)));
+
+        // 重复的 key 保留第一次的位置, 最后一次的值
+        let duplicated = NbtCompound::from(vec![
+            ("x".into(), NbtValue::Int(1)),
+            ("y".into(), NbtValue::Int(2)),
+            ("x".into(), NbtValue::Int(3)),
+        ]);
+        assert_eq!(duplicated.into_vec(), vec![("x".into(), 3.into()), ("y".into(), 2.into())]);
+        assert_eq!(NbtCompound::try_from(NbtValue::Int(1)), Err(NbtError::IncorrectType(10, 3)));
+    }
+}
@@ -0,0 +1,166 @@
+//! gzip/zlib 压缩的 NBT
+//!
+//! Java 版的 `level.dat`, 玩家数据, 结构文件之类的基本都是 gzip 压缩过的
+//!
+//! 读取的时候会根据开头的几个字节自动判断是 gzip, zlib 还是没压缩的
+//!
+//! ```rust
+//! use shen_nbt6::compression::Compression;
+//! use shen_nbt6::nbt_version::Java;
+//! use shen_nbt6::{nbt, NbtValue};
+//!
+//! let value = nbt!({ "name": "Bananrama" });
+//! let data = value.to_compressed::<Java>(Compression::Gzip).unwrap();
+//! assert_eq!(Compression::detect(&data), Compression::Gzip);
+//! let read = NbtValue::from_compressed::<Java>(&data).unwrap();
+//! assert_eq!(read, NbtValue::from_binary::<Java>(&value.to_binary::<Java>().unwrap()).unwrap());
+//! ```
+
+use std::borrow::Cow;
+use std::io::{Read, Write};
+use std::path::Path;
+
+use flate2::read::{GzDecoder, ZlibDecoder};
+use flate2::write::{GzEncoder, ZlibEncoder};
+
+use crate::traits::{NbtReadTrait, NbtWriteTrait};
+use crate::{NbtResult, NbtValue};
+
+/// 压缩方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Compression {
+    /// gzip, 开头是 `1F 8B`
+    #[default]
+    Gzip,
+    /// zlib, 开头一般是 `78 xx`
+    Zlib,
+    /// 没压缩
+    None,
+}
+
+impl Compression {
+    /// 根据开头的几个字节判断压缩方式
+    ///
+    /// 没压缩的 NBT 开头是 tag (`0A`/`09`), 不会跟 gzip/zlib 的头混在一起
+    pub fn detect(data: &[u8]) -> Compression {
+        match data {
+            [0x1F, 0x8B, ..] => Compression::Gzip,
+            // zlib 的头: CM = 8 (deflate), 而且 CMF * 256 + FLG 是 31 的倍数
+            [cmf, flg, ..]
+                if cmf & 0x0F == 8 && u16::from_be_bytes([*cmf, *flg]).is_multiple_of(31) =>
+            {
+                Compression::Zlib
+            }
+            _ => Compression::None,
+        }
+    }
+
+    /// 解压, 没压缩的时候不会复制
+    pub fn decompress(self, data: &[u8]) -> NbtResult<Cow<'_, [u8]>> {
+        let mut buff = Vec::new();
+        match self {
+            Compression::Gzip => GzDecoder::new(data).read_to_end(&mut buff)?,
+            Compression::Zlib => ZlibDecoder::new(data).read_to_end(&mut buff)?,
+            Compression::None => return Ok(Cow::Borrowed(data)),
+        };
+        Ok(Cow::Owned(buff))
+    }
+
+    /// 压缩, 用的是 `flate2` 的默认压缩等级
+    pub fn compress(self, data: &[u8]) -> NbtResult<Vec<u8>> {
+        let level = flate2::Compression::default();
+        Ok(match self {
+            Compression::Gzip => {
+                let mut encoder = GzEncoder::new(Vec::new(), level);
+                encoder.write_all(data)?;
+                encoder.finish()?
+            }
+            Compression::Zlib => {
+                let mut encoder = ZlibEncoder::new(Vec::new(), level);
+                encoder.write_all(data)?;
+                encoder.finish()?
+            }
+            Compression::None => data.to_vec(),
+        })
+    }
+}
+
+impl NbtValue {
+    /// 解析可能压缩过的 Nbt 数据
+    ///
+    /// 根据开头的几个字节自动判断是 gzip, zlib 还是没压缩的, 见 [`Compression::detect`]
+    pub fn from_compressed<R>(data: &[u8]) -> NbtResult<NbtValue>
+    where
+        R: NbtReadTrait,
+    {
+        let data = Compression::detect(data).decompress(data)?;
+        NbtValue::from_binary::<R>(&data)
+    }
+
+    /// 读取一个 Nbt 文件, 可以是压缩过的, 见 [`NbtValue::from_compressed`]
+    pub fn from_file<R>(path: impl AsRef<Path>) -> NbtResult<NbtValue>
+    where
+        R: NbtReadTrait,
+    {
+        let data = std::fs::read(path)?;
+        NbtValue::from_compressed::<R>(&data)
+    }
+
+    /// 按照 `W` 的格式写成二进制, 再用 `compression` 压缩
+    pub fn to_compressed<W>(&self, compression: Compression) -> NbtResult<Vec<u8>>
+    where
+        W: NbtWriteTrait,
+    {
+        compression.compress(&W::to_bytes(self)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nbt_version::{BedrockDisk, Java};
+    use crate::tests::{BIG_TEST_BEDROCK_DATA, BIG_TEST_DATA};
+    use crate::NbtError;
+
+    #[test]
+    fn detect() {
+        assert_eq!(Compression::detect(&BIG_TEST_DATA), Compression::None);
+        assert_eq!(Compression::detect(&[0x09, 0x00]), Compression::None);
+        assert_eq!(Compression::detect(&[]), Compression::None);
+        assert_eq!(Compression::detect(&[0x1F, 0x8B, 0x08]), Compression::Gzip);
+        // 不同压缩等级的 zlib 头
+        for flg in [0x01, 0x5E, 0x9C, 0xDA] {
+            assert_eq!(Compression::detect(&[0x78, flg]), Compression::Zlib);
+        }
+        assert_eq!(Compression::detect(&[0x78, 0x00]), Compression::None);
+    }
+
+    #[test]
+    fn compressed_round_trip() {
+        let expect = NbtValue::from_binary::<Java>(&BIG_TEST_DATA).unwrap();
+        for compression in [Compression::Gzip, Compression::Zlib, Compression::None] {
+            let data = expect.to_compressed::<Java>(compression).unwrap();
+            assert_eq!(Compression::detect(&data), compression);
+            assert_eq!(NbtValue::from_compressed::<Java>(&data).unwrap(), expect);
+        }
+        let bedrock = NbtValue::from_binary::<BedrockDisk>(&BIG_TEST_BEDROCK_DATA).unwrap();
+        let data = Compression::Zlib.compress(&BIG_TEST_BEDROCK_DATA).unwrap();
+        assert_eq!(NbtValue::from_compressed::<BedrockDisk>(&data).unwrap(), bedrock);
+        // 坏掉的 gzip
+        let data = expect.to_compressed::<Java>(Compression::Gzip).unwrap();
+        let result = NbtValue::from_compressed::<Java>(&data[..data.len() / 2]);
+        assert!(matches!(result, Err(NbtError::IoError(..))));
+    }
+
+    #[test]
+    fn from_file() {
+        let path = std::env::temp_dir().join(format!("shen-nbt6-{}.nbt", std::process::id()));
+        let value = NbtValue::from_binary::<Java>(&BIG_TEST_DATA).unwrap();
+        std::fs::write(&path, value.to_compressed::<Java>(Compression::Gzip).unwrap()).unwrap();
+        let read = NbtValue::from_file::<Java>(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(read.unwrap(), value);
+        let missing = NbtValue::from_file::<Java>(path);
+        assert!(matches!(missing, Err(NbtError::IoError(std::io::ErrorKind::NotFound, _))));
+    }
+}
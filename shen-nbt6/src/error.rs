@@ -1,9 +1,12 @@
+use std::str::Utf8Error;
+
+use crate::mutf8::Mutf8Error;
 use std::error::Error;
 use std::fmt::Display;
-use std::str::Utf8Error;
 
+use crate::snbt::write_key;
 use crate::traits::NbtTypeConversion;
-use crate::NbtTypeId;
+use crate::{NbtResult, NbtTypeId};
 
 /// Error
 #[derive(Debug, Clone, PartialEq)]
@@ -38,9 +41,43 @@ pub enum NbtError {
     /// 错误类型
     IncorrectType(NbtTypeId, NbtTypeId),
     /// m-utf8 解码错误
-    Mutf8Error(Utf8Error),
+    Mutf8Error(Mutf8Error),
+    /// utf-8 解码错误 (基岩版的字符串)
+    Utf8Error(Utf8Error),
     /// NBT 深度过大
     NbtDepthTooBig(usize),
+    /// SNBT 语法错误
+    ///
+    /// 行, 列 (都从 1 开始), 错误信息
+    SnbtSyntax(usize, usize, String),
+    /// serde 序列化/反序列化时的错误
+    SerdeCustom(String),
+    /// 写入的时候长度超过了格式的限制
+    ///
+    /// path, len, max
+    /// 三个参数分别表示
+    /// - 出问题的位置 (NBT path, 类似 `Inventory[0].tag.Name`, 空的表示根节点)
+    /// - 实际长度
+    /// - 最大长度
+    LenTooBig(String, usize, usize),
+    /// 数据提前结束了 (从 [`std::io::Read`] 读的时候)
+    ///
+    /// 已经读到的位置
+    UnexpectedEof(usize),
+    /// 读写的时候出现的 IO 错误
+    ///
+    /// 错误类型, 错误信息
+    IoError(std::io::ErrorKind, String),
+    /// region 文件 (`.mca`/`.mcr`/`.mcc`) 的格式错误
+    RegionError(String),
+    /// 基岩版 `level.dat` 头里的长度跟实际的数据长度对不上
+    ///
+    /// 头里的长度, 实际长度
+    LevelDatLenNotMatch(u32, usize),
+    /// NBT path 语法错误
+    ///
+    /// 列 (按字符计算, 从 1 开始), 错误信息
+    NbtPathSyntax(usize, String),
 }
 
 impl Error for NbtError {}
@@ -91,11 +128,80 @@ impl Display for NbtError {
                 write!(f, "错误类型: 期望: {}, 实际: {}", expect, got)
             }
             NbtError::Mutf8Error(e) => write!(f, "m-utf8 解码错误: {}", e),
+            NbtError::Utf8Error(e) => write!(f, "utf-8 解码错误: {}", e),
             NbtError::NbtDepthTooBig(n) => write!(f, "NBT 深度过大, 仅支持 {} 深度", n),
+            NbtError::SnbtSyntax(line, column, msg) => {
+                write!(f, "SNBT 语法错误: {} 于 第 {} 行 第 {} 列", msg, line, column)
+            }
+            NbtError::SerdeCustom(msg) => write!(f, "serde 错误: {}", msg),
+            NbtError::LenTooBig(path, len, max) => {
+                let path = if path.is_empty() { "(根节点)" } else { path };
+                write!(f, "{} 长度过大: {}, 最大为 {}", path, len, max)
+            }
+            NbtError::UnexpectedEof(pos) => {
+                write!(f, "数据提前结束: 读到 {} 的时候没有数据了", pos)
+            }
+            NbtError::IoError(kind, msg) => write!(f, "IO 错误: {:?} {}", kind, msg),
+            NbtError::RegionError(msg) => write!(f, "region 文件错误: {}", msg),
+            NbtError::LevelDatLenNotMatch(header, actual) => {
+                write!(f, "level.dat 头里的长度为 {}, 实际数据长度为 {}", header, actual)
+            }
+            NbtError::NbtPathSyntax(column, msg) => {
+                write!(f, "NBT path 语法错误: {} 于 第 {} 列", msg, column)
+            }
+        }
+    }
+}
+
+impl NbtError {
+    /// 超过长度限制的时候返回 [`NbtError::LenTooBig`], 位置由外层补上
+    pub(crate) fn check_len(len: usize, max: usize) -> NbtResult<()> {
+        match len > max {
+            true => Err(NbtError::LenTooBig(String::new(), len, max)),
+            false => Ok(()),
+        }
+    }
+
+    /// 在错误的位置前面加上所在的 compound key
+    pub(crate) fn in_compound(self, key: &str) -> Self {
+        match self {
+            NbtError::LenTooBig(path, len, max) => {
+                let mut parent = String::new();
+                // 写进 String 不会失败
+                let _ = write_key(&mut parent, key);
+                NbtError::LenTooBig(join_path(parent, path), len, max)
+            }
+            other => other,
+        }
+    }
+
+    /// 在错误的位置前面加上所在的 list 下标
+    pub(crate) fn in_list(self, idx: usize) -> Self {
+        match self {
+            NbtError::LenTooBig(path, len, max) => {
+                NbtError::LenTooBig(join_path(format!("[{}]", idx), path), len, max)
+            }
+            other => other,
         }
     }
 }
 
+fn join_path(parent: String, child: String) -> String {
+    if child.is_empty() || child.starts_with('[') {
+        parent + &child
+    } else {
+        parent + "." + &child
+    }
+}
+
+impl From<Mutf8Error> for NbtError {
+    fn from(e: Mutf8Error) -> Self { NbtError::Mutf8Error(e) }
+}
+
 impl From<Utf8Error> for NbtError {
-    fn from(e: Utf8Error) -> Self { NbtError::Mutf8Error(e) }
+    fn from(e: Utf8Error) -> Self { NbtError::Utf8Error(e) }
+}
+
+impl From<std::io::Error> for NbtError {
+    fn from(e: std::io::Error) -> Self { NbtError::IoError(e.kind(), e.to_string()) }
 }
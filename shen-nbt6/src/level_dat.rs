@@ -0,0 +1,203 @@
+//! 基岩版的 `level.dat`
+//!
+//! 跟普通的 [`BedrockDisk`](crate::nbt_version::BedrockDisk) NBT 不一样, 前面还有 8 字节的头:
+//!
+//! - 存储版本 (小端 i32, 现在的版本是 10 左右)
+//! - 后面 NBT 数据的长度 (小端 u32)
+//!
+//! 直接用 `BedrockDisk` 读会把头当成 NBT, 不是报错就是读出来一堆错的东西
+//!
+//! ```rust
+//! use shen_nbt6::{nbt, BedrockLevelDat};
+//!
+//! let level = BedrockLevelDat::new(10, nbt!({ "LevelName": "My World" }));
+//! let data = level.to_binary().unwrap();
+//! assert_eq!(&data[..4], &[10, 0, 0, 0]);
+//! assert_eq!(u32::from_le_bytes(data[4..8].try_into().unwrap()) as usize, data.len() - 8);
+//!
+//! let read = BedrockLevelDat::from_binary(&data).unwrap();
+//! assert_eq!(read.storage_version, 10);
+//! assert_eq!(read.to_binary().unwrap(), data);
+//! ```
+
+use std::io::{self, Read};
+use std::path::Path;
+
+use crate::nbt_version::BedrockDisk;
+use crate::traits::NbtWriteTrait;
+use crate::{NbtError, NbtResult, NbtValue};
+
+/// 头的长度
+pub const HEADER_LEN: usize = 8;
+
+/// 一个基岩版的 `level.dat`
+#[derive(Debug, Clone, PartialEq)]
+pub struct BedrockLevelDat {
+    /// 头里的存储版本
+    pub storage_version: i32,
+    /// 后面的 NBT 数据
+    pub value: NbtValue,
+}
+
+impl BedrockLevelDat {
+    /// 新建一个, 写入的时候长度会重新算
+    pub fn new(storage_version: i32, value: NbtValue) -> Self {
+        Self {
+            storage_version,
+            value,
+        }
+    }
+
+    /// 读取带头的数据
+    ///
+    /// 头里的长度必须跟后面的数据长度一样, 不然返回 [`NbtError::LevelDatLenNotMatch`]
+    pub fn from_binary(data: &[u8]) -> NbtResult<Self> {
+        if data.len() < HEADER_LEN {
+            return Err(NbtError::CursorOverflow(0, HEADER_LEN, data.len()));
+        }
+        let (storage_version, len) = parse_header(data[..HEADER_LEN].try_into().unwrap());
+        let payload = &data[HEADER_LEN..];
+        if len as usize != payload.len() {
+            return Err(NbtError::LevelDatLenNotMatch(len, payload.len()));
+        }
+        Ok(Self {
+            storage_version,
+            value: NbtValue::from_binary::<BedrockDisk>(payload)?,
+        })
+    }
+
+    /// 从 [`std::io::Read`] 里读取, 只会读头里说的那么多数据
+    ///
+    /// 数据不够的时候返回 [`NbtError::UnexpectedEof`]
+    pub fn from_stream(mut reader: impl Read) -> NbtResult<Self> {
+        let mut header = [0; HEADER_LEN];
+        reader.read_exact(&mut header).map_err(|e| match e.kind() {
+            io::ErrorKind::UnexpectedEof => NbtError::UnexpectedEof(0),
+            _ => e.into(),
+        })?;
+        let (storage_version, len) = parse_header(header);
+        let mut payload = Vec::new();
+        reader.take(len as u64).read_to_end(&mut payload)?;
+        if payload.len() != len as usize {
+            return Err(NbtError::UnexpectedEof(HEADER_LEN + payload.len()));
+        }
+        Ok(Self {
+            storage_version,
+            value: NbtValue::from_binary::<BedrockDisk>(&payload)?,
+        })
+    }
+
+    /// 读取一个 `level.dat` 文件
+    pub fn from_file(path: impl AsRef<Path>) -> NbtResult<Self> {
+        Self::from_binary(&std::fs::read(path)?)
+    }
+
+    /// 写入 `buff`, 长度是按照写出来的 NBT 算的
+    pub fn write_to(&self, buff: &mut Vec<u8>) -> NbtResult<()> {
+        let start = buff.len();
+        buff.extend_from_slice(&self.storage_version.to_le_bytes());
+        buff.extend_from_slice(&[0; 4]);
+        if let Err(e) = BedrockDisk::write_to(&self.value, buff) {
+            buff.truncate(start);
+            return Err(e);
+        }
+        let len = buff.len() - start - HEADER_LEN;
+        if let Err(e) = NbtError::check_len(len, u32::MAX as usize) {
+            buff.truncate(start);
+            return Err(e);
+        }
+        buff[start + 4..start + HEADER_LEN].copy_from_slice(&(len as u32).to_le_bytes());
+        Ok(())
+    }
+
+    /// 写成带头的二进制
+    pub fn to_binary(&self) -> NbtResult<Vec<u8>> {
+        let mut buff = Vec::new();
+        self.write_to(&mut buff)?;
+        Ok(buff)
+    }
+
+    /// 写入一个 `level.dat` 文件
+    pub fn write_file(&self, path: impl AsRef<Path>) -> NbtResult<()> {
+        std::fs::write(path, self.to_binary()?)?;
+        Ok(())
+    }
+}
+
+/// 存储版本, 长度
+fn parse_header(header: [u8; HEADER_LEN]) -> (i32, u32) {
+    (
+        i32::from_le_bytes(header[..4].try_into().unwrap()),
+        u32::from_le_bytes(header[4..].try_into().unwrap()),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nbt;
+    use crate::tests::BIG_TEST_BEDROCK_DATA;
+
+    fn with_header(version: i32, data: &[u8]) -> Vec<u8> {
+        let mut buff = version.to_le_bytes().to_vec();
+        buff.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        buff.extend_from_slice(data);
+        buff
+    }
+
+    #[test]
+    fn read_write() {
+        let data = with_header(10, &BIG_TEST_BEDROCK_DATA);
+        let expect = NbtValue::from_binary::<BedrockDisk>(&BIG_TEST_BEDROCK_DATA).unwrap();
+        // 没有专门处理头的话是读不对的
+        assert_ne!(NbtValue::from_binary::<BedrockDisk>(&data).ok(), Some(expect.clone()));
+        let level = BedrockLevelDat::from_binary(&data).unwrap();
+        assert_eq!(level.storage_version, 10);
+        assert_eq!(level.value, expect);
+        assert_eq!(BedrockLevelDat::from_stream(&data[..]).unwrap(), level);
+
+        // 改了之后长度会重新算
+        let mut level = BedrockLevelDat::new(9, nbt!({ "LevelName": "a" }));
+        let data = level.to_binary().unwrap();
+        assert_eq!(data, with_header(9, &BedrockDisk::to_bytes(&level.value).unwrap()));
+        level.value = nbt!({ "LevelName": "a much longer name" });
+        let data = level.to_binary().unwrap();
+        assert_eq!(BedrockLevelDat::from_binary(&data).unwrap().to_binary().unwrap(), data);
+        assert_eq!(data, with_header(9, &BedrockDisk::to_bytes(&level.value).unwrap()));
+        // 追加在已有的数据后面
+        let mut buff = vec![1, 2, 3];
+        level.write_to(&mut buff).unwrap();
+        assert_eq!(&buff[3..], &data[..]);
+    }
+
+    #[test]
+    fn bad_header() {
+        let data = with_header(10, &BIG_TEST_BEDROCK_DATA);
+        assert_eq!(
+            BedrockLevelDat::from_binary(&data[..5]),
+            Err(NbtError::CursorOverflow(0, 8, 5))
+        );
+        let result = BedrockLevelDat::from_binary(&data[..100]);
+        assert_eq!(result, Err(NbtError::LevelDatLenNotMatch(1544, 92)));
+        let mut longer = data.clone();
+        longer.push(0);
+        let result = BedrockLevelDat::from_binary(&longer);
+        assert_eq!(result, Err(NbtError::LevelDatLenNotMatch(1544, 1545)));
+        // 流式读取只读头里说的那么多
+        let mut reader = &longer[..];
+        assert!(BedrockLevelDat::from_stream(&mut reader).is_ok());
+        assert_eq!(reader, &[0]);
+        assert_eq!(BedrockLevelDat::from_stream(&data[..5]), Err(NbtError::UnexpectedEof(0)));
+        assert_eq!(BedrockLevelDat::from_stream(&data[..100]), Err(NbtError::UnexpectedEof(100)));
+    }
+
+    #[test]
+    fn file() {
+        let path = std::env::temp_dir().join(format!("shen-nbt6-level-{}.dat", std::process::id()));
+        let level = BedrockLevelDat::new(10, nbt!({ "LevelName": "My World", "GameType": 1 }));
+        level.write_file(&path).unwrap();
+        let read = BedrockLevelDat::from_file(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(read.unwrap().to_binary().unwrap(), level.to_binary().unwrap());
+    }
+}
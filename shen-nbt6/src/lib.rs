@@ -1,19 +1,41 @@
 /// 仅借用的实现
 pub mod borrow;
+/// 带索引的 compound
+pub mod compound;
+/// 如果 `compression` 特性被启用，则导出 `compression` 模块
+///
+/// 用于读写 gzip/zlib 压缩过的 Nbt 数据
+#[cfg(feature = "compression")]
+pub mod compression;
 /// Error
 pub mod error;
+/// 基岩版 `level.dat` (带 8 字节的头)
+pub mod level_dat;
 /// nbt! 宏
 pub mod macros;
 /// 感谢 @mat 允许我使用他的代码
 ///
 /// 用于处理 mutf8 编码
 pub mod mutf8;
+/// NBT path (`Inventory[{Slot:0b}].tag.display.Name`)
+pub mod path;
 /// 几乎就是从 v5 copy 过来的
 pub mod reader;
+/// 如果 `region` 特性被启用，则导出 `region` 模块
+///
+/// 用于读写 `.mca`/`.mcr` 区域文件
+#[cfg(feature = "region")]
+pub mod region;
+/// SNBT 解析/输出
+pub mod snbt;
+/// 从 `std::io::Read` 里流式读取
+pub mod stream;
 /// 一些实现
 pub mod traits;
 /// 核心 value 实现
 pub mod value;
+/// 各个版本的写入实现
+pub mod writer;
 
 #[cfg(test)]
 mod tests;
@@ -21,15 +43,25 @@ mod tests;
 /// 如果 `serde` 特性被启用，则导出 `serding` 模块
 ///
 /// 用于序列化和反序列化 Nbt 数据
-///
-/// TODO!
 #[cfg(feature = "serde")]
 pub mod serding;
 
+/// 如果 `tokio` 特性被启用，则导出 `tokio_io` 模块
+///
+/// 用于异步读写网络 NBT, 还有 `tokio_util` 的 codec
+#[cfg(feature = "tokio")]
+pub mod tokio_io;
+
 // re-exports
+pub use compound::NbtCompound;
 pub use error::NbtError;
+pub use level_dat::BedrockLevelDat;
 pub use mutf8::Mutf8String;
+pub use path::NbtPath;
 pub use reader::NbtReader;
+#[cfg(feature = "serde")]
+pub use serding::{from_bytes, from_value, to_bytes, to_value};
+pub use stream::NbtStreamReader;
 pub use value::NbtValue;
 
 /// 用于存储 Nbt 类型的标识符
@@ -55,6 +87,8 @@ pub mod nbt_version {
     /// # 编码特点
     ///
     /// 大端
+    ///
+    /// 字符串是 m-utf8 (Modified UTF-8)
     pub struct Java;
     /// 1.20.2+(协议号 >= 764) 及以后 的网路传输 NBT 格式
     ///
@@ -67,6 +101,8 @@ pub mod nbt_version {
     /// # 编码特点
     ///
     /// 小端
+    ///
+    /// 字符串是普通的 utf-8
     pub struct BedrockDisk;
     /// 基岩版 网络 NBT 格式
     /// 最痛苦的一集
@@ -75,6 +111,8 @@ pub mod nbt_version {
     ///
     /// VarInt, VarLong, ZigZagVarInt, ZigZagVarLong
     /// 全都有
+    ///
+    /// 字符串是普通的 utf-8
     pub struct BedrockNetVarInt;
 }
 
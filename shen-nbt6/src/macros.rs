@@ -1,6 +1,52 @@
-/// 参考实现:
-/// https://github.com/serde-rs/json/blob/master/src/macros.rs#L70
-/// TODO
+//! 用类似 SNBT 的语法直接构建 [`NbtValue`]
+//!
+//! 参考实现: <https://github.com/serde-rs/json/blob/master/src/macros.rs>
+//!
+//! ```rust
+//! use shen_nbt6::{nbt, NbtValue};
+//!
+//! let name = "shenjack";
+//! let value = nbt!({
+//!     Name: (name),
+//!     "Health": 20.0f,
+//!     Level: 3l,
+//!     OnGround: true,
+//!     Pos: [1.5, 64.0, -2.5],
+//!     UUID: [I; 1, 2, 3, -4],
+//!     Inventory: [{ Slot: 0B, Count: 64b, id: "minecraft:stone" }],
+//! });
+//! let NbtValue::Compound(None, items) = value else { unreachable!() };
+//! assert_eq!(items[0].1, NbtValue::String("shenjack".into()));
+//! assert_eq!(items[5].1, NbtValue::IntArray(vec![1, 2, 3, -4]));
+//! ```
+//!
+//! - 数字字面量跟 SNBT 一样: `1b` `2s` `3` `4l` `1.5f` `1.5`/`1.5d`,
+//!   也可以用 Rust 的后缀 `1i8` `2i16` `1.5f32` 之类的
+//!   (`0b` 会被 Rust 当成二进制数字的开头, 请写成 `0B` 或者 `0i8`)
+//! - `[B; ...]` `[I; ...]` `[L; ...]` 是 Array, 其他的 `[...]` 是 List
+//! - compound 的 key 可以是标识符 (`Name`), 字符串 (`"Name"`) 或者 `(表达式)`
+//! - `"name": { ... }` 是带名字的 compound
+//! - 其他的东西会当成 Rust 表达式, 用 `NbtValue::from` 转换
+
+use crate::snbt::{is_float, parse_unquoted};
+use crate::{nbt_consts, Mutf8String, NbtValue};
+
+/// 用类似 SNBT 的语法构建 [`NbtValue`], 见 [`crate::macros`]
+///
+/// 数字字面量和 Array 里的数字字面量会在编译的时候检查范围, 放不下就编译不过:
+///
+/// ```compile_fail
+/// shen_nbt6::nbt!(128b);
+/// ```
+///
+/// ```compile_fail
+/// shen_nbt6::nbt!([B; 1, 300]);
+/// ```
+///
+/// # Panics
+///
+/// 编译的时候检查不了表达式, 所以 Array 里的表达式放不下或者类型不对
+/// (比如 `[B; (x)]` 里 `x` 是 `300`) 的时候会 panic
 #[macro_export]
 macro_rules! nbt {
     ($($nbt:tt)+) => {
@@ -9,44 +55,89 @@ macro_rules! nbt {
 }
 
 #[macro_export]
+#[doc(hidden)]
 macro_rules! nbt_inner {
+    // 解析 list
+    // 每次吃掉一个 tt, 直到遇到逗号
+
+    // 结束
+    (@list [$($elems:expr,)*] ()) => {
+        vec![$($elems,)*]
+    };
+    // 最后一个没有逗号的
+    (@list [$($elems:expr,)*] ($($value:tt)+)) => {
+        vec![$($elems,)* $crate::nbt_inner!($($value)+),]
+    };
+    // 一个 tt 就是一整个值的情况, 一次处理掉, 省一点递归深度
+    (@list [$($elems:expr,)*] () $a:tt , $b:tt , $c:tt , $d:tt , $($rest:tt)*) => {
+        $crate::nbt_inner!(
+            @list
+            [
+                $($elems,)*
+                $crate::nbt_inner!($a),
+                $crate::nbt_inner!($b),
+                $crate::nbt_inner!($c),
+                $crate::nbt_inner!($d),
+            ]
+            ()
+            $($rest)*
+        )
+    };
+    (@list [$($elems:expr,)*] () $value:tt , $($rest:tt)*) => {
+        $crate::nbt_inner!(@list [$($elems,)* $crate::nbt_inner!($value),] () $($rest)*)
+    };
+    // 遇到逗号了
+    (@list [$($elems:expr,)*] ($($value:tt)+) , $($rest:tt)*) => {
+        $crate::nbt_inner!(@list [$($elems,)* $crate::nbt_inner!($($value)+),] () $($rest)*)
+    };
+    // 接着吃
+    (@list [$($elems:expr,)*] ($($value:tt)*) $next:tt $($rest:tt)*) => {
+        $crate::nbt_inner!(@list [$($elems,)*] ($($value)* $next) $($rest)*)
+    };
 
-    // // Done with trailing comma.
-    // (@array [$($elems:expr,)*]) => {
-    //     vec![$($elems,)*]
-    // };
-    // // Done without trailing comma.
-    // (@array [$($elems:expr),*]) => {
-    //     vec![$($elems),*]
-    // };
-
-    // // 解析 array
-
-    // // next true/false
-    // (@array [$($elems:expr,)*] true $($rest:tt)*) => {
-    //     $crate::nbt_inner!(@array [$($elems,)* $crate::NbtValue::value_true()] $($rest)*)
-    // };
-    // (@array [$($elems:expr,)*] false $($rest:tt)*) => {
-    //     $crate::nbt_inner!(@array [$($elems,)* $crate::NbtValue::value_false()] $($rest)*)
-    // };
-
-    // // next list
-    // (@array [$($elems:expr,)*] [$($next:tt),*] $($rest:tt)*) => {
-    //     $crate::nbt_inner!(@array [$($elems,)* $crate::NbtValue::List($crate::nbt_inner!(@array [] $($next)*))] $($rest)*)
-    // };
-
-    // // 下一个是跟着逗号的
-    // (@array [$($elems:expr,)*] $next:expr, $($rest:tt)*) => {
-    //     $crate::nbt_inner!(@array [$($elems,)* $crate::nbt_inner!($next),] $($rest)*)
-    // };
-    // // 最后一个
-    // (@array [$($elems:expr,)*] $last:expr) => {
-    //     $crate::nbt_inner!(@array [$($elems,)* $crate::nbt_inner!($last)])
-    // };
-    // // 跟着逗号的 (去掉尾逗号, 开始下一个匹配)
-    // (@array [$($elems:expr,)*] , $($rest:tt)*) => {
-    //     $crate::nbt_inner!(@array [$($elems,)*] $($rest)*)
-    // };
+    // 解析 compound
+
+    // 结束
+    (@compound [$($items:expr,)*]) => {
+        vec![$($items,)*]
+    };
+    // key: 一个 tt, 然后逗号
+    (@compound [$($items:expr,)*] $key:tt : $value:tt , $($rest:tt)*) => {
+        $crate::nbt_inner!(
+            @compound
+            [$($items,)* ($crate::nbt_inner!(@key $key), $crate::nbt_inner!($value)),]
+            $($rest)*
+        )
+    };
+    // key: 一个 tt, 最后一个
+    (@compound [$($items:expr,)*] $key:tt : $value:tt) => {
+        vec![$($items,)* ($crate::nbt_inner!(@key $key), $crate::nbt_inner!($value)),]
+    };
+    // key: 好几个 tt
+    (@compound [$($items:expr,)*] $key:tt : $($rest:tt)+) => {
+        $crate::nbt_inner!(@compound_value [$($items,)*] ($crate::nbt_inner!(@key $key)) () $($rest)+)
+    };
+    // 跟 list 一样, 吃到逗号为止
+    (@compound_value [$($items:expr,)*] ($key:expr) ($($value:tt)+)) => {
+        vec![$($items,)* ($key, $crate::nbt_inner!($($value)+)),]
+    };
+    (@compound_value [$($items:expr,)*] ($key:expr) ($($value:tt)+) , $($rest:tt)*) => {
+        $crate::nbt_inner!(@compound [$($items,)* ($key, $crate::nbt_inner!($($value)+)),] $($rest)*)
+    };
+    (@compound_value [$($items:expr,)*] ($key:expr) ($($value:tt)*) $next:tt $($rest:tt)*) => {
+        $crate::nbt_inner!(@compound_value [$($items,)*] ($key) ($($value)* $next) $($rest)*)
+    };
+
+    // compound 的 key
+    (@key ($key:expr)) => {
+        $crate::Mutf8String::from($key)
+    };
+    (@key $key:ident) => {
+        $crate::Mutf8String::from(stringify!($key))
+    };
+    (@key $key:literal) => {
+        $crate::macros::literal_key(stringify!($key))
+    };
 
     // snbt 的 true & false
     (true) => {
@@ -55,39 +146,338 @@ macro_rules! nbt_inner {
     (false) => {
         $crate::NbtValue::value_false()
     };
-    // string
-    // 放在 true & false 之后，因为 true & false 也是一个字面量
-    ($value:literal) => {
-        $crate::NbtValue::String($crate::Mutf8String::from($value))
+
+    // array
+    ([B; $($tt:tt)*]) => {{
+        const _: () = $crate::macros::check_array(b'B', stringify!($($tt)*));
+        $crate::macros::array(b'B', $crate::nbt_inner!(@list [] () $($tt)*))
+    }};
+    ([I; $($tt:tt)*]) => {{
+        const _: () = $crate::macros::check_array(b'I', stringify!($($tt)*));
+        $crate::macros::array(b'I', $crate::nbt_inner!(@list [] () $($tt)*))
+    }};
+    ([L; $($tt:tt)*]) => {{
+        const _: () = $crate::macros::check_array(b'L', stringify!($($tt)*));
+        $crate::macros::array(b'L', $crate::nbt_inner!(@list [] () $($tt)*))
+    }};
+    // list
+    ([ $($tt:tt)* ]) => {
+        $crate::NbtValue::List($crate::nbt_inner!(@list [] () $($tt)*))
     };
-    // 空 list
-    ([]) => {
-        $crate::NbtValue::List(Vec::new())
+
+    // 负数, 要在其他 literal 之前处理, 不然 `-x` 会让 literal 的匹配直接报错
+    (- $value:literal) => {{
+        const _: () = $crate::macros::check_literal(concat!("-", stringify!($value)));
+        $crate::macros::literal(concat!("-", stringify!($value)))
+    }};
+    (- $($other:tt)+) => {
+        $crate::NbtValue::from(- $($other)+)
     };
 
-    // list
-    ([ $($tt:tt)+ ]) => {
-        $crate::NbtValue::List($crate::nbt_inner!(@array [] $($tt)+))
+    // compound
+    ({ $($tt:tt)* }) => {
+        $crate::NbtValue::Compound(None, $crate::nbt_inner!(@compound [] $($tt)*))
     };
+    // 带名字的 compound
+    ($name:literal : { $($tt:tt)* }) => {
+        $crate::NbtValue::Compound(
+            Some($crate::macros::literal_key(stringify!($name))),
+            $crate::nbt_inner!(@compound [] $($tt)*),
+        )
+    };
+
+    // 字符串和数字
+    // 放在 true & false 之后，因为 true & false 也是一个字面量
+    ($value:literal) => {{
+        const _: () = $crate::macros::check_literal(stringify!($value));
+        $crate::macros::literal(stringify!($value))
+    }};
 
-    // 空 compound
-    ({}) => {
-        $crate::NbtValue::Compound(None, Vec::new())
+    // 其他的当作表达式
+    ($other:expr) => {
+        $crate::NbtValue::from($other)
     };
-    // compound with name
-    ($name:tt: { $($key:tt: $value:tt),* }) => {
-        $crate::NbtValue::Compound(Some($crate::nbt_inner!($name)), vec![$((nbt!($key), $crate::nbt_inner!($value))),*])
+}
+
+/// 解析 Rust 的字符串/字符字面量
+fn unescape(text: &str) -> Option<String> {
+    // raw string
+    if let Some(raw) = text.strip_prefix('r') {
+        let hashes = raw.len() - raw.trim_start_matches('#').len();
+        let body = raw.get(hashes..raw.len().checked_sub(hashes)?)?;
+        return Some(body.strip_prefix('"')?.strip_suffix('"')?.to_string());
+    }
+    let body = match text.as_bytes().first()? {
+        b'"' => text.strip_prefix('"')?.strip_suffix('"')?,
+        b'\'' => text.strip_prefix('\'')?.strip_suffix('\'')?,
+        _ => return None,
     };
-    // compound without name
-    ({ $($key:tt: $value:tt),* }) => {
-        $crate::NbtValue::Compound(None, vec![$(($crate::nbt_inner!($key), $crate::nbt_inner!($value))),*])
+    let mut result = String::with_capacity(body.len());
+    let mut chars = body.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+        match chars.next()? {
+            'n' => result.push('\n'),
+            'r' => result.push('\r'),
+            't' => result.push('\t'),
+            '0' => result.push('\0'),
+            '\\' => result.push('\\'),
+            '\'' => result.push('\''),
+            '"' => result.push('"'),
+            'x' => {
+                let hex: String = chars.by_ref().take(2).collect();
+                result.push(char::from(u8::from_str_radix(&hex, 16).ok()?));
+            }
+            'u' => {
+                let hex: String = chars.by_ref().skip(1).take_while(|c| *c != '}').collect();
+                result.push(char::from_u32(u32::from_str_radix(&hex, 16).ok()?)?);
+            }
+            // 行尾的 `\` 会吃掉后面的空白
+            '\n' => while chars.next_if(|c| c.is_whitespace()).is_some() {},
+            _ => return None,
+        }
+    }
+    Some(result)
+}
+
+/// 解析数字字面量, 同时支持 SNBT 和 Rust 的后缀
+fn parse_number(text: &str) -> Option<NbtValue> {
+    let text = text.replace('_', "");
+    macro_rules! rust_suffix {
+        ($($suffix:literal => $variant:ident($ty:ty as $target:ty)),*) => {
+            $(
+                if let Some(body) = text.strip_suffix($suffix) {
+                    return body.parse::<$ty>().ok().map(|v| NbtValue::$variant(v as $target));
+                }
+            )*
+        };
+    }
+    // 无符号的按位转换, 跟 serde 那边一样
+    rust_suffix! {
+        "i8" => Byte(i8 as i8),
+        "u8" => Byte(u8 as i8),
+        "i16" => Short(i16 as i16),
+        "u16" => Short(u16 as i16),
+        "i32" => Int(i32 as i32),
+        "u32" => Int(u32 as i32),
+        "i64" => Long(i64 as i64),
+        "u64" => Long(u64 as i64),
+        "f32" => Float(f32 as f32),
+        "f64" => Double(f64 as f64)
+    }
+    match parse_unquoted(&text) {
+        NbtValue::String(_) if is_float(&text, false) => text.parse().ok().map(NbtValue::Double),
+        NbtValue::String(_) => None,
+        value => Some(value),
+    }
+}
+
+/// `nbt!` 里的字面量
+///
+/// 字符串字面量 -> String, 数字字面量按照后缀决定类型
+#[doc(hidden)]
+pub fn literal(text: &str) -> NbtValue {
+    if let Some(value) = parse_number(text) {
+        return value;
+    }
+    match unescape(text) {
+        Some(s) => NbtValue::String(s.into()),
+        None => panic!("nbt! 里无法识别的字面量: {}", text),
+    }
+}
+
+/// `nbt!` 里 compound 的字面量 key
+///
+/// 字符串就是它本身, 数字之类的按原样当作 key
+#[doc(hidden)]
+pub fn literal_key(text: &str) -> Mutf8String {
+    match unescape(text) {
+        Some(s) => s.into(),
+        None => text.into(),
+    }
+}
+
+/// `nbt!` 里的 `[B;` `[I;` `[L;`
+///
+/// 跟 SNBT 一样, 元素可以不写后缀, 只要数字放得下就行
+#[doc(hidden)]
+pub fn array(array_type: u8, values: Vec<NbtValue>) -> NbtValue {
+    let number = |value: &NbtValue| -> Option<i64> {
+        match (array_type, value) {
+            (b'B', NbtValue::Byte(v)) => Some(*v as i64),
+            (b'L', NbtValue::Long(v)) => Some(*v),
+            (_, NbtValue::Int(v)) => Some(*v as i64),
+            _ => None,
+        }
     };
+    let numbers = values.iter().map(|value| {
+        number(value)
+            .unwrap_or_else(|| panic!("nbt! 的 [{};] 里不能放 {}", array_type as char, value))
+    });
+    let out_of_range =
+        |n: i64| -> ! { panic!("nbt! 的 [{};] 里的 {} 超出范围了", array_type as char, n) };
+    match array_type {
+        b'B' => NbtValue::ByteArray(
+            numbers.map(|n| i8::try_from(n).unwrap_or_else(|_| out_of_range(n))).collect(),
+        ),
+        b'I' => NbtValue::IntArray(numbers.map(|n| n as i32).collect()),
+        _ => NbtValue::LongArray(numbers.collect()),
+    }
+}
+
+/// 解析出来的整数字面量
+struct IntLiteral {
+    value: i128,
+    /// 后缀决定的范围
+    min: i128,
+    max: i128,
+    /// 后缀决定的类型
+    tag: u8,
+}
+
+/// 在编译期解析 `text[start..end]` 里的整数字面量, 浮点数和其他的东西都是 `None`
+///
+/// 跟 [`parse_number`] 认的后缀一样
+const fn int_literal(text: &[u8], start: usize, end: usize) -> Option<IntLiteral> {
+    const fn suffix_is(text: &[u8], start: usize, end: usize, suffix: &[u8]) -> bool {
+        if end - start != suffix.len() {
+            return false;
+        }
+        let mut i = 0;
+        while i < suffix.len() {
+            if text[start + i] != suffix[i] {
+                return false;
+            }
+            i += 1;
+        }
+        true
+    }
+
+    let mut i = start;
+    let negative = i < end && text[i] == b'-';
+    if negative {
+        i += 1;
+        while i < end && text[i] == b' ' {
+            i += 1;
+        }
+    }
+    if i >= end || !text[i].is_ascii_digit() {
+        return None;
+    }
+    // 0x 0o 0b 之类的交给运行时
+    if text[i] == b'0' && i + 1 < end && matches!(text[i + 1], b'x' | b'o' | b'b') {
+        return None;
+    }
+    let mut value: i128 = 0;
+    while i < end && (text[i].is_ascii_digit() || text[i] == b'_') {
+        if text[i] != b'_' {
+            value = value.saturating_mul(10).saturating_add((text[i] - b'0') as i128);
+        }
+        i += 1;
+    }
+    if negative {
+        value = -value;
+    }
+    macro_rules! range {
+        ($ty:ty, $tag:expr) => {
+            Some(IntLiteral {
+                value,
+                min: <$ty>::MIN as i128,
+                max: <$ty>::MAX as i128,
+                tag: $tag,
+            })
+        };
+    }
+    macro_rules! is {
+        ($suffix:literal) => {
+            suffix_is(text, i, end, $suffix)
+        };
+    }
+    if is!(b"") {
+        range!(i32, nbt_consts::TAG_INT)
+    } else if is!(b"b") || is!(b"B") || is!(b"i8") {
+        range!(i8, nbt_consts::TAG_BYTE)
+    } else if is!(b"u8") {
+        range!(u8, nbt_consts::TAG_BYTE)
+    } else if is!(b"s") || is!(b"S") || is!(b"i16") {
+        range!(i16, nbt_consts::TAG_SHORT)
+    } else if is!(b"u16") {
+        range!(u16, nbt_consts::TAG_SHORT)
+    } else if is!(b"i32") {
+        range!(i32, nbt_consts::TAG_INT)
+    } else if is!(b"u32") {
+        range!(u32, nbt_consts::TAG_INT)
+    } else if is!(b"l") || is!(b"L") || is!(b"i64") {
+        range!(i64, nbt_consts::TAG_LONG)
+    } else if is!(b"u64") {
+        range!(u64, nbt_consts::TAG_LONG)
+    } else {
+        None
+    }
+}
 
+/// `nbt!` 里的数字字面量放不下的时候让编译失败
+#[doc(hidden)]
+pub const fn check_literal(text: &str) {
+    let text = text.as_bytes();
+    if let Some(literal) = int_literal(text, 0, text.len()) {
+        if literal.value < literal.min || literal.value > literal.max {
+            panic!("nbt! 里的数字超出了它的类型的范围");
+        }
+    }
+}
+
+/// `nbt!` 里的 `[B;` `[I;` `[L;` 的数字字面量放不下或者类型不对的时候让编译失败
+///
+/// 表达式检查不了, 留给 [`array`] 在运行时检查
+#[doc(hidden)]
+pub const fn check_array(array_type: u8, text: &str) {
+    let (min, max, tag) = match array_type {
+        b'B' => (i8::MIN as i128, i8::MAX as i128, nbt_consts::TAG_BYTE),
+        b'I' => (i32::MIN as i128, i32::MAX as i128, nbt_consts::TAG_INT),
+        _ => (i64::MIN as i128, i64::MAX as i128, nbt_consts::TAG_LONG),
+    };
+    let text = text.as_bytes();
+    let mut start = 0;
+    let mut depth = 0;
+    let mut i = 0;
+    while i <= text.len() {
+        if i == text.len() || (depth == 0 && text[i] == b',') {
+            let (mut s, mut e) = (start, i);
+            while s < e && text[s] == b' ' {
+                s += 1;
+            }
+            while e > s && text[e - 1] == b' ' {
+                e -= 1;
+            }
+            if let Some(literal) = int_literal(text, s, e) {
+                // 不写后缀的会被当成 Int, 只要放得下就行
+                if literal.tag == nbt_consts::TAG_INT {
+                    if literal.value < min || literal.value > max {
+                        panic!("nbt! 的 Array 里的数字超出范围了");
+                    }
+                } else if literal.tag != tag {
+                    panic!("nbt! 的 Array 里的数字类型不对");
+                }
+            }
+            start = i + 1;
+        } else {
+            match text[i] {
+                b'(' | b'[' | b'{' => depth += 1,
+                b')' | b']' | b'}' => depth -= 1,
+                _ => {}
+            }
+        }
+        i += 1;
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::{nbt, NbtValue};
+    use crate::{Mutf8String, NbtValue};
 
     #[test]
     fn just_true_false() {
@@ -98,10 +488,151 @@ mod tests {
     #[test]
     fn just_string() {
         assert_eq!(nbt!("hello"), NbtValue::String("hello".into()));
+        assert_eq!(nbt!("a\"b\\n\n\u{4e2d}"), NbtValue::String("a\"b\\n\n中".into()));
+        assert_eq!(nbt!(r#"a"b"#), NbtValue::String("a\"b".into()));
+        assert_eq!(nbt!('c'), NbtValue::String("c".into()));
     }
 
     #[test]
     fn just_list() {
         assert_eq!(nbt!([]), NbtValue::List(Vec::new()));
     }
+
+    #[test]
+    fn numbers() {
+        assert_eq!(nbt!(1b), NbtValue::Byte(1));
+        assert_eq!(nbt!(-2b), NbtValue::Byte(-2));
+        assert_eq!(nbt!(0B), NbtValue::Byte(0));
+        assert_eq!(nbt!(2s), NbtValue::Short(2));
+        assert_eq!(nbt!(3), NbtValue::Int(3));
+        assert_eq!(nbt!(3l), NbtValue::Long(3));
+        assert_eq!(nbt!(1_000_000L), NbtValue::Long(1_000_000));
+        assert_eq!(nbt!(1.5f), NbtValue::Float(1.5));
+        assert_eq!(nbt!(1.5), NbtValue::Double(1.5));
+        assert_eq!(nbt!(1.5d), NbtValue::Double(1.5));
+        assert_eq!(nbt!(1e3), NbtValue::Double(1000.0));
+        assert_eq!(nbt!(1i8), NbtValue::Byte(1));
+        assert_eq!(nbt!(255u8), NbtValue::Byte(-1));
+        assert_eq!(nbt!(7i64), NbtValue::Long(7));
+        assert_eq!(nbt!(0.25f32), NbtValue::Float(0.25));
+    }
+
+    #[test]
+    fn lists() {
+        assert_eq!(
+            nbt!([1b, -2b, 3b,]),
+            NbtValue::List(vec![NbtValue::Byte(1), NbtValue::Byte(-2), NbtValue::Byte(3)])
+        );
+        assert_eq!(
+            nbt!([[1, 2], [], ["a"]]),
+            NbtValue::List(vec![
+                NbtValue::List(vec![NbtValue::Int(1), NbtValue::Int(2)]),
+                NbtValue::List(vec![]),
+                NbtValue::List(vec![NbtValue::String("a".into())]),
+            ])
+        );
+        let long_list = nbt!([
+            0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 0, 1, 2, 3, 4, 5, 6, 7, 8,
+            9, 0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 0, 1, 2, 3, 4, 5, 6, 7,
+            8, 9, 0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 0, 1, 2, 3, 4, 5, 6,
+            7, 8, 9, 0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 0, 1, 2, 3, 4, 5,
+            6, 7, 8, 9, 0, 1, 2, 3, 4, 5, 6, 7, 8, 9,
+        ]);
+        let NbtValue::List(long_list) = long_list else {
+            unreachable!()
+        };
+        assert_eq!(long_list.len(), 130);
+    }
+
+    #[test]
+    fn arrays() {
+        assert_eq!(nbt!([B; 1b, -2, true]), NbtValue::ByteArray(vec![1, -2, 1]));
+        assert_eq!(nbt!([I; 1, 2, 3]), NbtValue::IntArray(vec![1, 2, 3]));
+        assert_eq!(nbt!([I;]), NbtValue::IntArray(vec![]));
+        assert_eq!(nbt!([L; 1l, 2, -3L]), NbtValue::LongArray(vec![1, 2, -3]));
+        let x = 5;
+        assert_eq!(nbt!([I; x, x * 2]), NbtValue::IntArray(vec![5, 10]));
+    }
+
+    #[test]
+    #[should_panic]
+    fn array_out_of_range() {
+        let x = 300;
+        nbt!([B; x]);
+    }
+
+    #[test]
+    fn compounds() {
+        assert_eq!(nbt!({}), NbtValue::Compound(None, Vec::new()));
+        let value = nbt!({
+            a: 1b,
+            "b c": { d: [1s, 2s] },
+            ("e".to_string() + "f"): -1.5f,
+            0: "zero"
+        });
+        assert_eq!(
+            value,
+            NbtValue::Compound(
+                None,
+                vec![
+                    ("a".into(), NbtValue::Byte(1)),
+                    (
+                        "b c".into(),
+                        NbtValue::Compound(
+                            None,
+                            vec![(
+                                "d".into(),
+                                NbtValue::List(vec![NbtValue::Short(1), NbtValue::Short(2)])
+                            )]
+                        )
+                    ),
+                    ("ef".into(), NbtValue::Float(-1.5)),
+                    ("0".into(), NbtValue::String("zero".into())),
+                ]
+            )
+        );
+        assert_eq!(
+            nbt!("hello world": { name: "Bananrama" }),
+            NbtValue::Compound(
+                Some(Mutf8String::from("hello world")),
+                vec![("name".into(), NbtValue::String("Bananrama".into()))]
+            )
+        );
+    }
+
+    #[test]
+    fn expressions() {
+        let name = String::from("shenjack");
+        let count = 3;
+        let pos = vec![NbtValue::Double(1.0)];
+        let value = nbt!({
+            name: (name.as_str()),
+            count: count + 1,
+            neg: -count,
+            pos: (pos.clone()),
+            nested: [(nbt!({ a: 1b })), count as i8],
+        });
+        let NbtValue::Compound(None, items) = value else {
+            unreachable!()
+        };
+        assert_eq!(items[0].1, NbtValue::String("shenjack".into()));
+        assert_eq!(items[1].1, NbtValue::Int(4));
+        assert_eq!(items[2].1, NbtValue::Int(-3));
+        assert_eq!(items[3].1, NbtValue::List(pos));
+        assert_eq!(
+            items[4].1,
+            NbtValue::List(vec![
+                NbtValue::Compound(None, vec![("a".into(), NbtValue::Byte(1))]),
+                NbtValue::Byte(3),
+            ])
+        );
+    }
+
+    #[test]
+    fn hello_world() {
+        let value =
+            NbtValue::from_binary::<crate::nbt_version::Java>(&crate::tests::HELLO_WORLD_DATA)
+                .unwrap();
+        assert_eq!(value, nbt!("hello world": { name: "Bananrama" }));
+    }
 }
@@ -1,3 +1,5 @@
+use std::borrow::{Borrow, Cow};
+use std::fmt::{self, Display};
 use std::str::Utf8Error;
 
 use simd_cesu8::mutf8;
@@ -18,50 +20,235 @@ fn is_plain_ascii(slice: &[u8]) -> bool {
     slice.iter().all(|&c| c & 0b10000000 == 0)
 }
 
-#[derive(Debug, Clone, PartialEq)]
+/// m-utf8 校验/解码错误
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Mutf8Error {
+    valid_up_to: usize,
+}
+
+impl Mutf8Error {
+    /// 在这个位置之前的数据都是合法的
+    pub fn valid_up_to(&self) -> usize { self.valid_up_to }
+}
+
+impl Display for Mutf8Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "第 {} 个字节处不是合法的 m-utf8", self.valid_up_to)
+    }
+}
+
+impl std::error::Error for Mutf8Error {}
+
+/// 校验是否是合法的 m-utf8
+///
+/// 跟标准 utf-8 的区别:
+/// - `\0` 必须是 `C0 80`, 不能出现单独的 `00`
+/// - 不能有 4 字节的字符, 需要写成两个 3 字节的代理对 (CESU-8)
+///
+/// 单独的代理项在 Java 里是合法的, 但是没法转成 Rust 的 `str`, 所以这里当成错误
+pub fn validate(data: &[u8]) -> Result<(), Mutf8Error> {
+    let is_cont = |idx: usize| data.get(idx).is_some_and(|b| b & 0b1100_0000 == 0b1000_0000);
+    let mut i = 0;
+    while i < data.len() {
+        let len = match (data[i], data.get(i + 1).copied()) {
+            (0x01..=0x7F, _) => 1,
+            (0xC0, Some(0x80)) => 2,
+            (0xC2..=0xDF, _) if is_cont(i + 1) => 2,
+            (0xE0, Some(0xA0..=0xBF)) if is_cont(i + 2) => 3,
+            (0xE1..=0xEC | 0xEE..=0xEF, _) if is_cont(i + 1) && is_cont(i + 2) => 3,
+            (0xED, Some(0x80..=0x9F)) if is_cont(i + 2) => 3,
+            // 高位代理, 后面必须跟着一个低位代理
+            (0xED, Some(0xA0..=0xAF))
+                if is_cont(i + 2)
+                    && data.get(i + 3) == Some(&0xED)
+                    && matches!(data.get(i + 4), Some(0xB0..=0xBF))
+                    && is_cont(i + 5) =>
+            {
+                6
+            }
+            _ => return Err(Mutf8Error { valid_up_to: i }),
+        };
+        i += len;
+    }
+    Ok(())
+}
+
+/// 把 m-utf8 数据解码成字符串
+///
+/// 同时也是合法 utf-8 的时候 (比如纯 ASCII) 不会复制
+pub fn decode(data: &[u8]) -> Result<Cow<'_, str>, Mutf8Error> {
+    if is_plain_ascii(data) && !data.contains(&0) {
+        // SAFETY: 纯 ASCII 一定是合法的 utf-8
+        return Ok(Cow::Borrowed(unsafe { std::str::from_utf8_unchecked(data) }));
+    }
+    validate(data)?;
+    mutf8::decode_strict(data).map_err(|_| Mutf8Error { valid_up_to: 0 })
+}
+
+/// 存储 m-utf8 编码之后的字符串
+///
+/// 从 Rust 字符串转换的时候会编码成 m-utf8
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Mutf8String {
     data: Vec<u8>,
 }
 
 impl Mutf8String {
-    pub fn verify(&self) -> Option<Utf8Error> {
-        if !is_plain_ascii(&self.data) {
-            todo!()
-        } else {
-            if let Err(e) = std::str::from_utf8(&self.data) {
-                return Some(e);
-            }
-            None
+    /// 检查里面的数据是不是合法的 m-utf8
+    pub fn verify(&self) -> Option<Mutf8Error> { validate(&self.data).err() }
+
+    /// 解码成字符串
+    pub fn decode(&self) -> Result<Cow<'_, str>, Mutf8Error> { decode(&self.data) }
+
+    /// 解码成字符串, 不合法的部分会被替换成 `U+FFFD`
+    pub fn decode_lossy(&self) -> Cow<'_, str> {
+        match decode(&self.data) {
+            Ok(s) => s,
+            Err(_) => mutf8::decode_lossy_strict(&self.data),
         }
     }
 
-    pub fn decode(&self) -> String {
-        if is_plain_ascii(&self.data) {
-            unsafe { String::from_utf8_unchecked(self.data.clone()) }
-        } else {
-            mutf8::decode(&self.data).unwrap_or_default().to_string()
-        }
+    /// 跟普通的字符串比较
+    ///
+    /// 会把 `other` 编码成 m-utf8 (大部分时候不需要复制) 再比较字节, 不会解码自己
+    pub fn eq_str(&self, other: &str) -> bool { self.data == *mutf8::encode(other) }
+
+    /// 直接拿到里面存的字节
+    pub fn as_bytes(&self) -> &[u8] { &self.data }
+
+    /// 从已经是 m-utf8 的数据构建, 会检查是否合法
+    pub fn from_mutf8(data: Vec<u8>) -> Result<Self, Mutf8Error> {
+        validate(&data)?;
+        Ok(Self { data })
     }
 
+    /// 从普通的 utf-8 数据构建 (基岩版用的), 会转成 m-utf8
+    pub fn from_utf8(data: &[u8]) -> Result<Self, Utf8Error> {
+        std::str::from_utf8(data).map(Self::from)
+    }
+
+    /// 转成普通的 utf-8 (基岩版用的)
+    ///
+    /// 不需要转换的时候 (比如纯 ASCII) 不会复制
+    pub fn to_utf8(&self) -> Result<Cow<'_, [u8]>, Mutf8Error> {
+        Ok(match self.decode()? {
+            Cow::Borrowed(s) => Cow::Borrowed(s.as_bytes()),
+            Cow::Owned(s) => Cow::Owned(s.into_bytes()),
+        })
+    }
+
+    /// 从已经是 m-utf8 的数据构建, 不检查 (跟 [`Mutf8String::from_reader`] 一样)
+    pub(crate) fn from_mutf8_unchecked(data: Vec<u8>) -> Self { Self { data } }
+
     pub fn from_reader(reader: &mut NbtReader, start_idx: usize, len: usize) -> NbtResult<Self> {
         reader.roll_to(start_idx)?;
         let data = reader.read_u8_array(len)?.to_vec();
         Ok(Self { data })
     }
+
+    /// 跟 [`Mutf8String::from_reader`] 一样, 不过读的是普通的 utf-8
+    ///
+    /// 基岩版的 `from_reader` 已经用 [`NbtReader::roll_down_utf8`] 检查过了,
+    /// 所以这里只会因为越界之类的原因出错
+    pub fn from_utf8_reader(
+        reader: &mut NbtReader,
+        start_idx: usize,
+        len: usize,
+    ) -> NbtResult<Self> {
+        reader.roll_to(start_idx)?;
+        let data = reader.read_u8_array(len)?;
+        Ok(std::str::from_utf8(data)?.into())
+    }
 }
 
 impl From<String> for Mutf8String {
     fn from(s: String) -> Self {
-        Self {
-            data: s.into_bytes(),
-        }
+        let data = match mutf8::needs_encoded(&s) {
+            true => mutf8::encode(&s).into_owned(),
+            false => s.into_bytes(),
+        };
+        Self { data }
     }
 }
 
 impl From<&str> for Mutf8String {
     fn from(s: &str) -> Self {
         Self {
-            data: s.as_bytes().to_vec(),
+            data: mutf8::encode(s).into_owned(),
+        }
+    }
+}
+
+impl Borrow<[u8]> for Mutf8String {
+    fn borrow(&self) -> &[u8] { &self.data }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ascii_borrowed() {
+        let s = Mutf8String::from("hello world");
+        assert_eq!(s.as_bytes(), b"hello world");
+        assert!(s.verify().is_none());
+        assert!(matches!(s.decode(), Ok(Cow::Borrowed("hello world"))));
+    }
+
+    #[test]
+    fn encode_round_trip() {
+        for text in ["中文名字", "a\0b", "😀 emoji", "ÄÖÜ \u{ffff}", "\0"] {
+            let s = Mutf8String::from(text);
+            assert!(s.verify().is_none(), "{:?}", text);
+            assert!(!s.as_bytes().contains(&0));
+            assert_eq!(s.decode().unwrap(), text);
+            assert_eq!(Mutf8String::from(text.to_string()), s);
+            assert!(s.eq_str(text));
         }
+        assert_eq!(Mutf8String::from("a\0").as_bytes(), &[b'a', 0xC0, 0x80]);
+        assert_eq!(Mutf8String::from("😀").as_bytes(), &[0xED, 0xA0, 0xBD, 0xED, 0xB8, 0x80]);
+        assert_eq!(Mutf8String::from("中").as_bytes(), "中".as_bytes());
+        assert!(!Mutf8String::from("a\0").eq_str("a"));
+    }
+
+    #[test]
+    fn invalid() {
+        let cases: [(&[u8], usize); 7] = [
+            (b"a\0", 1),
+            // 4 字节的 utf-8
+            (&[b'a', b'b', 0xF0, 0x9F, 0x98, 0x80], 2),
+            // 单独的代理项
+            (&[0xED, 0xA0, 0xBD], 0),
+            (&[b'a', 0xED, 0xB8, 0x80], 1),
+            // 截断
+            (&[0xE4, 0xB8], 0),
+            // overlong
+            (&[0xC1, 0x81], 0),
+            (&[0x80], 0),
+        ];
+        for (data, valid_up_to) in cases {
+            let err = Mutf8String::from_mutf8(data.to_vec()).unwrap_err();
+            assert_eq!(err.valid_up_to(), valid_up_to, "{:?}", data);
+            assert_eq!(decode(data), Err(err));
+        }
+    }
+
+    #[test]
+    fn verify_strings_non_ascii() {
+        let value = crate::nbt!({ "名字": "中文", emoji: ["😀", "a\0b"] });
+        assert_eq!(value.verify_strings(), None);
+        let bad = crate::NbtValue::String(Mutf8String { data: vec![0xF0] });
+        let errors = crate::nbt!({ bad: (bad) }).verify_strings().unwrap();
+        assert_eq!(errors, vec![crate::NbtError::Mutf8Error(Mutf8Error { valid_up_to: 0 })]);
+    }
+
+    #[test]
+    fn lossy() {
+        let s = Mutf8String {
+            data: vec![b'a', 0xF0, 0x9F, 0x98, 0x80],
+        };
+        assert!(s.decode().is_err());
+        assert!(s.decode_lossy().starts_with('a'));
+        assert!(s.decode_lossy().contains(char::REPLACEMENT_CHARACTER));
     }
 }
@@ -0,0 +1,750 @@
+//! NBT path, 语法跟原版 `/data` 命令里的一样
+//!
+//! - `a.b`: compound 里的 key, 有特殊字符的 key 可以加引号: `"a b".c`
+//! - `a{b:1b}`: key 是 `a`, 而且能匹配上 `{b:1b}` 的值
+//! - `{b:1b}`: 能匹配上的根节点, 只能放在最前面
+//! - `a[0]` `a[-1]`: list 或者数组的下标, 负数从后往前数
+//! - `a[]`: list 或者数组里所有的元素
+//! - `a[{b:1b}]`: list 里所有能匹配上的元素
+//!
+//! 匹配的规则也跟原版一样: compound 只要求 pattern 里的 key 都有, 而且值都能匹配上;
+//! list 要求 pattern 里的每个元素都能在目标里找到能匹配上的, 空的 list 只能匹配空的 list;
+//! 其他的类型必须相等
+//!
+//! 数组 (`[B;` `[I;` `[L;`) 里的元素不是 [`NbtValue`](crate::NbtValue), 所以 [`NbtPath::get`]
+//! 返回的是 [`Cow`](std::borrow::Cow), 数组里的元素会复制成 `Byte`/`Int`/`Long`;
+//! 跟原版一样, 所有的数字都可以放进数组里, 会转换成数组元素的类型
+//!
+//! ```rust
+//! use shen_nbt6::path::NbtPath;
+//! use shen_nbt6::NbtValue;
+//!
+//! let mut player = NbtValue::from_snbt(
+//!     r#"{Inventory:[{Slot:0b,id:"minecraft:stone",tag:{display:{Name:"石头"}}},{Slot:1b}]}"#,
+//! )
+//! .unwrap();
+//! let path: NbtPath = "Inventory[{Slot:0b}].tag.display.Name".parse().unwrap();
+//! assert_eq!(path.get(&player).as_deref(), Some(&NbtValue::String("石头".into())));
+//!
+//! let count = NbtPath::parse("Inventory[].Count").unwrap();
+//! assert_eq!(count.set(&mut player, NbtValue::Byte(64)).unwrap(), 2);
+//! assert_eq!(count.get_all(&player).len(), 2);
+//! assert_eq!(count.remove(&mut player), 2);
+//! assert!(count.get(&player).is_none());
+//!
+//! let uuid = NbtPath::parse("UUID").unwrap();
+//! uuid.set(&mut player, NbtValue::IntArray(vec![1, 2, 3, 4])).unwrap();
+//! let last = NbtPath::parse("UUID[-1]").unwrap();
+//! assert_eq!(last.get(&player).as_deref(), Some(&NbtValue::Int(4)));
+//! assert_eq!(last.set(&mut player, NbtValue::Byte(5)).unwrap(), 1);
+//! assert_eq!(uuid.get(&player).as_deref(), Some(&NbtValue::IntArray(vec![1, 2, 3, 5])));
+//! ```
+
+use std::borrow::Cow;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::snbt::SnbtReader;
+use crate::{nbt_consts, Mutf8String, NbtError, NbtResult, NbtValue};
+
+/// path 里的一个节点
+#[derive(Debug, Clone, PartialEq)]
+enum PathNode {
+    /// `a`
+    Key(Mutf8String),
+    /// `a{b:1b}`
+    MatchElement(Mutf8String, NbtValue),
+    /// `{b:1b}`, 只能是第一个
+    MatchRoot(NbtValue),
+    /// `[0]`
+    Index(i32),
+    /// `[]`
+    AllElements,
+    /// `[{b:1b}]`
+    MatchObject(NbtValue),
+}
+
+/// 解析好的 NBT path
+///
+/// 见 [模块文档](self)
+#[derive(Debug, Clone, PartialEq)]
+pub struct NbtPath {
+    /// 原来的字符串
+    source: String,
+    /// 至少有一个
+    nodes: Vec<PathNode>,
+}
+
+impl NbtPath {
+    /// 解析 NBT path
+    ///
+    /// 语法错误的时候返回 [`NbtError::NbtPathSyntax`]
+    pub fn parse(path: &str) -> NbtResult<Self> {
+        let nodes = PathParser {
+            data: path,
+            cursor: 0,
+        }
+        .parse()?;
+        Ok(Self {
+            source: path.to_string(),
+            nodes,
+        })
+    }
+
+    /// 第一个能匹配上的值
+    pub fn get<'a>(&self, root: &'a NbtValue) -> Option<Cow<'a, NbtValue>> {
+        self.get_all(root).into_iter().next()
+    }
+
+    /// 所有能匹配上的值, 数组里的元素是复制出来的
+    pub fn get_all<'a>(&self, root: &'a NbtValue) -> Vec<Cow<'a, NbtValue>> {
+        let mut current = vec![Cow::Borrowed(root)];
+        for node in &self.nodes {
+            let mut next = Vec::new();
+            for value in current {
+                // 数组里的元素是数字, 不会再往下走了
+                if let Cow::Borrowed(value) = value {
+                    node.collect(value, &mut next);
+                }
+            }
+            current = next;
+        }
+        current
+    }
+
+    /// 把所有能匹配上的值换成 `value`, 返回换了几个
+    ///
+    /// 跟原版一样, 中间缺少的 compound/list 会自动创建, `a{b:1b}` 缺少的时候会放进去 `{b:1b}`,
+    /// `a[{b:1b}]` 没有能匹配上的时候会加一个 `{b:1b}` 进去
+    ///
+    /// 换掉 list 里的元素之后类型不一样的时候返回 [`NbtError::ListTypeNotSame`],
+    /// 放进数组里的不是数字的时候返回 [`NbtError::IncorrectType`]
+    pub fn set(&self, root: &mut NbtValue, value: NbtValue) -> NbtResult<usize> {
+        let last = self.nodes.last().unwrap();
+        let mut count = 0;
+        for parent in self.parents_mut(root, true) {
+            count += last.set(parent, &value)?;
+        }
+        Ok(count)
+    }
+
+    /// 删掉所有能匹配上的值, 返回删掉了几个
+    ///
+    /// 根节点删不掉
+    pub fn remove(&self, root: &mut NbtValue) -> usize {
+        let last = self.nodes.last().unwrap();
+        self.parents_mut(root, false)
+            .into_iter()
+            .map(|parent| last.remove(parent))
+            .sum()
+    }
+
+    /// 最后一个节点的所有父节点
+    fn parents_mut<'a>(&self, root: &'a mut NbtValue, create: bool) -> Vec<&'a mut NbtValue> {
+        let mut current = vec![root];
+        for (idx, node) in self.nodes[..self.nodes.len() - 1].iter().enumerate() {
+            let next_node = self.nodes.get(idx + 1).filter(|_| create);
+            let mut next = Vec::new();
+            for value in current {
+                node.collect_mut(value, next_node, &mut next);
+            }
+            current = next;
+        }
+        current
+    }
+}
+
+impl FromStr for NbtPath {
+    type Err = NbtError;
+
+    fn from_str(s: &str) -> NbtResult<Self> { Self::parse(s) }
+}
+
+impl fmt::Display for NbtPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { f.write_str(&self.source) }
+}
+
+impl PathNode {
+    fn collect<'a>(&self, value: &'a NbtValue, out: &mut Vec<Cow<'a, NbtValue>>) {
+        match (self, value) {
+            (PathNode::Key(key), NbtValue::Compound(_, data)) => {
+                out.extend(find(data, key).map(Cow::Borrowed))
+            }
+            (PathNode::MatchElement(key, filter), NbtValue::Compound(_, data)) => {
+                out.extend(find(data, key).filter(|v| matches(filter, v)).map(Cow::Borrowed))
+            }
+            (PathNode::MatchRoot(filter), value) if matches(filter, value) => {
+                out.push(Cow::Borrowed(value))
+            }
+            (PathNode::Index(idx), NbtValue::List(list)) => {
+                out.extend(list_index(list.len(), *idx).map(|idx| Cow::Borrowed(&list[idx])))
+            }
+            (PathNode::AllElements, NbtValue::List(list)) => {
+                out.extend(list.iter().map(Cow::Borrowed))
+            }
+            (PathNode::MatchObject(filter), NbtValue::List(list)) => {
+                out.extend(list.iter().filter(|v| matches(filter, v)).map(Cow::Borrowed))
+            }
+            (PathNode::Index(idx), array) => {
+                let idx = array_len(array).and_then(|len| list_index(len, *idx));
+                out.extend(idx.map(|idx| Cow::Owned(array_get(array, idx))))
+            }
+            (PathNode::AllElements, array) => {
+                let len = array_len(array).unwrap_or(0);
+                out.extend((0..len).map(|idx| Cow::Owned(array_get(array, idx))))
+            }
+            _ => {}
+        }
+    }
+
+    /// `create` 是下一个节点, 有的时候会创建缺少的值
+    fn collect_mut<'a>(
+        &self,
+        value: &'a mut NbtValue,
+        create: Option<&PathNode>,
+        out: &mut Vec<&'a mut NbtValue>,
+    ) {
+        match (self, value) {
+            (PathNode::Key(key), NbtValue::Compound(_, data)) => {
+                match data.iter().position(|(k, _)| k == key) {
+                    Some(idx) => out.push(&mut data[idx].1),
+                    None => {
+                        if let Some(next) = create {
+                            data.push((key.clone(), next.empty_parent()));
+                            out.push(&mut data.last_mut().unwrap().1);
+                        }
+                    }
+                }
+            }
+            (PathNode::MatchElement(key, filter), NbtValue::Compound(_, data)) => {
+                match data.iter().position(|(k, _)| k == key) {
+                    Some(idx) => {
+                        if matches(filter, &data[idx].1) {
+                            out.push(&mut data[idx].1)
+                        }
+                    }
+                    None => {
+                        if create.is_some() {
+                            data.push((key.clone(), filter.clone()));
+                            out.push(&mut data.last_mut().unwrap().1);
+                        }
+                    }
+                }
+            }
+            (PathNode::MatchRoot(filter), value) if matches(filter, value) => out.push(value),
+            (PathNode::Index(idx), NbtValue::List(list)) => {
+                if let Some(idx) = list_index(list.len(), *idx) {
+                    out.push(&mut list[idx])
+                }
+            }
+            (PathNode::AllElements, NbtValue::List(list)) => out.extend(list.iter_mut()),
+            (PathNode::MatchObject(filter), NbtValue::List(list)) => {
+                if create.is_some()
+                    && !list.iter().any(|v| matches(filter, v))
+                    && list.first().is_none_or(|v| v.tag() == filter.tag())
+                {
+                    list.push(filter.clone());
+                }
+                out.extend(list.iter_mut().filter(|v| matches(filter, v)))
+            }
+            _ => {}
+        }
+    }
+
+    /// 自动创建的时候, 这个节点的父节点应该是什么
+    fn empty_parent(&self) -> NbtValue {
+        match self {
+            PathNode::Key(_) | PathNode::MatchElement(..) | PathNode::MatchRoot(_) => {
+                NbtValue::Compound(None, Vec::new())
+            }
+            PathNode::Index(_) | PathNode::AllElements | PathNode::MatchObject(_) => {
+                NbtValue::List(Vec::new())
+            }
+        }
+    }
+
+    fn set(&self, parent: &mut NbtValue, value: &NbtValue) -> NbtResult<usize> {
+        match (self, parent) {
+            (PathNode::Key(key), NbtValue::Compound(_, data)) => {
+                match data.iter_mut().find(|(k, _)| k == key) {
+                    Some((_, old)) => *old = value.clone(),
+                    None => data.push((key.clone(), value.clone())),
+                }
+                Ok(1)
+            }
+            (PathNode::MatchElement(key, filter), NbtValue::Compound(_, data)) => {
+                match data.iter_mut().find(|(k, _)| k == key) {
+                    Some((_, old)) if matches(filter, old) => {
+                        *old = value.clone();
+                        Ok(1)
+                    }
+                    _ => Ok(0),
+                }
+            }
+            (PathNode::MatchRoot(filter), parent) => match matches(filter, parent) {
+                true => {
+                    *parent = value.clone();
+                    Ok(1)
+                }
+                false => Ok(0),
+            },
+            (PathNode::Index(idx), NbtValue::List(list)) => {
+                let Some(idx) = list_index(list.len(), *idx) else {
+                    return Ok(0);
+                };
+                check_list(list, value, |i, _| i == idx)?;
+                list[idx] = value.clone();
+                Ok(1)
+            }
+            (PathNode::AllElements, NbtValue::List(list)) => {
+                if list.is_empty() {
+                    list.push(value.clone());
+                } else {
+                    list.iter_mut().for_each(|v| *v = value.clone());
+                }
+                Ok(list.len())
+            }
+            (PathNode::MatchObject(filter), NbtValue::List(list)) => {
+                check_list(list, value, |_, v| matches(filter, v))?;
+                let mut count = 0;
+                for v in list.iter_mut().filter(|v| matches(filter, v)) {
+                    *v = value.clone();
+                    count += 1;
+                }
+                Ok(count)
+            }
+            (PathNode::Index(idx), array) => {
+                match array_len(array).and_then(|len| list_index(len, *idx)) {
+                    Some(idx) => array_set(array, Some(idx), value),
+                    None => Ok(0),
+                }
+            }
+            (PathNode::AllElements, array) if array_len(array).is_some() => {
+                array_set(array, None, value)
+            }
+            _ => Ok(0),
+        }
+    }
+
+    fn remove(&self, parent: &mut NbtValue) -> usize {
+        let Some(before) = child_count(parent) else {
+            return 0;
+        };
+        match (self, &mut *parent) {
+            (PathNode::Key(key), NbtValue::Compound(_, data)) => data.retain(|(k, _)| k != key),
+            (PathNode::MatchElement(key, filter), NbtValue::Compound(_, data)) => {
+                data.retain(|(k, v)| k != key || !matches(filter, v))
+            }
+            (PathNode::Index(idx), NbtValue::List(list)) => {
+                if let Some(idx) = list_index(list.len(), *idx) {
+                    list.remove(idx);
+                }
+            }
+            (PathNode::AllElements, NbtValue::List(list)) => list.clear(),
+            (PathNode::MatchObject(filter), NbtValue::List(list)) => {
+                list.retain(|v| !matches(filter, v))
+            }
+            (PathNode::Index(idx), array) => {
+                if let Some(idx) = array_len(array).and_then(|len| list_index(len, *idx)) {
+                    array_remove(array, Some(idx));
+                }
+            }
+            (PathNode::AllElements, array) => array_remove(array, None),
+            _ => {}
+        }
+        before - child_count(parent).unwrap_or(before)
+    }
+}
+
+fn find<'a>(data: &'a [(Mutf8String, NbtValue)], key: &Mutf8String) -> Option<&'a NbtValue> {
+    data.iter().find(|(k, _)| k == key).map(|(_, v)| v)
+}
+
+/// compound/list/数组里有几个元素, 其他的类型是 `None`
+fn child_count(value: &NbtValue) -> Option<usize> {
+    match value {
+        NbtValue::Compound(_, data) => Some(data.len()),
+        NbtValue::List(list) => Some(list.len()),
+        other => array_len(other),
+    }
+}
+
+/// 数组 (`[B;` `[I;` `[L;`) 的长度, 不是数组的时候是 `None`
+fn array_len(value: &NbtValue) -> Option<usize> {
+    match value {
+        NbtValue::ByteArray(data) => Some(data.len()),
+        NbtValue::IntArray(data) => Some(data.len()),
+        NbtValue::LongArray(data) => Some(data.len()),
+        _ => None,
+    }
+}
+
+/// 复制出数组里的一个元素, 下标要先检查过
+fn array_get(array: &NbtValue, idx: usize) -> NbtValue {
+    match array {
+        NbtValue::ByteArray(data) => NbtValue::Byte(data[idx]),
+        NbtValue::IntArray(data) => NbtValue::Int(data[idx]),
+        NbtValue::LongArray(data) => NbtValue::Long(data[idx]),
+        _ => unreachable!("先用 array_len 检查过了"),
+    }
+}
+
+/// 换掉数组里下标是 `idx` 的元素, `None` 的时候换掉所有的, 空的数组会放进去一个
+///
+/// 跟原版的 `NumericTag` 一样, 浮点数向下取整, 整数直接截断; 不是数字的时候返回 [`NbtError::IncorrectType`]
+fn array_set(array: &mut NbtValue, idx: Option<usize>, value: &NbtValue) -> NbtResult<usize> {
+    fn fill<T: Copy>(data: &mut Vec<T>, idx: Option<usize>, value: T) -> usize {
+        match idx {
+            Some(idx) => {
+                data[idx] = value;
+                1
+            }
+            None if data.is_empty() => {
+                data.push(value);
+                1
+            }
+            None => {
+                data.fill(value);
+                data.len()
+            }
+        }
+    }
+    let number = match value {
+        NbtValue::Byte(x) => *x as i64,
+        NbtValue::Short(x) => *x as i64,
+        NbtValue::Int(x) => *x as i64,
+        NbtValue::Long(x) => *x,
+        NbtValue::Float(x) => x.floor() as i64,
+        NbtValue::Double(x) => x.floor() as i64,
+        other => {
+            let expect = match array {
+                NbtValue::ByteArray(_) => nbt_consts::TAG_BYTE,
+                NbtValue::IntArray(_) => nbt_consts::TAG_INT,
+                _ => nbt_consts::TAG_LONG,
+            };
+            return Err(NbtError::IncorrectType(expect, other.tag()));
+        }
+    };
+    Ok(match array {
+        NbtValue::ByteArray(data) => fill(data, idx, number as i8),
+        NbtValue::IntArray(data) => fill(data, idx, number as i32),
+        NbtValue::LongArray(data) => fill(data, idx, number),
+        _ => 0,
+    })
+}
+
+/// 删掉数组里下标是 `idx` 的元素, `None` 的时候全删掉
+fn array_remove(array: &mut NbtValue, idx: Option<usize>) {
+    fn remove<T>(data: &mut Vec<T>, idx: Option<usize>) {
+        match idx {
+            Some(idx) => {
+                data.remove(idx);
+            }
+            None => data.clear(),
+        }
+    }
+    match array {
+        NbtValue::ByteArray(data) => remove(data, idx),
+        NbtValue::IntArray(data) => remove(data, idx),
+        NbtValue::LongArray(data) => remove(data, idx),
+        _ => {}
+    }
+}
+
+/// 负数从后往前数, 超出范围的时候是 `None`
+fn list_index(len: usize, idx: i32) -> Option<usize> {
+    let idx = match idx < 0 {
+        true => len as i64 + idx as i64,
+        false => idx as i64,
+    };
+    (0..len as i64).contains(&idx).then_some(idx as usize)
+}
+
+/// 没被换掉的元素跟 `value` 的类型不一样的时候返回错误
+fn check_list(
+    list: &[NbtValue],
+    value: &NbtValue,
+    replaced: impl Fn(usize, &NbtValue) -> bool,
+) -> NbtResult<()> {
+    match list
+        .iter()
+        .enumerate()
+        .find(|(idx, v)| !replaced(*idx, v) && v.tag() != value.tag())
+    {
+        Some((_, other)) => Err(NbtError::ListTypeNotSame(vec![other.tag(), value.tag()])),
+        None => Ok(()),
+    }
+}
+
+/// `target` 能不能匹配上 `pattern`
+///
+/// 跟原版的 `NbtUtils.compareNbt(pattern, target, true)` 一样
+pub fn matches(pattern: &NbtValue, target: &NbtValue) -> bool {
+    match (pattern, target) {
+        (NbtValue::Compound(_, pattern), NbtValue::Compound(_, target)) => {
+            pattern.iter().all(|(key, p)| find(target, key).is_some_and(|t| matches(p, t)))
+        }
+        (NbtValue::List(pattern), NbtValue::List(target)) => match pattern.is_empty() {
+            true => target.is_empty(),
+            false => pattern.iter().all(|p| target.iter().any(|t| matches(p, t))),
+        },
+        _ => pattern == target,
+    }
+}
+
+/// 不需要引号的 key 里可以有的字符
+fn is_key_char(c: char) -> bool { !matches!(c, ' ' | '"' | '\'' | '[' | ']' | '.' | '{' | '}') }
+
+struct PathParser<'data> {
+    data: &'data str,
+    /// 字节
+    cursor: usize,
+}
+
+impl<'data> PathParser<'data> {
+    fn parse(mut self) -> NbtResult<Vec<PathNode>> {
+        let mut nodes = Vec::new();
+        loop {
+            nodes.push(self.read_node(nodes.is_empty())?);
+            match self.peek() {
+                None => return Ok(nodes),
+                Some('[' | '{') => {}
+                Some('.') => self.cursor += 1,
+                Some(_) => return Err(self.error_at(self.cursor, "期望 '.' '[' 或者 '{'")),
+            }
+        }
+    }
+
+    fn read_node(&mut self, root: bool) -> NbtResult<PathNode> {
+        match self.peek() {
+            Some('{') if root => Ok(PathNode::MatchRoot(self.read_snbt(SnbtReader::read_value)?)),
+            Some('{') => Err(self.error_at(self.cursor, "只有第一个节点可以是 '{...}'")),
+            Some('[') => {
+                self.cursor += 1;
+                let node = match self.peek() {
+                    Some(']') => PathNode::AllElements,
+                    Some('{') => PathNode::MatchObject(self.read_snbt(SnbtReader::read_value)?),
+                    _ => {
+                        let start = self.cursor;
+                        let len = self.data[start..]
+                            .find(|c: char| c != '-' && !c.is_ascii_digit())
+                            .unwrap_or(self.data.len() - start);
+                        self.cursor += len;
+                        let idx = self.data[start..self.cursor]
+                            .parse()
+                            .map_err(|_| self.error_at(start, "期望一个下标"))?;
+                        PathNode::Index(idx)
+                    }
+                };
+                match self.peek() {
+                    Some(']') => {
+                        self.cursor += 1;
+                        Ok(node)
+                    }
+                    _ => Err(self.error_at(self.cursor, "期望 ']'")),
+                }
+            }
+            _ => {
+                let key = self.read_key()?;
+                match self.peek() {
+                    Some('{') => {
+                        let filter = self.read_snbt(SnbtReader::read_value)?;
+                        Ok(PathNode::MatchElement(key, filter))
+                    }
+                    _ => Ok(PathNode::Key(key)),
+                }
+            }
+        }
+    }
+
+    fn read_key(&mut self) -> NbtResult<Mutf8String> {
+        if let Some('"' | '\'') = self.peek() {
+            return Ok(self.read_snbt(SnbtReader::read_quoted)?.into());
+        }
+        let start = self.cursor;
+        let len = self.data[start..].find(|c| !is_key_char(c)).unwrap_or(self.data.len() - start);
+        if len == 0 {
+            return Err(self.error_at(start, "期望一个 key"));
+        }
+        self.cursor += len;
+        Ok(self.data[start..self.cursor].into())
+    }
+
+    /// 用 SNBT 的解析器读一段
+    fn read_snbt<T>(
+        &mut self,
+        read: impl FnOnce(&mut SnbtReader<'data>) -> NbtResult<T>,
+    ) -> NbtResult<T> {
+        let mut reader = SnbtReader::new(self.data);
+        reader.cursor = self.cursor;
+        let result = read(&mut reader).map_err(|e| match e {
+            NbtError::SnbtSyntax(_, column, msg) => NbtError::NbtPathSyntax(column, msg),
+            other => other,
+        })?;
+        self.cursor = reader.cursor;
+        Ok(result)
+    }
+
+    #[inline]
+    fn peek(&self) -> Option<char> { self.data[self.cursor..].chars().next() }
+
+    fn error_at(&self, pos: usize, msg: impl Into<String>) -> NbtError {
+        NbtError::NbtPathSyntax(self.data[..pos].chars().count() + 1, msg.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn player() -> NbtValue {
+        NbtValue::from_snbt(
+            r#"{
+                Inventory: [
+                    {Slot: 0b, id: "minecraft:diamond_sword", tag: {display: {Name: "剑"}}},
+                    {Slot: 1b, id: "minecraft:stone", Count: 64b}
+                ],
+                "a b": {c: [I; 1, 2]},
+                Pos: [1.0d, 2.0d, 3.0d]
+            }"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn path_parse() {
+        let nodes = |s: &str| NbtPath::parse(s).unwrap().nodes;
+        let compound = |s: &str| NbtValue::from_snbt(s).unwrap();
+        assert_eq!(
+            nodes(r#"a."b.c"[0][-1][]"#),
+            [
+                PathNode::Key("a".into()),
+                PathNode::Key("b.c".into()),
+                PathNode::Index(0),
+                PathNode::Index(-1),
+                PathNode::AllElements,
+            ]
+        );
+        assert_eq!(
+            nodes("{x:1b}.a{y:'z'}[{w:[]}]"),
+            [
+                PathNode::MatchRoot(compound("{x:1b}")),
+                PathNode::MatchElement("a".into(), compound("{y:z}")),
+                PathNode::MatchObject(compound("{w:[]}")),
+            ]
+        );
+        assert_eq!(NbtPath::parse("a[0].b").unwrap().to_string(), "a[0].b");
+
+        let err = |s: &str| NbtPath::parse(s).unwrap_err();
+        assert!(matches!(err(""), NbtError::NbtPathSyntax(1, _)));
+        assert!(matches!(err("a."), NbtError::NbtPathSyntax(3, _)));
+        assert!(matches!(err("a b"), NbtError::NbtPathSyntax(2, _)));
+        assert!(matches!(err("a[x]"), NbtError::NbtPathSyntax(3, _)));
+        assert!(matches!(err("a[0"), NbtError::NbtPathSyntax(4, _)));
+        assert!(matches!(err("a.{b:1}"), NbtError::NbtPathSyntax(3, _)));
+        assert!(matches!(err("a{b:}"), NbtError::NbtPathSyntax(5, _)));
+        assert!(matches!(err("'a"), NbtError::NbtPathSyntax(1, _)));
+    }
+
+    #[test]
+    fn path_get() {
+        let player = player();
+        let get = |s: &str| NbtPath::parse(s).unwrap().get(&player).map(Cow::into_owned);
+        let get_all = |s: &str| NbtPath::parse(s).unwrap().get_all(&player).len();
+        assert_eq!(
+            get("Inventory[{Slot:0b}].tag.display.Name"),
+            Some(NbtValue::String("剑".into()))
+        );
+        assert_eq!(get("Inventory[-1].Count"), Some(NbtValue::Byte(64)));
+        assert_eq!(get("Inventory[2]"), None);
+        assert_eq!(get("Inventory[-3]"), None);
+        assert_eq!(get(r#""a b".c"#), Some(NbtValue::IntArray(vec![1, 2])));
+        assert_eq!(get("Pos[1]"), Some(NbtValue::Double(2.0)));
+        assert_eq!(get(r#""a b".c[-1]"#), Some(NbtValue::Int(2)));
+        assert_eq!(get(r#""a b".c[2]"#), None);
+        assert_eq!(get(r#""a b".c[0].d"#), None);
+        assert_eq!(get_all(r#""a b".c[]"#), 2);
+        assert_eq!(get("Inventory[0].id{}"), None);
+        assert_eq!(get_all("Inventory[].Slot"), 2);
+        assert_eq!(get_all("Inventory[{tag:{}}]"), 1);
+        assert_eq!(get_all("{Pos:[2.0d]}.Inventory"), 1);
+        assert_eq!(get_all("{Pos:[4.0d]}.Inventory"), 0);
+        assert_eq!(get_all(r#"Inventory[{id:"minecraft:stone"}]"#), 1);
+        assert_eq!(get_all("Pos[{}]"), 0);
+        assert_eq!(get_all("\"a b\"{c:[I;1,2]}"), 1);
+    }
+
+    #[test]
+    fn path_set() {
+        let mut player = player();
+        let set = |player: &mut NbtValue, s: &str, value: NbtValue| {
+            NbtPath::parse(s).unwrap().set(player, value)
+        };
+        let snbt = |s: &str| NbtValue::from_snbt(s).unwrap();
+
+        assert_eq!(set(&mut player, "Inventory[].Count", NbtValue::Byte(1)), Ok(2));
+        assert_eq!(set(&mut player, "Inventory[{Slot:1b}].tag.a[]", snbt("{}")), Ok(1));
+        assert_eq!(set(&mut player, "Inventory[{Slot:2b}].Count", NbtValue::Byte(3)), Ok(1));
+        assert_eq!(set(&mut player, "x{y:1b}.z", NbtValue::Byte(3)), Ok(1));
+        assert_eq!(set(&mut player, "Pos[5]", NbtValue::Double(0.0)), Ok(0));
+        assert_eq!(set(&mut player, "Pos[0]", NbtValue::Double(0.5)), Ok(1));
+        assert_eq!(
+            set(&mut player, "Pos[0]", NbtValue::Int(0)),
+            Err(NbtError::ListTypeNotSame(vec![6, 3]))
+        );
+        assert_eq!(set(&mut player, r#""a b".c[0]"#, NbtValue::Float(-0.5)), Ok(1));
+        let first = NbtPath::parse(r#""a b".c[0]"#).unwrap();
+        assert_eq!(first.get(&player).as_deref(), Some(&NbtValue::Int(-1)));
+        assert_eq!(set(&mut player, r#""a b".c[]"#, NbtValue::Long(1 << 32 | 7)), Ok(2));
+        assert_eq!(
+            set(&mut player, r#""a b".c[1]"#, NbtValue::String("x".into())),
+            Err(NbtError::IncorrectType(nbt_consts::TAG_INT, nbt_consts::TAG_STRING))
+        );
+        assert_eq!(set(&mut player, r#""a b".c[5]"#, NbtValue::Int(0)), Ok(0));
+        assert_eq!(
+            player,
+            snbt(
+                r#"{
+                    Inventory: [
+                        {Slot: 0b, id: "minecraft:diamond_sword", tag: {display: {Name: "剑"}}, Count: 1b},
+                        {Slot: 1b, id: "minecraft:stone", Count: 1b, tag: {a: [{}]}},
+                        {Slot: 2b, Count: 3b}
+                    ],
+                    "a b": {c: [I; 7, 7]},
+                    Pos: [0.5d, 2.0d, 3.0d],
+                    x: {y: 1b, z: 3b}
+                }"#
+            )
+        );
+
+        assert_eq!(set(&mut player, "{x:{}}", NbtValue::Int(1)), Ok(1));
+        assert_eq!(player, NbtValue::Int(1));
+    }
+
+    #[test]
+    fn path_remove() {
+        let mut player = player();
+        let remove = |player: &mut NbtValue, s: &str| NbtPath::parse(s).unwrap().remove(player);
+        let mut other = player.clone();
+        assert_eq!(remove(&mut other, r#""a b".c[-1]"#), 1);
+        assert_eq!(remove(&mut other, r#""a b".c[2]"#), 0);
+        assert_eq!(remove(&mut other, r#""a b".c[]"#), 1);
+        let c = NbtPath::parse(r#""a b".c"#).unwrap();
+        assert_eq!(c.get(&other).as_deref(), Some(&NbtValue::IntArray(Vec::new())));
+        assert_eq!(remove(&mut player, "Inventory[{Slot:3b}]"), 0);
+        assert_eq!(remove(&mut player, "Inventory[0].tag.display.Name"), 1);
+        assert_eq!(remove(&mut player, "Inventory[{tag:{}}].tag.x.y"), 0);
+        assert_eq!(remove(&mut player, "Inventory[-1]"), 1);
+        assert_eq!(remove(&mut player, "Pos[]"), 3);
+        assert_eq!(remove(&mut player, "\"a b\"{c:[I;3]}"), 0);
+        assert_eq!(remove(&mut player, "\"a b\"{c:[I;1,2]}"), 1);
+        assert_eq!(remove(&mut player, "{}"), 0);
+        assert_eq!(
+            player,
+            NbtValue::from_snbt(
+                r#"{Inventory:[{Slot:0b,id:"minecraft:diamond_sword",tag:{display:{}}}],Pos:[]}"#
+            )
+            .unwrap()
+        );
+    }
+}
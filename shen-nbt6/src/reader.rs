@@ -1,4 +1,6 @@
-use crate::{NbtError, NbtResult};
+use crate::nbt_version::{BedrockDisk, BedrockNetVarInt, Java, JavaNetAfter1_20_2};
+use crate::traits::NbtReadTrait;
+use crate::{nbt_consts, NbtError, NbtResult};
 
 /// 用于读取 NBT 数据
 pub struct NbtReader<'data> {
@@ -44,8 +46,64 @@ macro_rules! read_uncheck {
     };
 }
 
+/// 一个字节一个字节地读 VarInt, [`NbtReader`] 和 [`NbtStreamReader`](crate::NbtStreamReader) 共用
+///
+/// 超过 5 个字节时返回 [`NbtError::VarIntTooBig`]
+#[inline]
+pub(crate) fn decode_var_i32(mut next: impl FnMut() -> NbtResult<u8>) -> NbtResult<i32> {
+    let mut value: u32 = 0;
+    let mut size = 0;
+    loop {
+        if size >= 5 {
+            return Err(NbtError::VarIntTooBig(size + 1));
+        }
+        let byte = next()?;
+        value |= ((byte & 0b0111_1111) as u32) << (size * 7);
+        size += 1;
+        if (byte & 0b1000_0000) == 0 {
+            break;
+        }
+    }
+    Ok(value as i32)
+}
+
+/// 一个字节一个字节地读 VarLong, [`NbtReader`] 和 [`NbtStreamReader`](crate::NbtStreamReader) 共用
+///
+/// 超过 10 个字节时返回 [`NbtError::VarlongTooBig`]
+#[inline]
+pub(crate) fn decode_var_i64(mut next: impl FnMut() -> NbtResult<u8>) -> NbtResult<i64> {
+    let mut value: u64 = 0;
+    let mut size = 0;
+    loop {
+        if size >= 10 {
+            return Err(NbtError::VarlongTooBig(size + 1));
+        }
+        let byte = next()?;
+        value |= ((byte & 0b0111_1111) as u64) << (size * 7);
+        size += 1;
+        if (byte & 0b1000_0000) == 0 {
+            break;
+        }
+    }
+    Ok(value as i64)
+}
+
+/// 解码 zigzag 编码的 i32
+#[inline]
+pub(crate) fn zigzag_i32(value: i32) -> i32 {
+    let value = value as u32;
+    ((value >> 1) as i32) ^ (-((value & 1) as i32))
+}
+
+/// 解码 zigzag 编码的 i64
+#[inline]
+pub(crate) fn zigzag_i64(value: i64) -> i64 {
+    let value = value as u64;
+    ((value >> 1) as i64) ^ (-((value & 1) as i64))
+}
+
 impl NbtReader<'_> {
-    pub fn new(data: &[u8]) -> NbtReader { NbtReader { data, cursor: 0 } }
+    pub fn new(data: &[u8]) -> NbtReader<'_> { NbtReader { data, cursor: 0 } }
     /// 向后滚动
     #[inline]
     pub fn roll_back(&mut self, len: usize) -> NbtResult<()> {
@@ -65,6 +123,14 @@ impl NbtReader<'_> {
         self.cursor += len;
         Ok(())
     }
+    /// 向前滚动, 同时检查跳过的部分是合法的 utf-8 (基岩版的字符串)
+    ///
+    /// 不合法的时候返回 [`NbtError::Utf8Error`]
+    #[inline]
+    pub fn roll_down_utf8(&mut self, len: usize) -> NbtResult<()> {
+        std::str::from_utf8(self.read_u8_array(len)?)?;
+        Ok(())
+    }
     #[inline]
     pub fn roll_to(&mut self, pos: usize) -> NbtResult<()> {
         if pos > self.data.len() {
@@ -94,11 +160,7 @@ impl NbtReader<'_> {
         let show_len = display_len.unwrap_or(10).min(self.data.len());
         // 中间位置
         let middle = show_len / 2;
-        let start = if self.cursor > middle {
-            self.cursor - middle
-        } else {
-            0
-        };
+        let start = self.cursor.saturating_sub(middle);
         let end = (start + show_len).min(self.data.len());
         let display_data = self.data[start..end]
             .iter()
@@ -219,62 +281,26 @@ impl NbtReader<'_> {
     ///
     /// 他有大小端区别吗? (其实是小端)
     ///
-    /// 会在超出长度时 panic
+    /// 超过 5 个字节时返回 [`NbtError::VarIntTooBig`]
     #[inline]
-    pub fn read_var_i32(&mut self) -> NbtResult<i32> {
-        let mut value = 0;
-        let mut size = 0;
-        loop {
-            let byte = self.read_u8()?;
-            value |= ((byte & 0b0111_1111) as i32) << (size * 7);
-            size += 1;
-            if size > 5 {
-                return Err(NbtError::VarIntTooBig(value as usize));
-            }
-            if (byte & 0b1000_0000) == 0 {
-                break;
-            }
-        }
-        Ok(value)
-    }
+    pub fn read_var_i32(&mut self) -> NbtResult<i32> { decode_var_i32(|| self.read_u8()) }
     /// 安全的读取一个 Varlong
     ///
     /// 他有大小端区别吗? (其实是小端)
     ///
-    /// 会在超出长度时 panic
+    /// 超过 10 个字节时返回 [`NbtError::VarlongTooBig`]
     #[inline]
-    pub fn read_var_i64(&mut self) -> NbtResult<i64> {
-        let mut value = 0;
-        let mut size = 0;
-        loop {
-            let byte = self.read_u8()?;
-            value |= ((byte & 0b0111_1111) as i64) << (size * 7);
-            size += 1;
-            if size > 10 {
-                return Err(NbtError::VarlongTooBig(value as usize));
-            }
-            if (byte & 0b1000_0000) == 0 {
-                break;
-            }
-        }
-        Ok(value)
-    }
+    pub fn read_var_i64(&mut self) -> NbtResult<i64> { decode_var_i64(|| self.read_u8()) }
     /// 安全的读取一个 zigzag 编码的 varint
     ///
-    /// 会在超出长度时 panic
+    /// 超过 5 个字节时返回 [`NbtError::VarIntTooBig`]
     #[inline]
-    pub fn read_zigzag_var_i32(&mut self) -> NbtResult<i32> {
-        let value = self.read_var_i32()?;
-        Ok((value >> 1) ^ (-(value & 1)))
-    }
+    pub fn read_zigzag_var_i32(&mut self) -> NbtResult<i32> { Ok(zigzag_i32(self.read_var_i32()?)) }
     /// 安全的读取一个 zigzag 编码的 varlong
     ///
-    /// 会在超出长度时 panic
+    /// 超过 10 个字节时返回 [`NbtError::VarlongTooBig`]
     #[inline]
-    pub fn read_zigzag_var_i64(&mut self) -> NbtResult<i64> {
-        let value = self.read_var_i64()?;
-        Ok((value >> 1) ^ (-(value & 1)))
-    }
+    pub fn read_zigzag_var_i64(&mut self) -> NbtResult<i64> { Ok(zigzag_i64(self.read_var_i64()?)) }
     /// 安全的读取一个小端 i32 数据
     #[inline]
     pub fn read_le_i32(&mut self) -> NbtResult<i32> {
@@ -465,46 +491,50 @@ impl NbtReader<'_> {
     /// 读取一个大端指定长度的 f32 数据
     ///
     /// # Safety
-    /// 会在超出长度时 panic
+    ///
+    /// 长度溢出会导致 UB
     #[inline]
     pub unsafe fn read_be_f32_array_unsafe(&mut self, len: usize) -> Vec<f32> {
-        let value =
-            std::slice::from_raw_parts(self.data[self.cursor..].as_ptr() as *const f32, len);
-        self.cursor += len * 4;
-        value.to_vec()
+        self.read_be_i32_array_unsafe(len)
+            .into_iter()
+            .map(|n| f32::from_bits(n as u32))
+            .collect()
     }
     /// 读取一个小端指定长度的 f32 数据
     ///
     /// # Safety
-    /// 会在超出长度时 panic
+    ///
+    /// 长度溢出会导致 UB
     #[inline]
     pub unsafe fn read_le_f32_array_unsafe(&mut self, len: usize) -> Vec<f32> {
-        let value =
-            std::slice::from_raw_parts(self.data[self.cursor..].as_ptr() as *const f32, len);
-        self.cursor += len * 4;
-        value.to_vec()
+        self.read_le_i32_array_unsafe(len)
+            .into_iter()
+            .map(|n| f32::from_bits(n as u32))
+            .collect()
     }
     /// 读取一个大端指定长度的 f64 数据
     ///
     /// # Safety
-    /// 会在超出长度时 panic
+    ///
+    /// 长度溢出会导致 UB
     #[inline]
     pub unsafe fn read_be_f64_array_unsafe(&mut self, len: usize) -> Vec<f64> {
-        let value =
-            std::slice::from_raw_parts(self.data[self.cursor..].as_ptr() as *const f64, len);
-        self.cursor += len * 8;
-        value.to_vec()
+        self.read_be_i64_array_unsafe(len)
+            .into_iter()
+            .map(|n| f64::from_bits(n as u64))
+            .collect()
     }
     /// 读取一个小端指定长度的 f64 数据
     ///
     /// # Safety
-    /// 会在超出长度时 panic
+    ///
+    /// 长度溢出会导致 UB
     #[inline]
     pub unsafe fn read_le_f64_array_unsafe(&mut self, len: usize) -> Vec<f64> {
-        let value =
-            std::slice::from_raw_parts(self.data[self.cursor..].as_ptr() as *const f64, len);
-        self.cursor += len * 8;
-        value.to_vec()
+        self.read_le_i64_array_unsafe(len)
+            .into_iter()
+            .map(|n| f64::from_bits(n as u64))
+            .collect()
     }
 
     /// 读取指定长度的 u8 数组
@@ -599,6 +629,66 @@ impl NbtReader<'_> {
         self.cursor += len * 8;
         value
     }
+    /// 读取指定长度的 le i16 数组
+    ///
+    /// # Safety
+    ///
+    /// 长度溢出会导致 UB
+    #[inline]
+    pub unsafe fn read_le_i16_array_unsafe(&mut self, len: usize) -> Vec<i16> {
+        let mut value: Vec<i16> = Vec::with_capacity(len);
+        std::ptr::copy_nonoverlapping(
+            self.data[self.cursor..].as_ptr(),
+            value.as_ptr() as *mut u8,
+            len * 2,
+        );
+        value.set_len(len);
+        for n in &mut value {
+            *n = n.to_le();
+        }
+        self.cursor += len * 2;
+        value
+    }
+    /// 读取指定长度的 le i32 数组
+    ///
+    /// # Safety
+    ///
+    /// 长度溢出会导致 UB
+    #[inline]
+    pub unsafe fn read_le_i32_array_unsafe(&mut self, len: usize) -> Vec<i32> {
+        let mut value: Vec<i32> = Vec::with_capacity(len);
+        std::ptr::copy_nonoverlapping(
+            self.data[self.cursor..].as_ptr(),
+            value.as_ptr() as *mut u8,
+            len * 4,
+        );
+        value.set_len(len);
+        for n in &mut value {
+            *n = n.to_le();
+        }
+        self.cursor += len * 4;
+        value
+    }
+    /// 读取指定长度的 le i64 数组
+    ///
+    /// # Safety
+    ///
+    /// 长度溢出会导致 UB
+    #[inline]
+    pub unsafe fn read_le_i64_array_unsafe(&mut self, len: usize) -> Vec<i64> {
+        let mut value: Vec<i64> = Vec::with_capacity(len);
+        std::ptr::copy_nonoverlapping(
+            self.data[self.cursor..].as_ptr(),
+            value.as_ptr() as *mut u8,
+            len * 8,
+        );
+        value.set_len(len);
+        for n in &mut value {
+            *n = n.to_le();
+        }
+        self.cursor += len * 8;
+        value
+    }
     /// 读取指定长度的 i32 数组
     ///
     /// # 安全性
@@ -684,3 +774,230 @@ impl NbtReader<'_> {
         Ok(value.into_owned())
     }
 }
+
+/// Java 版 绝大部分的 NBT 格式
+///
+/// 全都是大端
+impl NbtReadTrait for Java {
+    #[inline]
+    fn read_i16(reader: &mut NbtReader) -> NbtResult<i16> { reader.read_be_i16() }
+    #[inline]
+    fn read_i32(reader: &mut NbtReader) -> NbtResult<i32> { reader.read_be_i32() }
+    #[inline]
+    fn read_i64(reader: &mut NbtReader) -> NbtResult<i64> { reader.read_be_i64() }
+    #[inline]
+    fn read_f32(reader: &mut NbtReader) -> NbtResult<f32> { reader.read_be_f32() }
+    #[inline]
+    fn read_f64(reader: &mut NbtReader) -> NbtResult<f64> { reader.read_be_f64() }
+    #[inline]
+    fn read_len(reader: &mut NbtReader) -> NbtResult<i32> { reader.read_be_i32() }
+    #[inline]
+    fn read_str_len(reader: &mut NbtReader) -> NbtResult<usize> {
+        Ok(reader.read_be_u16()? as usize)
+    }
+    #[inline]
+    fn read_i32_array(reader: &mut NbtReader) -> NbtResult<Vec<i32>> {
+        let len = Self::read_checked_len(reader, nbt_consts::TAG_INT_ARRAY)?;
+        let data = reader.read_u8_array(len * 4)?;
+        Ok(data
+            .chunks_exact(4)
+            .map(|n| i32::from_be_bytes(n.try_into().unwrap()))
+            .collect())
+    }
+    #[inline]
+    fn read_i64_array(reader: &mut NbtReader) -> NbtResult<Vec<i64>> {
+        let len = Self::read_checked_len(reader, nbt_consts::TAG_LONG_ARRAY)?;
+        let data = reader.read_u8_array(len * 8)?;
+        Ok(data
+            .chunks_exact(8)
+            .map(|n| i64::from_be_bytes(n.try_into().unwrap()))
+            .collect())
+    }
+}
+
+/// 网络 NBT: 1.20.2+ 的网络 NBT 根节点没有名字
+impl NbtReadTrait for JavaNetAfter1_20_2 {
+    const ROOT_NAMED: bool = false;
+
+    #[inline]
+    fn read_i16(reader: &mut NbtReader) -> NbtResult<i16> { Java::read_i16(reader) }
+    #[inline]
+    fn read_i32(reader: &mut NbtReader) -> NbtResult<i32> { Java::read_i32(reader) }
+    #[inline]
+    fn read_i64(reader: &mut NbtReader) -> NbtResult<i64> { Java::read_i64(reader) }
+    #[inline]
+    fn read_f32(reader: &mut NbtReader) -> NbtResult<f32> { Java::read_f32(reader) }
+    #[inline]
+    fn read_f64(reader: &mut NbtReader) -> NbtResult<f64> { Java::read_f64(reader) }
+    #[inline]
+    fn read_len(reader: &mut NbtReader) -> NbtResult<i32> { Java::read_len(reader) }
+    #[inline]
+    fn read_str_len(reader: &mut NbtReader) -> NbtResult<usize> { Java::read_str_len(reader) }
+    #[inline]
+    fn read_i32_array(reader: &mut NbtReader) -> NbtResult<Vec<i32>> {
+        Java::read_i32_array(reader)
+    }
+    #[inline]
+    fn read_i64_array(reader: &mut NbtReader) -> NbtResult<Vec<i64>> {
+        Java::read_i64_array(reader)
+    }
+}
+
+/// 基岩版 实际用于存储的 NBT 格式
+///
+/// 全都是小端
+impl NbtReadTrait for BedrockDisk {
+    const LIST_ROOT: bool = true;
+    const MUTF8_STRING: bool = false;
+
+    #[inline]
+    fn read_i16(reader: &mut NbtReader) -> NbtResult<i16> { reader.read_le_i16() }
+    #[inline]
+    fn read_i32(reader: &mut NbtReader) -> NbtResult<i32> { reader.read_le_i32() }
+    #[inline]
+    fn read_i64(reader: &mut NbtReader) -> NbtResult<i64> { reader.read_le_i64() }
+    #[inline]
+    fn read_f32(reader: &mut NbtReader) -> NbtResult<f32> { reader.read_le_f32() }
+    #[inline]
+    fn read_f64(reader: &mut NbtReader) -> NbtResult<f64> { reader.read_le_f64() }
+    #[inline]
+    fn read_len(reader: &mut NbtReader) -> NbtResult<i32> { reader.read_le_i32() }
+    #[inline]
+    fn read_str_len(reader: &mut NbtReader) -> NbtResult<usize> {
+        Ok(reader.read_le_u16()? as usize)
+    }
+    #[inline]
+    fn read_i32_array(reader: &mut NbtReader) -> NbtResult<Vec<i32>> {
+        let len = Self::read_checked_len(reader, nbt_consts::TAG_INT_ARRAY)?;
+        let data = reader.read_u8_array(len * 4)?;
+        Ok(data
+            .chunks_exact(4)
+            .map(|n| i32::from_le_bytes(n.try_into().unwrap()))
+            .collect())
+    }
+    #[inline]
+    fn read_i64_array(reader: &mut NbtReader) -> NbtResult<Vec<i64>> {
+        let len = Self::read_checked_len(reader, nbt_consts::TAG_LONG_ARRAY)?;
+        let data = reader.read_u8_array(len * 8)?;
+        Ok(data
+            .chunks_exact(8)
+            .map(|n| i64::from_le_bytes(n.try_into().unwrap()))
+            .collect())
+    }
+}
+
+/// 基岩版 网络 NBT 格式
+///
+/// - Short, Float, Double 都是小端
+/// - Int 和 List/Array 的长度都是 zigzag VarInt
+/// - Long 是 zigzag VarLong
+/// - 字符串长度是 VarInt
+/// - IntArray/LongArray 的元素还是定长小端
+impl NbtReadTrait for BedrockNetVarInt {
+    const LIST_ROOT: bool = true;
+    const MUTF8_STRING: bool = false;
+
+    #[inline]
+    fn read_i16(reader: &mut NbtReader) -> NbtResult<i16> { reader.read_le_i16() }
+    #[inline]
+    fn read_i32(reader: &mut NbtReader) -> NbtResult<i32> { reader.read_zigzag_var_i32() }
+    #[inline]
+    fn read_i64(reader: &mut NbtReader) -> NbtResult<i64> { reader.read_zigzag_var_i64() }
+    #[inline]
+    fn read_f32(reader: &mut NbtReader) -> NbtResult<f32> { reader.read_le_f32() }
+    #[inline]
+    fn read_f64(reader: &mut NbtReader) -> NbtResult<f64> { reader.read_le_f64() }
+    #[inline]
+    fn read_len(reader: &mut NbtReader) -> NbtResult<i32> { reader.read_zigzag_var_i32() }
+    #[inline]
+    fn read_str_len(reader: &mut NbtReader) -> NbtResult<usize> {
+        let len = reader.read_var_i32()?;
+        if len < 0 {
+            return Err(NbtError::LenNegative(nbt_consts::TAG_STRING, len, reader.cursor));
+        }
+        Ok(len as usize)
+    }
+    #[inline]
+    fn read_i32_array(reader: &mut NbtReader) -> NbtResult<Vec<i32>> {
+        let len = Self::read_checked_len(reader, nbt_consts::TAG_INT_ARRAY)?;
+        let data = reader.read_u8_array(len * 4)?;
+        Ok(data
+            .chunks_exact(4)
+            .map(|n| i32::from_le_bytes(n.try_into().unwrap()))
+            .collect())
+    }
+    #[inline]
+    fn read_i64_array(reader: &mut NbtReader) -> NbtResult<Vec<i64>> {
+        let len = Self::read_checked_len(reader, nbt_consts::TAG_LONG_ARRAY)?;
+        let data = reader.read_u8_array(len * 8)?;
+        Ok(data
+            .chunks_exact(8)
+            .map(|n| i64::from_le_bytes(n.try_into().unwrap()))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{
+        BIG_TEST_BEDROCK_DATA, BIG_TEST_BEDROCK_NET_DATA, BIG_TEST_DATA, COMPLEX_PLR_DATA,
+        HELLO_WORLD_BEDROCK_DATA, HELLO_WORLD_BEDROCK_NET_DATA, HELLO_WORLD_DATA,
+    };
+    use crate::traits::NbtBorrowTrait;
+    use crate::NbtValue;
+
+    /// 直接读跟 borrow 之后再 own 的结果应该一样
+    fn same_as_borrow<T: NbtBorrowTrait + NbtReadTrait>(data: &[u8]) {
+        let mut reader = NbtReader::new(data);
+        let borrow_value = <T as NbtBorrowTrait>::from_reader(&mut reader).unwrap();
+        let owned_value = T::read_data(&borrow_value, &mut reader);
+        assert_eq!(NbtValue::from_binary::<T>(data).unwrap(), owned_value);
+    }
+
+    #[test]
+    fn owned_same_as_borrow() {
+        same_as_borrow::<Java>(&HELLO_WORLD_DATA);
+        same_as_borrow::<Java>(&BIG_TEST_DATA);
+        same_as_borrow::<Java>(&COMPLEX_PLR_DATA);
+        same_as_borrow::<BedrockDisk>(&HELLO_WORLD_BEDROCK_DATA);
+        same_as_borrow::<BedrockDisk>(&BIG_TEST_BEDROCK_DATA);
+        same_as_borrow::<BedrockNetVarInt>(&HELLO_WORLD_BEDROCK_NET_DATA);
+        same_as_borrow::<BedrockNetVarInt>(&BIG_TEST_BEDROCK_NET_DATA);
+    }
+
+    #[test]
+    fn java_net_no_root_name() {
+        let data = [0x0A, 0x01, 0x00, 0x01, 0x61, 0x7F, 0x00];
+        let value = NbtValue::from_binary::<JavaNetAfter1_20_2>(&data).unwrap();
+        assert_eq!(value, NbtValue::Compound(None, vec![("a".into(), NbtValue::Byte(127))]));
+    }
+
+    #[test]
+    fn owned_errors() {
+        // 根节点只能是 Compound
+        assert_eq!(NbtValue::from_binary::<Java>(&[0x09]), Err(NbtError::WrongRootType(9)));
+        // 负数长度
+        let data = [0x0A, 0x00, 0x00, 0x07, 0x00, 0x01, 0x61, 0xFF, 0xFF, 0xFF, 0xFF, 0x00];
+        assert_eq!(
+            NbtValue::from_binary::<Java>(&data),
+            Err(NbtError::LenNegative(nbt_consts::TAG_BYTE_ARRAY, -1, 11))
+        );
+        // 数据不够
+        let data = [0x0A, 0x00, 0x00, 0x03, 0x00, 0x01, 0x61, 0x00];
+        assert_eq!(NbtValue::from_binary::<Java>(&data), Err(NbtError::CursorOverflow(7, 4, 8)));
+    }
+
+    #[test]
+    fn owned_depth_limit() {
+        // 一大堆嵌套的 List<List<...>>, 长度都是 1
+        let mut data = vec![0x09];
+        for _ in 0..(crate::RECURSE_LIMIT + 10) {
+            data.extend([0x09, 0x02]);
+        }
+        assert_eq!(
+            NbtValue::from_binary::<BedrockNetVarInt>(&data),
+            Err(NbtError::NbtDepthTooBig(crate::RECURSE_LIMIT))
+        );
+    }
+}
@@ -0,0 +1,112 @@
+//! 1.20.5+ 的 LZ4 区块压缩 (压缩方式 4)
+//!
+//! 原版用的是 lz4-java 的 `LZ4BlockOutputStream`, 不是标准的 LZ4 frame
+//!
+//! 数据被切成最多 64 KiB 的块, 每个块前面有 21 字节的头:
+//!
+//! - `LZ4Block` (8 字节)
+//! - token: 高 4 位是压缩方式 (`0x10` 不压缩, `0x20` LZ4), 低 4 位是块大小的等级
+//! - 压缩后的长度, 原始长度, xxhash32 校验值 (都是小端 i32)
+//!
+//! 最后用一个长度都是 0 的块结尾
+
+use xxhash_rust::xxh32::xxh32;
+
+use crate::{NbtError, NbtResult};
+
+const MAGIC: &[u8; 8] = b"LZ4Block";
+const HEADER_LEN: usize = 21;
+const METHOD_RAW: u8 = 0x10;
+const METHOD_LZ4: u8 = 0x20;
+/// 原版用的是默认的块大小
+const BLOCK_SIZE: usize = 1 << 16;
+/// `32 - (BLOCK_SIZE - 1).leading_zeros() - 10`
+const BLOCK_LEVEL: u8 = 6;
+const CHECKSUM_SEED: u32 = 0x9747_B28C;
+
+/// lz4-java 只用了 xxhash32 的低 28 位
+fn checksum(data: &[u8]) -> u32 { xxh32(data, CHECKSUM_SEED) & 0x0FFF_FFFF }
+
+fn read_i32(data: &[u8], pos: usize) -> i32 {
+    i32::from_le_bytes(data[pos..pos + 4].try_into().unwrap())
+}
+
+fn write_header(buff: &mut Vec<u8>, method: u8, compressed: usize, original: usize, check: u32) {
+    buff.extend_from_slice(MAGIC);
+    buff.push(method | BLOCK_LEVEL);
+    buff.extend_from_slice(&(compressed as i32).to_le_bytes());
+    buff.extend_from_slice(&(original as i32).to_le_bytes());
+    buff.extend_from_slice(&check.to_le_bytes());
+}
+
+/// 解压, 没有结尾的块也可以
+pub(crate) fn decompress(data: &[u8]) -> NbtResult<Vec<u8>> {
+    let err = |msg: &str| NbtError::RegionError(format!("LZ4 数据错误: {}", msg));
+    let mut result = Vec::with_capacity(data.len() * 2);
+    let mut pos = 0;
+    while pos < data.len() {
+        if data.len() - pos < HEADER_LEN {
+            return Err(err("块头不完整"));
+        }
+        if &data[pos..pos + 8] != MAGIC {
+            return Err(err("magic 不对"));
+        }
+        let method = data[pos + 8] & 0xF0;
+        let block_size = 1_usize << ((data[pos + 8] & 0x0F) + 10);
+        let compressed = read_i32(data, pos + 9);
+        let original = read_i32(data, pos + 13);
+        let check = read_i32(data, pos + 17) as u32;
+        pos += HEADER_LEN;
+        if compressed < 0 || original < 0 || original as usize > block_size {
+            return Err(err("块长度不对"));
+        }
+        let (compressed, original) = (compressed as usize, original as usize);
+        if original == 0 {
+            if compressed != 0 || check != 0 {
+                return Err(err("结尾的块不对"));
+            }
+            break;
+        }
+        if data.len() - pos < compressed {
+            return Err(err("块数据不完整"));
+        }
+        let block = &data[pos..pos + compressed];
+        pos += compressed;
+        let start = result.len();
+        match method {
+            METHOD_RAW if compressed == original => result.extend_from_slice(block),
+            METHOD_LZ4 => {
+                result.resize(start + original, 0);
+                let len = lz4_flex::block::decompress_into(block, &mut result[start..])
+                    .map_err(|e| err(&e.to_string()))?;
+                if len != original {
+                    return Err(err("解压之后的长度不对"));
+                }
+            }
+            _ => return Err(err("未知的压缩方式")),
+        }
+        if checksum(&result[start..]) != check {
+            return Err(err("校验值不对"));
+        }
+    }
+    Ok(result)
+}
+
+/// 压缩成原版可以读的格式
+pub(crate) fn compress(data: &[u8]) -> Vec<u8> {
+    let mut result = Vec::with_capacity(data.len() / 2 + HEADER_LEN * 2);
+    for block in data.chunks(BLOCK_SIZE) {
+        let check = checksum(block);
+        let compressed = lz4_flex::block::compress(block);
+        // 压缩了反而更大的话就直接存
+        if compressed.len() >= block.len() {
+            write_header(&mut result, METHOD_RAW, block.len(), block.len(), check);
+            result.extend_from_slice(block);
+        } else {
+            write_header(&mut result, METHOD_LZ4, compressed.len(), block.len(), check);
+            result.extend_from_slice(&compressed);
+        }
+    }
+    write_header(&mut result, METHOD_RAW, 0, 0, 0);
+    result
+}
@@ -0,0 +1,389 @@
+//! Anvil (`.mca`) / MCRegion (`.mcr`) 区域文件
+//!
+//! # 文件格式
+//!
+//! 整个文件按 4 KiB 的 sector 对齐
+//!
+//! - sector 0: 1024 个区块的位置, 每个 4 字节 (3 字节 sector 偏移 + 1 字节 sector 数量, 大端)
+//! - sector 1: 1024 个区块的时间戳, 每个 4 字节 (大端, 秒)
+//! - 后面都是区块: 4 字节长度 (大端, 包括压缩方式那个字节) + 1 字节压缩方式 + 压缩之后的 NBT
+//!
+//! 区块的下标是 `x + z * 32` (区块坐标对 32 取模)
+//!
+//! 压缩之后超过 255 个 sector 的区块会放在同一个文件夹下的 `c.<x>.<z>.mcc` 里,
+//! 这时候压缩方式会加上 128, 区域文件里只剩下压缩方式那一个字节
+//!
+//! ```rust
+//! use std::io::Cursor;
+//!
+//! use shen_nbt6::nbt;
+//! use shen_nbt6::nbt_version::Java;
+//! use shen_nbt6::region::{ChunkCompression, RegionFile};
+//!
+//! let mut region = RegionFile::new(Cursor::new(Vec::new())).unwrap();
+//! let chunk = nbt!({ "DataVersion": 3953, "xPos": 1, "zPos": 2 });
+//! region.write_chunk(1, 2, &chunk, ChunkCompression::Zlib).unwrap();
+//! assert!(region.has_chunk(1, 2));
+//! let read = region.read_chunk(1, 2).unwrap().unwrap();
+//! assert_eq!(read.to_binary::<Java>(), chunk.to_binary::<Java>());
+//! assert_eq!(region.read_chunk(0, 0), Ok(None));
+//! ```
+
+mod lz4;
+#[cfg(test)]
+mod tests;
+
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::compression::Compression;
+use crate::nbt_version::Java;
+use crate::traits::NbtWriteTrait;
+use crate::{NbtError, NbtResult, NbtValue};
+
+/// 一个 sector 的大小
+pub const SECTOR_SIZE: usize = 4096;
+/// 一个区域文件里的区块数量 (32 * 32)
+pub const CHUNK_COUNT: usize = 1024;
+/// 一个区块在区域文件里最多能占多少个 sector, 再多就要放到 `.mcc` 里了
+pub const MAX_CHUNK_SECTORS: usize = 255;
+/// 位置表和时间戳表占的 sector
+const HEADER_SECTORS: usize = 2;
+/// 压缩方式加上这个表示数据在 `.mcc` 文件里
+const EXTERNAL_FLAG: u8 = 128;
+
+/// 区块的压缩方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ChunkCompression {
+    /// 1: gzip, 原版基本不用
+    Gzip,
+    /// 2: zlib, 原版默认
+    #[default]
+    Zlib,
+    /// 3: 不压缩
+    None,
+    /// 4: LZ4 (1.20.5+), 是 lz4-java 的 `LZ4BlockOutputStream` 格式, 不是标准的 LZ4 frame
+    Lz4,
+}
+
+impl ChunkCompression {
+    /// 区块头里的压缩方式 id
+    pub fn id(self) -> u8 {
+        match self {
+            ChunkCompression::Gzip => 1,
+            ChunkCompression::Zlib => 2,
+            ChunkCompression::None => 3,
+            ChunkCompression::Lz4 => 4,
+        }
+    }
+
+    /// 从区块头里的压缩方式 id 转换 (不包括 `.mcc` 的标记)
+    pub fn from_id(id: u8) -> NbtResult<Self> {
+        match id {
+            1 => Ok(ChunkCompression::Gzip),
+            2 => Ok(ChunkCompression::Zlib),
+            3 => Ok(ChunkCompression::None),
+            4 => Ok(ChunkCompression::Lz4),
+            x => Err(NbtError::RegionError(format!("未知的压缩方式: {}", x))),
+        }
+    }
+
+    /// 解压区块数据
+    pub fn decompress(self, data: &[u8]) -> NbtResult<Vec<u8>> {
+        match self {
+            ChunkCompression::Gzip => Ok(Compression::Gzip.decompress(data)?.into_owned()),
+            ChunkCompression::Zlib => Ok(Compression::Zlib.decompress(data)?.into_owned()),
+            ChunkCompression::None => Ok(data.to_vec()),
+            ChunkCompression::Lz4 => lz4::decompress(data),
+        }
+    }
+
+    /// 压缩区块数据
+    pub fn compress(self, data: &[u8]) -> NbtResult<Vec<u8>> {
+        match self {
+            ChunkCompression::Gzip => Compression::Gzip.compress(data),
+            ChunkCompression::Zlib => Compression::Zlib.compress(data),
+            ChunkCompression::None => Ok(data.to_vec()),
+            ChunkCompression::Lz4 => Ok(lz4::compress(data)),
+        }
+    }
+}
+
+/// 区块坐标对应的下标, 区块坐标可以是绝对坐标也可以是区域内的坐标
+#[inline]
+fn chunk_index(x: i32, z: i32) -> usize { (x.rem_euclid(32) + z.rem_euclid(32) * 32) as usize }
+
+/// 从 `r.<x>.<z>.mca` 这样的文件名里拿到区域坐标
+fn parse_region_name(path: &Path) -> Option<(i32, i32)> {
+    let name = path.file_name()?.to_str()?;
+    let mut parts = name.split('.');
+    match (parts.next(), parts.next(), parts.next(), parts.next(), parts.next()) {
+        (Some("r"), Some(x), Some(z), Some("mca" | "mcr"), None) => {
+            Some((x.parse().ok()?, z.parse().ok()?))
+        }
+        _ => None,
+    }
+}
+
+/// 一个区域文件
+///
+/// 位置表和时间戳表会一直放在内存里, 区块数据每次都从 `file` 里读写
+///
+/// 写入的时候会重新分配 sector: 放得下就原地覆盖, 放不下就找一段空的, 实在没有就放在文件末尾
+pub struct RegionFile<F = File> {
+    file: F,
+    /// 每个区块的位置, `sector 偏移 << 8 | sector 数量`, 0 表示没有
+    locations: Vec<u32>,
+    /// 每个区块最后保存的时间
+    timestamps: Vec<u32>,
+    /// 每个 sector 有没有被用
+    used: Vec<bool>,
+    /// `.mcc` 所在的文件夹和这个区域的坐标, 只有 [`RegionFile::open`] 的时候才知道
+    external: Option<(PathBuf, i32, i32)>,
+}
+
+impl RegionFile<File> {
+    /// 打开一个区域文件, 不存在的话会创建一个空的
+    ///
+    /// 文件名是 `r.<x>.<z>.mca` 的时候才能读写 `.mcc` 文件
+    pub fn open(path: impl AsRef<Path>) -> NbtResult<Self> {
+        let path = path.as_ref();
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(path)?;
+        let mut region = RegionFile::new(file)?;
+        if let Some((x, z)) = parse_region_name(path) {
+            let dir = path.parent().map(Path::to_path_buf).unwrap_or_default();
+            region.external = Some((dir, x, z));
+        }
+        Ok(region)
+    }
+}
+
+impl<F: Read + Write + Seek> RegionFile<F> {
+    /// 从任何可以读写的东西里打开一个区域文件, 空的话会写入空的位置表和时间戳表
+    ///
+    /// 位置不对的区块 (跟表头或者别的区块重叠, 超出文件末尾) 会被当成不存在
+    pub fn new(mut file: F) -> NbtResult<Self> {
+        let len = file.seek(SeekFrom::End(0))? as usize;
+        if len == 0 {
+            file.write_all(&[0; SECTOR_SIZE * HEADER_SECTORS])?;
+        } else if len < SECTOR_SIZE * HEADER_SECTORS {
+            return Err(NbtError::RegionError(format!("文件太短: {} 字节", len)));
+        }
+        let mut header = vec![0; SECTOR_SIZE * HEADER_SECTORS];
+        file.seek(SeekFrom::Start(0))?;
+        file.read_exact(&mut header)?;
+        let mut table = header.chunks_exact(4).map(|n| u32::from_be_bytes(n.try_into().unwrap()));
+        let mut locations: Vec<u32> = table.by_ref().take(CHUNK_COUNT).collect();
+        let timestamps = table.collect();
+
+        let mut used = vec![false; len.max(SECTOR_SIZE * HEADER_SECTORS).div_ceil(SECTOR_SIZE)];
+        used[..HEADER_SECTORS].fill(true);
+        for location in locations.iter_mut() {
+            let (offset, count) = ((*location >> 8) as usize, (*location & 0xFF) as usize);
+            if *location == 0 {
+                continue;
+            }
+            let range = offset..offset + count;
+            if count == 0
+                || offset < HEADER_SECTORS
+                || range.end > used.len()
+                || used[range.clone()].contains(&true)
+            {
+                *location = 0;
+                continue;
+            }
+            used[range].fill(true);
+        }
+        Ok(Self {
+            file,
+            locations,
+            timestamps,
+            used,
+            external: None,
+        })
+    }
+
+    /// 拿回里面的文件
+    pub fn into_inner(self) -> F { self.file }
+
+    /// 这个区块存不存在
+    pub fn has_chunk(&self, x: i32, z: i32) -> bool { self.locations[chunk_index(x, z)] != 0 }
+
+    /// 这个区块最后保存的时间 (秒), 没有的话是 0
+    pub fn timestamp(&self, x: i32, z: i32) -> u32 { self.timestamps[chunk_index(x, z)] }
+
+    /// 所有存在的区块的区域内坐标
+    pub fn chunks(&self) -> impl Iterator<Item = (i32, i32)> + '_ {
+        self.locations
+            .iter()
+            .enumerate()
+            .filter(|(_, location)| **location != 0)
+            .map(|(idx, _)| ((idx % 32) as i32, (idx / 32) as i32))
+    }
+
+    /// `.mcc` 文件的路径, 用的是区块的绝对坐标
+    fn external_path(&self, idx: usize) -> NbtResult<PathBuf> {
+        match &self.external {
+            Some((dir, region_x, region_z)) => {
+                let x = region_x * 32 + (idx % 32) as i32;
+                let z = region_z * 32 + (idx / 32) as i32;
+                Ok(dir.join(format!("c.{}.{}.mcc", x, z)))
+            }
+            None => Err(NbtError::RegionError(
+                "不知道区域坐标, 没法读写 .mcc 文件 (请使用 RegionFile::open 打开 r.<x>.<z>.mca)"
+                    .to_string(),
+            )),
+        }
+    }
+
+    /// 读取区块解压之后的 NBT 数据, 没有这个区块的话返回 `None`
+    pub fn read_chunk_data(&mut self, x: i32, z: i32) -> NbtResult<Option<Vec<u8>>> {
+        let idx = chunk_index(x, z);
+        let location = self.locations[idx];
+        if location == 0 {
+            return Ok(None);
+        }
+        let (offset, count) = ((location >> 8) as usize, (location & 0xFF) as usize);
+        self.file.seek(SeekFrom::Start((offset * SECTOR_SIZE) as u64))?;
+        let mut header = [0; 5];
+        self.file.read_exact(&mut header)?;
+        let len = u32::from_be_bytes(header[..4].try_into().unwrap()) as usize;
+        if len == 0 || len + 4 > count * SECTOR_SIZE {
+            return Err(NbtError::RegionError(format!(
+                "区块 ({}, {}) 的长度不对: {}, 只有 {} 个 sector",
+                x, z, len, count
+            )));
+        }
+        let compression = ChunkCompression::from_id(header[4] & !EXTERNAL_FLAG)?;
+        let data = match header[4] & EXTERNAL_FLAG != 0 {
+            true => std::fs::read(self.external_path(idx)?)?,
+            false => {
+                let mut data = vec![0; len - 1];
+                self.file.read_exact(&mut data)?;
+                data
+            }
+        };
+        compression.decompress(&data).map(Some)
+    }
+
+    /// 读取区块, 没有这个区块的话返回 `None`
+    pub fn read_chunk(&mut self, x: i32, z: i32) -> NbtResult<Option<NbtValue>> {
+        match self.read_chunk_data(x, z)? {
+            Some(data) => NbtValue::from_binary::<Java>(&data).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// 释放这个区块占的 sector
+    fn free(&mut self, idx: usize) {
+        let location = self.locations[idx];
+        let (offset, count) = ((location >> 8) as usize, (location & 0xFF) as usize);
+        self.used[offset..offset + count].fill(false);
+    }
+
+    /// 找一段 `count` 个空的 sector, 没有的话就放在文件末尾
+    fn allocate(&mut self, count: usize) -> usize {
+        let mut start = HEADER_SECTORS;
+        for idx in HEADER_SECTORS..self.used.len() {
+            if self.used[idx] {
+                start = idx + 1;
+            } else if idx + 1 - start == count {
+                break;
+            }
+        }
+        if start + count > self.used.len() {
+            self.used.resize(start + count, false);
+        }
+        self.used[start..start + count].fill(true);
+        start
+    }
+
+    /// 更新表头里这个区块的位置和时间戳
+    fn write_header(&mut self, idx: usize, location: u32, timestamp: u32) -> NbtResult<()> {
+        self.locations[idx] = location;
+        self.timestamps[idx] = timestamp;
+        self.file.seek(SeekFrom::Start((idx * 4) as u64))?;
+        self.file.write_all(&location.to_be_bytes())?;
+        self.file.seek(SeekFrom::Start((SECTOR_SIZE + idx * 4) as u64))?;
+        self.file.write_all(&timestamp.to_be_bytes())?;
+        Ok(())
+    }
+
+    /// 写入区块的 NBT 数据 (还没压缩的), 时间戳是现在
+    ///
+    /// 压缩之后超过 [`MAX_CHUNK_SECTORS`] 的会写进 `.mcc` 文件里
+    pub fn write_chunk_data(
+        &mut self,
+        x: i32,
+        z: i32,
+        data: &[u8],
+        compression: ChunkCompression,
+    ) -> NbtResult<()> {
+        let idx = chunk_index(x, z);
+        let compressed = compression.compress(data)?;
+        let mut payload = Vec::with_capacity(compressed.len() + 5);
+        if (compressed.len() + 5).div_ceil(SECTOR_SIZE) > MAX_CHUNK_SECTORS {
+            std::fs::write(self.external_path(idx)?, &compressed)?;
+            payload.extend_from_slice(&1_u32.to_be_bytes());
+            payload.push(compression.id() | EXTERNAL_FLAG);
+        } else {
+            payload.extend_from_slice(&(compressed.len() as u32 + 1).to_be_bytes());
+            payload.push(compression.id());
+            payload.extend_from_slice(&compressed);
+            // 之前放在 .mcc 里的话就删掉
+            if let Ok(path) = self.external_path(idx) {
+                if path.exists() {
+                    std::fs::remove_file(path)?;
+                }
+            }
+        }
+        // 补齐到整个 sector
+        payload.resize(payload.len().div_ceil(SECTOR_SIZE) * SECTOR_SIZE, 0);
+        let count = payload.len() / SECTOR_SIZE;
+
+        self.free(idx);
+        let offset = self.allocate(count);
+        self.file.seek(SeekFrom::Start((offset * SECTOR_SIZE) as u64))?;
+        self.file.write_all(&payload)?;
+        let timestamp =
+            SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |t| t.as_secs() as u32);
+        self.write_header(idx, ((offset as u32) << 8) | count as u32, timestamp)
+    }
+
+    /// 写入区块 (Java 版格式), 时间戳是现在
+    pub fn write_chunk(
+        &mut self,
+        x: i32,
+        z: i32,
+        value: &NbtValue,
+        compression: ChunkCompression,
+    ) -> NbtResult<()> {
+        let data = Java::to_bytes(value)?;
+        self.write_chunk_data(x, z, &data, compression)
+    }
+
+    /// 删掉一个区块, 它占的 sector 之后可以给别的区块用
+    pub fn remove_chunk(&mut self, x: i32, z: i32) -> NbtResult<()> {
+        let idx = chunk_index(x, z);
+        if self.locations[idx] == 0 {
+            return Ok(());
+        }
+        self.free(idx);
+        if let Ok(path) = self.external_path(idx) {
+            if path.exists() {
+                std::fs::remove_file(path)?;
+            }
+        }
+        self.write_header(idx, 0, 0)
+    }
+
+    /// 把缓冲的数据写进去
+    pub fn flush(&mut self) -> NbtResult<()> { Ok(self.file.flush()?) }
+}
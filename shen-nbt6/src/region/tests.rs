@@ -0,0 +1,168 @@
+use std::io::Cursor;
+
+use super::*;
+use crate::nbt;
+use crate::tests::BIG_TEST_DATA;
+
+/// 每次都不一样的测试目录
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("shen-nbt6-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// 压缩不了的数据
+fn noise(len: usize) -> Vec<i8> {
+    let mut seed: u32 = 0x1234_5678;
+    (0..len)
+        .map(|_| {
+            seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12345);
+            (seed >> 16) as i8
+        })
+        .collect()
+}
+
+fn chunk(x: i32, z: i32, extra: Vec<i8>) -> NbtValue {
+    let value = nbt!({ "DataVersion": 3953, "xPos": (x), "zPos": (z), "data": [B; ] });
+    let NbtValue::Compound(name, mut values) = value else {
+        unreachable!()
+    };
+    values[3].1 = NbtValue::ByteArray(extra);
+    // 读出来的根节点名字是 Some("")
+    NbtValue::Compound(name.or(Some("".into())), values)
+}
+
+#[test]
+fn compression_round_trip() {
+    let data = BIG_TEST_DATA.repeat(100);
+    for compression in [
+        ChunkCompression::Gzip,
+        ChunkCompression::Zlib,
+        ChunkCompression::None,
+        ChunkCompression::Lz4,
+    ] {
+        let compressed = compression.compress(&data).unwrap();
+        assert_eq!(compression.decompress(&compressed).unwrap(), data);
+        assert_eq!(ChunkCompression::from_id(compression.id()), Ok(compression));
+    }
+    assert!(ChunkCompression::from_id(127).is_err());
+}
+
+#[test]
+fn lz4_java_format() {
+    // lz4-java 写出来的 "abc": 一个不压缩的块 + 结尾
+    let mut data = b"LZ4Block\x16".to_vec();
+    data.extend_from_slice(&3_i32.to_le_bytes());
+    data.extend_from_slice(&3_i32.to_le_bytes());
+    data.extend_from_slice(
+        &(xxhash_rust::xxh32::xxh32(b"abc", 0x9747_B28C) & 0x0FFF_FFFF).to_le_bytes(),
+    );
+    data.extend_from_slice(b"abc");
+    data.extend_from_slice(b"LZ4Block\x16");
+    data.extend_from_slice(&[0; 12]);
+    assert_eq!(ChunkCompression::Lz4.decompress(&data).unwrap(), b"abc");
+    // 校验值不对
+    data[21] = b'x';
+    assert!(matches!(ChunkCompression::Lz4.decompress(&data), Err(NbtError::RegionError(_))));
+    // 超过 64 KiB 会分成好几个块, 而且可以压缩的块是真的压缩了
+    let big = vec![7_u8; 200_000];
+    let compressed = ChunkCompression::Lz4.compress(&big).unwrap();
+    assert!(compressed.len() < 10_000);
+    assert_eq!(compressed.windows(8).filter(|w| w == b"LZ4Block").count(), 5);
+    assert_eq!(&compressed[compressed.len() - 21..compressed.len() - 12], b"LZ4Block\x16");
+    assert_eq!(ChunkCompression::Lz4.decompress(&compressed).unwrap(), big);
+}
+
+#[test]
+fn read_write_in_memory() {
+    let mut region = RegionFile::new(Cursor::new(Vec::new())).unwrap();
+    assert_eq!(region.chunks().count(), 0);
+    let small = chunk(0, 0, vec![1; 10]);
+    let big = chunk(31, 31, noise(10_000));
+    region.write_chunk(0, 0, &small, ChunkCompression::Zlib).unwrap();
+    region.write_chunk(31, 31, &big, ChunkCompression::Lz4).unwrap();
+    region.write_chunk(-1, 0, &small, ChunkCompression::Gzip).unwrap();
+    assert_eq!(region.chunks().collect::<Vec<_>>(), vec![(0, 0), (31, 0), (31, 31)]);
+    assert!(region.timestamp(0, 0) > 0);
+    assert_eq!(region.timestamp(1, 1), 0);
+    // 绝对坐标也可以
+    assert_eq!(region.read_chunk(32, 64).unwrap(), Some(small.clone()));
+    assert_eq!(region.read_chunk(31, 31).unwrap(), Some(big.clone()));
+    assert_eq!(region.read_chunk(1, 1).unwrap(), None);
+
+    // 重新打开
+    let data = region.into_inner().into_inner();
+    assert_eq!(data.len() % SECTOR_SIZE, 0);
+    let mut region = RegionFile::new(Cursor::new(data)).unwrap();
+    assert_eq!(region.read_chunk(31, 0).unwrap(), Some(small.clone()));
+    assert_eq!(region.read_chunk(31, 31).unwrap(), Some(big.clone()));
+
+    // 变大了放不下, 换个地方, 之前的 sector 给后来的用
+    let (old_offset, old_count) = (region.locations[0] >> 8, region.locations[0] & 0xFF);
+    assert_eq!((old_offset, old_count), (2, 1));
+    let bigger = chunk(0, 0, noise(20_000));
+    region.write_chunk(0, 0, &bigger, ChunkCompression::None).unwrap();
+    assert!(region.locations[0] >> 8 > old_offset);
+    assert_eq!(region.locations[0] & 0xFF, 5);
+    region.write_chunk(5, 5, &small, ChunkCompression::Zlib).unwrap();
+    assert_eq!(region.locations[chunk_index(5, 5)] >> 8, old_offset);
+    // 变小了原地覆盖
+    let offset = region.locations[0] >> 8;
+    region.write_chunk(0, 0, &small, ChunkCompression::None).unwrap();
+    assert_eq!(region.locations[0], offset << 8 | 1);
+    assert_eq!(region.read_chunk(0, 0).unwrap(), Some(small.clone()));
+    assert_eq!(region.read_chunk(31, 31).unwrap(), Some(big));
+
+    region.remove_chunk(0, 0).unwrap();
+    assert!(!region.has_chunk(0, 0));
+    let data = region.into_inner().into_inner();
+    let region = RegionFile::new(Cursor::new(data)).unwrap();
+    assert!(!region.has_chunk(0, 0));
+    assert!(region.has_chunk(5, 5));
+}
+
+#[test]
+fn bad_files() {
+    assert!(matches!(
+        RegionFile::new(Cursor::new(vec![0; 100])),
+        Err(NbtError::RegionError(_))
+    ));
+    // 位置跟表头重叠的区块当作不存在
+    let mut data = vec![0; SECTOR_SIZE * 3];
+    data[..4].copy_from_slice(&[0, 0, 1, 1]);
+    data[4..8].copy_from_slice(&[0, 0, 2, 1]);
+    let mut region = RegionFile::new(Cursor::new(data)).unwrap();
+    assert!(!region.has_chunk(0, 0));
+    assert!(region.has_chunk(1, 0));
+    // 长度是 0
+    assert!(matches!(region.read_chunk(1, 0), Err(NbtError::RegionError(_))));
+}
+
+#[test]
+fn external_chunks() {
+    let dir = temp_dir("region");
+    let path = dir.join("r.-1.2.mca");
+    let mut region = RegionFile::open(&path).unwrap();
+    // 压缩之后超过 255 个 sector
+    let huge = chunk(3, 4, noise(MAX_CHUNK_SECTORS * SECTOR_SIZE));
+    region.write_chunk(3, 4, &huge, ChunkCompression::Zlib).unwrap();
+    let mcc = dir.join("c.-29.68.mcc");
+    assert!(mcc.exists());
+    assert_eq!(region.locations[chunk_index(3, 4)] & 0xFF, 1);
+    drop(region);
+
+    let mut region = RegionFile::open(&path).unwrap();
+    assert_eq!(region.read_chunk(3, 4).unwrap(), Some(huge.clone()));
+    // 变小了就不用 .mcc 了
+    let small = chunk(3, 4, vec![0; 10]);
+    region.write_chunk(3, 4, &small, ChunkCompression::Zlib).unwrap();
+    assert!(!mcc.exists());
+    assert_eq!(region.read_chunk(3, 4).unwrap(), Some(small));
+
+    // 不知道区域坐标的时候没法写 .mcc
+    let mut region = RegionFile::new(Cursor::new(Vec::new())).unwrap();
+    let result = region.write_chunk(3, 4, &huge, ChunkCompression::None);
+    assert!(matches!(result, Err(NbtError::RegionError(_))));
+    std::fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,227 @@
+//! 强制写成 `NbtByteArray`/`NbtIntArray`/`NbtLongArray`
+//!
+//! 默认情况下 `Vec<i32>` 会被写成 `NbtList`, 但是像 UUID 这样的东西必须是 `NbtIntArray`
+//!
+//! 可以用 `#[serde(with = "...")]`, 也可以直接用包装类型
+//!
+//! 读的时候 List 和 Array 都可以
+//!
+//! ```rust
+//! use serde::{Deserialize, Serialize};
+//! use shen_nbt6::nbt_version::Java;
+//! use shen_nbt6::serding::array::{self, LongArray};
+//! use shen_nbt6::NbtValue;
+//!
+//! #[derive(Debug, PartialEq, Serialize, Deserialize)]
+//! struct Entity {
+//!     #[serde(rename = "UUID", with = "array::int_array")]
+//!     uuid: [i32; 4],
+//!     heights: LongArray,
+//! }
+//!
+//! let entity = Entity {
+//!     uuid: [1, 2, 3, 4],
+//!     heights: LongArray(vec![5, 6]),
+//! };
+//! let data = shen_nbt6::to_bytes::<Java, _>(&entity).unwrap();
+//! let NbtValue::Compound(_, items) = NbtValue::from_binary::<Java>(&data).unwrap() else {
+//!     unreachable!()
+//! };
+//! assert_eq!(items[0].1, NbtValue::IntArray(vec![1, 2, 3, 4]));
+//! assert_eq!(shen_nbt6::from_bytes::<Java, Entity>(&data).unwrap(), entity);
+//! ```
+
+use std::fmt;
+use std::marker::PhantomData;
+
+use serde::de::{self, Deserialize, DeserializeSeed, Deserializer, Visitor};
+use serde::ser::{Serialize, Serializer};
+
+use super::value_ser::ValueSerializer;
+use crate::{nbt_consts, NbtError, NbtResult, NbtTypeId, NbtValue};
+
+/// 标记 `NbtByteArray` 的 newtype 名字
+pub(crate) const BYTE_ARRAY_TOKEN: &str = "__shen_nbt6_byte_array";
+/// 标记 `NbtIntArray` 的 newtype 名字
+pub(crate) const INT_ARRAY_TOKEN: &str = "__shen_nbt6_int_array";
+/// 标记 `NbtLongArray` 的 newtype 名字
+pub(crate) const LONG_ARRAY_TOKEN: &str = "__shen_nbt6_long_array";
+/// `NbtValue` 反序列化的时候用的 newtype 名字, 用来把 Array 和 List 区分开
+pub(crate) const NBT_VALUE_TOKEN: &str = "__shen_nbt6_value";
+
+/// newtype 名字对应的 Array 类型
+pub(crate) fn token_tag(name: &str) -> Option<NbtTypeId> {
+    match name {
+        BYTE_ARRAY_TOKEN => Some(nbt_consts::TAG_BYTE_ARRAY),
+        INT_ARRAY_TOKEN => Some(nbt_consts::TAG_INT_ARRAY),
+        LONG_ARRAY_TOKEN => Some(nbt_consts::TAG_LONG_ARRAY),
+        _ => None,
+    }
+}
+
+macro_rules! list_to_array {
+    ($list:expr, $variant:ident, $elem:ident, $elem_tag:ident) => {
+        $list
+            .into_iter()
+            .map(|value| match value {
+                NbtValue::$elem(v) => Ok(v),
+                other => Err(NbtError::IncorrectType(nbt_consts::$elem_tag, other.tag())),
+            })
+            .collect::<NbtResult<Vec<_>>>()
+            .map(NbtValue::$variant)
+    };
+}
+
+/// 先按普通的规则转成 `NbtValue`, 再转成 `tag` 对应的 Array
+///
+/// 返回 `None` 表示什么都没有 (`Option::None`)
+pub(crate) fn serialize_array<T: Serialize + ?Sized>(
+    tag: NbtTypeId,
+    value: &T,
+) -> NbtResult<Option<NbtValue>> {
+    let value = match value.serialize(ValueSerializer)? {
+        Some(value) => value,
+        None => return Ok(None),
+    };
+    let array = match (tag, value) {
+        (nbt_consts::TAG_BYTE_ARRAY, value @ NbtValue::ByteArray(_))
+        | (nbt_consts::TAG_INT_ARRAY, value @ NbtValue::IntArray(_))
+        | (nbt_consts::TAG_LONG_ARRAY, value @ NbtValue::LongArray(_)) => value,
+        (nbt_consts::TAG_BYTE_ARRAY, NbtValue::List(list)) => {
+            list_to_array!(list, ByteArray, Byte, TAG_BYTE)?
+        }
+        (nbt_consts::TAG_INT_ARRAY, NbtValue::List(list)) => {
+            list_to_array!(list, IntArray, Int, TAG_INT)?
+        }
+        (nbt_consts::TAG_LONG_ARRAY, NbtValue::List(list)) => {
+            list_to_array!(list, LongArray, Long, TAG_LONG)?
+        }
+        (_, other) => return Err(NbtError::IncorrectType(nbt_consts::TAG_LIST, other.tag())),
+    };
+    Ok(Some(array))
+}
+
+/// 给 `NbtValue` 用的, 把 Array 当成 `{ByteArray: [...]}` 这样的 enum 交出去
+pub(crate) struct ArrayVariant<D> {
+    name: &'static str,
+    de: D,
+}
+
+impl<D> ArrayVariant<D> {
+    pub(crate) fn new(tag: NbtTypeId, de: D) -> Option<Self> {
+        let name = match tag {
+            nbt_consts::TAG_BYTE_ARRAY => "ByteArray",
+            nbt_consts::TAG_INT_ARRAY => "IntArray",
+            nbt_consts::TAG_LONG_ARRAY => "LongArray",
+            _ => return None,
+        };
+        Some(Self { name, de })
+    }
+}
+
+impl<'de, D: Deserializer<'de, Error = NbtError>> de::EnumAccess<'de> for ArrayVariant<D> {
+    type Error = NbtError;
+    type Variant = Self;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(self, seed: V) -> NbtResult<(V::Value, Self)> {
+        let variant = seed.deserialize(de::value::StrDeserializer::<NbtError>::new(self.name))?;
+        Ok((variant, self))
+    }
+}
+
+impl<'de, D: Deserializer<'de, Error = NbtError>> de::VariantAccess<'de> for ArrayVariant<D> {
+    type Error = NbtError;
+
+    fn unit_variant(self) -> NbtResult<()> { Err(de::Error::custom("Array 不是 unit variant")) }
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> NbtResult<T::Value> {
+        seed.deserialize(self.de)
+    }
+    fn tuple_variant<V: Visitor<'de>>(self, _len: usize, _visitor: V) -> NbtResult<V::Value> {
+        Err(de::Error::custom("Array 不是 tuple variant"))
+    }
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        _fields: &'static [&'static str],
+        _visitor: V,
+    ) -> NbtResult<V::Value> {
+        Err(de::Error::custom("Array 不是 struct variant"))
+    }
+}
+
+/// 读的时候直接交给里面的类型
+struct ArrayVisitor<T>(PhantomData<T>);
+
+impl<'de, T: Deserialize<'de>> Visitor<'de> for ArrayVisitor<T> {
+    type Value = T;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("一个 NBT Array 或者 List")
+    }
+
+    fn visit_newtype_struct<D: Deserializer<'de>>(self, deserializer: D) -> Result<T, D::Error> {
+        T::deserialize(deserializer)
+    }
+}
+
+macro_rules! array_with {
+    ($module:ident, $token:ident, $doc:literal) => {
+        #[doc = $doc]
+        ///
+        /// 用于 `#[serde(with = "...")]`, 里面可以是任何能序列化成整数 seq 的类型
+        pub mod $module {
+            use super::*;
+
+            pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                T: Serialize + ?Sized,
+                S: Serializer,
+            {
+                serializer.serialize_newtype_struct($token, value)
+            }
+
+            pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+            where
+                T: Deserialize<'de>,
+                D: Deserializer<'de>,
+            {
+                deserializer.deserialize_newtype_struct($token, ArrayVisitor(PhantomData))
+            }
+        }
+    };
+}
+
+array_with!(byte_array, BYTE_ARRAY_TOKEN, "写成 `NbtByteArray`");
+array_with!(int_array, INT_ARRAY_TOKEN, "写成 `NbtIntArray`");
+array_with!(long_array, LONG_ARRAY_TOKEN, "写成 `NbtLongArray`");
+
+macro_rules! array_wrapper {
+    ($name:ident, $ty:ty, $module:ident, $doc:literal) => {
+        #[doc = $doc]
+        #[derive(Debug, Clone, Default, PartialEq, Eq)]
+        pub struct $name(pub Vec<$ty>);
+
+        impl From<Vec<$ty>> for $name {
+            fn from(value: Vec<$ty>) -> Self { Self(value) }
+        }
+
+        impl From<$name> for Vec<$ty> {
+            fn from(value: $name) -> Self { value.0 }
+        }
+
+        impl Serialize for $name {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                $module::serialize(&self.0, serializer)
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                $module::deserialize(deserializer).map(Self)
+            }
+        }
+    };
+}
+
+array_wrapper!(ByteArray, i8, byte_array, "一定会写成 `NbtByteArray` 的 `Vec<i8>`");
+array_wrapper!(IntArray, i32, int_array, "一定会写成 `NbtIntArray` 的 `Vec<i32>`");
+array_wrapper!(LongArray, i64, long_array, "一定会写成 `NbtLongArray` 的 `Vec<i64>`");
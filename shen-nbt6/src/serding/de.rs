@@ -0,0 +1,460 @@
+use std::borrow::Cow;
+use std::marker::PhantomData;
+
+use serde::de::value::SeqDeserializer;
+use serde::de::{self, IgnoredAny, IntoDeserializer, Visitor};
+
+use super::array::{ArrayVariant, NBT_VALUE_TOKEN};
+use super::{expect_list, expect_tag};
+use crate::mutf8;
+use crate::traits::NbtReadTrait;
+use crate::{nbt_consts, NbtError, NbtReader, NbtResult, NbtTypeId};
+
+/// 类型对上了再交给 `deserialize_any`
+macro_rules! deserialize_expect {
+    ($($method:ident => $tag:ident),* $(,)?) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> NbtResult<V::Value> {
+                expect_tag(nbt_consts::$tag, self.current_tag())?;
+                self.deserialize_any(visitor)
+            }
+        )*
+    };
+}
+pub(crate) use deserialize_expect;
+
+/// serde 没法手动模拟 stack, 只能递归, 所以限制比 [`crate::RECURSE_LIMIT`] 小
+///
+/// debug 下每一层大概要 4~5 KB 的栈, 256 层在 2 MB 的线程栈上也不会爆
+pub const SERDE_RECURSE_LIMIT: usize = 256;
+
+/// 直接从 NBT 数据里读出 Rust 的值
+///
+/// 每次读的值的类型由 `tag` 决定
+pub struct Deserializer<'de, R> {
+    reader: NbtReader<'de>,
+    /// 下一个值的类型
+    tag: NbtTypeId,
+    depth: usize,
+    _version: PhantomData<R>,
+}
+
+impl<'de, R: NbtReadTrait> Deserializer<'de, R> {
+    /// 读取根节点的 tag (和名字), 准备好读根节点的值
+    pub fn from_bytes(data: &'de [u8]) -> NbtResult<Self> {
+        let mut reader = NbtReader::new(data);
+        let tag = match reader.read_u8()? {
+            nbt_consts::TAG_COMPOUND => {
+                if R::ROOT_NAMED {
+                    let len = R::read_str_len(&mut reader)?;
+                    reader.read_u8_array(len)?;
+                }
+                nbt_consts::TAG_COMPOUND
+            }
+            nbt_consts::TAG_LIST if R::LIST_ROOT => nbt_consts::TAG_LIST,
+            x => return Err(NbtError::WrongRootType(x)),
+        };
+        Ok(Self {
+            reader,
+            tag,
+            depth: 0,
+            _version: PhantomData,
+        })
+    }
+
+    /// 取出 `len` 长度的数据, 生命周期跟原始数据一样
+    fn take(&mut self, len: usize) -> NbtResult<&'de [u8]> {
+        let data = self.reader.data;
+        let start = self.reader.cursor;
+        if start + len > data.len() {
+            return Err(NbtError::CursorOverflow(start, len, data.len()));
+        }
+        self.reader.cursor += len;
+        Ok(&data[start..start + len])
+    }
+
+    /// 读一个字符串, 能借用就借用
+    ///
+    /// 按照 `R` 的要求解码 m-utf8 或者 utf-8
+    fn read_str(&mut self) -> NbtResult<Cow<'de, str>> {
+        let len = R::read_str_len(&mut self.reader)?;
+        let data = self.take(len)?;
+        match R::MUTF8_STRING {
+            true => Ok(mutf8::decode(data)?),
+            false => Ok(Cow::Borrowed(std::str::from_utf8(data)?)),
+        }
+    }
+
+    fn visit_str<V: Visitor<'de>>(&mut self, visitor: V) -> NbtResult<V::Value> {
+        match self.read_str()? {
+            Cow::Borrowed(s) => visitor.visit_borrowed_str(s),
+            Cow::Owned(s) => visitor.visit_string(s),
+        }
+    }
+
+    fn current_tag(&self) -> NbtTypeId { self.tag }
+
+    fn enter(&mut self) -> NbtResult<()> {
+        self.depth += 1;
+        if self.depth > SERDE_RECURSE_LIMIT {
+            return Err(NbtError::NbtDepthTooBig(SERDE_RECURSE_LIMIT));
+        }
+        Ok(())
+    }
+}
+
+impl<'de, R: NbtReadTrait> de::Deserializer<'de> for &mut Deserializer<'de, R> {
+    type Error = NbtError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> NbtResult<V::Value> {
+        match self.tag {
+            nbt_consts::TAG_BYTE => visitor.visit_i8(self.reader.read_i8()?),
+            nbt_consts::TAG_SHORT => visitor.visit_i16(R::read_i16(&mut self.reader)?),
+            nbt_consts::TAG_INT => visitor.visit_i32(R::read_i32(&mut self.reader)?),
+            nbt_consts::TAG_LONG => visitor.visit_i64(R::read_i64(&mut self.reader)?),
+            nbt_consts::TAG_FLOAT => visitor.visit_f32(R::read_f32(&mut self.reader)?),
+            nbt_consts::TAG_DOUBLE => visitor.visit_f64(R::read_f64(&mut self.reader)?),
+            nbt_consts::TAG_BYTE_ARRAY => {
+                let len = R::read_checked_len(&mut self.reader, nbt_consts::TAG_BYTE_ARRAY)?;
+                let data = self.take(len)?;
+                visit_array(data.iter().map(|x| *x as i8), visitor)
+            }
+            nbt_consts::TAG_STRING => self.visit_str(visitor),
+            nbt_consts::TAG_LIST => {
+                let list_type = self.reader.read_u8()?;
+                let len = R::read_checked_len(&mut self.reader, nbt_consts::TAG_LIST)?;
+                self.enter()?;
+                let mut access = ListAccess {
+                    de: &mut *self,
+                    list_type,
+                    remaining: len,
+                };
+                let value = visitor.visit_seq(&mut access)?;
+                if access.remaining != 0 {
+                    return Err(de::Error::invalid_length(len, &"完整的 NbtList"));
+                }
+                self.depth -= 1;
+                Ok(value)
+            }
+            nbt_consts::TAG_COMPOUND => {
+                self.enter()?;
+                let mut access = CompoundAccess {
+                    de: &mut *self,
+                    finished: false,
+                };
+                let value = visitor.visit_map(&mut access)?;
+                if !access.finished {
+                    return Err(NbtError::SerdeCustom("NbtCompound 没有读完".to_string()));
+                }
+                self.depth -= 1;
+                Ok(value)
+            }
+            nbt_consts::TAG_INT_ARRAY => visit_array(R::read_i32_array(&mut self.reader)?, visitor),
+            nbt_consts::TAG_LONG_ARRAY => {
+                visit_array(R::read_i64_array(&mut self.reader)?, visitor)
+            }
+            x => Err(NbtError::UnknownType(x, self.reader.cursor)),
+        }
+    }
+
+    fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> NbtResult<V::Value> {
+        expect_tag(nbt_consts::TAG_BYTE, self.tag)?;
+        visitor.visit_bool(self.reader.read_i8()? != 0)
+    }
+    // 无符号的按位转回来
+    fn deserialize_u8<V: Visitor<'de>>(self, visitor: V) -> NbtResult<V::Value> {
+        expect_tag(nbt_consts::TAG_BYTE, self.tag)?;
+        visitor.visit_u8(self.reader.read_u8()?)
+    }
+    fn deserialize_u16<V: Visitor<'de>>(self, visitor: V) -> NbtResult<V::Value> {
+        expect_tag(nbt_consts::TAG_SHORT, self.tag)?;
+        visitor.visit_u16(R::read_i16(&mut self.reader)? as u16)
+    }
+    fn deserialize_u32<V: Visitor<'de>>(self, visitor: V) -> NbtResult<V::Value> {
+        expect_tag(nbt_consts::TAG_INT, self.tag)?;
+        visitor.visit_u32(R::read_i32(&mut self.reader)? as u32)
+    }
+    fn deserialize_u64<V: Visitor<'de>>(self, visitor: V) -> NbtResult<V::Value> {
+        expect_tag(nbt_consts::TAG_LONG, self.tag)?;
+        visitor.visit_u64(R::read_i64(&mut self.reader)? as u64)
+    }
+    fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> NbtResult<V::Value> {
+        expect_tag(nbt_consts::TAG_BYTE_ARRAY, self.tag)?;
+        let len = R::read_checked_len(&mut self.reader, nbt_consts::TAG_BYTE_ARRAY)?;
+        visitor.visit_borrowed_bytes(self.take(len)?)
+    }
+    fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> NbtResult<V::Value> {
+        self.deserialize_bytes(visitor)
+    }
+    /// 能读到就是 `Some`, 缺了的字段 serde 自己会当成 `None`
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> NbtResult<V::Value> {
+        visitor.visit_some(self)
+    }
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> NbtResult<V::Value> {
+        self.deserialize_ignored_any(IgnoredAny)?;
+        visitor.visit_unit()
+    }
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> NbtResult<V::Value> {
+        self.deserialize_unit(visitor)
+    }
+    /// `NbtValue` 需要知道是 Array 还是 List
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> NbtResult<V::Value> {
+        if name != NBT_VALUE_TOKEN {
+            return visitor.visit_newtype_struct(self);
+        }
+        let tag = self.tag;
+        match ArrayVariant::new(tag, &mut *self) {
+            Some(array) => visitor.visit_enum(array),
+            None => self.deserialize_any(visitor),
+        }
+    }
+    /// 字符串是只有名字的 variant, 只有一项的 Compound 是其他的 variant
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> NbtResult<V::Value> {
+        match self.tag {
+            nbt_consts::TAG_STRING => match self.read_str()? {
+                Cow::Borrowed(s) => visitor.visit_enum(s.into_deserializer()),
+                Cow::Owned(s) => visitor.visit_enum(s.into_deserializer()),
+            },
+            nbt_consts::TAG_COMPOUND => {
+                self.enter()?;
+                let value = visitor.visit_enum(EnumAccess { de: &mut *self })?;
+                match self.reader.read_u8()? {
+                    nbt_consts::TAG_END => (),
+                    _ => {
+                        return Err(NbtError::SerdeCustom(
+                            "表示 enum 的 NbtCompound 只能有一项".to_string(),
+                        ))
+                    }
+                }
+                self.depth -= 1;
+                Ok(value)
+            }
+            x => Err(NbtError::IncorrectType(nbt_consts::TAG_COMPOUND, x)),
+        }
+    }
+
+    fn is_human_readable(&self) -> bool { false }
+
+    deserialize_expect! {
+        deserialize_i8 => TAG_BYTE,
+        deserialize_i16 => TAG_SHORT,
+        deserialize_i32 => TAG_INT,
+        deserialize_i64 => TAG_LONG,
+        deserialize_f32 => TAG_FLOAT,
+        deserialize_f64 => TAG_DOUBLE,
+        deserialize_char => TAG_STRING,
+        deserialize_str => TAG_STRING,
+        deserialize_string => TAG_STRING,
+        deserialize_map => TAG_COMPOUND,
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> NbtResult<V::Value> {
+        expect_list(self.tag)?;
+        self.deserialize_any(visitor)
+    }
+    fn deserialize_tuple<V: Visitor<'de>>(self, _len: usize, visitor: V) -> NbtResult<V::Value> {
+        self.deserialize_seq(visitor)
+    }
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> NbtResult<V::Value> {
+        self.deserialize_seq(visitor)
+    }
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> NbtResult<V::Value> {
+        self.deserialize_map(visitor)
+    }
+
+    serde::forward_to_deserialize_any! {
+        i128 u128 identifier ignored_any
+    }
+}
+
+/// 把 Array 当成 seq 交给 visitor
+fn visit_array<'de, V, I>(iter: I, visitor: V) -> NbtResult<V::Value>
+where
+    V: Visitor<'de>,
+    I: IntoIterator,
+    I::Item: IntoDeserializer<'de, NbtError>,
+{
+    let mut seq = SeqDeserializer::new(iter.into_iter());
+    let value = visitor.visit_seq(&mut seq)?;
+    seq.end()?;
+    Ok(value)
+}
+
+struct ListAccess<'a, 'de, R> {
+    de: &'a mut Deserializer<'de, R>,
+    list_type: NbtTypeId,
+    remaining: usize,
+}
+
+impl<'de, R: NbtReadTrait> de::SeqAccess<'de> for ListAccess<'_, 'de, R> {
+    type Error = NbtError;
+
+    fn next_element_seed<T: de::DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> NbtResult<Option<T::Value>> {
+        if self.remaining == 0 {
+            return Ok(None);
+        }
+        self.remaining -= 1;
+        self.de.tag = self.list_type;
+        seed.deserialize(&mut *self.de).map(Some)
+    }
+
+    fn size_hint(&self) -> Option<usize> { Some(self.remaining) }
+}
+
+struct CompoundAccess<'a, 'de, R> {
+    de: &'a mut Deserializer<'de, R>,
+    finished: bool,
+}
+
+impl<'de, R: NbtReadTrait> de::MapAccess<'de> for CompoundAccess<'_, 'de, R> {
+    type Error = NbtError;
+
+    fn next_key_seed<K: de::DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> NbtResult<Option<K::Value>> {
+        if self.finished {
+            return Ok(None);
+        }
+        let tag = self.de.reader.read_u8()?;
+        if tag == nbt_consts::TAG_END {
+            self.finished = true;
+            return Ok(None);
+        }
+        self.de.tag = tag;
+        seed.deserialize(KeyDeserializer(self.de.read_str()?)).map(Some)
+    }
+
+    fn next_value_seed<V: de::DeserializeSeed<'de>>(&mut self, seed: V) -> NbtResult<V::Value> {
+        seed.deserialize(&mut *self.de)
+    }
+}
+
+/// Compound 的 key
+///
+/// 写的时候整数 key 被转成了字符串, 这里再转回去
+pub(crate) struct KeyDeserializer<'de>(pub(crate) Cow<'de, str>);
+
+macro_rules! deserialize_key_number {
+    ($($method:ident => $visit:ident: $ty:ty),* $(,)?) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> NbtResult<V::Value> {
+                match self.0.parse::<$ty>() {
+                    Ok(n) => visitor.$visit(n),
+                    Err(_) => self.deserialize_any(visitor),
+                }
+            }
+        )*
+    };
+}
+
+impl<'de> de::Deserializer<'de> for KeyDeserializer<'de> {
+    type Error = NbtError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> NbtResult<V::Value> {
+        match self.0 {
+            Cow::Borrowed(s) => visitor.visit_borrowed_str(s),
+            Cow::Owned(s) => visitor.visit_string(s),
+        }
+    }
+
+    deserialize_key_number! {
+        deserialize_i8 => visit_i8: i8,
+        deserialize_i16 => visit_i16: i16,
+        deserialize_i32 => visit_i32: i32,
+        deserialize_i64 => visit_i64: i64,
+        deserialize_u8 => visit_u8: u8,
+        deserialize_u16 => visit_u16: u16,
+        deserialize_u32 => visit_u32: u32,
+        deserialize_u64 => visit_u64: u64,
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> NbtResult<V::Value> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> NbtResult<V::Value> {
+        match self.0 {
+            Cow::Borrowed(s) => visitor.visit_enum(s.into_deserializer()),
+            Cow::Owned(s) => visitor.visit_enum(s.into_deserializer()),
+        }
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i128 u128 f32 f64 char str string bytes byte_buf option unit unit_struct
+        seq tuple tuple_struct map struct identifier ignored_any
+    }
+}
+
+struct EnumAccess<'a, 'de, R> {
+    de: &'a mut Deserializer<'de, R>,
+}
+
+impl<'de, R: NbtReadTrait> de::EnumAccess<'de> for EnumAccess<'_, 'de, R> {
+    type Error = NbtError;
+    type Variant = Self;
+
+    fn variant_seed<V: de::DeserializeSeed<'de>>(self, seed: V) -> NbtResult<(V::Value, Self)> {
+        let tag = self.de.reader.read_u8()?;
+        if tag == nbt_consts::TAG_END {
+            return Err(NbtError::SerdeCustom("表示 enum 的 NbtCompound 是空的".to_string()));
+        }
+        self.de.tag = tag;
+        let variant = seed.deserialize(KeyDeserializer(self.de.read_str()?))?;
+        Ok((variant, self))
+    }
+}
+
+impl<'de, R: NbtReadTrait> de::VariantAccess<'de> for EnumAccess<'_, 'de, R> {
+    type Error = NbtError;
+
+    fn unit_variant(self) -> NbtResult<()> { de::Deserialize::deserialize(&mut *self.de) }
+    fn newtype_variant_seed<T: de::DeserializeSeed<'de>>(self, seed: T) -> NbtResult<T::Value> {
+        seed.deserialize(&mut *self.de)
+    }
+    fn tuple_variant<V: Visitor<'de>>(self, _len: usize, visitor: V) -> NbtResult<V::Value> {
+        de::Deserializer::deserialize_seq(&mut *self.de, visitor)
+    }
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> NbtResult<V::Value> {
+        de::Deserializer::deserialize_map(&mut *self.de, visitor)
+    }
+}
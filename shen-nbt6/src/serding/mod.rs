@@ -0,0 +1,140 @@
+//! 直接在 Rust 的值和 NBT 二进制数据之间转换, 中间不经过 [`NbtValue`](crate::NbtValue)
+//!
+//! # 对应关系
+//!
+//! - `bool` -> `NbtByte` (0/1)
+//! - `i8`/`i16`/`i32`/`i64` -> `NbtByte`/`NbtShort`/`NbtInt`/`NbtLong`
+//! - `u8`/`u16`/`u32`/`u64` -> 同样宽度的有符号类型 (按位转换)
+//! - `f32`/`f64` -> `NbtFloat`/`NbtDouble`
+//! - `char`/`String` -> `NbtString`
+//! - `&[u8]` (`serde_bytes`) -> `NbtByteArray`
+//! - `Vec<T>`/元组 -> `NbtList` (元素类型必须一样)
+//! - 想要 `NbtByteArray`/`NbtIntArray`/`NbtLongArray` 的话见 [`array`]
+//! - 结构体/`HashMap` -> `NbtCompound`
+//! - `Option<T>`: `None` 的字段直接不写, 读的时候缺了的字段就是 `None`
+//! - `()` -> 空的 `NbtCompound`
+//! - enum: 只有名字的 variant 是 `NbtString`, 其他的是 `{variant: value}`
+//!
+//! # 例子
+//!
+//! ```rust
+//! use serde::{Deserialize, Serialize};
+//! use shen_nbt6::nbt_version::Java;
+//!
+//! #[derive(Debug, PartialEq, Serialize, Deserialize)]
+//! struct Player {
+//!     name: String,
+//!     health: f32,
+//!     pos: Vec<f64>,
+//!     title: Option<String>,
+//! }
+//!
+//! let player = Player {
+//!     name: "shenjack".to_string(),
+//!     health: 20.0,
+//!     pos: vec![0.5, 64.0, 0.5],
+//!     title: None,
+//! };
+//! let data = shen_nbt6::to_bytes::<Java, _>(&player).unwrap();
+//! let read: Player = shen_nbt6::from_bytes::<Java, _>(&data).unwrap();
+//! assert_eq!(player, read);
+//! ```
+
+use serde::{Deserialize, Serialize};
+
+use crate::traits::{NbtReadTrait, NbtWriteTrait};
+use crate::{nbt_consts, NbtError, NbtResult, NbtTypeId, NbtValue};
+
+/// 强制写成 Array
+pub mod array;
+/// 反序列化
+pub mod de;
+/// 序列化
+pub mod ser;
+/// `NbtValue` 的 serde 实现
+mod value;
+/// 从 `NbtValue` 反序列化
+pub mod value_de;
+/// 序列化成 `NbtValue`
+pub mod value_ser;
+
+pub use de::Deserializer;
+pub use ser::Serializer;
+pub use value_de::ValueDeserializer;
+pub use value_ser::ValueSerializer;
+
+/// 把 `value` 写成 `W` 格式的 NBT 数据
+///
+/// 根节点的名字是空字符串 (如果这个格式的根节点有名字的话)
+pub fn to_bytes<W: NbtWriteTrait, T: Serialize + ?Sized>(value: &T) -> NbtResult<Vec<u8>> {
+    // 先当成 Compound 写名字, 是 List 的话再把名字去掉
+    let mut buff = vec![nbt_consts::TAG_END];
+    W::write_root_name(&mut buff, None)?;
+    let name_end = buff.len();
+    match value.serialize(Serializer::<W>::new(&mut buff))? {
+        Some(nbt_consts::TAG_COMPOUND) => buff[0] = nbt_consts::TAG_COMPOUND,
+        Some(nbt_consts::TAG_LIST) if W::LIST_ROOT => {
+            buff[0] = nbt_consts::TAG_LIST;
+            buff.drain(1..name_end);
+        }
+        Some(tag) => return Err(NbtError::WrongRootType(tag)),
+        None => return Err(NbtError::WrongRootType(nbt_consts::TAG_END)),
+    }
+    Ok(buff)
+}
+
+/// 从 `R` 格式的 NBT 数据里读出 `T`
+///
+/// 根节点的名字会被忽略
+pub fn from_bytes<'de, R: NbtReadTrait, T: Deserialize<'de>>(data: &'de [u8]) -> NbtResult<T> {
+    let mut deserializer = Deserializer::<R>::from_bytes(data)?;
+    T::deserialize(&mut deserializer)
+}
+
+/// 检查类型是不是想要的那个
+pub(crate) fn expect_tag(expect: NbtTypeId, got: NbtTypeId) -> NbtResult<()> {
+    match expect == got {
+        true => Ok(()),
+        false => Err(NbtError::IncorrectType(expect, got)),
+    }
+}
+
+/// seq 可以是 List 也可以是各种 Array
+pub(crate) fn expect_list(got: NbtTypeId) -> NbtResult<()> {
+    match got {
+        nbt_consts::TAG_LIST
+        | nbt_consts::TAG_BYTE_ARRAY
+        | nbt_consts::TAG_INT_ARRAY
+        | nbt_consts::TAG_LONG_ARRAY => Ok(()),
+        _ => Err(NbtError::IncorrectType(nbt_consts::TAG_LIST, got)),
+    }
+}
+
+/// 把 `value` 转成 [`NbtValue`]
+///
+/// Compound 的名字都是 `None`
+pub fn to_value<T: Serialize + ?Sized>(value: &T) -> NbtResult<NbtValue> {
+    value
+        .serialize(ValueSerializer)?
+        .ok_or_else(|| NbtError::SerdeCustom("None 没法转成 NbtValue".to_string()))
+}
+
+/// 从 [`NbtValue`] 里读出 `T`
+///
+/// 比如从一个已经读出来的大文件里只取一部分
+///
+/// 类型对不上的时候返回 [`NbtError::IncorrectType`]
+pub fn from_value<'de, T: Deserialize<'de>>(value: &'de NbtValue) -> NbtResult<T> {
+    T::deserialize(ValueDeserializer::new(value))
+}
+
+impl serde::ser::Error for NbtError {
+    fn custom<T: std::fmt::Display>(msg: T) -> Self { NbtError::SerdeCustom(msg.to_string()) }
+}
+
+impl serde::de::Error for NbtError {
+    fn custom<T: std::fmt::Display>(msg: T) -> Self { NbtError::SerdeCustom(msg.to_string()) }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,536 @@
+use std::borrow::Cow;
+use std::marker::PhantomData;
+
+use serde::ser::{self, Serialize};
+use simd_cesu8::mutf8;
+
+use super::array::{serialize_array, token_tag};
+use crate::traits::NbtWriteTrait;
+use crate::{nbt_consts, NbtError, NbtResult, NbtTypeId};
+
+/// 写入一个字符串 (长度 + 编码之后的数据)
+///
+/// 按照 `W` 的要求编码成 m-utf8 或者 utf-8
+pub(crate) fn write_str<W: NbtWriteTrait>(buff: &mut Vec<u8>, data: &str) -> NbtResult<()> {
+    let data = match W::MUTF8_STRING {
+        true => mutf8::encode(data),
+        false => Cow::Borrowed(data.as_bytes()),
+    };
+    NbtError::check_len(data.len(), W::MAX_STR_LEN)?;
+    W::write_str_len(buff, data.len());
+    buff.extend_from_slice(&data);
+    Ok(())
+}
+
+/// 写入 Compound 里的一项 (tag + 名字 + 值)
+///
+/// 先写一个占位的 tag, 值写完了再补上
+///
+/// 值是 `None` 的时候整项都不写
+fn write_entry<W: NbtWriteTrait, T: Serialize + ?Sized>(
+    buff: &mut Vec<u8>,
+    key: &str,
+    value: &T,
+) -> NbtResult<()> {
+    let start = buff.len();
+    buff.push(nbt_consts::TAG_END);
+    write_str::<W>(buff, key)
+        .and_then(|_| value.serialize(Serializer::<W>::new(buff)))
+        .map(|tag| match tag {
+            Some(tag) => buff[start] = tag,
+            None => buff.truncate(start),
+        })
+        .map_err(|e| e.in_compound(key))
+}
+
+/// 在 `{variant: ...}` 里面的时候, 错误的位置前面要加上 variant 的名字
+fn in_variant(e: NbtError, variant: Option<&str>) -> NbtError {
+    match variant {
+        Some(variant) => e.in_compound(variant),
+        None => e,
+    }
+}
+
+/// 把 Rust 的值直接写成 NBT 的 payload (不包括 tag)
+///
+/// `Ok` 是写入的值的类型, `None` 表示什么都没写 (`Option::None`)
+pub struct Serializer<'a, W> {
+    buff: &'a mut Vec<u8>,
+    _version: PhantomData<W>,
+}
+
+impl<'a, W: NbtWriteTrait> Serializer<'a, W> {
+    pub fn new(buff: &'a mut Vec<u8>) -> Self {
+        Self {
+            buff,
+            _version: PhantomData,
+        }
+    }
+}
+
+impl<'a, W: NbtWriteTrait> ser::Serializer for Serializer<'a, W> {
+    type Ok = Option<NbtTypeId>;
+    type Error = NbtError;
+    type SerializeSeq = ListSerializer<'a, W>;
+    type SerializeTuple = ListSerializer<'a, W>;
+    type SerializeTupleStruct = ListSerializer<'a, W>;
+    type SerializeTupleVariant = ListSerializer<'a, W>;
+    type SerializeMap = CompoundSerializer<'a, W>;
+    type SerializeStruct = CompoundSerializer<'a, W>;
+    type SerializeStructVariant = CompoundSerializer<'a, W>;
+
+    fn serialize_bool(self, v: bool) -> NbtResult<Self::Ok> { self.serialize_i8(v as i8) }
+    fn serialize_i8(self, v: i8) -> NbtResult<Self::Ok> {
+        self.buff.push(v as u8);
+        Ok(Some(nbt_consts::TAG_BYTE))
+    }
+    fn serialize_i16(self, v: i16) -> NbtResult<Self::Ok> {
+        W::write_i16(self.buff, v);
+        Ok(Some(nbt_consts::TAG_SHORT))
+    }
+    fn serialize_i32(self, v: i32) -> NbtResult<Self::Ok> {
+        W::write_i32(self.buff, v);
+        Ok(Some(nbt_consts::TAG_INT))
+    }
+    fn serialize_i64(self, v: i64) -> NbtResult<Self::Ok> {
+        W::write_i64(self.buff, v);
+        Ok(Some(nbt_consts::TAG_LONG))
+    }
+    // NBT 没有无符号类型, 直接按位转成同样宽度的有符号类型
+    fn serialize_u8(self, v: u8) -> NbtResult<Self::Ok> { self.serialize_i8(v as i8) }
+    fn serialize_u16(self, v: u16) -> NbtResult<Self::Ok> { self.serialize_i16(v as i16) }
+    fn serialize_u32(self, v: u32) -> NbtResult<Self::Ok> { self.serialize_i32(v as i32) }
+    fn serialize_u64(self, v: u64) -> NbtResult<Self::Ok> { self.serialize_i64(v as i64) }
+    fn serialize_f32(self, v: f32) -> NbtResult<Self::Ok> {
+        W::write_f32(self.buff, v);
+        Ok(Some(nbt_consts::TAG_FLOAT))
+    }
+    fn serialize_f64(self, v: f64) -> NbtResult<Self::Ok> {
+        W::write_f64(self.buff, v);
+        Ok(Some(nbt_consts::TAG_DOUBLE))
+    }
+    fn serialize_char(self, v: char) -> NbtResult<Self::Ok> {
+        self.serialize_str(v.encode_utf8(&mut [0; 4]))
+    }
+    fn serialize_str(self, v: &str) -> NbtResult<Self::Ok> {
+        write_str::<W>(self.buff, v)?;
+        Ok(Some(nbt_consts::TAG_STRING))
+    }
+    fn serialize_bytes(self, v: &[u8]) -> NbtResult<Self::Ok> {
+        NbtError::check_len(v.len(), W::MAX_LEN)?;
+        W::write_len(self.buff, v.len());
+        self.buff.extend_from_slice(v);
+        Ok(Some(nbt_consts::TAG_BYTE_ARRAY))
+    }
+    fn serialize_none(self) -> NbtResult<Self::Ok> { Ok(None) }
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> NbtResult<Self::Ok> {
+        value.serialize(self)
+    }
+    /// `()` 写成一个空的 Compound
+    fn serialize_unit(self) -> NbtResult<Self::Ok> {
+        self.buff.push(nbt_consts::TAG_END);
+        Ok(Some(nbt_consts::TAG_COMPOUND))
+    }
+    fn serialize_unit_struct(self, _name: &'static str) -> NbtResult<Self::Ok> {
+        self.serialize_unit()
+    }
+    /// 只有名字的 variant 写成一个字符串
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> NbtResult<Self::Ok> {
+        self.serialize_str(variant)
+    }
+    /// 被标记成 Array 的值写成对应的 Array
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        name: &'static str,
+        value: &T,
+    ) -> NbtResult<Self::Ok> {
+        match token_tag(name) {
+            Some(tag) => match serialize_array(tag, value)? {
+                Some(array) => {
+                    W::write_payload(self.buff, &array)?;
+                    Ok(Some(tag))
+                }
+                None => Ok(None),
+            },
+            None => value.serialize(self),
+        }
+    }
+    /// 其他的 variant 都写成 `{variant: value}`
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> NbtResult<Self::Ok> {
+        write_entry::<W, T>(self.buff, variant, value)?;
+        self.buff.push(nbt_consts::TAG_END);
+        Ok(Some(nbt_consts::TAG_COMPOUND))
+    }
+    fn serialize_seq(self, len: Option<usize>) -> NbtResult<Self::SerializeSeq> {
+        ListSerializer::new(self.buff, len, None)
+    }
+    fn serialize_tuple(self, len: usize) -> NbtResult<Self::SerializeTuple> {
+        self.serialize_seq(Some(len))
+    }
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> NbtResult<Self::SerializeTupleStruct> {
+        self.serialize_seq(Some(len))
+    }
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> NbtResult<Self::SerializeTupleVariant> {
+        self.buff.push(nbt_consts::TAG_LIST);
+        write_str::<W>(self.buff, variant)?;
+        ListSerializer::new(self.buff, Some(len), Some(variant))
+    }
+    fn serialize_map(self, _len: Option<usize>) -> NbtResult<Self::SerializeMap> {
+        Ok(CompoundSerializer::new(self.buff, None))
+    }
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> NbtResult<Self::SerializeStruct> {
+        Ok(CompoundSerializer::new(self.buff, None))
+    }
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> NbtResult<Self::SerializeStructVariant> {
+        self.buff.push(nbt_consts::TAG_COMPOUND);
+        write_str::<W>(self.buff, variant)?;
+        Ok(CompoundSerializer::new(self.buff, Some(variant)))
+    }
+
+    fn is_human_readable(&self) -> bool { false }
+}
+
+/// 写一个 NbtList
+///
+/// 长度已知的时候直接写, 不知道长度的时候先写到一个临时的 buffer 里
+pub struct ListSerializer<'a, W> {
+    buff: &'a mut Vec<u8>,
+    /// 长度未知时的临时 buffer
+    pending: Option<Vec<u8>>,
+    /// 元素类型的位置
+    type_pos: usize,
+    /// 预先给出的长度
+    len: Option<usize>,
+    count: usize,
+    list_type: NbtTypeId,
+    /// 是 `{variant: [...]}` 的时候是 variant 的名字
+    variant: Option<&'static str>,
+    _version: PhantomData<W>,
+}
+
+impl<'a, W: NbtWriteTrait> ListSerializer<'a, W> {
+    fn new(
+        buff: &'a mut Vec<u8>,
+        len: Option<usize>,
+        variant: Option<&'static str>,
+    ) -> NbtResult<Self> {
+        let type_pos = buff.len();
+        buff.push(nbt_consts::TAG_END);
+        let pending = match len {
+            Some(len) => {
+                NbtError::check_len(len, W::MAX_LEN).map_err(|e| in_variant(e, variant))?;
+                W::write_len(buff, len);
+                None
+            }
+            None => Some(Vec::new()),
+        };
+        Ok(Self {
+            buff,
+            pending,
+            type_pos,
+            len,
+            count: 0,
+            list_type: nbt_consts::TAG_END,
+            variant,
+            _version: PhantomData,
+        })
+    }
+
+    fn push<T: Serialize + ?Sized>(&mut self, value: &T) -> NbtResult<()> {
+        let target = match self.pending.as_mut() {
+            Some(pending) => pending,
+            None => &mut *self.buff,
+        };
+        let tag = value
+            .serialize(Serializer::<W>::new(target))
+            .map_err(|e| in_variant(e.in_list(self.count), self.variant))?
+            .ok_or_else(|| NbtError::SerdeCustom("NbtList 里不能有 None".to_string()))?;
+        if self.count == 0 {
+            self.list_type = tag;
+            self.buff[self.type_pos] = tag;
+        } else if tag != self.list_type {
+            return Err(NbtError::ListTypeNotSame(vec![self.list_type, tag]));
+        }
+        self.count += 1;
+        Ok(())
+    }
+
+    fn finish(self) -> NbtResult<Option<NbtTypeId>> {
+        match (self.len, self.pending) {
+            (Some(len), _) if len != self.count => {
+                return Err(NbtError::SerdeCustom(format!(
+                    "NbtList 长度不对: 预期 {}, 实际 {}",
+                    len, self.count
+                )));
+            }
+            (_, Some(pending)) => {
+                NbtError::check_len(self.count, W::MAX_LEN)?;
+                W::write_len(self.buff, self.count);
+                self.buff.extend_from_slice(&pending);
+            }
+            _ => (),
+        }
+        if self.variant.is_some() {
+            self.buff.push(nbt_consts::TAG_END);
+            Ok(Some(nbt_consts::TAG_COMPOUND))
+        } else {
+            Ok(Some(nbt_consts::TAG_LIST))
+        }
+    }
+}
+
+impl<W: NbtWriteTrait> ser::SerializeSeq for ListSerializer<'_, W> {
+    type Ok = Option<NbtTypeId>;
+    type Error = NbtError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> NbtResult<()> {
+        self.push(value)
+    }
+    fn end(self) -> NbtResult<Self::Ok> { self.finish() }
+}
+
+impl<W: NbtWriteTrait> ser::SerializeTuple for ListSerializer<'_, W> {
+    type Ok = Option<NbtTypeId>;
+    type Error = NbtError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> NbtResult<()> {
+        self.push(value)
+    }
+    fn end(self) -> NbtResult<Self::Ok> { self.finish() }
+}
+
+impl<W: NbtWriteTrait> ser::SerializeTupleStruct for ListSerializer<'_, W> {
+    type Ok = Option<NbtTypeId>;
+    type Error = NbtError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> NbtResult<()> {
+        self.push(value)
+    }
+    fn end(self) -> NbtResult<Self::Ok> { self.finish() }
+}
+
+impl<W: NbtWriteTrait> ser::SerializeTupleVariant for ListSerializer<'_, W> {
+    type Ok = Option<NbtTypeId>;
+    type Error = NbtError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> NbtResult<()> {
+        self.push(value)
+    }
+    fn end(self) -> NbtResult<Self::Ok> { self.finish() }
+}
+
+/// 写一个 NbtCompound
+pub struct CompoundSerializer<'a, W> {
+    buff: &'a mut Vec<u8>,
+    /// map 的 key, 等着 value 来
+    key: Option<String>,
+    /// 是 `{variant: {...}}` 的时候是 variant 的名字
+    variant: Option<&'static str>,
+    _version: PhantomData<W>,
+}
+
+impl<'a, W: NbtWriteTrait> CompoundSerializer<'a, W> {
+    fn new(buff: &'a mut Vec<u8>, variant: Option<&'static str>) -> Self {
+        Self {
+            buff,
+            key: None,
+            variant,
+            _version: PhantomData,
+        }
+    }
+
+    fn finish(self) -> NbtResult<Option<NbtTypeId>> {
+        self.buff.push(nbt_consts::TAG_END);
+        if self.variant.is_some() {
+            self.buff.push(nbt_consts::TAG_END);
+        }
+        Ok(Some(nbt_consts::TAG_COMPOUND))
+    }
+}
+
+impl<W: NbtWriteTrait> ser::SerializeMap for CompoundSerializer<'_, W> {
+    type Ok = Option<NbtTypeId>;
+    type Error = NbtError;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> NbtResult<()> {
+        self.key = Some(key.serialize(KeySerializer)?);
+        Ok(())
+    }
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> NbtResult<()> {
+        let key = self
+            .key
+            .take()
+            .ok_or_else(|| NbtError::SerdeCustom("没有 key 的 value".to_string()))?;
+        write_entry::<W, T>(self.buff, &key, value).map_err(|e| in_variant(e, self.variant))
+    }
+    fn end(self) -> NbtResult<Self::Ok> { self.finish() }
+}
+
+impl<W: NbtWriteTrait> ser::SerializeStruct for CompoundSerializer<'_, W> {
+    type Ok = Option<NbtTypeId>;
+    type Error = NbtError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> NbtResult<()> {
+        write_entry::<W, T>(self.buff, key, value).map_err(|e| in_variant(e, self.variant))
+    }
+    fn end(self) -> NbtResult<Self::Ok> { self.finish() }
+}
+
+impl<W: NbtWriteTrait> ser::SerializeStructVariant for CompoundSerializer<'_, W> {
+    type Ok = Option<NbtTypeId>;
+    type Error = NbtError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> NbtResult<()> {
+        write_entry::<W, T>(self.buff, key, value).map_err(|e| in_variant(e, self.variant))
+    }
+    fn end(self) -> NbtResult<Self::Ok> { self.finish() }
+}
+
+/// Compound 的 key 只能是字符串
+///
+/// 整数和 char 也可以, 会转成字符串
+pub(crate) struct KeySerializer;
+
+fn key_must_be_string() -> NbtError {
+    NbtError::SerdeCustom("NbtCompound 的 key 只能是字符串/整数".to_string())
+}
+
+impl ser::Serializer for KeySerializer {
+    type Ok = String;
+    type Error = NbtError;
+    type SerializeSeq = ser::Impossible<String, NbtError>;
+    type SerializeTuple = ser::Impossible<String, NbtError>;
+    type SerializeTupleStruct = ser::Impossible<String, NbtError>;
+    type SerializeTupleVariant = ser::Impossible<String, NbtError>;
+    type SerializeMap = ser::Impossible<String, NbtError>;
+    type SerializeStruct = ser::Impossible<String, NbtError>;
+    type SerializeStructVariant = ser::Impossible<String, NbtError>;
+
+    fn serialize_str(self, v: &str) -> NbtResult<String> { Ok(v.to_string()) }
+    fn serialize_char(self, v: char) -> NbtResult<String> { Ok(v.to_string()) }
+    fn serialize_i8(self, v: i8) -> NbtResult<String> { Ok(v.to_string()) }
+    fn serialize_i16(self, v: i16) -> NbtResult<String> { Ok(v.to_string()) }
+    fn serialize_i32(self, v: i32) -> NbtResult<String> { Ok(v.to_string()) }
+    fn serialize_i64(self, v: i64) -> NbtResult<String> { Ok(v.to_string()) }
+    fn serialize_u8(self, v: u8) -> NbtResult<String> { Ok(v.to_string()) }
+    fn serialize_u16(self, v: u16) -> NbtResult<String> { Ok(v.to_string()) }
+    fn serialize_u32(self, v: u32) -> NbtResult<String> { Ok(v.to_string()) }
+    fn serialize_u64(self, v: u64) -> NbtResult<String> { Ok(v.to_string()) }
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> NbtResult<String> {
+        Ok(variant.to_string())
+    }
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> NbtResult<String> {
+        value.serialize(self)
+    }
+
+    fn serialize_bool(self, _v: bool) -> NbtResult<String> { Err(key_must_be_string()) }
+    fn serialize_f32(self, _v: f32) -> NbtResult<String> { Err(key_must_be_string()) }
+    fn serialize_f64(self, _v: f64) -> NbtResult<String> { Err(key_must_be_string()) }
+    fn serialize_bytes(self, _v: &[u8]) -> NbtResult<String> { Err(key_must_be_string()) }
+    fn serialize_none(self) -> NbtResult<String> { Err(key_must_be_string()) }
+    fn serialize_some<T: Serialize + ?Sized>(self, _value: &T) -> NbtResult<String> {
+        Err(key_must_be_string())
+    }
+    fn serialize_unit(self) -> NbtResult<String> { Err(key_must_be_string()) }
+    fn serialize_unit_struct(self, _name: &'static str) -> NbtResult<String> {
+        Err(key_must_be_string())
+    }
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> NbtResult<String> {
+        Err(key_must_be_string())
+    }
+    fn serialize_seq(self, _len: Option<usize>) -> NbtResult<Self::SerializeSeq> {
+        Err(key_must_be_string())
+    }
+    fn serialize_tuple(self, _len: usize) -> NbtResult<Self::SerializeTuple> {
+        Err(key_must_be_string())
+    }
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> NbtResult<Self::SerializeTupleStruct> {
+        Err(key_must_be_string())
+    }
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> NbtResult<Self::SerializeTupleVariant> {
+        Err(key_must_be_string())
+    }
+    fn serialize_map(self, _len: Option<usize>) -> NbtResult<Self::SerializeMap> {
+        Err(key_must_be_string())
+    }
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> NbtResult<Self::SerializeStruct> {
+        Err(key_must_be_string())
+    }
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> NbtResult<Self::SerializeStructVariant> {
+        Err(key_must_be_string())
+    }
+}
@@ -0,0 +1,430 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::array::{self, IntArray};
+use crate::nbt_version::{BedrockDisk, BedrockNetVarInt, Java, JavaNetAfter1_20_2};
+use crate::tests::{BIG_TEST_DATA, COMPLEX_PLR_DATA, HELLO_WORLD_DATA};
+use crate::traits::{NbtReadTrait, NbtWriteTrait};
+use crate::{from_bytes, from_value, nbt_consts, to_bytes, to_value, NbtError, NbtValue};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct HelloWorld {
+    name: String,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+enum Mode {
+    Survival,
+    Level(i32),
+    Pos(i32, i32),
+    Named { name: String },
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Inner {
+    a: u8,
+    b: Vec<String>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Everything {
+    flag: bool,
+    byte: i8,
+    short: i16,
+    int: i32,
+    long: i64,
+    unsigned: u64,
+    float: f32,
+    double: f64,
+    letter: char,
+    text: String,
+    list: Vec<i32>,
+    nested_list: Vec<Vec<i16>>,
+    empty_list: Vec<i64>,
+    tuple: (i32, i32, i32),
+    inner: Inner,
+    inners: Vec<Inner>,
+    some: Option<i32>,
+    none: Option<i32>,
+    map: HashMap<String, i32>,
+    int_keys: HashMap<u8, String>,
+    modes: Vec<Mode>,
+    unit: (),
+}
+
+fn everything() -> Everything {
+    Everything {
+        flag: true,
+        byte: -1,
+        short: i16::MIN,
+        int: 114514,
+        long: -1919810,
+        unsigned: u64::MAX,
+        float: 1.5,
+        double: -0.25,
+        letter: '喵',
+        text: "你好\0世界 😀".to_string(),
+        list: vec![1, -2, 3],
+        nested_list: vec![vec![1, 2], vec![], vec![3]],
+        empty_list: vec![],
+        tuple: (1, 2, 3),
+        inner: Inner {
+            a: 255,
+            b: vec!["x".to_string(), "y".to_string()],
+        },
+        inners: vec![
+            Inner { a: 1, b: vec![] },
+            Inner {
+                a: 2,
+                b: vec!["z".to_string()],
+            },
+        ],
+        some: Some(7),
+        none: None,
+        map: HashMap::from([("a".to_string(), 1), ("b".to_string(), 2)]),
+        int_keys: HashMap::from([(1, "one".to_string()), (2, "two".to_string())]),
+        modes: vec![Mode::Level(3), Mode::Pos(1, 2)],
+        unit: (),
+    }
+}
+
+fn round_trip<T: NbtWriteTrait + NbtReadTrait>() {
+    let value = everything();
+    let data = to_bytes::<T, _>(&value).unwrap();
+    let read: Everything = from_bytes::<T, _>(&data).unwrap();
+    assert_eq!(value, read);
+    // 跟 NbtValue 读出来的结构应该能对上
+    let nbt = NbtValue::from_binary::<T>(&data).unwrap();
+    let NbtValue::Compound(_, items) = nbt else {
+        panic!("根节点不是 Compound")
+    };
+    let get =
+        |key: &str| items.iter().find(|(k, _)| k.as_bytes() == key.as_bytes()).map(|(_, v)| v);
+    assert_eq!(get("flag"), Some(&NbtValue::Byte(1)));
+    assert_eq!(get("unsigned"), Some(&NbtValue::Long(-1)));
+    assert_eq!(get("none"), None);
+    assert_eq!(get("empty_list"), Some(&NbtValue::List(vec![])));
+    assert_eq!(get("unit"), Some(&NbtValue::Compound(None, vec![])));
+    assert_eq!(
+        get("modes"),
+        Some(&NbtValue::List(vec![
+            NbtValue::Compound(None, vec![("Level".into(), NbtValue::Int(3))]),
+            NbtValue::Compound(
+                None,
+                vec![("Pos".into(), NbtValue::List(vec![NbtValue::Int(1), NbtValue::Int(2)]))]
+            ),
+        ]))
+    );
+}
+
+#[test]
+fn round_trip_all_versions() {
+    round_trip::<Java>();
+    round_trip::<JavaNetAfter1_20_2>();
+    round_trip::<BedrockDisk>();
+    round_trip::<BedrockNetVarInt>();
+}
+
+#[test]
+fn enum_variants() {
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Modes {
+        a: Mode,
+        b: Mode,
+        c: Mode,
+        d: Mode,
+    }
+    let value = Modes {
+        a: Mode::Survival,
+        b: Mode::Level(-5),
+        c: Mode::Pos(1, 2),
+        d: Mode::Named {
+            name: "creative".to_string(),
+        },
+    };
+    let data = to_bytes::<Java, _>(&value).unwrap();
+    assert_eq!(from_bytes::<Java, Modes>(&data).unwrap(), value);
+}
+
+#[test]
+fn hello_world() {
+    // 根节点的名字会被忽略
+    let value: HelloWorld = from_bytes::<Java, _>(&HELLO_WORLD_DATA).unwrap();
+    assert_eq!(value.name, "Bananrama");
+    // 写出来的根节点名字是空的
+    let expect =
+        NbtValue::Compound(None, vec![("name".into(), NbtValue::String("Bananrama".into()))]);
+    assert_eq!(to_bytes::<Java, _>(&value).unwrap(), expect.to_binary::<Java>().unwrap());
+}
+
+#[test]
+fn borrowed_str() {
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Borrowed<'a> {
+        name: &'a str,
+    }
+    let value: Borrowed = from_bytes::<Java, _>(&HELLO_WORLD_DATA).unwrap();
+    assert_eq!(value.name, "Bananrama");
+}
+
+#[test]
+fn string_encoding() {
+    let value = HelloWorld {
+        name: "中文 😀\0".to_string(),
+    };
+    let expect = NbtValue::Compound(
+        None,
+        vec![("name".into(), NbtValue::String(value.name.as_str().into()))],
+    );
+    fn check<V: NbtReadTrait + NbtWriteTrait>(value: &HelloWorld, expect: &NbtValue) {
+        let data = to_bytes::<V, _>(value).unwrap();
+        assert_eq!(data, expect.to_binary::<V>().unwrap());
+        assert_eq!(&from_bytes::<V, HelloWorld>(&data).unwrap(), value);
+    }
+    check::<Java>(&value, &expect);
+    check::<JavaNetAfter1_20_2>(&value, &expect);
+    check::<BedrockDisk>(&value, &expect);
+    check::<BedrockNetVarInt>(&value, &expect);
+}
+
+#[test]
+fn nbt_value_serialize() {
+    for data in [&BIG_TEST_DATA[..], &COMPLEX_PLR_DATA[..]] {
+        let value = NbtValue::from_binary::<Java>(data).unwrap();
+        let NbtValue::Compound(_, items) = &value else {
+            panic!("根节点不是 Compound")
+        };
+        let expect = NbtValue::Compound(None, items.clone());
+        // Array 写回去还是 Array
+        let data = to_bytes::<Java, _>(&value).unwrap();
+        assert_eq!(
+            NbtValue::from_binary::<Java>(&data).unwrap(),
+            NbtValue::Compound(Some("".into()), items.clone())
+        );
+        assert_eq!(from_bytes::<Java, NbtValue>(&data).unwrap(), expect);
+        assert_eq!(to_value(&value).unwrap(), expect);
+        assert_eq!(from_value::<NbtValue>(&value).unwrap(), expect);
+    }
+}
+
+#[test]
+fn force_array() {
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Arrays {
+        #[serde(with = "array::byte_array")]
+        bytes: Vec<i8>,
+        #[serde(with = "array::int_array")]
+        uuid: [i32; 4],
+        #[serde(with = "array::long_array")]
+        longs: Vec<i64>,
+        #[serde(with = "array::int_array")]
+        empty: Vec<u32>,
+        wrapped: IntArray,
+        #[serde(with = "array::long_array", skip_serializing_if = "Option::is_none", default)]
+        none: Option<Vec<i64>>,
+    }
+    fn check<T: NbtWriteTrait + NbtReadTrait>() {
+        let value = Arrays {
+            bytes: vec![1, -1],
+            uuid: [1, 2, -3, i32::MAX],
+            longs: vec![i64::MIN, 0],
+            empty: vec![],
+            wrapped: IntArray(vec![7]),
+            none: None,
+        };
+        let data = to_bytes::<T, _>(&value).unwrap();
+        assert_eq!(from_bytes::<T, Arrays>(&data).unwrap(), value);
+        let expect = NbtValue::Compound(
+            None,
+            vec![
+                ("bytes".into(), NbtValue::ByteArray(vec![1, -1])),
+                ("uuid".into(), NbtValue::IntArray(vec![1, 2, -3, i32::MAX])),
+                ("longs".into(), NbtValue::LongArray(vec![i64::MIN, 0])),
+                ("empty".into(), NbtValue::IntArray(vec![])),
+                ("wrapped".into(), NbtValue::IntArray(vec![7])),
+            ],
+        );
+        let NbtValue::Compound(_, items) = NbtValue::from_binary::<T>(&data).unwrap() else {
+            panic!("根节点不是 Compound")
+        };
+        assert_eq!(NbtValue::Compound(None, items), expect);
+        assert_eq!(to_value(&value).unwrap(), expect);
+        assert_eq!(from_value::<Arrays>(&expect).unwrap(), value);
+    }
+    check::<Java>();
+    check::<JavaNetAfter1_20_2>();
+    check::<BedrockDisk>();
+    check::<BedrockNetVarInt>();
+}
+
+#[test]
+fn array_accepts_list() {
+    #[derive(Debug, PartialEq, Serialize)]
+    struct Plain {
+        uuid: Vec<i32>,
+    }
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Forced {
+        #[serde(with = "array::int_array")]
+        uuid: Vec<i32>,
+    }
+    let data = to_bytes::<Java, _>(&Plain { uuid: vec![1, 2] }).unwrap();
+    assert_eq!(from_bytes::<Java, Forced>(&data).unwrap(), Forced { uuid: vec![1, 2] });
+    // 元素类型不对
+    #[derive(Serialize)]
+    struct Wrong {
+        #[serde(with = "array::int_array")]
+        uuid: Vec<String>,
+    }
+    let wrong = Wrong {
+        uuid: vec!["a".to_string()],
+    };
+    assert_eq!(
+        to_bytes::<Java, _>(&wrong),
+        Err(NbtError::IncorrectType(nbt_consts::TAG_INT, nbt_consts::TAG_STRING))
+    );
+}
+
+#[test]
+fn errors() {
+    // 根节点只能是 Compound (基岩版可以是 List)
+    assert_eq!(to_bytes::<Java, _>(&1_i32), Err(NbtError::WrongRootType(nbt_consts::TAG_INT)));
+    assert_eq!(
+        to_bytes::<Java, _>(&vec![1_i32]),
+        Err(NbtError::WrongRootType(nbt_consts::TAG_LIST))
+    );
+    let data = to_bytes::<BedrockDisk, _>(&vec![1_i32, 2]).unwrap();
+    assert_eq!(
+        data,
+        NbtValue::List(vec![NbtValue::Int(1), NbtValue::Int(2)])
+            .to_binary::<BedrockDisk>()
+            .unwrap()
+    );
+    assert_eq!(from_bytes::<BedrockDisk, Vec<i32>>(&data).unwrap(), vec![1, 2]);
+    // List 里的类型必须一样, 只有名字的 variant 跟其他 variant 不能放在一起
+    let modes = HashMap::from([("modes", vec![Mode::Survival, Mode::Level(1)])]);
+    assert_eq!(
+        to_bytes::<Java, _>(&modes),
+        Err(NbtError::ListTypeNotSame(vec![
+            nbt_consts::TAG_STRING,
+            nbt_consts::TAG_COMPOUND
+        ]))
+    );
+    // List 里不能有 None
+    let list = HashMap::from([("list", vec![Some(1), None])]);
+    assert!(matches!(to_bytes::<Java, _>(&list), Err(NbtError::SerdeCustom(_))));
+    // 类型对不上
+    assert_eq!(
+        from_bytes::<Java, HashMap<String, i32>>(&HELLO_WORLD_DATA),
+        Err(NbtError::IncorrectType(nbt_consts::TAG_INT, nbt_consts::TAG_STRING))
+    );
+    // 数据不完整
+    assert!(matches!(
+        from_bytes::<Java, HelloWorld>(&HELLO_WORLD_DATA[..20]),
+        Err(NbtError::CursorOverflow(..))
+    ));
+}
+
+#[test]
+fn value_round_trip() {
+    let value = everything();
+    let nbt = to_value(&value).unwrap();
+    assert_eq!(from_value::<Everything>(&nbt).unwrap(), value);
+    // 再写成二进制跟直接写的一样
+    let data = to_bytes::<Java, _>(&value).unwrap();
+    assert_eq!(to_bytes::<Java, _>(&nbt).unwrap(), data);
+    // 不是 Compound 也可以
+    assert_eq!(to_value(&Mode::Survival).unwrap(), NbtValue::String("Survival".into()));
+    assert_eq!(
+        from_value::<Mode>(&to_value(&Mode::Pos(1, 2)).unwrap()).unwrap(),
+        Mode::Pos(1, 2)
+    );
+    assert!(to_value(&None::<i32>).is_err());
+}
+
+#[test]
+fn value_subtree() {
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Food {
+        name: String,
+        value: f32,
+    }
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Nested {
+        egg: Food,
+        ham: Food,
+    }
+    let value = NbtValue::from_binary::<Java>(&BIG_TEST_DATA).unwrap();
+    let NbtValue::Compound(_, items) = &value else {
+        panic!("根节点不是 Compound")
+    };
+    let (_, nested) = items.iter().find(|(k, _)| k.as_bytes() == b"nested compound test").unwrap();
+    let nested: Nested = from_value(nested).unwrap();
+    assert_eq!(
+        nested.egg,
+        Food {
+            name: "Eggbert".to_string(),
+            value: 0.5
+        }
+    );
+    assert_eq!(nested.ham.name, "Hampus");
+}
+
+#[test]
+fn value_incorrect_type() {
+    #[derive(Debug, Deserialize)]
+    struct Wrong {
+        #[allow(unused)]
+        name: i32,
+    }
+    let value = NbtValue::from_binary::<Java>(&HELLO_WORLD_DATA).unwrap();
+    assert_eq!(
+        from_value::<Wrong>(&value).unwrap_err(),
+        NbtError::IncorrectType(nbt_consts::TAG_INT, nbt_consts::TAG_STRING)
+    );
+    assert_eq!(
+        from_value::<Vec<i32>>(&value).unwrap_err(),
+        NbtError::IncorrectType(nbt_consts::TAG_LIST, nbt_consts::TAG_COMPOUND)
+    );
+    // 宽度不一样也不行
+    assert_eq!(
+        from_value::<i64>(&NbtValue::Int(1)).unwrap_err(),
+        NbtError::IncorrectType(nbt_consts::TAG_LONG, nbt_consts::TAG_INT)
+    );
+    assert_eq!(from_value::<u32>(&NbtValue::Int(-1)).unwrap(), u32::MAX);
+}
+
+#[test]
+fn depth_limit() {
+    // 一层一层套 List, 超过限制要报错而不是爆栈
+    let mut data = vec![nbt_consts::TAG_LIST];
+    for _ in 0..super::de::SERDE_RECURSE_LIMIT + 8 {
+        data.extend_from_slice(&[nbt_consts::TAG_LIST, 0x02]);
+    }
+    assert_eq!(
+        from_bytes::<BedrockNetVarInt, NbtValue>(&data),
+        Err(NbtError::NbtDepthTooBig(super::de::SERDE_RECURSE_LIMIT))
+    );
+}
+
+#[test]
+fn len_too_big() {
+    let long = "a".repeat(70000);
+    let value = vec![Inner {
+        a: 1,
+        b: vec![String::new(), long.clone()],
+    }];
+    let err = NbtError::LenTooBig("inner[0].b[1]".to_string(), 70000, 65535);
+    let mut map = HashMap::new();
+    map.insert("inner", value);
+    assert_eq!(to_bytes::<Java, _>(&map), Err(err.clone()));
+    assert_eq!(to_bytes::<BedrockDisk, _>(&map), Err(err));
+    assert!(to_bytes::<BedrockNetVarInt, _>(&map).is_ok());
+    // variant 的名字也算在位置里
+    let mode = HashMap::from([("mode", Mode::Named { name: long })]);
+    assert_eq!(
+        to_bytes::<Java, _>(&mode),
+        Err(NbtError::LenTooBig("mode.Named.name".to_string(), 70000, 65535))
+    );
+}
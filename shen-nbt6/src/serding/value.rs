@@ -0,0 +1,122 @@
+use std::fmt;
+
+use serde::de::{
+    self, Deserialize, Deserializer, EnumAccess, MapAccess, SeqAccess, VariantAccess, Visitor,
+};
+use serde::ser::{self, Serialize, SerializeMap, SerializeSeq, Serializer};
+
+use super::array::{INT_ARRAY_TOKEN, LONG_ARRAY_TOKEN, NBT_VALUE_TOKEN};
+use crate::NbtValue;
+
+/// 按照 NBT 本身的样子序列化
+///
+/// Compound 的名字会被丢掉
+impl Serialize for NbtValue {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            NbtValue::Byte(v) => serializer.serialize_i8(*v),
+            NbtValue::Short(v) => serializer.serialize_i16(*v),
+            NbtValue::Int(v) => serializer.serialize_i32(*v),
+            NbtValue::Long(v) => serializer.serialize_i64(*v),
+            NbtValue::Float(v) => serializer.serialize_f32(*v),
+            NbtValue::Double(v) => serializer.serialize_f64(*v),
+            NbtValue::ByteArray(v) => {
+                let data: Vec<u8> = v.iter().map(|x| *x as u8).collect();
+                serializer.serialize_bytes(&data)
+            }
+            NbtValue::String(v) => {
+                serializer.serialize_str(&v.decode().map_err(ser::Error::custom)?)
+            }
+            NbtValue::List(v) => {
+                let mut seq = serializer.serialize_seq(Some(v.len()))?;
+                for item in v {
+                    seq.serialize_element(item)?;
+                }
+                seq.end()
+            }
+            NbtValue::Compound(_, v) => {
+                let mut map = serializer.serialize_map(Some(v.len()))?;
+                for (key, value) in v {
+                    map.serialize_entry(&key.decode().map_err(ser::Error::custom)?, value)?;
+                }
+                map.end()
+            }
+            NbtValue::IntArray(v) => serializer.serialize_newtype_struct(INT_ARRAY_TOKEN, v),
+            NbtValue::LongArray(v) => serializer.serialize_newtype_struct(LONG_ARRAY_TOKEN, v),
+        }
+    }
+}
+
+struct NbtValueVisitor;
+
+impl<'de> Visitor<'de> for NbtValueVisitor {
+    type Value = NbtValue;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("一个 NBT 值")
+    }
+
+    fn visit_bool<E: de::Error>(self, v: bool) -> Result<NbtValue, E> {
+        Ok(NbtValue::Byte(v as i8))
+    }
+    fn visit_i8<E: de::Error>(self, v: i8) -> Result<NbtValue, E> { Ok(NbtValue::Byte(v)) }
+    fn visit_i16<E: de::Error>(self, v: i16) -> Result<NbtValue, E> { Ok(NbtValue::Short(v)) }
+    fn visit_i32<E: de::Error>(self, v: i32) -> Result<NbtValue, E> { Ok(NbtValue::Int(v)) }
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<NbtValue, E> { Ok(NbtValue::Long(v)) }
+    fn visit_u8<E: de::Error>(self, v: u8) -> Result<NbtValue, E> { Ok(NbtValue::Byte(v as i8)) }
+    fn visit_u16<E: de::Error>(self, v: u16) -> Result<NbtValue, E> {
+        Ok(NbtValue::Short(v as i16))
+    }
+    fn visit_u32<E: de::Error>(self, v: u32) -> Result<NbtValue, E> { Ok(NbtValue::Int(v as i32)) }
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<NbtValue, E> { Ok(NbtValue::Long(v as i64)) }
+    fn visit_f32<E: de::Error>(self, v: f32) -> Result<NbtValue, E> { Ok(NbtValue::Float(v)) }
+    fn visit_f64<E: de::Error>(self, v: f64) -> Result<NbtValue, E> { Ok(NbtValue::Double(v)) }
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<NbtValue, E> {
+        Ok(NbtValue::String(v.into()))
+    }
+    fn visit_string<E: de::Error>(self, v: String) -> Result<NbtValue, E> {
+        Ok(NbtValue::String(v.into()))
+    }
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<NbtValue, E> {
+        Ok(NbtValue::ByteArray(v.iter().map(|x| *x as i8).collect()))
+    }
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<NbtValue, D::Error> {
+        deserializer.deserialize_any(self)
+    }
+    fn visit_newtype_struct<D: Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> Result<NbtValue, D::Error> {
+        deserializer.deserialize_any(self)
+    }
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<NbtValue, A::Error> {
+        let mut values = Vec::with_capacity(seq.size_hint().unwrap_or(0).min(4096));
+        while let Some(value) = seq.next_element()? {
+            values.push(value);
+        }
+        Ok(NbtValue::List(values))
+    }
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<NbtValue, A::Error> {
+        let mut values = Vec::with_capacity(map.size_hint().unwrap_or(0).min(4096));
+        while let Some((key, value)) = map.next_entry::<String, NbtValue>()? {
+            values.push((key.into(), value));
+        }
+        Ok(NbtValue::Compound(None, values))
+    }
+    /// NBT 的 Deserializer 会把 Array 当成 `{IntArray: [...]}` 这样的 enum 交过来
+    fn visit_enum<A: EnumAccess<'de>>(self, data: A) -> Result<NbtValue, A::Error> {
+        let (name, variant) = data.variant::<String>()?;
+        match name.as_str() {
+            "ByteArray" => variant.newtype_variant().map(NbtValue::ByteArray),
+            "IntArray" => variant.newtype_variant().map(NbtValue::IntArray),
+            "LongArray" => variant.newtype_variant().map(NbtValue::LongArray),
+            _ => Err(de::Error::unknown_variant(&name, &["ByteArray", "IntArray", "LongArray"])),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for NbtValue {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<NbtValue, D::Error> {
+        deserializer.deserialize_newtype_struct(NBT_VALUE_TOKEN, NbtValueVisitor)
+    }
+}
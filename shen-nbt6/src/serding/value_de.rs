@@ -0,0 +1,315 @@
+use std::borrow::Cow;
+
+use serde::de::value::SeqDeserializer;
+use serde::de::{self, IntoDeserializer, Visitor};
+
+use super::array::{ArrayVariant, NBT_VALUE_TOKEN};
+use super::de::{deserialize_expect, KeyDeserializer, SERDE_RECURSE_LIMIT};
+use super::{expect_list, expect_tag};
+use crate::{nbt_consts, Mutf8String, NbtError, NbtResult, NbtTypeId, NbtValue};
+
+/// 从 [`NbtValue`] 里读出 Rust 的值
+///
+/// 跟 [`super::Deserializer`] 的对应关系一样
+#[derive(Clone, Copy)]
+pub struct ValueDeserializer<'de> {
+    value: &'de NbtValue,
+    depth: usize,
+}
+
+impl<'de> ValueDeserializer<'de> {
+    pub fn new(value: &'de NbtValue) -> Self { Self { value, depth: 0 } }
+
+    fn current_tag(&self) -> NbtTypeId { self.value.tag() }
+
+    /// 读里面一层的值
+    fn child(&self, value: &'de NbtValue) -> NbtResult<Self> {
+        if self.depth >= SERDE_RECURSE_LIMIT {
+            return Err(NbtError::NbtDepthTooBig(SERDE_RECURSE_LIMIT));
+        }
+        Ok(Self {
+            value,
+            depth: self.depth + 1,
+        })
+    }
+}
+
+impl<'de> de::Deserializer<'de> for ValueDeserializer<'de> {
+    type Error = NbtError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> NbtResult<V::Value> {
+        match self.value {
+            NbtValue::Byte(v) => visitor.visit_i8(*v),
+            NbtValue::Short(v) => visitor.visit_i16(*v),
+            NbtValue::Int(v) => visitor.visit_i32(*v),
+            NbtValue::Long(v) => visitor.visit_i64(*v),
+            NbtValue::Float(v) => visitor.visit_f32(*v),
+            NbtValue::Double(v) => visitor.visit_f64(*v),
+            NbtValue::ByteArray(v) => visit_array(v.iter().copied(), visitor),
+            NbtValue::String(v) => match v.decode()? {
+                Cow::Borrowed(s) => visitor.visit_borrowed_str(s),
+                Cow::Owned(s) => visitor.visit_string(s),
+            },
+            NbtValue::List(v) => {
+                let mut access = ValueListAccess {
+                    de: &self,
+                    iter: v.iter(),
+                };
+                let value = visitor.visit_seq(&mut access)?;
+                match access.iter.len() {
+                    0 => Ok(value),
+                    _ => Err(de::Error::invalid_length(v.len(), &"完整的 NbtList")),
+                }
+            }
+            NbtValue::Compound(_, v) => {
+                let mut access = ValueCompoundAccess {
+                    de: &self,
+                    iter: v.iter(),
+                    value: None,
+                };
+                let value = visitor.visit_map(&mut access)?;
+                match access.iter.len() {
+                    0 => Ok(value),
+                    _ => Err(NbtError::SerdeCustom("NbtCompound 没有读完".to_string())),
+                }
+            }
+            NbtValue::IntArray(v) => visit_array(v.iter().copied(), visitor),
+            NbtValue::LongArray(v) => visit_array(v.iter().copied(), visitor),
+        }
+    }
+
+    fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> NbtResult<V::Value> {
+        match self.value {
+            NbtValue::Byte(v) => visitor.visit_bool(*v != 0),
+            _ => Err(NbtError::IncorrectType(nbt_consts::TAG_BYTE, self.current_tag())),
+        }
+    }
+    // 无符号的按位转回来
+    fn deserialize_u8<V: Visitor<'de>>(self, visitor: V) -> NbtResult<V::Value> {
+        match self.value {
+            NbtValue::Byte(v) => visitor.visit_u8(*v as u8),
+            _ => Err(NbtError::IncorrectType(nbt_consts::TAG_BYTE, self.current_tag())),
+        }
+    }
+    fn deserialize_u16<V: Visitor<'de>>(self, visitor: V) -> NbtResult<V::Value> {
+        match self.value {
+            NbtValue::Short(v) => visitor.visit_u16(*v as u16),
+            _ => Err(NbtError::IncorrectType(nbt_consts::TAG_SHORT, self.current_tag())),
+        }
+    }
+    fn deserialize_u32<V: Visitor<'de>>(self, visitor: V) -> NbtResult<V::Value> {
+        match self.value {
+            NbtValue::Int(v) => visitor.visit_u32(*v as u32),
+            _ => Err(NbtError::IncorrectType(nbt_consts::TAG_INT, self.current_tag())),
+        }
+    }
+    fn deserialize_u64<V: Visitor<'de>>(self, visitor: V) -> NbtResult<V::Value> {
+        match self.value {
+            NbtValue::Long(v) => visitor.visit_u64(*v as u64),
+            _ => Err(NbtError::IncorrectType(nbt_consts::TAG_LONG, self.current_tag())),
+        }
+    }
+    fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> NbtResult<V::Value> {
+        match self.value {
+            NbtValue::ByteArray(v) => visitor.visit_byte_buf(v.iter().map(|x| *x as u8).collect()),
+            _ => Err(NbtError::IncorrectType(nbt_consts::TAG_BYTE_ARRAY, self.current_tag())),
+        }
+    }
+    fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> NbtResult<V::Value> {
+        self.deserialize_bytes(visitor)
+    }
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> NbtResult<V::Value> {
+        visitor.visit_some(self)
+    }
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> NbtResult<V::Value> {
+        visitor.visit_unit()
+    }
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> NbtResult<V::Value> {
+        self.deserialize_unit(visitor)
+    }
+    /// `NbtValue` 需要知道是 Array 还是 List
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> NbtResult<V::Value> {
+        if name != NBT_VALUE_TOKEN {
+            return visitor.visit_newtype_struct(self);
+        }
+        match ArrayVariant::new(self.current_tag(), self) {
+            Some(array) => visitor.visit_enum(array),
+            None => self.deserialize_any(visitor),
+        }
+    }
+    /// 字符串是只有名字的 variant, 只有一项的 Compound 是其他的 variant
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> NbtResult<V::Value> {
+        match self.value {
+            NbtValue::String(v) => visitor.visit_enum(v.decode()?.into_owned().into_deserializer()),
+            NbtValue::Compound(_, v) => match v.as_slice() {
+                [(key, value)] => visitor.visit_enum(ValueEnumAccess {
+                    key,
+                    value: self.child(value)?,
+                }),
+                _ => Err(NbtError::SerdeCustom("表示 enum 的 NbtCompound 只能有一项".to_string())),
+            },
+            _ => Err(NbtError::IncorrectType(nbt_consts::TAG_COMPOUND, self.current_tag())),
+        }
+    }
+
+    fn is_human_readable(&self) -> bool { false }
+
+    deserialize_expect! {
+        deserialize_i8 => TAG_BYTE,
+        deserialize_i16 => TAG_SHORT,
+        deserialize_i32 => TAG_INT,
+        deserialize_i64 => TAG_LONG,
+        deserialize_f32 => TAG_FLOAT,
+        deserialize_f64 => TAG_DOUBLE,
+        deserialize_char => TAG_STRING,
+        deserialize_str => TAG_STRING,
+        deserialize_string => TAG_STRING,
+        deserialize_map => TAG_COMPOUND,
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> NbtResult<V::Value> {
+        expect_list(self.current_tag())?;
+        self.deserialize_any(visitor)
+    }
+    fn deserialize_tuple<V: Visitor<'de>>(self, _len: usize, visitor: V) -> NbtResult<V::Value> {
+        self.deserialize_seq(visitor)
+    }
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> NbtResult<V::Value> {
+        self.deserialize_seq(visitor)
+    }
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> NbtResult<V::Value> {
+        self.deserialize_map(visitor)
+    }
+
+    serde::forward_to_deserialize_any! {
+        i128 u128 identifier ignored_any
+    }
+}
+
+/// 把 Array 当成 seq 交给 visitor
+fn visit_array<'de, V, I>(iter: I, visitor: V) -> NbtResult<V::Value>
+where
+    V: Visitor<'de>,
+    I: Iterator,
+    I::Item: IntoDeserializer<'de, NbtError>,
+{
+    let mut seq = SeqDeserializer::new(iter);
+    let value = visitor.visit_seq(&mut seq)?;
+    seq.end()?;
+    Ok(value)
+}
+
+struct ValueListAccess<'a, 'de> {
+    de: &'a ValueDeserializer<'de>,
+    iter: std::slice::Iter<'de, NbtValue>,
+}
+
+impl<'de> de::SeqAccess<'de> for ValueListAccess<'_, 'de> {
+    type Error = NbtError;
+
+    fn next_element_seed<T: de::DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> NbtResult<Option<T::Value>> {
+        match self.iter.next() {
+            Some(value) => seed.deserialize(self.de.child(value)?).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> { Some(self.iter.len()) }
+}
+
+struct ValueCompoundAccess<'a, 'de> {
+    de: &'a ValueDeserializer<'de>,
+    iter: std::slice::Iter<'de, (Mutf8String, NbtValue)>,
+    /// 等着被读的 value
+    value: Option<&'de NbtValue>,
+}
+
+impl<'de> de::MapAccess<'de> for ValueCompoundAccess<'_, 'de> {
+    type Error = NbtError;
+
+    fn next_key_seed<K: de::DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> NbtResult<Option<K::Value>> {
+        match self.iter.next() {
+            Some((key, value)) => {
+                self.value = Some(value);
+                seed.deserialize(KeyDeserializer(key.decode()?)).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V: de::DeserializeSeed<'de>>(&mut self, seed: V) -> NbtResult<V::Value> {
+        let value = self
+            .value
+            .take()
+            .ok_or_else(|| NbtError::SerdeCustom("没有 key 的 value".to_string()))?;
+        seed.deserialize(self.de.child(value)?)
+    }
+
+    fn size_hint(&self) -> Option<usize> { Some(self.iter.len()) }
+}
+
+struct ValueEnumAccess<'de> {
+    key: &'de Mutf8String,
+    value: ValueDeserializer<'de>,
+}
+
+impl<'de> de::EnumAccess<'de> for ValueEnumAccess<'de> {
+    type Error = NbtError;
+    type Variant = ValueDeserializer<'de>;
+
+    fn variant_seed<V: de::DeserializeSeed<'de>>(
+        self,
+        seed: V,
+    ) -> NbtResult<(V::Value, Self::Variant)> {
+        let variant = seed.deserialize(KeyDeserializer(self.key.decode()?))?;
+        Ok((variant, self.value))
+    }
+}
+
+impl<'de> de::VariantAccess<'de> for ValueDeserializer<'de> {
+    type Error = NbtError;
+
+    fn unit_variant(self) -> NbtResult<()> { Ok(()) }
+    fn newtype_variant_seed<T: de::DeserializeSeed<'de>>(self, seed: T) -> NbtResult<T::Value> {
+        seed.deserialize(self)
+    }
+    fn tuple_variant<V: Visitor<'de>>(self, _len: usize, visitor: V) -> NbtResult<V::Value> {
+        de::Deserializer::deserialize_seq(self, visitor)
+    }
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> NbtResult<V::Value> {
+        de::Deserializer::deserialize_map(self, visitor)
+    }
+}
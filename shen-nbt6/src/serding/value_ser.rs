@@ -0,0 +1,276 @@
+use serde::ser::{self, Serialize};
+
+use super::array::{serialize_array, token_tag};
+use super::ser::KeySerializer;
+use crate::{Mutf8String, NbtError, NbtResult, NbtValue};
+
+/// 把 Rust 的值转成 [`NbtValue`]
+///
+/// 跟 [`super::Serializer`] 的对应关系一样, `None` 表示什么都没有 (`Option::None`)
+pub struct ValueSerializer;
+
+impl ser::Serializer for ValueSerializer {
+    type Ok = Option<NbtValue>;
+    type Error = NbtError;
+    type SerializeSeq = ValueListSerializer;
+    type SerializeTuple = ValueListSerializer;
+    type SerializeTupleStruct = ValueListSerializer;
+    type SerializeTupleVariant = ValueListSerializer;
+    type SerializeMap = ValueCompoundSerializer;
+    type SerializeStruct = ValueCompoundSerializer;
+    type SerializeStructVariant = ValueCompoundSerializer;
+
+    fn serialize_bool(self, v: bool) -> NbtResult<Self::Ok> { Ok(Some(NbtValue::Byte(v as i8))) }
+    fn serialize_i8(self, v: i8) -> NbtResult<Self::Ok> { Ok(Some(NbtValue::Byte(v))) }
+    fn serialize_i16(self, v: i16) -> NbtResult<Self::Ok> { Ok(Some(NbtValue::Short(v))) }
+    fn serialize_i32(self, v: i32) -> NbtResult<Self::Ok> { Ok(Some(NbtValue::Int(v))) }
+    fn serialize_i64(self, v: i64) -> NbtResult<Self::Ok> { Ok(Some(NbtValue::Long(v))) }
+    fn serialize_u8(self, v: u8) -> NbtResult<Self::Ok> { self.serialize_i8(v as i8) }
+    fn serialize_u16(self, v: u16) -> NbtResult<Self::Ok> { self.serialize_i16(v as i16) }
+    fn serialize_u32(self, v: u32) -> NbtResult<Self::Ok> { self.serialize_i32(v as i32) }
+    fn serialize_u64(self, v: u64) -> NbtResult<Self::Ok> { self.serialize_i64(v as i64) }
+    fn serialize_f32(self, v: f32) -> NbtResult<Self::Ok> { Ok(Some(NbtValue::Float(v))) }
+    fn serialize_f64(self, v: f64) -> NbtResult<Self::Ok> { Ok(Some(NbtValue::Double(v))) }
+    fn serialize_char(self, v: char) -> NbtResult<Self::Ok> {
+        self.serialize_str(v.encode_utf8(&mut [0; 4]))
+    }
+    fn serialize_str(self, v: &str) -> NbtResult<Self::Ok> { Ok(Some(NbtValue::String(v.into()))) }
+    fn serialize_bytes(self, v: &[u8]) -> NbtResult<Self::Ok> {
+        Ok(Some(NbtValue::ByteArray(v.iter().map(|x| *x as i8).collect())))
+    }
+    fn serialize_none(self) -> NbtResult<Self::Ok> { Ok(None) }
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> NbtResult<Self::Ok> {
+        value.serialize(self)
+    }
+    fn serialize_unit(self) -> NbtResult<Self::Ok> {
+        Ok(Some(NbtValue::Compound(None, Vec::new())))
+    }
+    fn serialize_unit_struct(self, _name: &'static str) -> NbtResult<Self::Ok> {
+        self.serialize_unit()
+    }
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> NbtResult<Self::Ok> {
+        self.serialize_str(variant)
+    }
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        name: &'static str,
+        value: &T,
+    ) -> NbtResult<Self::Ok> {
+        match token_tag(name) {
+            Some(tag) => serialize_array(tag, value),
+            None => value.serialize(self),
+        }
+    }
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> NbtResult<Self::Ok> {
+        let values = match value.serialize(self)? {
+            Some(value) => vec![(variant.into(), value)],
+            None => Vec::new(),
+        };
+        Ok(Some(NbtValue::Compound(None, values)))
+    }
+    fn serialize_seq(self, len: Option<usize>) -> NbtResult<Self::SerializeSeq> {
+        Ok(ValueListSerializer::new(len, None))
+    }
+    fn serialize_tuple(self, len: usize) -> NbtResult<Self::SerializeTuple> {
+        self.serialize_seq(Some(len))
+    }
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> NbtResult<Self::SerializeTupleStruct> {
+        self.serialize_seq(Some(len))
+    }
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> NbtResult<Self::SerializeTupleVariant> {
+        Ok(ValueListSerializer::new(Some(len), Some(variant)))
+    }
+    fn serialize_map(self, len: Option<usize>) -> NbtResult<Self::SerializeMap> {
+        Ok(ValueCompoundSerializer::new(len, None))
+    }
+    fn serialize_struct(self, _name: &'static str, len: usize) -> NbtResult<Self::SerializeStruct> {
+        Ok(ValueCompoundSerializer::new(Some(len), None))
+    }
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> NbtResult<Self::SerializeStructVariant> {
+        Ok(ValueCompoundSerializer::new(Some(len), Some(variant)))
+    }
+
+    fn is_human_readable(&self) -> bool { false }
+}
+
+/// 把 `value` 包成 `{variant: value}`
+fn wrap_variant(variant: Option<&'static str>, value: NbtValue) -> NbtValue {
+    match variant {
+        Some(variant) => NbtValue::Compound(None, vec![(variant.into(), value)]),
+        None => value,
+    }
+}
+
+/// 转成 NbtList, 元素类型必须一样
+pub struct ValueListSerializer {
+    values: Vec<NbtValue>,
+    variant: Option<&'static str>,
+}
+
+impl ValueListSerializer {
+    fn new(len: Option<usize>, variant: Option<&'static str>) -> Self {
+        Self {
+            values: Vec::with_capacity(len.unwrap_or(0)),
+            variant,
+        }
+    }
+
+    fn push<T: Serialize + ?Sized>(&mut self, value: &T) -> NbtResult<()> {
+        let value = value
+            .serialize(ValueSerializer)?
+            .ok_or_else(|| NbtError::SerdeCustom("NbtList 里不能有 None".to_string()))?;
+        if let Some(first) = self.values.first() {
+            if first.tag() != value.tag() {
+                return Err(NbtError::ListTypeNotSame(vec![first.tag(), value.tag()]));
+            }
+        }
+        self.values.push(value);
+        Ok(())
+    }
+
+    fn finish(self) -> NbtResult<Option<NbtValue>> {
+        Ok(Some(wrap_variant(self.variant, NbtValue::List(self.values))))
+    }
+}
+
+impl ser::SerializeSeq for ValueListSerializer {
+    type Ok = Option<NbtValue>;
+    type Error = NbtError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> NbtResult<()> {
+        self.push(value)
+    }
+    fn end(self) -> NbtResult<Self::Ok> { self.finish() }
+}
+
+impl ser::SerializeTuple for ValueListSerializer {
+    type Ok = Option<NbtValue>;
+    type Error = NbtError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> NbtResult<()> {
+        self.push(value)
+    }
+    fn end(self) -> NbtResult<Self::Ok> { self.finish() }
+}
+
+impl ser::SerializeTupleStruct for ValueListSerializer {
+    type Ok = Option<NbtValue>;
+    type Error = NbtError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> NbtResult<()> {
+        self.push(value)
+    }
+    fn end(self) -> NbtResult<Self::Ok> { self.finish() }
+}
+
+impl ser::SerializeTupleVariant for ValueListSerializer {
+    type Ok = Option<NbtValue>;
+    type Error = NbtError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> NbtResult<()> {
+        self.push(value)
+    }
+    fn end(self) -> NbtResult<Self::Ok> { self.finish() }
+}
+
+/// 转成 NbtCompound
+pub struct ValueCompoundSerializer {
+    values: Vec<(Mutf8String, NbtValue)>,
+    /// map 的 key, 等着 value 来
+    key: Option<String>,
+    variant: Option<&'static str>,
+}
+
+impl ValueCompoundSerializer {
+    fn new(len: Option<usize>, variant: Option<&'static str>) -> Self {
+        Self {
+            values: Vec::with_capacity(len.unwrap_or(0)),
+            key: None,
+            variant,
+        }
+    }
+
+    fn push<T: Serialize + ?Sized>(&mut self, key: &str, value: &T) -> NbtResult<()> {
+        if let Some(value) = value.serialize(ValueSerializer)? {
+            self.values.push((key.into(), value));
+        }
+        Ok(())
+    }
+
+    fn finish(self) -> NbtResult<Option<NbtValue>> {
+        Ok(Some(wrap_variant(self.variant, NbtValue::Compound(None, self.values))))
+    }
+}
+
+impl ser::SerializeMap for ValueCompoundSerializer {
+    type Ok = Option<NbtValue>;
+    type Error = NbtError;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> NbtResult<()> {
+        self.key = Some(key.serialize(KeySerializer)?);
+        Ok(())
+    }
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> NbtResult<()> {
+        let key = self
+            .key
+            .take()
+            .ok_or_else(|| NbtError::SerdeCustom("没有 key 的 value".to_string()))?;
+        self.push(&key, value)
+    }
+    fn end(self) -> NbtResult<Self::Ok> { self.finish() }
+}
+
+impl ser::SerializeStruct for ValueCompoundSerializer {
+    type Ok = Option<NbtValue>;
+    type Error = NbtError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> NbtResult<()> {
+        self.push(key, value)
+    }
+    fn end(self) -> NbtResult<Self::Ok> { self.finish() }
+}
+
+impl ser::SerializeStructVariant for ValueCompoundSerializer {
+    type Ok = Option<NbtValue>;
+    type Error = NbtError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> NbtResult<()> {
+        self.push(key, value)
+    }
+    fn end(self) -> NbtResult<Self::Ok> { self.finish() }
+}
@@ -0,0 +1,815 @@
+use std::fmt::{self, Write};
+
+use crate::traits::NbtTypeConversion;
+use crate::{Mutf8String, NbtError, NbtResult, NbtTypeId, NbtValue, RECURSE_LIMIT};
+
+/// 不需要引号的字符
+///
+/// 跟原版的 `StringReader::isAllowedInUnquotedString` 一致
+#[inline]
+fn is_unquoted_char(c: u8) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, b'_' | b'-' | b'.' | b'+')
+}
+
+/// 是不是 `[-+]?(0|[1-9][0-9]*)`
+fn is_integer(s: &str) -> bool {
+    let digits = s.strip_prefix(['-', '+']).unwrap_or(s);
+    match digits.as_bytes() {
+        [] => false,
+        [b'0'] => true,
+        [b'0', ..] => false,
+        digits => digits.iter().all(u8::is_ascii_digit),
+    }
+}
+
+/// 是不是 `[-+]?([0-9]+[.]?|[0-9]*[.][0-9]+)(e[-+]?[0-9]+)?`
+///
+/// `need_dot` 为 true 时必须带一个 `.` (没有后缀的 double)
+pub(crate) fn is_float(s: &str, need_dot: bool) -> bool {
+    let s = s.strip_prefix(['-', '+']).unwrap_or(s);
+    let (body, exp) = match s.find(['e', 'E']) {
+        Some(idx) => (&s[..idx], Some(&s[idx + 1..])),
+        None => (s, None),
+    };
+    if let Some(exp) = exp {
+        let exp = exp.strip_prefix(['-', '+']).unwrap_or(exp);
+        if exp.is_empty() || !exp.bytes().all(|c| c.is_ascii_digit()) {
+            return false;
+        }
+    }
+    let (int_part, frac_part) = match body.split_once('.') {
+        Some((int_part, frac_part)) => (int_part, Some(frac_part)),
+        None => (body, None),
+    };
+    if !int_part.bytes().all(|c| c.is_ascii_digit()) {
+        return false;
+    }
+    match frac_part {
+        Some(frac_part) => {
+            frac_part.bytes().all(|c| c.is_ascii_digit())
+                && !(int_part.is_empty() && frac_part.is_empty())
+        }
+        None => !need_dot && !int_part.is_empty(),
+    }
+}
+
+/// 推断一个没有引号的值的类型
+///
+/// 跟原版一样, 数字超出范围的时候会当成字符串
+pub(crate) fn parse_unquoted(token: &str) -> NbtValue {
+    if token.eq_ignore_ascii_case("true") {
+        return NbtValue::value_true();
+    }
+    if token.eq_ignore_ascii_case("false") {
+        return NbtValue::value_false();
+    }
+    let (body, suffix) = token.split_at(token.len() - 1);
+    let value = match suffix {
+        "b" | "B" if is_integer(body) => body.parse().ok().map(NbtValue::Byte),
+        "s" | "S" if is_integer(body) => body.parse().ok().map(NbtValue::Short),
+        "l" | "L" if is_integer(body) => body.parse().ok().map(NbtValue::Long),
+        "f" | "F" if is_float(body, false) => body.parse().ok().map(NbtValue::Float),
+        "d" | "D" if is_float(body, false) => body.parse().ok().map(NbtValue::Double),
+        _ if is_integer(token) => token.parse().ok().map(NbtValue::Int),
+        _ if is_float(token, true) => token.parse().ok().map(NbtValue::Double),
+        _ => None,
+    };
+    value.unwrap_or_else(|| NbtValue::String(token.into()))
+}
+
+/// 解析过程中的栈帧
+enum SnbtFrame {
+    /// values, 第一个值的类型
+    List(Vec<NbtValue>, Option<NbtTypeId>),
+    /// values, 正在读的值的 key
+    Compound(Vec<(Mutf8String, NbtValue)>, Option<Mutf8String>),
+}
+
+/// 用于读取 SNBT 数据
+pub struct SnbtReader<'data> {
+    /// SNBT 数据
+    pub data: &'data str,
+    /// 当前读取的位置 (字节)
+    pub cursor: usize,
+}
+
+impl<'data> SnbtReader<'data> {
+    pub fn new(data: &'data str) -> Self { SnbtReader { data, cursor: 0 } }
+
+    /// 把字节位置转换成 (行, 列), 都从 1 开始
+    ///
+    /// 列按字符计算
+    pub fn line_column(&self, pos: usize) -> (usize, usize) {
+        let before = &self.data[..pos.min(self.data.len())];
+        let line = before.matches('\n').count() + 1;
+        let line_start = before.rfind('\n').map(|idx| idx + 1).unwrap_or(0);
+        let column = before[line_start..].chars().count() + 1;
+        (line, column)
+    }
+
+    /// 在 `pos` 的位置生成一个语法错误
+    pub fn error_at(&self, pos: usize, msg: impl Into<String>) -> NbtError {
+        let (line, column) = self.line_column(pos);
+        NbtError::SnbtSyntax(line, column, msg.into())
+    }
+
+    #[inline]
+    fn peek(&self) -> Option<u8> { self.data.as_bytes().get(self.cursor).copied() }
+
+    #[inline]
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(|c| c.is_ascii_whitespace()) {
+            self.cursor += 1;
+        }
+    }
+
+    /// 跳过空白之后, 期望一个 `expect` 字符
+    fn expect(&mut self, expect: u8) -> NbtResult<()> {
+        self.skip_whitespace();
+        if self.peek() == Some(expect) {
+            self.cursor += 1;
+            Ok(())
+        } else {
+            Err(self.error_at(self.cursor, format!("期望 '{}'", expect as char)))
+        }
+    }
+
+    /// 读取一段不需要引号的字符串
+    fn read_unquoted(&mut self) -> &'data str {
+        let start = self.cursor;
+        while self.peek().is_some_and(is_unquoted_char) {
+            self.cursor += 1;
+        }
+        &self.data[start..self.cursor]
+    }
+
+    /// 读取一个带引号的字符串 (`"` 或者 `'`)
+    pub(crate) fn read_quoted(&mut self) -> NbtResult<String> {
+        let start = self.cursor;
+        let quote = self.data[self.cursor..].chars().next().unwrap();
+        self.cursor += 1;
+        let mut result = String::new();
+        let mut chars = self.data[self.cursor..].char_indices();
+        while let Some((idx, c)) = chars.next() {
+            match c {
+                '\\' => {
+                    let escape_pos = self.cursor + idx;
+                    let escaped = match chars.next() {
+                        Some((_, c)) => c,
+                        None => break,
+                    };
+                    match escaped {
+                        '\\' | '"' | '\'' => result.push(escaped),
+                        'n' => result.push('\n'),
+                        't' => result.push('\t'),
+                        'r' => result.push('\r'),
+                        'b' => result.push('\u{8}'),
+                        'f' => result.push('\u{c}'),
+                        'u' => {
+                            let hex: String = chars.by_ref().take(4).map(|(_, c)| c).collect();
+                            let code = u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32);
+                            match code {
+                                Some(c) if hex.len() == 4 => result.push(c),
+                                _ => {
+                                    return Err(self.error_at(escape_pos, "无效的 \\u 转义"));
+                                }
+                            }
+                        }
+                        x => return Err(self.error_at(escape_pos, format!("无效的转义: \\{}", x))),
+                    }
+                }
+                c if c == quote => {
+                    self.cursor += idx + c.len_utf8();
+                    return Ok(result);
+                }
+                c => result.push(c),
+            }
+        }
+        Err(self.error_at(start, "字符串没有结束"))
+    }
+
+    /// 读取一个 key
+    fn read_key(&mut self) -> NbtResult<Mutf8String> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some(b'"' | b'\'') => Ok(self.read_quoted()?.into()),
+            _ => {
+                let start = self.cursor;
+                let key = self.read_unquoted();
+                if key.is_empty() {
+                    return Err(self.error_at(start, "期望一个 key"));
+                }
+                Ok(key.into())
+            }
+        }
+    }
+
+    /// 读取一个 `[B;` `[I;` `[L;` 数组
+    ///
+    /// 当前 cursor 在 `;` 后面
+    fn read_array(&mut self, array_type: u8) -> NbtResult<NbtValue> {
+        let mut bytes = Vec::new();
+        let mut ints = Vec::new();
+        let mut longs = Vec::new();
+        self.skip_whitespace();
+        if self.peek() == Some(b']') {
+            self.cursor += 1;
+        } else {
+            loop {
+                self.skip_whitespace();
+                let start = self.cursor;
+                let token = self.read_unquoted();
+                // 后缀可有可无
+                let body = match array_type {
+                    b'B' => token.strip_suffix(['b', 'B']),
+                    b'L' => token.strip_suffix(['l', 'L']),
+                    _ => None,
+                }
+                .unwrap_or(token);
+                let number = match token {
+                    t if array_type == b'B' && t.eq_ignore_ascii_case("true") => Some(1),
+                    t if array_type == b'B' && t.eq_ignore_ascii_case("false") => Some(0),
+                    _ if is_integer(body) => body.parse::<i64>().ok(),
+                    _ => None,
+                };
+                let parsed = match (array_type, number) {
+                    (b'B', Some(n)) => i8::try_from(n).map(|n| bytes.push(n)).is_ok(),
+                    (b'I', Some(n)) => i32::try_from(n).map(|n| ints.push(n)).is_ok(),
+                    (_, Some(n)) => {
+                        longs.push(n);
+                        true
+                    }
+                    (_, None) => false,
+                };
+                if !parsed {
+                    let type_name = match array_type {
+                        b'B' => "Byte",
+                        b'I' => "Int",
+                        _ => "Long",
+                    };
+                    return Err(self.error_at(
+                        start,
+                        format!(
+                            "[{};] 里只能放 {}, 实际: {:?}",
+                            array_type as char, type_name, token
+                        ),
+                    ));
+                }
+                self.skip_whitespace();
+                match self.peek() {
+                    Some(b',') => self.cursor += 1,
+                    Some(b']') => {
+                        self.cursor += 1;
+                        break;
+                    }
+                    _ => return Err(self.error_at(self.cursor, "期望 ',' 或 ']'")),
+                }
+            }
+        }
+        Ok(match array_type {
+            b'B' => NbtValue::ByteArray(bytes),
+            b'I' => NbtValue::IntArray(ints),
+            _ => NbtValue::LongArray(longs),
+        })
+    }
+
+    /// 读取一个不是 List/Compound 的值
+    fn read_scalar(&mut self) -> NbtResult<NbtValue> {
+        match self.peek() {
+            Some(b'"' | b'\'') => Ok(NbtValue::String(self.read_quoted()?.into())),
+            _ => {
+                let start = self.cursor;
+                let token = self.read_unquoted();
+                if token.is_empty() {
+                    return Err(self.error_at(start, "期望一个值"));
+                }
+                Ok(parse_unquoted(token))
+            }
+        }
+    }
+
+    /// 读取一个完整的值
+    ///
+    /// 跟 borrow 里一样手动模拟 stack, 防止嵌套太深爆栈
+    pub fn read_value(&mut self) -> NbtResult<NbtValue> {
+        let mut stack: Vec<SnbtFrame> = Vec::new();
+        loop {
+            // 期望一个值
+            self.skip_whitespace();
+            let value_start = self.cursor;
+            let mut value = match self.peek() {
+                Some(b'{') | Some(b'[') if stack.len() >= RECURSE_LIMIT => {
+                    return Err(NbtError::NbtDepthTooBig(RECURSE_LIMIT));
+                }
+                Some(b'{') => {
+                    self.cursor += 1;
+                    self.skip_whitespace();
+                    if self.peek() == Some(b'}') {
+                        self.cursor += 1;
+                        NbtValue::Compound(None, Vec::new())
+                    } else {
+                        let key = self.read_key()?;
+                        self.expect(b':')?;
+                        stack.push(SnbtFrame::Compound(Vec::new(), Some(key)));
+                        continue;
+                    }
+                }
+                Some(b'[') => {
+                    let bytes = self.data.as_bytes();
+                    let array_type = bytes.get(self.cursor + 1).copied();
+                    if matches!(array_type, Some(b'B' | b'I' | b'L'))
+                        && bytes.get(self.cursor + 2) == Some(&b';')
+                    {
+                        self.cursor += 3;
+                        self.read_array(array_type.unwrap())?
+                    } else {
+                        self.cursor += 1;
+                        self.skip_whitespace();
+                        if self.peek() == Some(b']') {
+                            self.cursor += 1;
+                            NbtValue::List(Vec::new())
+                        } else {
+                            stack.push(SnbtFrame::List(Vec::new(), None));
+                            continue;
+                        }
+                    }
+                }
+                _ => self.read_scalar()?,
+            };
+            // 读完了一个值, 塞进上一层里
+            // 如果上一层也读完了, 就继续往上塞
+            let mut value_start = value_start;
+            loop {
+                let Some(frame) = stack.last_mut() else {
+                    return Ok(value);
+                };
+                match frame {
+                    SnbtFrame::List(values, list_type) => {
+                        let tag = value.tag();
+                        match list_type {
+                            Some(list_type) if *list_type != tag => {
+                                return Err(self.error_at(
+                                    value_start,
+                                    format!(
+                                        "List 里的类型不同: 期望 {}, 实际 {}",
+                                        list_type.as_nbt_type_name(),
+                                        tag.as_nbt_type_name()
+                                    ),
+                                ));
+                            }
+                            _ => *list_type = Some(tag),
+                        }
+                        values.push(value);
+                    }
+                    SnbtFrame::Compound(values, key) => {
+                        values.push((key.take().unwrap(), value));
+                    }
+                }
+                self.skip_whitespace();
+                let close = match frame {
+                    SnbtFrame::List(..) => b']',
+                    SnbtFrame::Compound(..) => b'}',
+                };
+                match self.peek() {
+                    Some(b',') => {
+                        self.cursor += 1;
+                        if let SnbtFrame::Compound(_, key) = frame {
+                            *key = Some(self.read_key()?);
+                            self.expect(b':')?;
+                        }
+                        // 继续读下一个值
+                        break;
+                    }
+                    Some(c) if c == close => {
+                        self.cursor += 1;
+                        value = match stack.pop().unwrap() {
+                            SnbtFrame::List(values, _) => NbtValue::List(values),
+                            SnbtFrame::Compound(values, _) => NbtValue::Compound(None, values),
+                        };
+                        // 出错时指向整个 List/Compound 的开头并不准确, 不过指向结尾也能看
+                        value_start = self.cursor - 1;
+                    }
+                    _ => {
+                        return Err(
+                            self.error_at(self.cursor, format!("期望 ',' 或 '{}'", close as char))
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// SNBT 的输出格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SnbtStyle {
+    /// `{a:1b,b:[1,2]}`
+    ///
+    /// 没有任何多余的空格, 跟原版的 `toString` 一样
+    Compact,
+    /// `{a: 1b, b: [1, 2]}`
+    ///
+    /// [`Display`](std::fmt::Display) 用的就是这个
+    Spaced,
+    /// 多行, 每一层缩进 n 个空格
+    ///
+    /// 数组不会换行
+    Pretty(usize),
+}
+
+/// 写一个带引号的字符串
+///
+/// 跟原版一样, 有 `"` 没有 `'` 的时候用 `'`, 其他时候都用 `"`
+///
+/// 只转义 `\` 和用到的引号
+pub fn write_quoted<W: Write>(w: &mut W, s: &str) -> fmt::Result {
+    let quote = if s.contains('"') && !s.contains('\'') {
+        '\''
+    } else {
+        '"'
+    };
+    w.write_char(quote)?;
+    for c in s.chars() {
+        if c == '\\' || c == quote {
+            w.write_char('\\')?;
+        }
+        w.write_char(c)?;
+    }
+    w.write_char(quote)
+}
+
+/// 写一个 key, 需要的时候才加引号
+pub fn write_key<W: Write>(w: &mut W, key: &str) -> fmt::Result {
+    if !key.is_empty() && key.bytes().all(is_unquoted_char) {
+        w.write_str(key)
+    } else {
+        write_quoted(w, key)
+    }
+}
+
+/// `{}` 和 `{:?}` 输出的都是能原样解析回来的最短表示
+///
+/// 区别只是 `{:?}` 在很大/很小的时候会用科学计数法, 所以取短的那个
+fn write_float<W: Write>(w: &mut W, display: String, debug: String, suffix: char) -> fmt::Result {
+    let value = if debug.len() < display.len() {
+        debug
+    } else {
+        display
+    };
+    write!(w, "{}{}", value, suffix)
+}
+
+/// 把一个 NbtValue 按照 `style` 写成 SNBT
+///
+/// 根节点 Compound 的名字不会写出来 (SNBT 里没有地方放)
+///
+/// NaN 和 Infinity 在 SNBT 里没有对应的写法, 原版也一样, 写出来也解析不回去
+pub fn write_snbt<W: Write>(w: &mut W, value: &NbtValue, style: SnbtStyle) -> fmt::Result {
+    write_snbt_inner(w, value, style, 0)
+}
+
+fn write_snbt_inner<W: Write>(
+    w: &mut W,
+    value: &NbtValue,
+    style: SnbtStyle,
+    depth: usize,
+) -> fmt::Result {
+    // List/Compound 的分隔符, key 后面的冒号, 数组的分隔符
+    let (sep, colon, array_sep) = match style {
+        SnbtStyle::Compact => (",", ":", ","),
+        SnbtStyle::Spaced => (", ", ": ", ", "),
+        SnbtStyle::Pretty(_) => (",", ": ", ", "),
+    };
+    let array_start = |w: &mut W, array_type: char| -> fmt::Result {
+        match style {
+            SnbtStyle::Compact => write!(w, "[{};", array_type),
+            _ => write!(w, "[{}; ", array_type),
+        }
+    };
+    // 多行的时候, 换行 + 缩进
+    let new_line = |w: &mut W, depth: usize| -> fmt::Result {
+        if let SnbtStyle::Pretty(indent) = style {
+            writeln!(w)?;
+            write!(w, "{:width$}", "", width = indent * depth)?;
+        }
+        Ok(())
+    };
+    match value {
+        NbtValue::Byte(v) => write!(w, "{}b", v),
+        NbtValue::Short(v) => write!(w, "{}s", v),
+        NbtValue::Int(v) => write!(w, "{}", v),
+        NbtValue::Long(v) => write!(w, "{}l", v),
+        NbtValue::Float(v) => write_float(w, format!("{}", v), format!("{:?}", v), 'f'),
+        NbtValue::Double(v) => write_float(w, format!("{}", v), format!("{:?}", v), 'd'),
+        NbtValue::ByteArray(v) => {
+            array_start(w, 'B')?;
+            for (i, v) in v.iter().enumerate() {
+                if i != 0 {
+                    w.write_str(array_sep)?;
+                }
+                write!(w, "{}b", v)?;
+            }
+            w.write_char(']')
+        }
+        NbtValue::IntArray(v) => {
+            array_start(w, 'I')?;
+            for (i, v) in v.iter().enumerate() {
+                if i != 0 {
+                    w.write_str(array_sep)?;
+                }
+                write!(w, "{}", v)?;
+            }
+            w.write_char(']')
+        }
+        NbtValue::LongArray(v) => {
+            array_start(w, 'L')?;
+            for (i, v) in v.iter().enumerate() {
+                if i != 0 {
+                    w.write_str(array_sep)?;
+                }
+                write!(w, "{}l", v)?;
+            }
+            w.write_char(']')
+        }
+        NbtValue::String(v) => write_quoted(w, &v.decode_lossy()),
+        NbtValue::List(lst) => {
+            w.write_char('[')?;
+            for (i, v) in lst.iter().enumerate() {
+                if i != 0 {
+                    w.write_str(sep)?;
+                }
+                new_line(w, depth + 1)?;
+                write_snbt_inner(w, v, style, depth + 1)?;
+            }
+            if !lst.is_empty() {
+                new_line(w, depth)?;
+            }
+            w.write_char(']')
+        }
+        NbtValue::Compound(_, map) => {
+            w.write_char('{')?;
+            for (i, (k, v)) in map.iter().enumerate() {
+                if i != 0 {
+                    w.write_str(sep)?;
+                }
+                new_line(w, depth + 1)?;
+                write_key(w, &k.decode_lossy())?;
+                w.write_str(colon)?;
+                write_snbt_inner(w, v, style, depth + 1)?;
+            }
+            if !map.is_empty() {
+                new_line(w, depth)?;
+            }
+            w.write_char('}')
+        }
+    }
+}
+
+impl NbtValue {
+    /// 解析 SNBT
+    ///
+    /// 支持:
+    /// - `1b` `2s` `3` `4l` `5.0f` `6.0d` (`7.0` 是 double)
+    /// - `[B; 1b, 2b]` `[I; 1, 2]` `[L; 1l, 2l]`
+    /// - 带引号和不带引号的 key
+    /// - `"` 和 `'` 的字符串
+    /// - `true` / `false` (会变成 byte)
+    ///
+    /// 根节点的 Compound 没有名字
+    pub fn from_snbt(data: &str) -> NbtResult<NbtValue> {
+        let mut reader = SnbtReader::new(data);
+        let value = reader.read_value()?;
+        reader.skip_whitespace();
+        if reader.cursor != data.len() {
+            return Err(reader.error_at(reader.cursor, "多余的内容"));
+        }
+        Ok(value)
+    }
+
+    /// 输出 SNBT, 不带任何多余的空格
+    pub fn to_snbt(&self) -> String { self.to_snbt_with_style(SnbtStyle::Compact) }
+
+    /// 输出多行缩进的 SNBT
+    pub fn to_snbt_pretty(&self, indent: usize) -> String {
+        self.to_snbt_with_style(SnbtStyle::Pretty(indent))
+    }
+
+    /// 按照 `style` 输出 SNBT
+    pub fn to_snbt_with_style(&self, style: SnbtStyle) -> String {
+        let mut result = String::new();
+        // UNWRAP safety: 往 String 里写不会失败
+        write_snbt(&mut result, self, style).unwrap();
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snbt_numbers() {
+        let parse = |s: &str| NbtValue::from_snbt(s).unwrap();
+        assert_eq!(parse("1b"), NbtValue::Byte(1));
+        assert_eq!(parse("-2S"), NbtValue::Short(-2));
+        assert_eq!(parse("+3"), NbtValue::Int(3));
+        assert_eq!(parse("4l"), NbtValue::Long(4));
+        assert_eq!(parse("5.5f"), NbtValue::Float(5.5));
+        assert_eq!(parse("6.5d"), NbtValue::Double(6.5));
+        assert_eq!(parse("7."), NbtValue::Double(7.0));
+        assert_eq!(parse(".5"), NbtValue::Double(0.5));
+        assert_eq!(parse("1e3f"), NbtValue::Float(1000.0));
+        assert_eq!(parse("1e3"), NbtValue::String("1e3".into()));
+        assert_eq!(parse("true"), NbtValue::Byte(1));
+        assert_eq!(parse("False"), NbtValue::Byte(0));
+        // 超出范围/不合法的数字都是字符串
+        assert_eq!(parse("2147483648"), NbtValue::String("2147483648".into()));
+        assert_eq!(parse("128b"), NbtValue::String("128b".into()));
+        assert_eq!(parse("01"), NbtValue::String("01".into()));
+    }
+
+    #[test]
+    fn snbt_compound() {
+        // 不允许多余的逗号
+        let value = NbtValue::from_snbt("{a: 1,\n}");
+        assert!(matches!(value, Err(NbtError::SnbtSyntax(2, 1, _))));
+
+        let value = NbtValue::from_snbt(
+            r#"{
+                name: "Bananrama",
+                'quoted key': 'it\'s',
+                "escape": "a\"b\\cé",
+                list: [{a: 1b}, {}],
+                empty: [],
+                nested: {a: [[1, 2], [3]]},
+                bytes: [B; 1b, -2B, true],
+                ints: [I; 1, -2],
+                longs: [L; 1l, 2L, 3]
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            value,
+            NbtValue::Compound(
+                None,
+                vec![
+                    ("name".into(), NbtValue::String("Bananrama".into())),
+                    ("quoted key".into(), NbtValue::String("it's".into())),
+                    ("escape".into(), NbtValue::String("a\"b\\cé".into())),
+                    (
+                        "list".into(),
+                        NbtValue::List(vec![
+                            NbtValue::Compound(None, vec![("a".into(), NbtValue::Byte(1))]),
+                            NbtValue::Compound(None, vec![]),
+                        ])
+                    ),
+                    ("empty".into(), NbtValue::List(vec![])),
+                    (
+                        "nested".into(),
+                        NbtValue::Compound(
+                            None,
+                            vec![(
+                                "a".into(),
+                                NbtValue::List(vec![
+                                    NbtValue::List(vec![NbtValue::Int(1), NbtValue::Int(2)]),
+                                    NbtValue::List(vec![NbtValue::Int(3)]),
+                                ])
+                            )]
+                        )
+                    ),
+                    ("bytes".into(), NbtValue::ByteArray(vec![1, -2, 1])),
+                    ("ints".into(), NbtValue::IntArray(vec![1, -2])),
+                    ("longs".into(), NbtValue::LongArray(vec![1, 2, 3])),
+                ]
+            )
+        );
+    }
+
+    #[test]
+    fn snbt_errors() {
+        let err = |s: &str| match NbtValue::from_snbt(s) {
+            Err(NbtError::SnbtSyntax(line, column, _)) => (line, column),
+            x => panic!("应该是语法错误: {:?}", x),
+        };
+        // List 里类型不同
+        assert_eq!(err("[1, 2b]"), (1, 5));
+        // 少了冒号
+        assert_eq!(err("{a 1}"), (1, 4));
+        // 字符串没结束
+        assert_eq!(err("{a: \"abc}"), (1, 5));
+        // 多行
+        assert_eq!(err("{\n  a: 1,\n  b: }"), (3, 6));
+        // 列按字符算
+        assert_eq!(err("{\"键\": 值}"), (1, 7));
+        // 数组里的类型不对
+        assert_eq!(err("[I; 1, 2l]"), (1, 8));
+        // 多余的内容
+        assert_eq!(err("{} {}"), (1, 4));
+        // 空的
+        assert_eq!(err("  "), (1, 3));
+    }
+
+    #[test]
+    fn snbt_depth_limit() {
+        let data = "[".repeat(RECURSE_LIMIT + 1);
+        assert_eq!(NbtValue::from_snbt(&data), Err(NbtError::NbtDepthTooBig(RECURSE_LIMIT)));
+    }
+
+    #[test]
+    fn snbt_display_round_trip() {
+        let value = NbtValue::Compound(
+            None,
+            vec![
+                ("byte".into(), NbtValue::Byte(1)),
+                ("short".into(), NbtValue::Short(2)),
+                ("int".into(), NbtValue::Int(3)),
+                ("long".into(), NbtValue::Long(4)),
+                ("float".into(), NbtValue::Float(5.5)),
+                ("double".into(), NbtValue::Double(6.5)),
+                ("byte_array".into(), NbtValue::ByteArray(vec![1, 2, 3])),
+                ("int_array".into(), NbtValue::IntArray(vec![1, 2, 3])),
+                ("long_array".into(), NbtValue::LongArray(vec![1, 2, 3])),
+                ("string".into(), NbtValue::String("test".into())),
+                ("list".into(), NbtValue::List(vec![NbtValue::Int(1), NbtValue::Int(2)])),
+            ],
+        );
+        assert_eq!(NbtValue::from_snbt(&value.to_string()).unwrap(), value);
+    }
+
+    #[test]
+    fn snbt_big_test_round_trip() {
+        let value = NbtValue::from_binary::<crate::nbt_version::Java>(&crate::tests::BIG_TEST_DATA)
+            .unwrap();
+        // SNBT 里没有根节点的名字
+        let value = match value {
+            NbtValue::Compound(_, values) => NbtValue::Compound(None, values),
+            _ => unreachable!(),
+        };
+        for style in [SnbtStyle::Compact, SnbtStyle::Spaced, SnbtStyle::Pretty(4)] {
+            let snbt = value.to_snbt_with_style(style);
+            assert!(snbt.contains("\"nested compound test\""));
+            assert_eq!(NbtValue::from_snbt(&snbt).unwrap(), value);
+        }
+    }
+
+    #[test]
+    fn snbt_quote_and_escape() {
+        let value = NbtValue::Compound(
+            Some("root".into()),
+            vec![
+                ("a b".into(), NbtValue::String("say \"hi\"".into())),
+                ("c:d".into(), NbtValue::String("it's \"quoted\"".into())),
+                ("".into(), NbtValue::String("back\\slash".into())),
+                ("ok_key-1.2+3".into(), NbtValue::String("".into())),
+            ],
+        );
+        let snbt = value.to_snbt();
+        assert_eq!(
+            snbt,
+            r#"{"a b":'say "hi"',"c:d":"it's \"quoted\"","":"back\\slash",ok_key-1.2+3:""}"#
+        );
+        assert_eq!(
+            NbtValue::from_snbt(&snbt).unwrap(),
+            NbtValue::Compound(
+                None,
+                match value {
+                    NbtValue::Compound(_, values) => values,
+                    _ => unreachable!(),
+                }
+            )
+        );
+    }
+
+    #[test]
+    fn snbt_pretty() {
+        let value =
+            NbtValue::from_snbt("{a: [1, 2], b: {}, c: [], d: [B; 1b, 2b], e: {f: 1s}}").unwrap();
+        assert_eq!(
+            value.to_snbt_pretty(2),
+            "{\n  a: [\n    1,\n    2\n  ],\n  b: {},\n  c: [],\n  d: [B; 1b, 2b],\n  e: {\n    f: 1s\n  }\n}"
+        );
+        assert_eq!(value.to_snbt(), "{a:[1,2],b:{},c:[],d:[B;1b,2b],e:{f:1s}}");
+    }
+
+    #[test]
+    fn snbt_float_round_trip() {
+        let floats = [0.1, 1.0 / 3.0, f32::MAX, f32::MIN_POSITIVE, 1e-40, -0.0, 16777217.0];
+        for f in floats {
+            let snbt = NbtValue::Float(f).to_snbt();
+            match NbtValue::from_snbt(&snbt).unwrap() {
+                NbtValue::Float(parsed) => assert_eq!(parsed.to_bits(), f.to_bits(), "{}", snbt),
+                x => panic!("{} 解析成了 {:?}", snbt, x),
+            }
+        }
+        let doubles = [0.1, 1.0 / 3.0, f64::MAX, f64::MIN_POSITIVE, 5e-324, -0.0, 1e100];
+        for d in doubles {
+            let snbt = NbtValue::Double(d).to_snbt();
+            match NbtValue::from_snbt(&snbt).unwrap() {
+                NbtValue::Double(parsed) => assert_eq!(parsed.to_bits(), d.to_bits(), "{}", snbt),
+                x => panic!("{} 解析成了 {:?}", snbt, x),
+            }
+        }
+        assert_eq!(NbtValue::Float(1e30).to_snbt(), "1e30f");
+        assert_eq!(NbtValue::Double(5.0).to_snbt(), "5d");
+    }
+}
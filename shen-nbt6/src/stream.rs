@@ -0,0 +1,399 @@
+//! 从 [`std::io::Read`] 里一点一点读 NBT
+//!
+//! 跟 [`NbtReader`](crate::NbtReader) 不一样, 不需要先把所有数据读进内存
+//!
+//! 每个值只会读它自己需要的字节, 读完根节点之后后面的数据不会动
+//!
+//! 会有很多次很小的 `read`, 读文件或者 socket 的时候最好套一层 `BufReader`
+//!
+//! ```rust
+//! use shen_nbt6::nbt_version::Java;
+//! use shen_nbt6::NbtValue;
+//!
+//! let mut input: &[u8] = &[0x0A, 0x00, 0x00, 0x01, 0x00, 0x01, b'a', 0x7F, 0x00, 0xFF];
+//! let value = NbtValue::from_stream::<Java>(&mut input).unwrap();
+//! assert_eq!(value, NbtValue::Compound(Some("".into()), vec![("a".into(), NbtValue::Byte(127))]));
+//! // 根节点后面的数据还在
+//! assert_eq!(input, &[0xFF]);
+//! ```
+
+use std::io::{self, Read};
+
+use crate::nbt_version::{BedrockDisk, BedrockNetVarInt, Java, JavaNetAfter1_20_2};
+use crate::reader::{decode_var_i32, decode_var_i64, zigzag_i32, zigzag_i64};
+use crate::traits::NbtStreamReadTrait;
+use crate::{nbt_consts, NbtError, NbtResult};
+
+/// 从 [`Read`] 里读取 NBT 数据
+pub struct NbtStreamReader<R> {
+    inner: R,
+    /// 已经读了多少字节
+    pub cursor: usize,
+}
+
+macro_rules! read_number {
+    ($be_name:ident, $le_name:ident, $ty:ty) => {
+        #[doc = concat!("读取一个大端 ", stringify!($ty))]
+        #[inline]
+        pub fn $be_name(&mut self) -> NbtResult<$ty> {
+            Ok(<$ty>::from_be_bytes(self.read_array()?))
+        }
+        #[doc = concat!("读取一个小端 ", stringify!($ty))]
+        #[inline]
+        pub fn $le_name(&mut self) -> NbtResult<$ty> {
+            Ok(<$ty>::from_le_bytes(self.read_array()?))
+        }
+    };
+}
+
+impl<R: Read> NbtStreamReader<R> {
+    pub fn new(inner: R) -> Self { Self { inner, cursor: 0 } }
+
+    /// 拿回里面的 reader
+    pub fn into_inner(self) -> R { self.inner }
+
+    /// 数据不够的时候返回 [`NbtError::UnexpectedEof`]
+    fn map_io_err(&self, e: io::Error) -> NbtError {
+        match e.kind() {
+            io::ErrorKind::UnexpectedEof => NbtError::UnexpectedEof(self.cursor),
+            _ => e.into(),
+        }
+    }
+
+    /// 读取 `N` 个字节
+    #[inline]
+    pub fn read_array<const N: usize>(&mut self) -> NbtResult<[u8; N]> {
+        let mut buf = [0; N];
+        if let Err(e) = self.inner.read_exact(&mut buf) {
+            return Err(self.map_io_err(e));
+        }
+        self.cursor += N;
+        Ok(buf)
+    }
+
+    /// 读取 `len` 个字节
+    ///
+    /// 不会按照 `len` 提前分配内存, 防止一个假的长度直接把内存吃满
+    pub fn read_bytes(&mut self, len: usize) -> NbtResult<Vec<u8>> {
+        let mut data = Vec::new();
+        let read = (&mut self.inner).take(len as u64).read_to_end(&mut data);
+        self.cursor += data.len();
+        if let Err(e) = read {
+            return Err(self.map_io_err(e));
+        }
+        if data.len() < len {
+            return Err(NbtError::UnexpectedEof(self.cursor));
+        }
+        Ok(data)
+    }
+
+    /// 读取一个 u8 类型的数据
+    #[inline]
+    pub fn read_u8(&mut self) -> NbtResult<u8> { Ok(self.read_array::<1>()?[0]) }
+    /// 读取一个 i8 类型的数据
+    #[inline]
+    pub fn read_i8(&mut self) -> NbtResult<i8> { Ok(self.read_u8()? as i8) }
+
+    read_number!(read_be_i16, read_le_i16, i16);
+    read_number!(read_be_u16, read_le_u16, u16);
+    read_number!(read_be_i32, read_le_i32, i32);
+    read_number!(read_be_i64, read_le_i64, i64);
+    read_number!(read_be_f32, read_le_f32, f32);
+    read_number!(read_be_f64, read_le_f64, f64);
+
+    /// 读取一个 VarInt
+    ///
+    /// 超过 5 个字节时返回 [`NbtError::VarIntTooBig`]
+    #[inline]
+    pub fn read_var_i32(&mut self) -> NbtResult<i32> { decode_var_i32(|| self.read_u8()) }
+    /// 读取一个 VarLong
+    ///
+    /// 超过 10 个字节时返回 [`NbtError::VarlongTooBig`]
+    #[inline]
+    pub fn read_var_i64(&mut self) -> NbtResult<i64> { decode_var_i64(|| self.read_u8()) }
+    /// 读取一个 zigzag 编码的 VarInt
+    #[inline]
+    pub fn read_zigzag_var_i32(&mut self) -> NbtResult<i32> { Ok(zigzag_i32(self.read_var_i32()?)) }
+    /// 读取一个 zigzag 编码的 VarLong
+    #[inline]
+    pub fn read_zigzag_var_i64(&mut self) -> NbtResult<i64> { Ok(zigzag_i64(self.read_var_i64()?)) }
+}
+
+/// Java 版 绝大部分的 NBT 格式
+///
+/// 全都是大端
+impl NbtStreamReadTrait for Java {
+    #[inline]
+    fn read_i16<R: Read>(reader: &mut NbtStreamReader<R>) -> NbtResult<i16> { reader.read_be_i16() }
+    #[inline]
+    fn read_i32<R: Read>(reader: &mut NbtStreamReader<R>) -> NbtResult<i32> { reader.read_be_i32() }
+    #[inline]
+    fn read_i64<R: Read>(reader: &mut NbtStreamReader<R>) -> NbtResult<i64> { reader.read_be_i64() }
+    #[inline]
+    fn read_f32<R: Read>(reader: &mut NbtStreamReader<R>) -> NbtResult<f32> { reader.read_be_f32() }
+    #[inline]
+    fn read_f64<R: Read>(reader: &mut NbtStreamReader<R>) -> NbtResult<f64> { reader.read_be_f64() }
+    #[inline]
+    fn read_len<R: Read>(reader: &mut NbtStreamReader<R>) -> NbtResult<i32> { reader.read_be_i32() }
+    #[inline]
+    fn read_str_len<R: Read>(reader: &mut NbtStreamReader<R>) -> NbtResult<usize> {
+        Ok(reader.read_be_u16()? as usize)
+    }
+    #[inline]
+    fn read_i32_array<R: Read>(reader: &mut NbtStreamReader<R>) -> NbtResult<Vec<i32>> {
+        let len = Self::read_checked_len(reader, nbt_consts::TAG_INT_ARRAY)?;
+        let data = reader.read_bytes(len * 4)?;
+        Ok(data
+            .chunks_exact(4)
+            .map(|n| i32::from_be_bytes(n.try_into().unwrap()))
+            .collect())
+    }
+    #[inline]
+    fn read_i64_array<R: Read>(reader: &mut NbtStreamReader<R>) -> NbtResult<Vec<i64>> {
+        let len = Self::read_checked_len(reader, nbt_consts::TAG_LONG_ARRAY)?;
+        let data = reader.read_bytes(len * 8)?;
+        Ok(data
+            .chunks_exact(8)
+            .map(|n| i64::from_be_bytes(n.try_into().unwrap()))
+            .collect())
+    }
+}
+
+/// 网络 NBT: 1.20.2+ 的网络 NBT 根节点没有名字
+impl NbtStreamReadTrait for JavaNetAfter1_20_2 {
+    const ROOT_NAMED: bool = false;
+
+    #[inline]
+    fn read_i16<R: Read>(reader: &mut NbtStreamReader<R>) -> NbtResult<i16> {
+        Java::read_i16(reader)
+    }
+    #[inline]
+    fn read_i32<R: Read>(reader: &mut NbtStreamReader<R>) -> NbtResult<i32> {
+        Java::read_i32(reader)
+    }
+    #[inline]
+    fn read_i64<R: Read>(reader: &mut NbtStreamReader<R>) -> NbtResult<i64> {
+        Java::read_i64(reader)
+    }
+    #[inline]
+    fn read_f32<R: Read>(reader: &mut NbtStreamReader<R>) -> NbtResult<f32> {
+        Java::read_f32(reader)
+    }
+    #[inline]
+    fn read_f64<R: Read>(reader: &mut NbtStreamReader<R>) -> NbtResult<f64> {
+        Java::read_f64(reader)
+    }
+    #[inline]
+    fn read_len<R: Read>(reader: &mut NbtStreamReader<R>) -> NbtResult<i32> {
+        Java::read_len(reader)
+    }
+    #[inline]
+    fn read_str_len<R: Read>(reader: &mut NbtStreamReader<R>) -> NbtResult<usize> {
+        Java::read_str_len(reader)
+    }
+    #[inline]
+    fn read_i32_array<R: Read>(reader: &mut NbtStreamReader<R>) -> NbtResult<Vec<i32>> {
+        Java::read_i32_array(reader)
+    }
+    #[inline]
+    fn read_i64_array<R: Read>(reader: &mut NbtStreamReader<R>) -> NbtResult<Vec<i64>> {
+        Java::read_i64_array(reader)
+    }
+}
+
+/// 基岩版 实际用于存储的 NBT 格式
+///
+/// 全都是小端
+impl NbtStreamReadTrait for BedrockDisk {
+    const LIST_ROOT: bool = true;
+    const MUTF8_STRING: bool = false;
+
+    #[inline]
+    fn read_i16<R: Read>(reader: &mut NbtStreamReader<R>) -> NbtResult<i16> { reader.read_le_i16() }
+    #[inline]
+    fn read_i32<R: Read>(reader: &mut NbtStreamReader<R>) -> NbtResult<i32> { reader.read_le_i32() }
+    #[inline]
+    fn read_i64<R: Read>(reader: &mut NbtStreamReader<R>) -> NbtResult<i64> { reader.read_le_i64() }
+    #[inline]
+    fn read_f32<R: Read>(reader: &mut NbtStreamReader<R>) -> NbtResult<f32> { reader.read_le_f32() }
+    #[inline]
+    fn read_f64<R: Read>(reader: &mut NbtStreamReader<R>) -> NbtResult<f64> { reader.read_le_f64() }
+    #[inline]
+    fn read_len<R: Read>(reader: &mut NbtStreamReader<R>) -> NbtResult<i32> { reader.read_le_i32() }
+    #[inline]
+    fn read_str_len<R: Read>(reader: &mut NbtStreamReader<R>) -> NbtResult<usize> {
+        Ok(reader.read_le_u16()? as usize)
+    }
+    #[inline]
+    fn read_i32_array<R: Read>(reader: &mut NbtStreamReader<R>) -> NbtResult<Vec<i32>> {
+        let len = Self::read_checked_len(reader, nbt_consts::TAG_INT_ARRAY)?;
+        let data = reader.read_bytes(len * 4)?;
+        Ok(data
+            .chunks_exact(4)
+            .map(|n| i32::from_le_bytes(n.try_into().unwrap()))
+            .collect())
+    }
+    #[inline]
+    fn read_i64_array<R: Read>(reader: &mut NbtStreamReader<R>) -> NbtResult<Vec<i64>> {
+        let len = Self::read_checked_len(reader, nbt_consts::TAG_LONG_ARRAY)?;
+        let data = reader.read_bytes(len * 8)?;
+        Ok(data
+            .chunks_exact(8)
+            .map(|n| i64::from_le_bytes(n.try_into().unwrap()))
+            .collect())
+    }
+}
+
+/// 基岩版 网络 NBT 格式
+///
+/// - Short, Float, Double 都是小端
+/// - Int 和 List/Array 的长度都是 zigzag VarInt
+/// - Long 是 zigzag VarLong
+/// - 字符串长度是 VarInt
+/// - IntArray/LongArray 的元素还是定长小端
+impl NbtStreamReadTrait for BedrockNetVarInt {
+    const LIST_ROOT: bool = true;
+    const MUTF8_STRING: bool = false;
+
+    #[inline]
+    fn read_i16<R: Read>(reader: &mut NbtStreamReader<R>) -> NbtResult<i16> { reader.read_le_i16() }
+    #[inline]
+    fn read_i32<R: Read>(reader: &mut NbtStreamReader<R>) -> NbtResult<i32> {
+        reader.read_zigzag_var_i32()
+    }
+    #[inline]
+    fn read_i64<R: Read>(reader: &mut NbtStreamReader<R>) -> NbtResult<i64> {
+        reader.read_zigzag_var_i64()
+    }
+    #[inline]
+    fn read_f32<R: Read>(reader: &mut NbtStreamReader<R>) -> NbtResult<f32> { reader.read_le_f32() }
+    #[inline]
+    fn read_f64<R: Read>(reader: &mut NbtStreamReader<R>) -> NbtResult<f64> { reader.read_le_f64() }
+    #[inline]
+    fn read_len<R: Read>(reader: &mut NbtStreamReader<R>) -> NbtResult<i32> {
+        reader.read_zigzag_var_i32()
+    }
+    #[inline]
+    fn read_str_len<R: Read>(reader: &mut NbtStreamReader<R>) -> NbtResult<usize> {
+        let len = reader.read_var_i32()?;
+        if len < 0 {
+            return Err(NbtError::LenNegative(nbt_consts::TAG_STRING, len, reader.cursor));
+        }
+        Ok(len as usize)
+    }
+    #[inline]
+    fn read_i32_array<R: Read>(reader: &mut NbtStreamReader<R>) -> NbtResult<Vec<i32>> {
+        let len = Self::read_checked_len(reader, nbt_consts::TAG_INT_ARRAY)?;
+        let data = reader.read_bytes(len * 4)?;
+        Ok(data
+            .chunks_exact(4)
+            .map(|n| i32::from_le_bytes(n.try_into().unwrap()))
+            .collect())
+    }
+    #[inline]
+    fn read_i64_array<R: Read>(reader: &mut NbtStreamReader<R>) -> NbtResult<Vec<i64>> {
+        let len = Self::read_checked_len(reader, nbt_consts::TAG_LONG_ARRAY)?;
+        let data = reader.read_bytes(len * 8)?;
+        Ok(data
+            .chunks_exact(8)
+            .map(|n| i64::from_le_bytes(n.try_into().unwrap()))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{
+        BIG_TEST_BEDROCK_DATA, BIG_TEST_BEDROCK_NET_DATA, BIG_TEST_DATA, COMPLEX_PLR_DATA,
+        HELLO_WORLD_BEDROCK_DATA, HELLO_WORLD_BEDROCK_NET_DATA, HELLO_WORLD_DATA,
+    };
+    use crate::NbtValue;
+
+    /// 每次只给一个字节的 reader
+    struct OneByte<'a>(&'a [u8]);
+
+    impl Read for OneByte<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            if self.0.is_empty() || buf.is_empty() {
+                return Ok(0);
+            }
+            buf[0] = self.0[0];
+            self.0 = &self.0[1..];
+            Ok(1)
+        }
+    }
+
+    fn same_as_binary<R>(data: &[u8])
+    where
+        R: NbtStreamReadTrait + crate::traits::NbtReadTrait,
+    {
+        let value = NbtValue::from_binary::<R>(data).unwrap();
+        assert_eq!(NbtValue::from_stream::<R>(data).unwrap(), value);
+        assert_eq!(NbtValue::from_stream::<R>(OneByte(data)).unwrap(), value);
+        // 少一个字节都不行
+        for len in 0..data.len() {
+            assert!(
+                matches!(NbtValue::from_stream::<R>(&data[..len]), Err(NbtError::UnexpectedEof(_))),
+                "{}",
+                len
+            );
+        }
+    }
+
+    #[test]
+    fn stream_same_as_binary() {
+        same_as_binary::<Java>(&HELLO_WORLD_DATA);
+        same_as_binary::<Java>(&BIG_TEST_DATA);
+        same_as_binary::<Java>(&COMPLEX_PLR_DATA);
+        same_as_binary::<BedrockDisk>(&HELLO_WORLD_BEDROCK_DATA);
+        same_as_binary::<BedrockDisk>(&BIG_TEST_BEDROCK_DATA);
+        same_as_binary::<BedrockNetVarInt>(&HELLO_WORLD_BEDROCK_NET_DATA);
+        same_as_binary::<BedrockNetVarInt>(&BIG_TEST_BEDROCK_NET_DATA);
+        same_as_binary::<JavaNetAfter1_20_2>(&[0x0A, 0x01, 0x00, 0x01, 0x61, 0x7F, 0x00]);
+    }
+
+    #[test]
+    fn stream_leaves_rest() {
+        let mut data = BIG_TEST_DATA.to_vec();
+        data.extend(HELLO_WORLD_DATA);
+        let mut reader = NbtStreamReader::new(data.as_slice());
+        let big = Java::from_stream(&mut reader).unwrap();
+        assert_eq!(reader.cursor, BIG_TEST_DATA.len());
+        let hello = Java::from_stream(&mut reader).unwrap();
+        assert_eq!(big, NbtValue::from_binary::<Java>(&BIG_TEST_DATA).unwrap());
+        assert_eq!(hello, NbtValue::from_binary::<Java>(&HELLO_WORLD_DATA).unwrap());
+        assert!(reader.into_inner().is_empty());
+    }
+
+    #[test]
+    fn stream_errors() {
+        assert_eq!(NbtValue::from_stream::<Java>(&[0x09][..]), Err(NbtError::WrongRootType(9)));
+        // 假的超长数组不会把内存吃满
+        let data = [0x0A, 0x00, 0x00, 0x0B, 0x00, 0x01, 0x61, 0x7F, 0xFF, 0xFF, 0xFF];
+        assert_eq!(NbtValue::from_stream::<Java>(&data[..]), Err(NbtError::UnexpectedEof(11)));
+        let data = [0x0A, 0x00, 0x00, 0x07, 0x00, 0x01, 0x61, 0xFF, 0xFF, 0xFF, 0xFF, 0x00];
+        assert_eq!(
+            NbtValue::from_stream::<Java>(&data[..]),
+            Err(NbtError::LenNegative(nbt_consts::TAG_BYTE_ARRAY, -1, 11))
+        );
+        let mut data = vec![0x09];
+        for _ in 0..(crate::RECURSE_LIMIT + 10) {
+            data.extend([0x09, 0x02]);
+        }
+        assert_eq!(
+            NbtValue::from_stream::<BedrockNetVarInt>(data.as_slice()),
+            Err(NbtError::NbtDepthTooBig(crate::RECURSE_LIMIT))
+        );
+        // VarInt 的长度限制跟 NbtReader 一样
+        let data = [0x0A, 0x00, 0x03, 0x01, 0x61, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x01, 0x00];
+        assert_eq!(
+            NbtValue::from_stream::<BedrockNetVarInt>(&data[..]),
+            NbtValue::from_binary::<BedrockNetVarInt>(&data)
+        );
+        assert_eq!(
+            NbtValue::from_stream::<BedrockNetVarInt>(&data[..]),
+            Err(NbtError::VarIntTooBig(6))
+        );
+    }
+}
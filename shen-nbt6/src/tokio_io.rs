@@ -0,0 +1,470 @@
+//! tokio 的异步读写
+//!
+//! 只支持网络用的两种格式: [`JavaNetAfter1_20_2`](crate::nbt_version::JavaNetAfter1_20_2) 和 [`BedrockNetVarInt`](crate::nbt_version::BedrockNetVarInt)
+//!
+//! - [`read_nbt`](crate::tokio_io::read_nbt)/[`write_nbt`](crate::tokio_io::write_nbt):
+//!   直接从 `AsyncRead` 读/往 `AsyncWrite` 写一个 NBT
+//! - [`NbtFrameCodec`](crate::tokio_io::NbtFrameCodec): `tokio_util` 的 `Decoder`/`Encoder`,
+//!   每一帧是 VarInt 长度 + NBT
+//!
+//! 读取的时候会先按照格式把这个 NBT 的字节原样读出来 (只读需要的部分), 再交给
+//! [`NbtValue::from_binary`](crate::NbtValue::from_binary)
+//!
+//! ```rust
+//! use shen_nbt6::nbt_version::JavaNetAfter1_20_2;
+//! use shen_nbt6::{nbt, tokio_io};
+//!
+//! # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+//! let value = nbt!({ "name": "Bananrama" });
+//! let mut buff = Vec::new();
+//! tokio_io::write_nbt::<JavaNetAfter1_20_2, _>(&mut buff, &value).await.unwrap();
+//! let read = tokio_io::read_nbt::<JavaNetAfter1_20_2, _>(&mut buff.as_slice()).await.unwrap();
+//! assert_eq!(read, value);
+//! # });
+//! ```
+
+use std::io;
+use std::marker::PhantomData;
+
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio_util::bytes::{Buf, BytesMut};
+use tokio_util::codec::{Decoder, Encoder};
+
+use crate::nbt_version::{BedrockNetVarInt, JavaNetAfter1_20_2};
+use crate::traits::{NbtReadTrait, NbtTypeConversion, NbtWriteTrait};
+use crate::writer::write_var_u32;
+use crate::{nbt_consts, NbtError, NbtResult, NbtTypeId, NbtValue, RECURSE_LIMIT};
+
+/// 可以异步读写的 NBT 格式
+pub trait NbtAsyncTrait: NbtReadTrait + NbtWriteTrait {
+    /// int/long 和各种长度是不是 VarInt
+    const VAR_INT: bool;
+}
+
+impl NbtAsyncTrait for JavaNetAfter1_20_2 {
+    const VAR_INT: bool = false;
+}
+
+impl NbtAsyncTrait for BedrockNetVarInt {
+    const VAR_INT: bool = true;
+}
+
+/// 把读到的字节原样存下来的 reader
+struct RawReader<'a, R> {
+    inner: &'a mut R,
+    data: Vec<u8>,
+}
+
+impl<R: AsyncRead + Unpin> RawReader<'_, R> {
+    fn map_io_err(&self, e: io::Error) -> NbtError {
+        match e.kind() {
+            io::ErrorKind::UnexpectedEof => NbtError::UnexpectedEof(self.data.len()),
+            _ => e.into(),
+        }
+    }
+
+    async fn read_u8(&mut self) -> NbtResult<u8> {
+        match self.inner.read_u8().await {
+            Ok(byte) => {
+                self.data.push(byte);
+                Ok(byte)
+            }
+            Err(e) => Err(self.map_io_err(e)),
+        }
+    }
+
+    /// 读取 `len` 个字节
+    ///
+    /// 不会按照 `len` 提前分配内存, 防止一个假的长度直接把内存吃满
+    async fn read_bytes(&mut self, len: usize) -> NbtResult<()> {
+        let start = self.data.len();
+        let read = (&mut *self.inner).take(len as u64).read_to_end(&mut self.data).await;
+        if let Err(e) = read {
+            return Err(self.map_io_err(e));
+        }
+        if self.data.len() - start < len {
+            return Err(NbtError::UnexpectedEof(self.data.len()));
+        }
+        Ok(())
+    }
+
+    /// 读取一个最多 `max_size` 个字节的 VarInt/VarLong
+    async fn read_var(&mut self, max_size: usize) -> NbtResult<u64> {
+        let mut value: u64 = 0;
+        let mut size = 0;
+        loop {
+            if size >= max_size {
+                return Err(match max_size {
+                    5 => NbtError::VarIntTooBig(size + 1),
+                    _ => NbtError::VarlongTooBig(size + 1),
+                });
+            }
+            let byte = self.read_u8().await?;
+            value |= ((byte & 0b0111_1111) as u64) << (size * 7);
+            size += 1;
+            if (byte & 0b1000_0000) == 0 {
+                return Ok(value);
+            }
+        }
+    }
+
+    /// 读取 List/Array 的长度
+    async fn read_len<V: NbtAsyncTrait>(&mut self, type_id: NbtTypeId) -> NbtResult<usize> {
+        let len = match V::VAR_INT {
+            true => {
+                let value = self.read_var(5).await? as u32;
+                ((value >> 1) as i32) ^ (-((value & 1) as i32))
+            }
+            false => {
+                self.read_bytes(4).await?;
+                let end = self.data.len();
+                i32::from_be_bytes(self.data[end - 4..].try_into().unwrap())
+            }
+        };
+        if len < 0 {
+            return Err(NbtError::LenNegative(type_id, len, self.data.len()));
+        }
+        Ok(len as usize)
+    }
+
+    /// 读取一个字符串
+    async fn read_string<V: NbtAsyncTrait>(&mut self) -> NbtResult<()> {
+        let len = match V::VAR_INT {
+            true => {
+                let len = self.read_var(5).await? as u32 as i32;
+                if len < 0 {
+                    let cursor = self.data.len();
+                    return Err(NbtError::LenNegative(nbt_consts::TAG_STRING, len, cursor));
+                }
+                len as usize
+            }
+            false => {
+                self.read_bytes(2).await?;
+                let end = self.data.len();
+                u16::from_be_bytes([self.data[end - 2], self.data[end - 1]]) as usize
+            }
+        };
+        self.read_bytes(len).await
+    }
+
+    /// 读取一个非 List/Compound 的值
+    async fn read_primitive<V: NbtAsyncTrait>(&mut self, type_id: NbtTypeId) -> NbtResult<()> {
+        match type_id {
+            nbt_consts::TAG_INT if V::VAR_INT => self.read_var(5).await.map(|_| ()),
+            nbt_consts::TAG_LONG if V::VAR_INT => self.read_var(10).await.map(|_| ()),
+            nbt_consts::TAG_BYTE => self.read_bytes(1).await,
+            nbt_consts::TAG_SHORT => self.read_bytes(2).await,
+            nbt_consts::TAG_INT | nbt_consts::TAG_FLOAT => self.read_bytes(4).await,
+            nbt_consts::TAG_LONG | nbt_consts::TAG_DOUBLE => self.read_bytes(8).await,
+            nbt_consts::TAG_STRING => self.read_string::<V>().await,
+            nbt_consts::TAG_BYTE_ARRAY | nbt_consts::TAG_INT_ARRAY | nbt_consts::TAG_LONG_ARRAY => {
+                let len = self.read_len::<V>(type_id).await?;
+                let size = match type_id {
+                    nbt_consts::TAG_BYTE_ARRAY => 1,
+                    nbt_consts::TAG_INT_ARRAY => 4,
+                    _ => 8,
+                };
+                self.read_bytes(len.saturating_mul(size)).await
+            }
+            x => Err(NbtError::UnknownType(x, self.data.len())),
+        }
+    }
+}
+
+/// 读的时候的栈
+enum RawFrame {
+    /// List 里面的类型, 还剩多少个
+    List(NbtTypeId, usize),
+    Compound,
+}
+
+impl RawFrame {
+    async fn open<V: NbtAsyncTrait, R: AsyncRead + Unpin>(
+        reader: &mut RawReader<'_, R>,
+        type_id: NbtTypeId,
+    ) -> NbtResult<Self> {
+        if type_id == nbt_consts::TAG_COMPOUND {
+            return Ok(RawFrame::Compound);
+        }
+        let lst_type = reader.read_u8().await?;
+        if !lst_type.is_valid_nbt_type() {
+            return Err(NbtError::UnknownType(lst_type, reader.data.len()));
+        }
+        let len = reader.read_len::<V>(lst_type).await?;
+        if lst_type == nbt_consts::TAG_END {
+            // TAG_End 的 list 里面不会有东西
+            return Ok(RawFrame::List(lst_type, 0));
+        }
+        Ok(RawFrame::List(lst_type, len))
+    }
+}
+
+/// 把一个 NBT 的字节原样读出来
+async fn read_raw<V: NbtAsyncTrait, R: AsyncRead + Unpin>(reader: &mut R) -> NbtResult<Vec<u8>> {
+    let mut reader = RawReader {
+        inner: reader,
+        data: Vec::new(),
+    };
+    let root = match reader.read_u8().await? {
+        nbt_consts::TAG_COMPOUND => {
+            if <V as NbtReadTrait>::ROOT_NAMED {
+                reader.read_string::<V>().await?;
+            }
+            RawFrame::Compound
+        }
+        nbt_consts::TAG_LIST if <V as NbtReadTrait>::LIST_ROOT => {
+            RawFrame::open::<V, R>(&mut reader, nbt_consts::TAG_LIST).await?
+        }
+        x => return Err(NbtError::WrongRootType(x)),
+    };
+    let mut stack = vec![root];
+    while let Some(current) = stack.last_mut() {
+        let next_type = match current {
+            RawFrame::List(_, 0) => None,
+            RawFrame::List(lst_type, remain) => {
+                *remain -= 1;
+                Some(*lst_type)
+            }
+            RawFrame::Compound => match reader.read_u8().await? {
+                nbt_consts::TAG_END => None,
+                value_type => {
+                    reader.read_string::<V>().await?;
+                    Some(value_type)
+                }
+            },
+        };
+        match next_type {
+            None => {
+                stack.pop();
+            }
+            Some(value_type) if value_type.is_list_or_compound() => {
+                if stack.len() >= RECURSE_LIMIT {
+                    return Err(NbtError::NbtDepthTooBig(RECURSE_LIMIT));
+                }
+                stack.push(RawFrame::open::<V, R>(&mut reader, value_type).await?);
+            }
+            Some(value_type) => reader.read_primitive::<V>(value_type).await?,
+        }
+    }
+    Ok(reader.data)
+}
+
+/// 从 `reader` 里读一个 NBT
+///
+/// 只会读这个 NBT 需要的字节, 数据不够的时候返回 [`NbtError::UnexpectedEof`]
+pub async fn read_nbt<V, R>(reader: &mut R) -> NbtResult<NbtValue>
+where
+    V: NbtAsyncTrait,
+    R: AsyncRead + Unpin,
+{
+    let data = read_raw::<V, R>(reader).await?;
+    NbtValue::from_binary::<V>(&data)
+}
+
+/// 往 `writer` 里写一个 NBT
+///
+/// 会先在内存里编码好再一次性写进去
+pub async fn write_nbt<V, W>(writer: &mut W, value: &NbtValue) -> NbtResult<()>
+where
+    V: NbtAsyncTrait,
+    W: AsyncWrite + Unpin,
+{
+    let data = V::to_bytes(value)?;
+    writer.write_all(&data).await?;
+    Ok(())
+}
+
+/// `tokio_util` 的 codec, 每一帧是一个 VarInt 长度加上一个 NBT
+///
+/// 长度超过 `max_len` 的帧会返回 [`NbtError::LenTooBig`]
+pub struct NbtFrameCodec<V> {
+    max_len: usize,
+    _version: PhantomData<V>,
+}
+
+impl<V> NbtFrameCodec<V> {
+    /// 默认的最大帧长度, 跟原版的数据包一样 (3 个字节的 VarInt)
+    pub const DEFAULT_MAX_LEN: usize = 2097151;
+
+    pub fn new() -> Self { Self::with_max_len(Self::DEFAULT_MAX_LEN) }
+
+    pub fn with_max_len(max_len: usize) -> Self {
+        Self {
+            max_len,
+            _version: PhantomData,
+        }
+    }
+
+    pub fn max_len(&self) -> usize { self.max_len }
+}
+
+impl<V> Default for NbtFrameCodec<V> {
+    fn default() -> Self { Self::new() }
+}
+
+impl<V> Clone for NbtFrameCodec<V> {
+    fn clone(&self) -> Self { Self::with_max_len(self.max_len) }
+}
+
+impl<V> std::fmt::Debug for NbtFrameCodec<V> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("NbtFrameCodec").field("max_len", &self.max_len).finish()
+    }
+}
+
+/// 看一下开头的 VarInt, 不够的时候返回 `None`
+///
+/// 返回 (值, 占用的字节数)
+fn peek_var_u32(data: &[u8]) -> NbtResult<Option<(usize, usize)>> {
+    let mut value: u32 = 0;
+    for (size, byte) in data.iter().enumerate() {
+        if size >= 5 {
+            return Err(NbtError::VarIntTooBig(size + 1));
+        }
+        value |= ((byte & 0b0111_1111) as u32) << (size * 7);
+        if (byte & 0b1000_0000) == 0 {
+            return Ok(Some((value as usize, size + 1)));
+        }
+    }
+    Ok(None)
+}
+
+impl<V: NbtAsyncTrait> Decoder for NbtFrameCodec<V> {
+    type Item = NbtValue;
+    type Error = NbtError;
+
+    fn decode(&mut self, src: &mut BytesMut) -> NbtResult<Option<NbtValue>> {
+        let (len, prefix) = match peek_var_u32(src)? {
+            Some(x) => x,
+            None => return Ok(None),
+        };
+        if len > self.max_len {
+            return Err(NbtError::LenTooBig(String::new(), len, self.max_len));
+        }
+        if src.len() < prefix + len {
+            src.reserve(prefix + len - src.len());
+            return Ok(None);
+        }
+        src.advance(prefix);
+        let frame = src.split_to(len);
+        NbtValue::from_binary::<V>(&frame).map(Some)
+    }
+}
+
+impl<V: NbtAsyncTrait> Encoder<&NbtValue> for NbtFrameCodec<V> {
+    type Error = NbtError;
+
+    fn encode(&mut self, item: &NbtValue, dst: &mut BytesMut) -> NbtResult<()> {
+        let data = V::to_bytes(item)?;
+        NbtError::check_len(data.len(), self.max_len)?;
+        let mut prefix = Vec::with_capacity(5);
+        write_var_u32(&mut prefix, data.len() as u32);
+        dst.reserve(prefix.len() + data.len());
+        dst.extend_from_slice(&prefix);
+        dst.extend_from_slice(&data);
+        Ok(())
+    }
+}
+
+impl<V: NbtAsyncTrait> Encoder<NbtValue> for NbtFrameCodec<V> {
+    type Error = NbtError;
+
+    fn encode(&mut self, item: NbtValue, dst: &mut BytesMut) -> NbtResult<()> {
+        self.encode(&item, dst)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nbt;
+    use crate::tests::{BIG_TEST_BEDROCK_NET_DATA, HELLO_WORLD_BEDROCK_NET_DATA};
+
+    fn block_on<F: std::future::Future>(future: F) -> F::Output {
+        tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(future)
+    }
+
+    fn test_value() -> NbtValue {
+        nbt!({
+            "name": "Bananrama",
+            "list": [1, 2, 3],
+            "nested": { "longs": [L; 1, -1], "pi": 1.5 },
+            "compounds": [{ "a": 1b }, {}],
+            "empty": [],
+        })
+    }
+
+    #[test]
+    fn read_write_round_trip() {
+        block_on(async {
+            let value = test_value();
+            // 很小的管道, 每次只能读到一点点
+            let (mut client, mut server) = tokio::io::duplex(3);
+            let writer = tokio::spawn(async move {
+                write_nbt::<JavaNetAfter1_20_2, _>(&mut client, &value).await.unwrap();
+                write_nbt::<BedrockNetVarInt, _>(&mut client, &value).await.unwrap();
+                client.write_all(&[0xFF]).await.unwrap();
+            });
+            let java = read_nbt::<JavaNetAfter1_20_2, _>(&mut server).await.unwrap();
+            let bedrock = read_nbt::<BedrockNetVarInt, _>(&mut server).await.unwrap();
+            writer.await.unwrap();
+            let java_bytes = JavaNetAfter1_20_2::to_bytes(&test_value()).unwrap();
+            assert_eq!(java, NbtValue::from_binary::<JavaNetAfter1_20_2>(&java_bytes).unwrap());
+            let bedrock_bytes = BedrockNetVarInt::to_bytes(&test_value()).unwrap();
+            assert_eq!(bedrock, NbtValue::from_binary::<BedrockNetVarInt>(&bedrock_bytes).unwrap());
+            // 后面的数据不会被读掉
+            assert_eq!(server.read_u8().await.unwrap(), 0xFF);
+        });
+    }
+
+    #[test]
+    fn read_same_as_binary() {
+        block_on(async {
+            for data in [&HELLO_WORLD_BEDROCK_NET_DATA[..], &BIG_TEST_BEDROCK_NET_DATA[..]] {
+                let value = read_nbt::<BedrockNetVarInt, _>(&mut &data[..]).await.unwrap();
+                assert_eq!(value, NbtValue::from_binary::<BedrockNetVarInt>(data).unwrap());
+                for len in 0..data.len() {
+                    let result = read_nbt::<BedrockNetVarInt, _>(&mut &data[..len]).await;
+                    assert!(matches!(result, Err(NbtError::UnexpectedEof(_))), "{}", len);
+                }
+            }
+            assert_eq!(
+                read_nbt::<JavaNetAfter1_20_2, _>(&mut &[0x09][..]).await,
+                Err(NbtError::WrongRootType(9))
+            );
+        });
+    }
+
+    #[test]
+    fn frame_codec() {
+        let mut codec = NbtFrameCodec::<JavaNetAfter1_20_2>::new();
+        let value = test_value();
+        let mut buff = BytesMut::new();
+        codec.encode(&value, &mut buff).unwrap();
+        codec.encode(value.clone(), &mut buff).unwrap();
+        let (len, prefix) = peek_var_u32(&buff).unwrap().unwrap();
+        assert_eq!((prefix + len) * 2, buff.len());
+
+        // 一个字节一个字节的喂进去
+        let mut input = BytesMut::new();
+        let mut decoded = Vec::new();
+        for byte in buff.iter() {
+            input.extend_from_slice(&[*byte]);
+            if let Some(value) = codec.decode(&mut input).unwrap() {
+                decoded.push(value);
+            }
+        }
+        assert!(input.is_empty());
+        let expect =
+            NbtValue::from_binary::<JavaNetAfter1_20_2>(&buff[prefix..prefix + len]).unwrap();
+        assert_eq!(decoded, vec![expect.clone(), expect]);
+
+        // 太长的帧
+        let mut codec = NbtFrameCodec::<BedrockNetVarInt>::with_max_len(8);
+        let mut input = BytesMut::from(&[0x80, 0x01][..]);
+        assert_eq!(codec.decode(&mut input), Err(NbtError::LenTooBig(String::new(), 128, 8)));
+        assert!(matches!(codec.encode(&value, &mut buff), Err(NbtError::LenTooBig(..))));
+        let mut input = BytesMut::from(&[0xFF; 6][..]);
+        assert_eq!(codec.decode(&mut input), Err(NbtError::VarIntTooBig(6)));
+    }
+}
@@ -1,7 +1,14 @@
+use std::borrow::Cow;
 use std::fmt::Display;
+use std::io::Read;
+use std::marker::PhantomData;
 
 use crate::{
-    borrow::BorrowNbtValue, nbt_consts, Mutf8String, NbtReader, NbtResult, NbtTypeId, NbtValue,
+    borrow::BorrowNbtValue,
+    nbt_consts,
+    snbt::{write_snbt, SnbtStyle},
+    stream::NbtStreamReader,
+    Mutf8String, NbtError, NbtReader, NbtResult, NbtTypeId, NbtValue, RECURSE_LIMIT,
 };
 
 /// 把 u8 转换成对应的 Nbt 类型名称
@@ -47,21 +54,387 @@ impl NbtTypeConversion for NbtTypeId {
     }
 }
 
+/// 直接从二进制读出一个 NbtValue
+///
+/// 跟 [`NbtWriteTrait`] 一样, 每个版本只需要实现各自的基础类型解码方式
+///
+/// 不需要 borrow 树的时候可以直接用这个, 少一次遍历
 pub trait NbtReadTrait {
-    /// 从 `reader` 读取一个 i8(byte) 数组
-    fn read_i8_array(reader: &mut NbtReader) -> NbtResult<Vec<i8>>;
+    /// 根节点有没有名字
+    const ROOT_NAMED: bool = true;
+    /// 根节点可不可以是 List
+    const LIST_ROOT: bool = false;
+    /// 字符串是不是 m-utf8, 不是的话就是普通的 utf-8
+    const MUTF8_STRING: bool = true;
+
+    /// 从 `reader` 读取一个 i16(short)
+    fn read_i16(reader: &mut NbtReader) -> NbtResult<i16>;
+    /// 从 `reader` 读取一个 i32(int)
+    fn read_i32(reader: &mut NbtReader) -> NbtResult<i32>;
+    /// 从 `reader` 读取一个 i64(long)
+    fn read_i64(reader: &mut NbtReader) -> NbtResult<i64>;
+    /// 从 `reader` 读取一个 f32(float)
+    fn read_f32(reader: &mut NbtReader) -> NbtResult<f32>;
+    /// 从 `reader` 读取一个 f64(double)
+    fn read_f64(reader: &mut NbtReader) -> NbtResult<f64>;
+    /// 从 `reader` 读取 List/Array 的长度
+    ///
+    /// 这里不检查负数, 请使用 [`NbtReadTrait::read_checked_len`]
+    fn read_len(reader: &mut NbtReader) -> NbtResult<i32>;
+    /// 从 `reader` 读取字符串的长度
+    fn read_str_len(reader: &mut NbtReader) -> NbtResult<usize>;
     /// 从 `reader` 读取一个 i32(int) 数组
     fn read_i32_array(reader: &mut NbtReader) -> NbtResult<Vec<i32>>;
     /// 从 `reader` 读取一个 i64(long) 数组
     fn read_i64_array(reader: &mut NbtReader) -> NbtResult<Vec<i64>>;
+
+    /// 从 `reader` 读取根节点
+    fn from_reader(mut reader: NbtReader) -> NbtResult<NbtValue> {
+        match reader.read_u8()? {
+            nbt_consts::TAG_COMPOUND => {
+                let name = match Self::ROOT_NAMED {
+                    true => Some(Self::read_nbt_string(&mut reader)?),
+                    false => None,
+                };
+                Ok(NbtValue::Compound(name, Self::read_compound(&mut reader)?))
+            }
+            nbt_consts::TAG_LIST if Self::LIST_ROOT => {
+                Ok(NbtValue::List(Self::read_list(&mut reader)?))
+            }
+            x => Err(NbtError::WrongRootType(x)),
+        }
+    }
+
+    /// 读取 List/Array 的长度, 并且检查是不是负数
+    fn read_checked_len(reader: &mut NbtReader, type_id: NbtTypeId) -> NbtResult<usize> {
+        let len = Self::read_len(reader)?;
+        if len < 0 {
+            return Err(NbtError::LenNegative(type_id, len, reader.cursor));
+        }
+        Ok(len as usize)
+    }
+    /// 从 `reader` 读取一个 i8(byte) 数组
+    fn read_i8_array(reader: &mut NbtReader) -> NbtResult<Vec<i8>> {
+        let len = Self::read_checked_len(reader, nbt_consts::TAG_BYTE_ARRAY)?;
+        Ok(reader.read_u8_array(len)?.iter().map(|x| *x as i8).collect())
+    }
+    /// 从 `reader` 读取一个 NBT 字符串
+    ///
+    /// m-utf8 的不会解码, 直接存原始的字节
+    ///
+    /// utf-8 的会检查并转成 m-utf8
+    fn read_nbt_string(reader: &mut NbtReader) -> NbtResult<Mutf8String> {
+        let len = Self::read_str_len(reader)?;
+        let start = reader.cursor;
+        match Self::MUTF8_STRING {
+            true => Mutf8String::from_reader(reader, start, len),
+            false => Ok(Mutf8String::from_utf8(reader.read_u8_array(len)?)?),
+        }
+    }
+    /// 从 `reader` 读取一个非 List/Compound 的值 (不包括 tag)
+    fn read_primitive(reader: &mut NbtReader, type_id: NbtTypeId) -> NbtResult<NbtValue> {
+        Ok(match type_id {
+            nbt_consts::TAG_BYTE => NbtValue::Byte(reader.read_i8()?),
+            nbt_consts::TAG_SHORT => NbtValue::Short(Self::read_i16(reader)?),
+            nbt_consts::TAG_INT => NbtValue::Int(Self::read_i32(reader)?),
+            nbt_consts::TAG_LONG => NbtValue::Long(Self::read_i64(reader)?),
+            nbt_consts::TAG_FLOAT => NbtValue::Float(Self::read_f32(reader)?),
+            nbt_consts::TAG_DOUBLE => NbtValue::Double(Self::read_f64(reader)?),
+            nbt_consts::TAG_BYTE_ARRAY => NbtValue::ByteArray(Self::read_i8_array(reader)?),
+            nbt_consts::TAG_STRING => NbtValue::String(Self::read_nbt_string(reader)?),
+            nbt_consts::TAG_INT_ARRAY => NbtValue::IntArray(Self::read_i32_array(reader)?),
+            nbt_consts::TAG_LONG_ARRAY => NbtValue::LongArray(Self::read_i64_array(reader)?),
+            x => return Err(NbtError::UnknownType(x, reader.cursor)),
+        })
+    }
+    /// 从 `reader` 读取一个列表类型(List)
+    fn read_list(reader: &mut NbtReader) -> NbtResult<Vec<NbtValue>> {
+        match read_nested(&mut SliceSource::<Self>(reader, PhantomData), nbt_consts::TAG_LIST)? {
+            NbtValue::List(list) => Ok(list),
+            _ => unreachable!("读的就是 List"),
+        }
+    }
+    /// 从 `reader` 读取一个复合标签类型(Compound)
+    fn read_compound(reader: &mut NbtReader) -> NbtResult<Vec<(Mutf8String, NbtValue)>> {
+        match read_nested(&mut SliceSource::<Self>(reader, PhantomData), nbt_consts::TAG_COMPOUND)?
+        {
+            NbtValue::Compound(_, values) => Ok(values),
+            _ => unreachable!("读的就是 Compound"),
+        }
+    }
+}
+
+/// [`read_nested`] 里用的栈帧
+enum ReadFrame {
+    /// type_id, len, values
+    List(NbtTypeId, usize, Vec<NbtValue>),
+    /// values, 正在读的子节点的 key
+    Compound(Vec<(Mutf8String, NbtValue)>, Option<Mutf8String>),
+}
+
+impl ReadFrame {
+    /// List 的栈帧, 最多先分配 `capacity` 个
+    fn list(lst_type: NbtTypeId, len: usize, capacity: usize) -> Self {
+        if lst_type == nbt_consts::TAG_END {
+            // TAG_End 的 list 里面不会有东西
+            return ReadFrame::List(lst_type, 0, Vec::new());
+        }
+        ReadFrame::List(lst_type, len, Vec::with_capacity(len.min(capacity)))
+    }
+
+    fn into_value(self) -> NbtValue {
+        match self {
+            ReadFrame::List(_, _, values) => NbtValue::List(values),
+            ReadFrame::Compound(values, _) => NbtValue::Compound(None, values),
+        }
+    }
+
+    fn push(&mut self, value: NbtValue) {
+        match self {
+            ReadFrame::List(_, _, values) => values.push(value),
+            ReadFrame::Compound(values, key) => {
+                values.push((key.take().expect("先读 key 再读 value"), value))
+            }
+        }
+    }
+}
+
+/// [`read_nested`] 从哪里读数据
+///
+/// [`NbtReader`] 和 [`NbtStreamReader`] 只有这几个地方不一样, 循环是同一个
+trait NestedSource {
+    /// 读取一个 tag
+    fn read_tag(&mut self) -> NbtResult<NbtTypeId>;
+    /// 读取 Compound 里的一个 key
+    fn read_key(&mut self) -> NbtResult<Mutf8String>;
+    /// 读一个 List/Compound 的开头, 创建对应的栈帧
+    fn open(&mut self, type_id: NbtTypeId) -> NbtResult<ReadFrame>;
+    /// 读取一个非 List/Compound 的值
+    fn read_primitive(&mut self, type_id: NbtTypeId) -> NbtResult<NbtValue>;
+}
+
+/// 按照 `V` 的格式从 [`NbtReader`] 里读
+struct SliceSource<'r, 'data, V: ?Sized>(&'r mut NbtReader<'data>, PhantomData<V>);
+
+impl<V: NbtReadTrait + ?Sized> NestedSource for SliceSource<'_, '_, V> {
+    fn read_tag(&mut self) -> NbtResult<NbtTypeId> { self.0.read_u8() }
+
+    fn read_key(&mut self) -> NbtResult<Mutf8String> { V::read_nbt_string(self.0) }
+
+    fn open(&mut self, type_id: NbtTypeId) -> NbtResult<ReadFrame> {
+        if type_id == nbt_consts::TAG_COMPOUND {
+            return Ok(ReadFrame::Compound(Vec::new(), None));
+        }
+        let lst_type = self.0.read_u8()?;
+        if !lst_type.is_valid_nbt_type() {
+            return Err(NbtError::UnknownType(lst_type, self.0.cursor));
+        }
+        let len = V::read_checked_len(self.0, lst_type)?;
+        // 防止一个假的长度直接把内存吃满
+        let capacity = self.0.data.len().saturating_sub(self.0.cursor);
+        Ok(ReadFrame::list(lst_type, len, capacity))
+    }
+
+    fn read_primitive(&mut self, type_id: NbtTypeId) -> NbtResult<NbtValue> {
+        V::read_primitive(self.0, type_id)
+    }
+}
+
+/// 读取一个 List/Compound
+///
+/// 跟 borrow 里一样, 手动模拟 stack, 防止嵌套太深直接爆栈
+///
+/// 嵌套超过 [`RECURSE_LIMIT`] 时返回 [`NbtError::NbtDepthTooBig`]
+fn read_nested(source: &mut impl NestedSource, type_id: NbtTypeId) -> NbtResult<NbtValue> {
+    let mut stack = vec![source.open(type_id)?];
+    loop {
+        let current = stack.last_mut().unwrap();
+        // 找到下一个要读的值的类型
+        // None 说明当前这个已经读完了
+        let next_type = match current {
+            ReadFrame::List(lst_type, len, values) => {
+                if values.len() == *len {
+                    None
+                } else {
+                    Some(*lst_type)
+                }
+            }
+            ReadFrame::Compound(_, key) => {
+                let value_type = source.read_tag()?;
+                if value_type == nbt_consts::TAG_END {
+                    None
+                } else {
+                    *key = Some(source.read_key()?);
+                    Some(value_type)
+                }
+            }
+        };
+        match next_type {
+            None => {
+                // 出栈, 塞进上一层里
+                let value = stack.pop().unwrap().into_value();
+                match stack.last_mut() {
+                    Some(parent) => parent.push(value),
+                    None => return Ok(value),
+                }
+            }
+            Some(value_type) if value_type.is_list_or_compound() => {
+                if stack.len() >= RECURSE_LIMIT {
+                    return Err(NbtError::NbtDepthTooBig(RECURSE_LIMIT));
+                }
+                stack.push(source.open(value_type)?);
+            }
+            Some(value_type) => {
+                let value = source.read_primitive(value_type)?;
+                current.push(value);
+            }
+        }
+    }
+}
+
+/// 从 [`NbtStreamReader`] 里一点一点读出一个 NbtValue
+///
+/// 跟 [`NbtReadTrait`] 一样, 每个版本只需要实现各自的基础类型解码方式, 读出来的值也完全一样
+///
+/// 数据不够的时候返回 [`NbtError::UnexpectedEof`]
+pub trait NbtStreamReadTrait {
+    /// 根节点有没有名字
+    const ROOT_NAMED: bool = true;
+    /// 根节点可不可以是 List
+    const LIST_ROOT: bool = false;
+    /// 字符串是不是 m-utf8, 不是的话就是普通的 utf-8
+    const MUTF8_STRING: bool = true;
+
+    /// 从 `reader` 读取一个 i16(short)
+    fn read_i16<R: Read>(reader: &mut NbtStreamReader<R>) -> NbtResult<i16>;
+    /// 从 `reader` 读取一个 i32(int)
+    fn read_i32<R: Read>(reader: &mut NbtStreamReader<R>) -> NbtResult<i32>;
+    /// 从 `reader` 读取一个 i64(long)
+    fn read_i64<R: Read>(reader: &mut NbtStreamReader<R>) -> NbtResult<i64>;
+    /// 从 `reader` 读取一个 f32(float)
+    fn read_f32<R: Read>(reader: &mut NbtStreamReader<R>) -> NbtResult<f32>;
+    /// 从 `reader` 读取一个 f64(double)
+    fn read_f64<R: Read>(reader: &mut NbtStreamReader<R>) -> NbtResult<f64>;
+    /// 从 `reader` 读取 List/Array 的长度
+    ///
+    /// 这里不检查负数, 请使用 [`NbtStreamReadTrait::read_checked_len`]
+    fn read_len<R: Read>(reader: &mut NbtStreamReader<R>) -> NbtResult<i32>;
+    /// 从 `reader` 读取字符串的长度
+    fn read_str_len<R: Read>(reader: &mut NbtStreamReader<R>) -> NbtResult<usize>;
+    /// 从 `reader` 读取一个 i32(int) 数组
+    fn read_i32_array<R: Read>(reader: &mut NbtStreamReader<R>) -> NbtResult<Vec<i32>>;
+    /// 从 `reader` 读取一个 i64(long) 数组
+    fn read_i64_array<R: Read>(reader: &mut NbtStreamReader<R>) -> NbtResult<Vec<i64>>;
+
+    /// 从 `reader` 读取根节点
+    fn from_stream<R: Read>(reader: &mut NbtStreamReader<R>) -> NbtResult<NbtValue> {
+        match reader.read_u8()? {
+            nbt_consts::TAG_COMPOUND => {
+                let name = match Self::ROOT_NAMED {
+                    true => Some(Self::read_nbt_string(reader)?),
+                    false => None,
+                };
+                Ok(NbtValue::Compound(name, Self::read_compound(reader)?))
+            }
+            nbt_consts::TAG_LIST if Self::LIST_ROOT => Ok(NbtValue::List(Self::read_list(reader)?)),
+            x => Err(NbtError::WrongRootType(x)),
+        }
+    }
+
+    /// 读取 List/Array 的长度, 并且检查是不是负数
+    fn read_checked_len<R: Read>(
+        reader: &mut NbtStreamReader<R>,
+        type_id: NbtTypeId,
+    ) -> NbtResult<usize> {
+        let len = Self::read_len(reader)?;
+        if len < 0 {
+            return Err(NbtError::LenNegative(type_id, len, reader.cursor));
+        }
+        Ok(len as usize)
+    }
+    /// 从 `reader` 读取一个 i8(byte) 数组
+    fn read_i8_array<R: Read>(reader: &mut NbtStreamReader<R>) -> NbtResult<Vec<i8>> {
+        let len = Self::read_checked_len(reader, nbt_consts::TAG_BYTE_ARRAY)?;
+        Ok(reader.read_bytes(len)?.into_iter().map(|x| x as i8).collect())
+    }
     /// 从 `reader` 读取一个 NBT 字符串
-    fn read_nbt_string(reader: &mut NbtReader) -> NbtResult<Mutf8String>;
+    ///
+    /// 跟 [`NbtReadTrait::read_nbt_string`] 一样, m-utf8 的不会解码, utf-8 的会检查并转成 m-utf8
+    fn read_nbt_string<R: Read>(reader: &mut NbtStreamReader<R>) -> NbtResult<Mutf8String> {
+        let len = Self::read_str_len(reader)?;
+        let data = reader.read_bytes(len)?;
+        match Self::MUTF8_STRING {
+            true => Ok(Mutf8String::from_mutf8_unchecked(data)),
+            false => Ok(Mutf8String::from_utf8(&data)?),
+        }
+    }
+    /// 从 `reader` 读取一个非 List/Compound 的值 (不包括 tag)
+    fn read_primitive<R: Read>(
+        reader: &mut NbtStreamReader<R>,
+        type_id: NbtTypeId,
+    ) -> NbtResult<NbtValue> {
+        Ok(match type_id {
+            nbt_consts::TAG_BYTE => NbtValue::Byte(reader.read_i8()?),
+            nbt_consts::TAG_SHORT => NbtValue::Short(Self::read_i16(reader)?),
+            nbt_consts::TAG_INT => NbtValue::Int(Self::read_i32(reader)?),
+            nbt_consts::TAG_LONG => NbtValue::Long(Self::read_i64(reader)?),
+            nbt_consts::TAG_FLOAT => NbtValue::Float(Self::read_f32(reader)?),
+            nbt_consts::TAG_DOUBLE => NbtValue::Double(Self::read_f64(reader)?),
+            nbt_consts::TAG_BYTE_ARRAY => NbtValue::ByteArray(Self::read_i8_array(reader)?),
+            nbt_consts::TAG_STRING => NbtValue::String(Self::read_nbt_string(reader)?),
+            nbt_consts::TAG_INT_ARRAY => NbtValue::IntArray(Self::read_i32_array(reader)?),
+            nbt_consts::TAG_LONG_ARRAY => NbtValue::LongArray(Self::read_i64_array(reader)?),
+            x => return Err(NbtError::UnknownType(x, reader.cursor)),
+        })
+    }
     /// 从 `reader` 读取一个列表类型(List)
-    fn read_list(reader: &mut NbtReader) -> NbtResult<Vec<NbtValue>>;
+    fn read_list<R: Read>(reader: &mut NbtStreamReader<R>) -> NbtResult<Vec<NbtValue>> {
+        match read_nested(&mut StreamSource::<R, Self>(reader, PhantomData), nbt_consts::TAG_LIST)?
+        {
+            NbtValue::List(list) => Ok(list),
+            _ => unreachable!("读的就是 List"),
+        }
+    }
     /// 从 `reader` 读取一个复合标签类型(Compound)
-    fn read_compound(reader: &mut NbtReader) -> NbtResult<Vec<(Mutf8String, NbtValue)>>;
+    fn read_compound<R: Read>(
+        reader: &mut NbtStreamReader<R>,
+    ) -> NbtResult<Vec<(Mutf8String, NbtValue)>> {
+        match read_nested(
+            &mut StreamSource::<R, Self>(reader, PhantomData),
+            nbt_consts::TAG_COMPOUND,
+        )? {
+            NbtValue::Compound(_, values) => Ok(values),
+            _ => unreachable!("读的就是 Compound"),
+        }
+    }
+}
+
+/// 从流里读的时候不知道还剩多少数据, List 最多先分配这么多
+const STREAM_LIST_CAPACITY: usize = 1024;
+
+/// 按照 `V` 的格式从 [`NbtStreamReader`] 里读
+struct StreamSource<'r, R, V: ?Sized>(&'r mut NbtStreamReader<R>, PhantomData<V>);
 
-    fn from_reader(reader: NbtReader) -> NbtResult<NbtValue>;
+impl<R: Read, V: NbtStreamReadTrait + ?Sized> NestedSource for StreamSource<'_, R, V> {
+    fn read_tag(&mut self) -> NbtResult<NbtTypeId> { self.0.read_u8() }
+
+    fn read_key(&mut self) -> NbtResult<Mutf8String> { V::read_nbt_string(self.0) }
+
+    fn open(&mut self, type_id: NbtTypeId) -> NbtResult<ReadFrame> {
+        if type_id == nbt_consts::TAG_COMPOUND {
+            return Ok(ReadFrame::Compound(Vec::new(), None));
+        }
+        let lst_type = self.0.read_u8()?;
+        if !lst_type.is_valid_nbt_type() {
+            return Err(NbtError::UnknownType(lst_type, self.0.cursor));
+        }
+        let len = V::read_checked_len(self.0, lst_type)?;
+        Ok(ReadFrame::list(lst_type, len, STREAM_LIST_CAPACITY))
+    }
+
+    fn read_primitive(&mut self, type_id: NbtTypeId) -> NbtResult<NbtValue> {
+        V::read_primitive(self.0, type_id)
+    }
 }
 
 pub trait NbtBorrowTrait {
@@ -73,79 +446,162 @@ pub trait NbtBorrowTrait {
     fn read_data(value: &BorrowNbtValue, reader: &mut NbtReader) -> NbtValue;
 }
 
-/// 输出 SNBT
-/// 这里的格式是为了方便阅读
-/// 更接近客户端里实际的格式(命令里使用的格式)
-impl Display for NbtValue {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            NbtValue::Byte(v) => write!(f, "{}b", v),
-            NbtValue::Short(v) => write!(f, "{}s", v),
-            NbtValue::Int(v) => write!(f, "{}", v),
-            NbtValue::Long(v) => write!(f, "{}l", v),
-            // float 后面跟一个 f
-            NbtValue::Float(v) => write!(f, "{}f", v),
-            // double 后面跟一个 d?
-            NbtValue::Double(v) => write!(f, "{}d", v),
-            NbtValue::ByteArray(v) => {
-                write!(f, "[B; ")?;
-                for (i, v) in v.iter().enumerate() {
-                    if i != 0 {
-                        write!(f, ", ")?;
-                    }
-                    write!(f, "{}b", v)?;
-                }
-                write!(f, "]")
-            }
-            NbtValue::IntArray(v) => {
-                write!(f, "[I; ")?;
-                for (i, v) in v.iter().enumerate() {
-                    if i != 0 {
-                        write!(f, ", ")?;
-                    }
-                    write!(f, "{}", v)?;
-                }
-                write!(f, "]")
+/// 把 NbtValue 写成对应版本的二进制
+///
+/// 每个版本只需要实现各自的基础类型编码方式
+/// List/Compound 的结构在所有版本里都是一样的, 所以直接给了默认实现
+///
+/// m-utf8 的版本会直接写入 [`Mutf8String`] 里存的字节, 不会重新编码
+///
+/// utf-8 的版本 (基岩版) 会先转成 utf-8
+pub trait NbtWriteTrait {
+    /// 根节点有没有名字
+    const ROOT_NAMED: bool = true;
+    /// 根节点可不可以是 List
+    const LIST_ROOT: bool = false;
+    /// 字符串是不是 m-utf8, 不是的话就是普通的 utf-8
+    const MUTF8_STRING: bool = true;
+    /// 字符串 (编码之后) 的最大长度
+    const MAX_STR_LEN: usize = u16::MAX as usize;
+    /// List/Array 的最大长度
+    const MAX_LEN: usize = i32::MAX as usize;
+
+    /// 写入一个 i16(short)
+    fn write_i16(writer: &mut Vec<u8>, data: i16);
+    /// 写入一个 i32(int)
+    fn write_i32(writer: &mut Vec<u8>, data: i32);
+    /// 写入一个 i64(long)
+    fn write_i64(writer: &mut Vec<u8>, data: i64);
+    /// 写入一个 f32(float)
+    fn write_f32(writer: &mut Vec<u8>, data: f32);
+    /// 写入一个 f64(double)
+    fn write_f64(writer: &mut Vec<u8>, data: f64);
+    /// 写入 List/Array 的长度
+    fn write_len(writer: &mut Vec<u8>, len: usize);
+    /// 写入字符串的长度
+    fn write_str_len(writer: &mut Vec<u8>, len: usize);
+    /// 写入一个 i32(int) 数组
+    fn write_i32_array(writer: &mut Vec<u8>, data: &[i32]);
+    /// 写入一个 i64(long) 数组
+    fn write_i64_array(writer: &mut Vec<u8>, data: &[i64]);
+
+    /// 写入一个 i8(byte) 数组
+    fn write_i8_array(writer: &mut Vec<u8>, data: &[i8]) {
+        Self::write_len(writer, data.len());
+        writer.extend(data.iter().map(|x| *x as u8));
+    }
+    /// 写入一个 NBT 字符串
+    fn write_nbt_string(writer: &mut Vec<u8>, data: &Mutf8String) -> NbtResult<()> {
+        let data = match Self::MUTF8_STRING {
+            true => Cow::Borrowed(data.as_bytes()),
+            false => data.to_utf8()?,
+        };
+        NbtError::check_len(data.len(), Self::MAX_STR_LEN)?;
+        Self::write_str_len(writer, data.len());
+        writer.extend_from_slice(&data);
+        Ok(())
+    }
+    /// 写入一个值的内容 (不包括 tag)
+    ///
+    /// 长度超过限制的时候返回 [`NbtError::LenTooBig`]
+    fn write_payload(writer: &mut Vec<u8>, value: &NbtValue) -> NbtResult<()> {
+        match value {
+            NbtValue::Byte(x) => writer.push(*x as u8),
+            NbtValue::Short(x) => Self::write_i16(writer, *x),
+            NbtValue::Int(x) => Self::write_i32(writer, *x),
+            NbtValue::Long(x) => Self::write_i64(writer, *x),
+            NbtValue::Float(x) => Self::write_f32(writer, *x),
+            NbtValue::Double(x) => Self::write_f64(writer, *x),
+            NbtValue::ByteArray(x) => {
+                NbtError::check_len(x.len(), Self::MAX_LEN)?;
+                Self::write_i8_array(writer, x)
             }
-            NbtValue::LongArray(v) => {
-                write!(f, "[L; ")?;
-                for (i, v) in v.iter().enumerate() {
-                    if i != 0 {
-                        write!(f, ", ")?;
-                    }
-                    write!(f, "{}l", v)?;
-                }
-                write!(f, "]")
+            NbtValue::IntArray(x) => {
+                NbtError::check_len(x.len(), Self::MAX_LEN)?;
+                Self::write_i32_array(writer, x)
             }
-            NbtValue::String(v) => {
-                write!(f, "\"{}\"", v.decode())
+            NbtValue::LongArray(x) => {
+                NbtError::check_len(x.len(), Self::MAX_LEN)?;
+                Self::write_i64_array(writer, x)
             }
-            NbtValue::List(lst) => {
-                write!(f, "[")?;
-                for (i, v) in lst.iter().enumerate() {
-                    if i != 0 {
-                        write!(f, ", ")?;
-                    }
-                    write!(f, "{}", v)?;
-                }
-                write!(f, "]")
+            NbtValue::String(x) => Self::write_nbt_string(writer, x)?,
+            NbtValue::List(x) => Self::write_list(writer, x)?,
+            NbtValue::Compound(_, x) => Self::write_compound(writer, x)?,
+        }
+        Ok(())
+    }
+    /// 向 `writer` 写入一个列表类型(List)
+    ///
+    /// 空列表的类型是 TAG_End
+    fn write_list(writer: &mut Vec<u8>, data: &[NbtValue]) -> NbtResult<()> {
+        let tag = data.first().map(|x| x.tag()).unwrap_or(nbt_consts::TAG_END);
+        if !data.iter().all(|x| x.tag() == tag) {
+            return Err(NbtError::ListTypeNotSame(data.iter().map(|x| x.tag()).collect()));
+        }
+        NbtError::check_len(data.len(), Self::MAX_LEN)?;
+        writer.push(tag);
+        Self::write_len(writer, data.len());
+        for (idx, value) in data.iter().enumerate() {
+            Self::write_payload(writer, value).map_err(|e| e.in_list(idx))?;
+        }
+        Ok(())
+    }
+    /// 向 `writer` 写入一个复合标签类型(Compound)
+    ///
+    /// 不会写入 Compound 自己的名字, 嵌套的 Compound 用的是 key
+    fn write_compound(writer: &mut Vec<u8>, data: &[(Mutf8String, NbtValue)]) -> NbtResult<()> {
+        for (key, value) in data {
+            writer.push(value.tag());
+            Self::write_nbt_string(writer, key)
+                .and_then(|_| Self::write_payload(writer, value))
+                .map_err(|e| e.in_compound(&key.decode_lossy()))?;
+        }
+        writer.push(nbt_consts::TAG_END);
+        Ok(())
+    }
+    /// 写入根节点的名字
+    ///
+    /// 根节点没有名字的版本什么都不写, 没有给名字的时候写一个空字符串
+    fn write_root_name(writer: &mut Vec<u8>, name: Option<&Mutf8String>) -> NbtResult<()> {
+        match (Self::ROOT_NAMED, name) {
+            (true, Some(name)) => Self::write_nbt_string(writer, name)?,
+            (true, None) => Self::write_str_len(writer, 0),
+            (false, _) => (),
+        }
+        Ok(())
+    }
+    /// 写入根节点 (包括 tag 和名字)
+    fn write_to(value: &NbtValue, buff: &mut Vec<u8>) -> NbtResult<()> {
+        match value {
+            NbtValue::Compound(name, data) => {
+                buff.push(nbt_consts::TAG_COMPOUND);
+                Self::write_root_name(buff, name.as_ref())?;
+                Self::write_compound(buff, data)
             }
-            NbtValue::Compound(name, map) => {
-                if let Some(name) = name {
-                    write!(f, "{}: {{", name.decode())?;
-                } else {
-                    write!(f, "{{")?;
-                }
-                for (i, (k, v)) in map.iter().enumerate() {
-                    if i != 0 {
-                        write!(f, ", ")?;
-                    }
-                    write!(f, "{}: {}", k.decode(), v)?;
-                }
-                write!(f, "}}")
+            NbtValue::List(data) if Self::LIST_ROOT => {
+                buff.push(nbt_consts::TAG_LIST);
+                Self::write_list(buff, data)
             }
+            x => Err(NbtError::WrongRootType(x.tag())),
         }
     }
+    /// 直接写成一个新的 `Vec<u8>`
+    fn to_bytes(value: &NbtValue) -> NbtResult<Vec<u8>> {
+        let mut buff = Vec::new();
+        Self::write_to(value, &mut buff)?;
+        Ok(buff)
+    }
+}
+
+/// 输出 SNBT
+/// 这里的格式是为了方便阅读
+/// 更接近客户端里实际的格式(命令里使用的格式)
+///
+/// 需要别的格式请用 [`NbtValue::to_snbt_with_style`]
+impl Display for NbtValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write_snbt(f, self, SnbtStyle::Spaced)
+    }
 }
 
 #[cfg(test)]
@@ -1,12 +1,11 @@
-#[cfg(feature = "serde")]
-use serde::{Deserialize, Serialize};
-
 use crate::mutf8::Mutf8String;
-use crate::NbtError;
+use crate::traits::{NbtReadTrait, NbtStreamReadTrait, NbtWriteTrait};
+use crate::{nbt_consts, NbtError, NbtReader, NbtResult, NbtStreamReader, NbtTypeId};
 
 /// Nbt Value!
+///
+/// 开启 `serde` 特性之后实现了 `Serialize`/`Deserialize`, 见 [`crate::serding`]
 #[derive(Debug, Clone, PartialEq)]
-#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum NbtValue {
     // end: 0
     /// 1: Byte
@@ -42,6 +41,63 @@ pub enum NbtValue {
 }
 
 impl NbtValue {
+    /// 获取这个值对应的类型 id
+    pub fn tag(&self) -> NbtTypeId {
+        match self {
+            NbtValue::Byte(_) => nbt_consts::TAG_BYTE,
+            NbtValue::Short(_) => nbt_consts::TAG_SHORT,
+            NbtValue::Int(_) => nbt_consts::TAG_INT,
+            NbtValue::Long(_) => nbt_consts::TAG_LONG,
+            NbtValue::Float(_) => nbt_consts::TAG_FLOAT,
+            NbtValue::Double(_) => nbt_consts::TAG_DOUBLE,
+            NbtValue::ByteArray(_) => nbt_consts::TAG_BYTE_ARRAY,
+            NbtValue::String(_) => nbt_consts::TAG_STRING,
+            NbtValue::List(_) => nbt_consts::TAG_LIST,
+            NbtValue::Compound(_, _) => nbt_consts::TAG_COMPOUND,
+            NbtValue::IntArray(_) => nbt_consts::TAG_INT_ARRAY,
+            NbtValue::LongArray(_) => nbt_consts::TAG_LONG_ARRAY,
+        }
+    }
+
+    /// 解析 Nbt 数据
+    ///
+    /// 不会构建 borrow 树, 直接读成 NbtValue
+    pub fn from_binary<R>(data: &[u8]) -> NbtResult<NbtValue>
+    where
+        R: NbtReadTrait,
+    {
+        let reader = NbtReader::new(data);
+        R::from_reader(reader)
+    }
+
+    /// 从 [`std::io::Read`] 里读一个 NbtValue
+    ///
+    /// 只会读根节点需要的字节, 后面的数据不会动, 读文件的时候最好套一层 `BufReader`
+    ///
+    /// 数据不够的时候返回 [`NbtError::UnexpectedEof`]
+    pub fn from_stream<R>(reader: impl std::io::Read) -> NbtResult<NbtValue>
+    where
+        R: NbtStreamReadTrait,
+    {
+        R::from_stream(&mut NbtStreamReader::new(reader))
+    }
+
+    /// 按照 `W` 的格式写入 `buff`
+    pub fn write_to<W>(&self, buff: &mut Vec<u8>) -> NbtResult<()>
+    where
+        W: NbtWriteTrait,
+    {
+        W::write_to(self, buff)
+    }
+
+    /// 按照 `W` 的格式写成二进制
+    pub fn to_binary<W>(&self) -> NbtResult<Vec<u8>>
+    where
+        W: NbtWriteTrait,
+    {
+        W::to_bytes(self)
+    }
+
     /// 检验所有的 mut8 字符串 是否合法
     pub fn verify_strings(&self) -> Option<Vec<NbtError>> {
         let mut errors = Vec::new();
@@ -122,7 +178,7 @@ impl NbtValue {
             NbtValue::Long(l) => l.to_string(),
             NbtValue::Float(f) => f.to_string(),
             NbtValue::Double(d) => d.to_string(),
-            NbtValue::String(s) => format!("\"{}\"", s.decode()),
+            NbtValue::String(s) => format!("\"{}\"", s.decode_lossy()),
             NbtValue::ByteArray(b) => {
                 let mut s = String::from("[B; ");
                 for i in b {
@@ -161,12 +217,12 @@ impl NbtValue {
             NbtValue::Compound(name, values) => {
                 let mut result = String::new();
                 let possible_name = match name {
-                    Some(n) => n.decode(),
+                    Some(n) => n.decode_lossy().into_owned(),
                     None => String::new(),
                 };
                 for (name, value) in values {
                     result.push('"');
-                    result.push_str(&name.decode());
+                    result.push_str(&name.decode_lossy());
                     result.push_str("\": ");
                     result.push_str(&value.display_data());
                     result.push_str(", ");
@@ -176,3 +232,189 @@ impl NbtValue {
         }
     }
 }
+
+macro_rules! value_from {
+    ($($ty:ty => $variant:ident),* $(,)?) => {
+        $(
+            impl From<$ty> for NbtValue {
+                fn from(value: $ty) -> Self { NbtValue::$variant(value.into()) }
+            }
+        )*
+    };
+}
+
+value_from! {
+    i8 => Byte,
+    i16 => Short,
+    i32 => Int,
+    i64 => Long,
+    f32 => Float,
+    f64 => Double,
+    Mutf8String => String,
+    String => String,
+    &str => String,
+    Vec<i8> => ByteArray,
+    Vec<NbtValue> => List,
+    Vec<i32> => IntArray,
+    Vec<i64> => LongArray,
+}
+
+impl From<bool> for NbtValue {
+    fn from(value: bool) -> Self { NbtValue::Byte(value as i8) }
+}
+
+/// 生成取值的方法, 类型不对的时候都返回 [`NbtError::IncorrectType`]
+///
+/// - 数字: `as_x` 复制出来, `as_x_mut` 可变借用
+/// - 其他: `as_x_ref` 借用, `as_x_mut` 可变借用, `into_x` 拿走里面的值, 都不会复制
+macro_rules! value_accessors {
+    (
+        $($num:ident($num_tag:ident) => $as_fn:ident, $num_mut_fn:ident: $num_ty:ty;)*
+        ---
+        $($variant:ident($tag:ident) => $ref_fn:ident: $ref_ty:ty, $mut_fn:ident, $into_fn:ident: $ty:ty;)*
+    ) => {
+        impl NbtValue {
+            $(
+                #[doc = concat!("`", stringify!($num), "` 的值")]
+                #[inline]
+                pub fn $as_fn(&self) -> NbtResult<$num_ty> {
+                    match self {
+                        NbtValue::$num(v) => Ok(*v),
+                        other => Err(NbtError::IncorrectType(nbt_consts::$num_tag, other.tag())),
+                    }
+                }
+
+                #[doc = concat!("可变借用 `", stringify!($num), "` 的值")]
+                #[inline]
+                pub fn $num_mut_fn(&mut self) -> NbtResult<&mut $num_ty> {
+                    match self {
+                        NbtValue::$num(v) => Ok(v),
+                        other => Err(NbtError::IncorrectType(nbt_consts::$num_tag, other.tag())),
+                    }
+                }
+            )*
+            $(
+                #[doc = concat!("借用 `", stringify!($variant), "` 的值")]
+                #[inline]
+                pub fn $ref_fn(&self) -> NbtResult<$ref_ty> {
+                    match self {
+                        NbtValue::$variant(v) => Ok(v),
+                        other => Err(NbtError::IncorrectType(nbt_consts::$tag, other.tag())),
+                    }
+                }
+
+                #[doc = concat!("可变借用 `", stringify!($variant), "` 的值")]
+                #[inline]
+                pub fn $mut_fn(&mut self) -> NbtResult<&mut $ty> {
+                    match self {
+                        NbtValue::$variant(v) => Ok(v),
+                        other => Err(NbtError::IncorrectType(nbt_consts::$tag, other.tag())),
+                    }
+                }
+
+                #[doc = concat!("拿走 `", stringify!($variant), "` 的值")]
+                #[inline]
+                pub fn $into_fn(self) -> NbtResult<$ty> {
+                    match self {
+                        NbtValue::$variant(v) => Ok(v),
+                        other => Err(NbtError::IncorrectType(nbt_consts::$tag, other.tag())),
+                    }
+                }
+            )*
+        }
+    };
+}
+
+value_accessors! {
+    Byte(TAG_BYTE) => as_i8, as_i8_mut: i8;
+    Short(TAG_SHORT) => as_i16, as_i16_mut: i16;
+    Int(TAG_INT) => as_i32, as_i32_mut: i32;
+    Long(TAG_LONG) => as_i64, as_i64_mut: i64;
+    Float(TAG_FLOAT) => as_f32, as_f32_mut: f32;
+    Double(TAG_DOUBLE) => as_f64, as_f64_mut: f64;
+    ---
+    ByteArray(TAG_BYTE_ARRAY) => as_i8_array_ref: &[i8], as_i8_array_mut, into_i8_array: Vec<i8>;
+    IntArray(TAG_INT_ARRAY) => as_i32_array_ref: &[i32], as_i32_array_mut, into_i32_array: Vec<i32>;
+    LongArray(TAG_LONG_ARRAY) => as_i64_array_ref: &[i64], as_i64_array_mut, into_i64_array: Vec<i64>;
+    String(TAG_STRING) => as_string_ref: &Mutf8String, as_string_mut, into_string: Mutf8String;
+    List(TAG_LIST) => as_list_ref: &[NbtValue], as_list_mut, into_list: Vec<NbtValue>;
+}
+
+impl NbtValue {
+    /// 借用 `Compound` 的名字和内容
+    #[inline]
+    #[allow(clippy::type_complexity)]
+    pub fn as_compound_ref(&self) -> NbtResult<(Option<&Mutf8String>, &[(Mutf8String, NbtValue)])> {
+        match self {
+            NbtValue::Compound(name, v) => Ok((name.as_ref(), v)),
+            other => Err(NbtError::IncorrectType(nbt_consts::TAG_COMPOUND, other.tag())),
+        }
+    }
+
+    /// 可变借用 `Compound` 的名字和内容
+    #[inline]
+    #[allow(clippy::type_complexity)]
+    pub fn as_compound_mut(
+        &mut self,
+    ) -> NbtResult<(&mut Option<Mutf8String>, &mut Vec<(Mutf8String, NbtValue)>)> {
+        match self {
+            NbtValue::Compound(name, v) => Ok((name, v)),
+            other => Err(NbtError::IncorrectType(nbt_consts::TAG_COMPOUND, other.tag())),
+        }
+    }
+
+    /// 拿走 `Compound` 的名字和内容
+    #[inline]
+    #[allow(clippy::type_complexity)]
+    pub fn into_compound(self) -> NbtResult<(Option<Mutf8String>, Vec<(Mutf8String, NbtValue)>)> {
+        match self {
+            NbtValue::Compound(name, v) => Ok((name, v)),
+            other => Err(NbtError::IncorrectType(nbt_consts::TAG_COMPOUND, other.tag())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nbt;
+
+    #[test]
+    fn accessors() {
+        let mut value = nbt!({
+            Level: 3,
+            Data: [B; 1b, 2b],
+            Name: "shenjack",
+            Pos: [1.0, 2.0],
+        });
+        let (name, data) = value.as_compound_ref().unwrap();
+        assert_eq!(name, None);
+        assert_eq!(data[0].1.as_i32(), Ok(3));
+        assert_eq!(data[1].1.as_i8_array_ref(), Ok(&[1_i8, 2][..]));
+        assert_eq!(data[2].1.as_string_ref().unwrap().decode_lossy(), "shenjack");
+        assert_eq!(data[3].1.as_list_ref().map(<[_]>::len), Ok(2));
+        // 类型不对
+        assert_eq!(data[0].1.as_i64(), Err(NbtError::IncorrectType(4, 3)));
+        assert_eq!(data[1].1.as_i32_array_ref(), Err(NbtError::IncorrectType(11, 7)));
+        assert_eq!(data[2].1.as_list_ref(), Err(NbtError::IncorrectType(9, 8)));
+        assert_eq!(data[3].1.as_compound_ref(), Err(NbtError::IncorrectType(10, 9)));
+
+        let (name, data) = value.as_compound_mut().unwrap();
+        *name = Some("root".into());
+        *data[0].1.as_i32_mut().unwrap() += 1;
+        data[1].1.as_i8_array_mut().unwrap().push(3);
+        *data[2].1.as_string_mut().unwrap() = "InfyniteHeap".into();
+        *data[3].1.as_list_mut().unwrap()[0].as_f64_mut().unwrap() = 0.5;
+        assert!(data[3].1.as_f64_mut().is_err());
+
+        let (name, data) = value.into_compound().unwrap();
+        assert_eq!(name, Some("root".into()));
+        let mut data = data.into_iter().map(|(_, v)| v);
+        assert_eq!(data.next().unwrap().as_i32(), Ok(4));
+        assert_eq!(data.next().unwrap().into_i8_array(), Ok(vec![1, 2, 3]));
+        assert_eq!(data.next().unwrap().into_string(), Ok("InfyniteHeap".into()));
+        let pos = data.next().unwrap();
+        assert_eq!(pos.clone().into_i64_array(), Err(NbtError::IncorrectType(12, 9)));
+        assert_eq!(pos.into_list(), Ok(vec![NbtValue::Double(0.5), NbtValue::Double(2.0)]));
+    }
+}
@@ -0,0 +1,333 @@
+use crate::nbt_version::{BedrockDisk, BedrockNetVarInt, Java, JavaNetAfter1_20_2};
+use crate::traits::NbtWriteTrait;
+
+/// 写入一个 VarInt
+#[inline]
+pub fn write_var_u32(writer: &mut Vec<u8>, value: u32) {
+    let mut value = value;
+    loop {
+        let temp = (value & 0b0111_1111) as u8;
+        value >>= 7;
+        if value == 0 {
+            writer.push(temp);
+            break;
+        }
+        writer.push(temp | 0b1000_0000);
+    }
+}
+
+/// 写入一个 VarLong
+#[inline]
+pub fn write_var_u64(writer: &mut Vec<u8>, value: u64) {
+    let mut value = value;
+    loop {
+        let temp = (value & 0b0111_1111) as u8;
+        value >>= 7;
+        if value == 0 {
+            writer.push(temp);
+            break;
+        }
+        writer.push(temp | 0b1000_0000);
+    }
+}
+
+/// 写入一个 zigzag 编码的 VarInt
+#[inline]
+pub fn write_zigzag_var_i32(writer: &mut Vec<u8>, value: i32) {
+    write_var_u32(writer, ((value << 1) ^ (value >> 31)) as u32);
+}
+
+/// 写入一个 zigzag 编码的 VarLong
+#[inline]
+pub fn write_zigzag_var_i64(writer: &mut Vec<u8>, value: i64) {
+    write_var_u64(writer, ((value << 1) ^ (value >> 63)) as u64);
+}
+
+/// 最简单的一集
+///
+/// 全都是大端
+impl NbtWriteTrait for Java {
+    #[inline]
+    fn write_i16(writer: &mut Vec<u8>, data: i16) { writer.extend_from_slice(&data.to_be_bytes()); }
+    #[inline]
+    fn write_i32(writer: &mut Vec<u8>, data: i32) { writer.extend_from_slice(&data.to_be_bytes()); }
+    #[inline]
+    fn write_i64(writer: &mut Vec<u8>, data: i64) { writer.extend_from_slice(&data.to_be_bytes()); }
+    #[inline]
+    fn write_f32(writer: &mut Vec<u8>, data: f32) { writer.extend_from_slice(&data.to_be_bytes()); }
+    #[inline]
+    fn write_f64(writer: &mut Vec<u8>, data: f64) { writer.extend_from_slice(&data.to_be_bytes()); }
+    #[inline]
+    fn write_len(writer: &mut Vec<u8>, len: usize) {
+        writer.extend_from_slice(&(len as i32).to_be_bytes());
+    }
+    #[inline]
+    fn write_str_len(writer: &mut Vec<u8>, len: usize) {
+        writer.extend_from_slice(&(len as u16).to_be_bytes());
+    }
+    #[inline]
+    fn write_i32_array(writer: &mut Vec<u8>, data: &[i32]) {
+        Self::write_len(writer, data.len());
+        writer.extend(data.iter().flat_map(|x| x.to_be_bytes()));
+    }
+    #[inline]
+    fn write_i64_array(writer: &mut Vec<u8>, data: &[i64]) {
+        Self::write_len(writer, data.len());
+        writer.extend(data.iter().flat_map(|x| x.to_be_bytes()));
+    }
+}
+
+/// 除了根节点没有名字, 其他的都跟 Java 一样
+impl NbtWriteTrait for JavaNetAfter1_20_2 {
+    const ROOT_NAMED: bool = false;
+
+    #[inline]
+    fn write_i16(writer: &mut Vec<u8>, data: i16) { Java::write_i16(writer, data); }
+    #[inline]
+    fn write_i32(writer: &mut Vec<u8>, data: i32) { Java::write_i32(writer, data); }
+    #[inline]
+    fn write_i64(writer: &mut Vec<u8>, data: i64) { Java::write_i64(writer, data); }
+    #[inline]
+    fn write_f32(writer: &mut Vec<u8>, data: f32) { Java::write_f32(writer, data); }
+    #[inline]
+    fn write_f64(writer: &mut Vec<u8>, data: f64) { Java::write_f64(writer, data); }
+    #[inline]
+    fn write_len(writer: &mut Vec<u8>, len: usize) { Java::write_len(writer, len); }
+    #[inline]
+    fn write_str_len(writer: &mut Vec<u8>, len: usize) { Java::write_str_len(writer, len); }
+    #[inline]
+    fn write_i32_array(writer: &mut Vec<u8>, data: &[i32]) { Java::write_i32_array(writer, data); }
+    #[inline]
+    fn write_i64_array(writer: &mut Vec<u8>, data: &[i64]) { Java::write_i64_array(writer, data); }
+}
+
+/// 全都是小端
+impl NbtWriteTrait for BedrockDisk {
+    const LIST_ROOT: bool = true;
+    const MUTF8_STRING: bool = false;
+
+    #[inline]
+    fn write_i16(writer: &mut Vec<u8>, data: i16) { writer.extend_from_slice(&data.to_le_bytes()); }
+    #[inline]
+    fn write_i32(writer: &mut Vec<u8>, data: i32) { writer.extend_from_slice(&data.to_le_bytes()); }
+    #[inline]
+    fn write_i64(writer: &mut Vec<u8>, data: i64) { writer.extend_from_slice(&data.to_le_bytes()); }
+    #[inline]
+    fn write_f32(writer: &mut Vec<u8>, data: f32) { writer.extend_from_slice(&data.to_le_bytes()); }
+    #[inline]
+    fn write_f64(writer: &mut Vec<u8>, data: f64) { writer.extend_from_slice(&data.to_le_bytes()); }
+    #[inline]
+    fn write_len(writer: &mut Vec<u8>, len: usize) {
+        writer.extend_from_slice(&(len as i32).to_le_bytes());
+    }
+    #[inline]
+    fn write_str_len(writer: &mut Vec<u8>, len: usize) {
+        writer.extend_from_slice(&(len as u16).to_le_bytes());
+    }
+    #[inline]
+    fn write_i32_array(writer: &mut Vec<u8>, data: &[i32]) {
+        Self::write_len(writer, data.len());
+        writer.extend(data.iter().flat_map(|x| x.to_le_bytes()));
+    }
+    #[inline]
+    fn write_i64_array(writer: &mut Vec<u8>, data: &[i64]) {
+        Self::write_len(writer, data.len());
+        writer.extend(data.iter().flat_map(|x| x.to_le_bytes()));
+    }
+}
+
+/// 最痛苦的一集
+///
+/// - Short, Float, Double 都是小端
+/// - Int 和 List/Array 的长度都是 zigzag VarInt
+/// - Long 是 zigzag VarLong
+/// - 字符串长度是 VarInt
+/// - IntArray/LongArray 的元素还是定长小端
+impl NbtWriteTrait for BedrockNetVarInt {
+    const LIST_ROOT: bool = true;
+    const MUTF8_STRING: bool = false;
+    const MAX_STR_LEN: usize = i32::MAX as usize;
+
+    #[inline]
+    fn write_i16(writer: &mut Vec<u8>, data: i16) { BedrockDisk::write_i16(writer, data); }
+    #[inline]
+    fn write_i32(writer: &mut Vec<u8>, data: i32) { write_zigzag_var_i32(writer, data); }
+    #[inline]
+    fn write_i64(writer: &mut Vec<u8>, data: i64) { write_zigzag_var_i64(writer, data); }
+    #[inline]
+    fn write_f32(writer: &mut Vec<u8>, data: f32) { BedrockDisk::write_f32(writer, data); }
+    #[inline]
+    fn write_f64(writer: &mut Vec<u8>, data: f64) { BedrockDisk::write_f64(writer, data); }
+    #[inline]
+    fn write_len(writer: &mut Vec<u8>, len: usize) { write_zigzag_var_i32(writer, len as i32); }
+    #[inline]
+    fn write_str_len(writer: &mut Vec<u8>, len: usize) { write_var_u32(writer, len as u32); }
+    #[inline]
+    fn write_i32_array(writer: &mut Vec<u8>, data: &[i32]) {
+        Self::write_len(writer, data.len());
+        writer.extend(data.iter().flat_map(|x| x.to_le_bytes()));
+    }
+    #[inline]
+    fn write_i64_array(writer: &mut Vec<u8>, data: &[i64]) {
+        Self::write_len(writer, data.len());
+        writer.extend(data.iter().flat_map(|x| x.to_le_bytes()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{
+        BIG_TEST_BEDROCK_DATA, BIG_TEST_BEDROCK_NET_DATA, BIG_TEST_DATA, COMPLEX_PLR_DATA,
+        HELLO_WORLD_BEDROCK_DATA, HELLO_WORLD_BEDROCK_NET_DATA, HELLO_WORLD_DATA,
+    };
+    use crate::traits::NbtBorrowTrait;
+    use crate::NbtReader;
+    use crate::{nbt_consts, NbtError, NbtValue};
+
+    /// 读出来再写回去, 应该跟原来的数据完全一致
+    fn round_trip<T: NbtBorrowTrait + NbtWriteTrait>(data: &[u8]) {
+        let mut reader = NbtReader::new(data);
+        let borrow_value = T::from_reader(&mut reader).unwrap();
+        let value = T::read_data(&borrow_value, &mut reader);
+        assert_eq!(value.to_binary::<T>().unwrap(), data);
+    }
+
+    #[test]
+    fn java_round_trip() {
+        round_trip::<Java>(&HELLO_WORLD_DATA);
+        round_trip::<Java>(&BIG_TEST_DATA);
+        round_trip::<Java>(&COMPLEX_PLR_DATA);
+    }
+
+    #[test]
+    fn java_net_no_root_name() {
+        let value = NbtValue::Compound(
+            Some("hello world".into()),
+            vec![("name".into(), NbtValue::String("Bananrama".into()))],
+        );
+        let data = value.to_binary::<JavaNetAfter1_20_2>().unwrap();
+        // 去掉根节点名字 (2 + 11 字节) 之后跟 Java 的一样
+        let mut java_data = HELLO_WORLD_DATA.to_vec();
+        java_data.drain(1..14);
+        assert_eq!(data, java_data);
+    }
+
+    #[test]
+    fn bedrock_disk_round_trip() {
+        round_trip::<BedrockDisk>(&HELLO_WORLD_BEDROCK_DATA);
+        round_trip::<BedrockDisk>(&BIG_TEST_BEDROCK_DATA);
+    }
+
+    #[test]
+    fn bedrock_net_round_trip() {
+        round_trip::<BedrockNetVarInt>(&HELLO_WORLD_BEDROCK_NET_DATA);
+        round_trip::<BedrockNetVarInt>(&BIG_TEST_BEDROCK_NET_DATA);
+    }
+
+    #[test]
+    fn bedrock_list_root() {
+        let value = NbtValue::List(vec![NbtValue::Int(1), NbtValue::Int(-1)]);
+        assert_eq!(value.to_binary::<BedrockNetVarInt>().unwrap(), [0x09, 0x03, 0x04, 0x02, 0x01]);
+        assert_eq!(
+            value.to_binary::<BedrockDisk>().unwrap(),
+            [0x09, 0x03, 0x02, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0xFF, 0xFF, 0xFF, 0xFF]
+        );
+        // Java 的根节点只能是 Compound
+        assert_eq!(value.to_binary::<Java>(), Err(NbtError::WrongRootType(nbt_consts::TAG_LIST)));
+    }
+
+    #[test]
+    fn string_encoding() {
+        let value = NbtValue::Compound(
+            Some("".into()),
+            vec![("名字".into(), NbtValue::String("😀\0".into()))],
+        );
+        // Java: emoji 是两个 3 字节的代理项, \0 是 C0 80
+        let java = value.to_binary::<Java>().unwrap();
+        assert!(java.ends_with(&[0x08, 0xED, 0xA0, 0xBD, 0xED, 0xB8, 0x80, 0xC0, 0x80, 0x00]));
+        // 基岩版: 普通的 utf-8
+        let bedrock = value.to_binary::<BedrockDisk>().unwrap();
+        assert!(bedrock.ends_with(&[0x05, 0x00, 0xF0, 0x9F, 0x98, 0x80, 0x00, 0x00]));
+        // Java -> 基岩版
+        let from_java = NbtValue::from_binary::<Java>(&java).unwrap();
+        assert_eq!(from_java.to_binary::<BedrockDisk>().unwrap(), bedrock);
+        assert_eq!(NbtValue::from_binary::<BedrockDisk>(&bedrock).unwrap(), value);
+        let bedrock_net = from_java.to_binary::<BedrockNetVarInt>().unwrap();
+        assert_eq!(NbtValue::from_binary::<BedrockNetVarInt>(&bedrock_net).unwrap(), value);
+        // 基岩版 -> Java
+        let from_bedrock = NbtValue::from_binary::<BedrockDisk>(&bedrock).unwrap();
+        assert_eq!(from_bedrock.to_binary::<Java>().unwrap(), java);
+    }
+
+    #[test]
+    fn bedrock_invalid_utf8() {
+        let data = [0x0A, 0x00, 0x00, 0x08, 0x01, 0x00, b'a', 0x01, 0x00, 0xFF, 0x00];
+        assert!(matches!(
+            NbtValue::from_binary::<BedrockDisk>(&data),
+            Err(NbtError::Utf8Error(_))
+        ));
+        // Java 读的时候不检查, 存的不是合法的 m-utf8 就没法转成 utf-8
+        let data = [0x0A, 0x00, 0x00, 0x08, 0x00, 0x01, b'a', 0x00, 0x01, 0xFF, 0x00];
+        let value = NbtValue::from_binary::<Java>(&data).unwrap();
+        assert!(matches!(value.to_binary::<BedrockDisk>(), Err(NbtError::Mutf8Error(_))));
+        assert_eq!(value.to_binary::<Java>().unwrap(), data);
+    }
+
+    #[test]
+    fn list_type_not_same() {
+        let value = NbtValue::Compound(
+            None,
+            vec![("a".into(), NbtValue::List(vec![NbtValue::Int(1), NbtValue::Byte(1)]))],
+        );
+        assert_eq!(
+            value.to_binary::<Java>(),
+            Err(NbtError::ListTypeNotSame(vec![nbt_consts::TAG_INT, nbt_consts::TAG_BYTE]))
+        );
+    }
+
+    #[test]
+    fn len_too_big() {
+        let long = NbtValue::String("a".repeat(70000).as_str().into());
+        let value = crate::nbt!({ Inventory: [{ tag: { Name: (long.clone()) } }] });
+        let err = NbtError::LenTooBig("Inventory[0].tag.Name".to_string(), 70000, 65535);
+        assert_eq!(value.to_binary::<Java>(), Err(err.clone()));
+        assert_eq!(value.to_binary::<JavaNetAfter1_20_2>(), Err(err.clone()));
+        assert_eq!(value.to_binary::<BedrockDisk>(), Err(err));
+        // 基岩版网络格式的字符串长度是 VarInt
+        let data = value.to_binary::<BedrockNetVarInt>().unwrap();
+        let read = NbtValue::from_binary::<BedrockNetVarInt>(&data).unwrap();
+        assert_eq!(read.to_binary::<BedrockNetVarInt>().unwrap(), data);
+        // 需要引号的 key
+        let value = crate::nbt!({ "a b": [(long)] });
+        assert_eq!(
+            value.to_binary::<Java>().unwrap_err().to_string(),
+            "\"a b\"[0] 长度过大: 70000, 最大为 65535"
+        );
+        // 算的是编码之后的长度, `\0` 在 m-utf8 里是两个字节
+        let value = crate::nbt!({ nul: ("\0".repeat(40000)) });
+        assert_eq!(
+            value.to_binary::<Java>(),
+            Err(NbtError::LenTooBig("nul".to_string(), 80000, 65535))
+        );
+        assert!(value.to_binary::<BedrockDisk>().is_ok());
+        // 根节点的名字
+        let value = NbtValue::Compound(Some("a".repeat(70000).as_str().into()), vec![]);
+        assert_eq!(
+            value.to_binary::<Java>().unwrap_err().to_string(),
+            "(根节点) 长度过大: 70000, 最大为 65535"
+        );
+    }
+
+    #[test]
+    fn varint_edge() {
+        let mut buff = Vec::new();
+        write_zigzag_var_i32(&mut buff, i32::MIN);
+        write_zigzag_var_i64(&mut buff, i64::MIN);
+        write_var_u32(&mut buff, u32::MAX);
+        let mut reader = NbtReader::new(&buff);
+        assert_eq!(reader.read_zigzag_var_i32().unwrap(), i32::MIN);
+        assert_eq!(reader.read_zigzag_var_i64().unwrap(), i64::MIN);
+        assert_eq!(reader.read_var_i32().unwrap(), -1);
+    }
+}